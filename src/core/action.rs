@@ -13,11 +13,16 @@
 //! This makes everything testable: `assert_eq!(update(state, action), expected)`.
 //! And debuggable: log every action, replay the exact session.
 
-use crate::core::config::ModelEntry;
+use crate::core::config::{self, ModelEntry, ResolvedConfig};
 use crate::core::session::SessionData;
-use crate::core::state::{ActiveModel, App, SessionState};
-use crate::inference::{ToolCall, ToolResult, UsageStats};
-use log::{debug, warn};
+use crate::core::state::{ActiveModel, App, SessionState, StreamPhase};
+use crate::inference::{
+    ContextSegment, Effort, ImageAttachment, LiveRate, RateLimitInfo, Source, ToolCall, ToolResult,
+    TurnUsage, UsageStats,
+};
+use log::{debug, info, warn};
+use std::ops::Range;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum Action {
@@ -25,6 +30,10 @@ pub enum Action {
     Quit,
     // Submit a user message (TUI passes the message content)
     Submit(String),
+    // Answer the latest user message again, keeping the old answer as a variant
+    Regenerate,
+    // Show the next (true) or previous (false) answer to the latest user message
+    CycleVariant(bool),
     // Receive a chunk of content from the API (streaming)
     ResponseChunk {
         text: String,
@@ -35,8 +44,14 @@ pub enum Action {
         text: String,
         item_id: Option<String>,
     },
+    // The provider closed a reasoning item; its thinking segment is complete
+    ThinkingDone {
+        item_id: Option<String>,
+    },
     // Signal that the streaming response is complete, with optional usage stats.
     ResponseDone(Option<UsageStats>),
+    // The provider reported an error mid-stream; ends the turn
+    StreamError(String),
     // Model wants to call a tool
     ToolCallReceived(ToolCall),
     // A tool execution completed
@@ -44,14 +59,32 @@ pub enum Action {
         call_id: String,
         output: String,
     },
+    // The provider reported its rate limit and credit headers
+    RateLimitUpdated(RateLimitInfo),
+    // Content arrived from the provider, before smoothing: its length in
+    // characters and the time since the request was sent
+    ContentReceived {
+        chars: usize,
+        elapsed: Duration,
+    },
     // User cancelled the in-progress generation
     CancelGeneration,
     // Cycle to next reasoning effort level
     CycleEffort,
+    // Set the reasoning effort level directly (e.g. `/effort high`)
+    SetEffort(Effort),
+    // Input box grown or shrunk — None restores its default height cap
+    SetInputMaxLines(Option<u16>),
+    // Pinned messages changed (context indices, ascending)
+    SetPins(Vec<usize>),
+    // Queue a one-off instruction for the next submitted message (`/once`)
+    SetTurnDirective(String),
     // Switch to a different model/provider
     SwitchModel(ActiveModel),
     // Replace context with a loaded session
     LoadSession(SessionData),
+    // Drop the conversation but keep the directive (session file stays on disk)
+    ClearConversation,
     // Reset to a fresh conversation with the given title
     NewSession {
         title: String,
@@ -65,6 +98,23 @@ pub enum Action {
     SessionDeleted(String),
     // Dynamic models fetched from provider APIs (handled by TUI, not core)
     ModelsFetched(Vec<ModelEntry>),
+    // Image pasted or read from the clipboard — held until the next Submit
+    StageImage(ImageAttachment),
+    // No image could be read from the clipboard; the reason for the status bar
+    ClipboardImageFailed(String),
+    // Drop the staged image without sending it
+    UnstageImage,
+    // Config file re-read from disk — apply the hot-reloadable subset
+    ConfigReloaded(Box<ResolvedConfig>),
+    // Config file could not be re-read — keep the current config
+    ConfigReloadFailed(String),
+    // The model condensed these older items; replace them and send the turn
+    ContextSummarized {
+        range: Range<usize>,
+        summary: String,
+    },
+    // Summarizing failed — send the turn with the full context
+    SummaryFailed(String),
 }
 
 #[derive(Debug, PartialEq)]
@@ -73,9 +123,79 @@ pub enum Effect {
     Render,
     Quit,
     SpawnRequest,
-    ExecuteTool(ToolCall), // Run a tool asynchronously
-    SaveSession,           // Persist current session to disk
-    SwitchProvider,       // Reconstruct the provider after model switch
+    ExecuteTool(ToolCall),          // Run a tool asynchronously
+    SaveSession,                    // Persist current session to disk
+    SwitchProvider,                 // Reconstruct the provider after model switch or config reload
+    SummarizeContext(Range<usize>), // Ask the model to condense these items first
+}
+
+/// Share of the model's context window past which older turns are
+/// summarized before the next request.
+const SUMMARIZE_AT_PERCENT: u64 = 80;
+
+/// The older items to summarize before sending the next request, if
+/// summarizing is on and the context is close to the model's window.
+fn summary_due(app_state: &App) -> Option<Range<usize>> {
+    if !app_state.config.summarize_context {
+        return None;
+    }
+    let window = u64::from(app_state.context_window()?);
+    let used = u64::from(app_state.session.context.estimate_tokens());
+    if used * 100 < window * SUMMARIZE_AT_PERCENT {
+        return None;
+    }
+    app_state.session.context.summarizable_range()
+}
+
+/// Refuse a turn the provider chain has no credentials for, up front rather
+/// than with a 401 mid-stream. The guidance goes in the transcript and the
+/// status bar. Returns true if the turn was refused.
+fn refuse_without_credentials(app_state: &mut App) -> bool {
+    let Some(guidance) = app_state.missing_credentials() else {
+        return false;
+    };
+    let s = &mut app_state.session;
+    s.context.add(ContextSegment {
+        source: Source::Status,
+        content: guidance.to_string(),
+        timestamp: None,
+    });
+    s.status_message = guidance.to_string();
+    true
+}
+
+/// Reset the per-turn state for a new request, shown with `status`. The
+/// request waits for a summary of older turns when the context is nearly
+/// full.
+fn begin_turn(app_state: &mut App, status: &str) -> Effect {
+    let s = &mut app_state.session;
+    s.dirty = true;
+    s.is_loading = true;
+    s.agentic_rounds = 0;
+    s.stream_phase = StreamPhase::Waiting;
+    s.stream_done = false;
+    s.had_tool_calls = false;
+    s.usage_stats = UsageStats::default();
+    s.live_rate = LiveRate::default();
+    s.message_stats.clear();
+    // A queued `/once` instruction applies to this turn only
+    s.turn_directive = s.next_turn_directive.take();
+    s.status_message = status.to_string();
+    if let Some(range) = summary_due(app_state) {
+        let s = &mut app_state.session;
+        s.summarizing = true;
+        s.status_message = String::from("Summarizing earlier turns...");
+        return Effect::SummarizeContext(range);
+    }
+    Effect::SpawnRequest
+}
+
+/// Drop pins past the variant anchor: regenerating or cycling answers puts
+/// other items at those indices.
+fn drop_answer_pins(s: &mut SessionState) {
+    if let Some(anchor) = s.context.variant_anchor() {
+        s.pins.retain(|&pin| pin <= anchor);
+    }
 }
 
 /// Checks whether the current agentic round is fully complete (stream finished
@@ -85,6 +205,8 @@ pub enum Effect {
 /// the stream has finished sending all tool calls, which would prematurely fire
 /// `SpawnRequest` and re-enter the agentic loop with incomplete context.
 fn check_round_complete(app_state: &mut App) -> Effect {
+    let pricing = app_state.pricing();
+    let inline_usage = app_state.config.inline_usage;
     let s = &mut app_state.session;
     if s.stream_done && s.pending_tool_calls.is_empty() {
         if s.had_tool_calls {
@@ -104,6 +226,7 @@ fn check_round_complete(app_state: &mut App) -> Effect {
                 Effect::Render
             } else {
                 s.status_message = String::from("Resuming...");
+                s.stream_phase = StreamPhase::Waiting;
                 s.stream_done = false;
                 s.had_tool_calls = false;
                 Effect::SpawnRequest
@@ -111,7 +234,19 @@ fn check_round_complete(app_state: &mut App) -> Effect {
         } else {
             // Pure text response — no tools were called
             s.is_loading = false;
-            s.status_message = s.usage_stats.display_summary();
+            let summary = s.usage_stats.display_summary(pricing.as_ref());
+            if inline_usage {
+                // Status items are never sent to the provider or saved
+                s.context.add(ContextSegment {
+                    source: Source::Status,
+                    content: summary.clone(),
+                    timestamp: None,
+                });
+            }
+            s.status_message = match s.usage_stats.finish_hint() {
+                Some(hint) => hint.to_string(),
+                None => summary,
+            };
             Effect::SaveSession
         }
     } else if !s.pending_tool_calls.is_empty() {
@@ -130,22 +265,54 @@ pub fn update(app_state: &mut App, action: Action) -> Effect {
             if message.is_empty() || app_state.session.is_loading {
                 return Effect::None; // noop on empty input or if already loading
             }
+            if refuse_without_credentials(app_state) {
+                return Effect::Render;
+            }
             let s = &mut app_state.session;
+            if let Some(image) = s.pending_image.take() {
+                s.context.add_image(image);
+            }
             s.context.add_user_message(message);
-            s.is_loading = true;
-            s.agentic_rounds = 0;
-            s.stream_done = false;
-            s.had_tool_calls = false;
-            s.usage_stats = UsageStats::default();
-            s.message_stats.clear();
-            s.status_message = String::from("Loading...");
-            Effect::SpawnRequest
+            begin_turn(app_state, "Loading...")
+        }
+        Action::Regenerate => {
+            if app_state.session.is_loading {
+                return Effect::None;
+            }
+            if refuse_without_credentials(app_state) {
+                return Effect::Render;
+            }
+            let s = &mut app_state.session;
+            if !s.context.begin_variant() {
+                s.status_message = String::from("Nothing to regenerate.");
+                return Effect::Render;
+            }
+            drop_answer_pins(s);
+            begin_turn(app_state, "Regenerating...")
+        }
+        Action::CycleVariant(forward) => {
+            let s = &mut app_state.session;
+            if s.is_loading {
+                return Effect::None;
+            }
+            match s.context.cycle_variant(forward) {
+                Some((shown, count)) => {
+                    // Stats are keyed by item index, which now holds another answer
+                    s.message_stats.clear();
+                    drop_answer_pins(s);
+                    s.dirty = true;
+                    s.status_message = format!("Answer {} of {}", shown + 1, count);
+                }
+                None => s.status_message = String::from("No other answers to show."),
+            }
+            Effect::Render
         }
         Action::ResponseChunk { text, item_id } => {
             app_state
                 .session
                 .context
                 .append_to_last_model_message(&text, item_id.as_deref());
+            app_state.session.dirty = true;
             // Log total message length after append
             if let Some(crate::inference::ContextItem::Message(last)) =
                 app_state.session.context.items.last()
@@ -156,6 +323,7 @@ pub fn update(app_state: &mut App, action: Action) -> Effect {
                     last.content.len()
                 );
             }
+            app_state.session.stream_phase = StreamPhase::Content;
             app_state.session.status_message = String::from("Receiving...");
             Effect::Render
         }
@@ -164,19 +332,31 @@ pub fn update(app_state: &mut App, action: Action) -> Effect {
                 .session
                 .context
                 .append_to_last_thinking_message(&text, item_id.as_deref());
+            app_state.session.dirty = true;
             debug!("ThinkingChunk applied: chunk_len={}", text.len());
+            // Reasoning interleaved after the response started doesn't switch back
+            if app_state.session.stream_phase == StreamPhase::Waiting {
+                app_state.session.stream_phase = StreamPhase::Thinking;
+            }
             app_state.session.status_message = String::from("Thinking...");
             Effect::Render
         }
+        Action::ThinkingDone { item_id } => {
+            if let Some(id) = item_id {
+                app_state.session.context.end_stream(&id);
+            }
+            debug!("ThinkingDone applied");
+            Effect::None
+        }
         Action::ResponseDone(stats) => {
             app_state.session.context.clear_active_streams();
+            app_state.session.context.advance_cache_watermark();
             app_state.session.stream_done = true;
+            app_state.session.dirty = true;
             if let Some(round_stats) = stats {
                 app_state.session.usage_stats.accumulate(&round_stats);
                 // Accumulate into session-level running total
-                if let Some(tokens) = round_stats.total_tokens {
-                    app_state.session.session_total_tokens += tokens;
-                }
+                app_state.session.session_usage.accumulate(&round_stats);
                 // Store per-message stats on the last Model message
                 if let Some(idx) = app_state
                     .session
@@ -187,7 +367,11 @@ pub fn update(app_state: &mut App, action: Action) -> Effect {
                         matches!(item, crate::inference::ContextItem::Message(seg) if seg.source == crate::inference::Source::Model)
                     })
                 {
-                    app_state.session.message_stats.insert(idx, round_stats);
+                    let usage = TurnUsage {
+                        stats: round_stats,
+                        pricing: app_state.pricing(),
+                    };
+                    app_state.session.message_stats.insert(idx, usage);
                 }
             }
             if let Some(crate::inference::ContextItem::Message(last)) =
@@ -197,6 +381,22 @@ pub fn update(app_state: &mut App, action: Action) -> Effect {
             }
             check_round_complete(app_state)
         }
+        Action::StreamError(message) => {
+            let s = &mut app_state.session;
+            s.context.clear_active_streams();
+            s.context.add(ContextSegment {
+                source: Source::Status,
+                content: format!("Error: {}", message),
+                timestamp: None,
+            });
+            s.dirty = true;
+            s.is_loading = false;
+            s.pending_tool_calls.clear();
+            s.stream_done = false;
+            s.had_tool_calls = false;
+            s.status_message = String::from("Response failed.");
+            Effect::SaveSession
+        }
         Action::ToolCallReceived(tool_call) => {
             if tool_call.call_id.is_empty() {
                 warn!(
@@ -207,41 +407,61 @@ pub fn update(app_state: &mut App, action: Action) -> Effect {
             }
             let s = &mut app_state.session;
             s.had_tool_calls = true;
+            s.stream_phase = StreamPhase::Content;
             s.pending_tool_calls.insert(tool_call.call_id.clone());
             s.context.add_tool_call(tool_call.clone());
+            s.dirty = true;
             s.status_message = format!("Calling: {}...", tool_call.name);
             Effect::ExecuteTool(tool_call)
         }
         Action::ToolResultReady { call_id, output } => {
             app_state.session.pending_tool_calls.remove(&call_id);
+            let max_bytes = app_state.config.max_tool_output_bytes;
             app_state
                 .session
                 .context
-                .add_tool_result(ToolResult { call_id, output });
+                .add_tool_result_capped(ToolResult { call_id, output }, max_bytes);
+            app_state.session.dirty = true;
             check_round_complete(app_state)
         }
+        Action::RateLimitUpdated(info) => {
+            app_state.rate_limit = Some(info);
+            Effect::Render
+        }
+        Action::ContentReceived { chars, elapsed } => {
+            app_state.session.live_rate.record(chars, elapsed);
+            Effect::Render
+        }
         Action::CancelGeneration => {
             let s = &mut app_state.session;
             s.is_loading = false;
+            s.summarizing = false;
             s.pending_tool_calls.clear();
             s.stream_done = false;
             s.had_tool_calls = false;
             s.usage_stats = UsageStats::default();
-            s.context.clear_active_streams();
+            s.context.cancel_partial_model_message();
+            s.dirty = true;
             s.status_message = String::from("Cancelled.");
             Effect::Render
         }
         Action::LoadSession(data) => {
             let mut session = SessionState::new(&app_state.system_prompt);
+            // Saved pins count only the saved items; the directive comes first
+            let offset = session.context.items.len();
+            session.pins = data.meta.pins.iter().map(|pin| pin + offset).collect();
             for item in data.items {
                 session.context.items.push(item);
             }
+            // A session saved mid-turn may have lost a tool result
+            session.context.repair_tool_pairs();
             session.current_session_id = Some(data.meta.id);
             session.session_title = data.meta.title.clone();
+            session.scroll = data.meta.scroll;
             session.status_message = format!("Loaded: {}", data.meta.title);
             let loaded_model = ActiveModel::new(data.meta.model_name, data.meta.provider_name);
-            let provider_changed =
-                !loaded_model.provider.is_empty() && loaded_model.provider != app_state.model.provider;
+            let provider_changed = !loaded_model.provider.is_empty()
+                && loaded_model.provider != app_state.model.provider;
             if !loaded_model.provider.is_empty() {
                 app_state.model = loaded_model;
             } else {
@@ -261,6 +481,10 @@ pub fn update(app_state: &mut App, action: Action) -> Effect {
             app_state.session.status_message = String::from("New session.");
             Effect::Render
         }
+        Action::ClearConversation => {
+            app_state.clear_conversation();
+            Effect::Render
+        }
         Action::SessionRenamed { id, new_title } => {
             if app_state.session.current_session_id.as_deref() == Some(&id) {
                 app_state.session.session_title = new_title;
@@ -277,14 +501,116 @@ pub fn update(app_state: &mut App, action: Action) -> Effect {
         Action::SwitchModel(model) => {
             app_state.session.status_message =
                 format!("Switched to {} ({})", model.name, model.provider);
+            let params = config::params_for_model(&app_state.config, &model.name, &model.provider);
+            app_state.apply_model_params(params);
             app_state.model = model;
+            // The new model may be served under different limits
+            app_state.rate_limit = None;
+            Effect::SwitchProvider
+        }
+        Action::StageImage(image) => {
+            app_state.session.status_message = format!("Image attached: {}", image.label());
+            app_state.session.pending_image = Some(image);
+            Effect::Render
+        }
+        Action::ClipboardImageFailed(message) => {
+            app_state.session.status_message = message;
+            Effect::Render
+        }
+        Action::UnstageImage => {
+            if app_state.session.pending_image.take().is_some() {
+                app_state.session.status_message = String::from("Image removed.");
+            }
+            Effect::Render
+        }
+        Action::ConfigReloaded(new_config) => {
+            // The active model and provider stay as they are; everything else
+            // takes effect on the next request. The tools are rebuilt here and
+            // the provider by the caller, so their settings reload too.
+            let mut new_config = *new_config;
+            new_config.provider = app_state.config.provider.clone();
+            new_config.model_name = app_state.config.model_name.clone();
+            if let Some(effort) = app_state.effort_override {
+                new_config.effort = effort;
+            }
+
+            app_state.max_agentic_rounds = new_config.max_agentic_rounds;
+            app_state.system_prompt = new_config.system_prompt.clone();
+            app_state
+                .session
+                .context
+                .set_system_prompt(new_config.system_prompt.clone());
+            app_state.available_models = new_config.models.clone();
+            let params = config::params_for_model(
+                &new_config,
+                &app_state.model.name,
+                &app_state.model.provider,
+            );
+            app_state.apply_model_params(params);
+            app_state.config = new_config;
+            app_state.rebuild_tools();
+            app_state.session.status_message = String::from("Config reloaded.");
             Effect::SwitchProvider
         }
+        Action::ConfigReloadFailed(error) => {
+            app_state.session.status_message = format!("Config reload failed: {error}");
+            Effect::Render
+        }
+        Action::ContextSummarized { range, summary } => {
+            let s = &mut app_state.session;
+            if !s.summarizing {
+                return Effect::None; // Cancelled while summarizing
+            }
+            s.summarizing = false;
+            let removed = range.len();
+            if s.context.replace_with_summary(range.clone(), &summary) {
+                info!("Replaced {} older items with a summary", removed);
+                // Pins on summarized items are gone; later ones moved up
+                s.pins.retain(|pin| !range.contains(pin));
+                for pin in &mut s.pins {
+                    if *pin >= range.end {
+                        *pin -= removed - 1;
+                    }
+                }
+                s.dirty = true;
+            }
+            s.status_message = String::from("Loading...");
+            Effect::SpawnRequest
+        }
+        Action::SummaryFailed(error) => {
+            let s = &mut app_state.session;
+            if !s.summarizing {
+                return Effect::None;
+            }
+            s.summarizing = false;
+            warn!("Summarizing older turns failed: {}", error);
+            s.status_message = String::from("Summary failed; sending the full conversation...");
+            Effect::SpawnRequest
+        }
         Action::CycleEffort => {
             app_state.effort = app_state.effort.next();
             app_state.session.status_message = format!("Reasoning: {}", app_state.effort.label());
             Effect::Render
         }
+        Action::SetEffort(effort) => {
+            app_state.effort = effort;
+            app_state.session.status_message = format!("Reasoning: {}", app_state.effort.label());
+            Effect::Render
+        }
+        Action::SetInputMaxLines(lines) => {
+            app_state.input_max_lines = lines;
+            Effect::Render
+        }
+        Action::SetPins(pins) => {
+            app_state.session.pins = pins;
+            app_state.session.dirty = true;
+            Effect::Render
+        }
+        Action::SetTurnDirective(directive) => {
+            app_state.session.status_message = format!("Next message only: {}", directive);
+            app_state.session.next_turn_directive = Some(directive);
+            Effect::Render
+        }
         // ModelsFetched carries TUI-only state (picker list). The TUI event loop
         // intercepts this action before it reaches update(). This no-op handler
         // exists as a defensive fallthrough — if the TUI intercept is ever removed,
@@ -334,6 +660,79 @@ mod tests {
         assert_eq!(effect, Effect::SpawnRequest);
     }
 
+    #[test]
+    fn test_content_received_updates_live_rate_until_next_turn() {
+        let mut app = test_app();
+        update(&mut app, Action::Submit("Hello".to_string()));
+        let effect = update(
+            &mut app,
+            Action::ContentReceived {
+                chars: 40,
+                elapsed: Duration::from_millis(500),
+            },
+        );
+        assert_eq!(effect, Effect::Render);
+        assert_eq!(app.session.live_rate.tokens_per_sec(), Some(20.0));
+
+        app.session.is_loading = false;
+        update(&mut app, Action::Submit("Again".to_string()));
+        assert_eq!(app.session.live_rate.tokens_per_sec(), None);
+    }
+
+    #[test]
+    fn test_submit_without_openrouter_key_explains_instead_of_sending() {
+        let mut app = test_app();
+        app.model.provider = "openrouter".to_string();
+        app.config.openrouter_api_key = None;
+
+        let effect = update(&mut app, Action::Submit("Hello, model!".to_string()));
+
+        assert_eq!(effect, Effect::Render);
+        assert!(!app.session.is_loading);
+        assert!(matches!(
+            app.session.context.items.last(),
+            Some(ContextItem::Message(seg))
+                if seg.source == Source::Status
+                    && seg.content == "Set OPENROUTER_API_KEY or [openrouter] api_key"
+        ));
+
+        // With a key the same message goes out
+        app.config.openrouter_api_key = Some("sk-or-test".to_string());
+        let effect = update(&mut app, Action::Submit("Hello, model!".to_string()));
+        assert_eq!(effect, Effect::SpawnRequest);
+    }
+
+    #[test]
+    fn test_submit_without_azure_credentials_explains_instead_of_sending() {
+        let mut app = test_app();
+        app.model.provider = "azure".to_string();
+        app.config.azure_api_key = Some("az-key".to_string());
+        app.config.azure_endpoint = None;
+
+        let effect = update(&mut app, Action::Submit("Hello, model!".to_string()));
+
+        assert_eq!(effect, Effect::Render);
+        assert_eq!(
+            app.session.status_message,
+            crate::inference::credentials_hint("azure")
+        );
+    }
+
+    #[test]
+    fn test_submit_without_key_sends_when_a_fallback_has_credentials() {
+        let mut app = test_app();
+        app.model.provider = "openrouter".to_string();
+        app.config.openrouter_api_key = None;
+        app.config.fallback_providers = vec![crate::core::config::FallbackEntry {
+            provider: "lmstudio".to_string(),
+            model: "qwen3-8b".to_string(),
+        }];
+
+        let effect = update(&mut app, Action::Submit("Hello, model!".to_string()));
+
+        assert_eq!(effect, Effect::SpawnRequest);
+    }
+
     #[test]
     fn test_response_chunk_appends_and_updates_status() {
         let mut app = test_app();
@@ -356,6 +755,33 @@ mod tests {
         assert_eq!(effect, Effect::Render);
     }
 
+    #[test]
+    fn test_stream_phase_thinking_then_content() {
+        let mut app = test_app();
+        update(&mut app, Action::Submit("Hello".to_string()));
+        assert_eq!(app.session.stream_phase, StreamPhase::Waiting);
+
+        let thinking = |text: &str| Action::ThinkingChunk {
+            text: text.to_string(),
+            item_id: None,
+        };
+        update(&mut app, thinking("Let me see"));
+        assert_eq!(app.session.stream_phase, StreamPhase::Thinking);
+
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Hi!".to_string(),
+                item_id: None,
+            },
+        );
+        assert_eq!(app.session.stream_phase, StreamPhase::Content);
+
+        // Late reasoning doesn't take the indicator back to thinking
+        update(&mut app, thinking("more"));
+        assert_eq!(app.session.stream_phase, StreamPhase::Content);
+    }
+
     #[test]
     fn test_response_done_stops_loading() {
         let mut app = test_app();
@@ -368,6 +794,116 @@ mod tests {
         assert_eq!(effect, Effect::SaveSession);
     }
 
+    #[test]
+    fn test_response_done_advances_cache_watermark() {
+        let mut app = test_app();
+        update(&mut app, Action::Submit("Hello".to_string()));
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Hi there".to_string(),
+                item_id: None,
+            },
+        );
+        // Mid-stream the new turn isn't part of the stable prefix yet
+        assert_eq!(app.session.context.cache_watermark(), 0);
+
+        update(&mut app, Action::ResponseDone(None));
+
+        assert_eq!(app.session.context.cache_watermark(), 3); // System + User + Model
+    }
+
+    #[test]
+    fn test_summary_replaces_older_turns_before_sending() {
+        let mut app = test_app();
+        app.available_models = vec![ModelEntry {
+            name: app.model.name.clone(),
+            provider: app.model.provider.clone(),
+            context_window: Some(100), // The directive alone fills it
+            ..Default::default()
+        }];
+        for (question, answer) in [("one", "1"), ("two", "2")] {
+            // Until a second turn completes there is nothing to condense
+            assert_eq!(
+                update(&mut app, Action::Submit(question.to_string())),
+                Effect::SpawnRequest
+            );
+            update(
+                &mut app,
+                Action::ResponseChunk {
+                    text: answer.to_string(),
+                    item_id: None,
+                },
+            );
+            update(&mut app, Action::ResponseDone(None));
+        }
+
+        let effect = update(&mut app, Action::Submit("three".to_string()));
+        assert_eq!(effect, Effect::SummarizeContext(1..3));
+        assert!(app.session.summarizing);
+
+        let effect = update(
+            &mut app,
+            Action::ContextSummarized {
+                range: 1..3,
+                summary: "Asked for one, got 1.".to_string(),
+            },
+        );
+        assert_eq!(effect, Effect::SpawnRequest);
+        assert!(!app.session.summarizing);
+        let texts: Vec<&str> = app
+            .session
+            .context
+            .items
+            .iter()
+            .skip(1)
+            .map(|item| match item {
+                ContextItem::Message(seg) => seg.content.as_str(),
+                _ => panic!("expected only messages"),
+            })
+            .collect();
+        assert!(texts[0].ends_with("Asked for one, got 1."));
+        assert_eq!(texts[1..], ["two", "2", "three"]);
+
+        // A summary no longer awaited, e.g. after a cancel, is dropped
+        update(&mut app, Action::CancelGeneration);
+        let stale = Action::ContextSummarized {
+            range: 1..3,
+            summary: "late".to_string(),
+        };
+        assert_eq!(update(&mut app, stale), Effect::None);
+        assert_eq!(app.session.context.items.len(), 5);
+    }
+
+    #[test]
+    fn test_stream_error_adds_status_message_and_ends_turn() {
+        let mut app = test_app();
+        update(&mut app, Action::Submit("Hi".to_string()));
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Partial".to_string(),
+                item_id: None,
+            },
+        );
+
+        let error = Action::StreamError("Overloaded (529)".to_string());
+        let effect = update(&mut app, error);
+
+        assert!(
+            matches!(app.session.context.items.last(), Some(ContextItem::Message(seg)) if seg.source == Source::Status && seg.content == "Error: Overloaded (529)")
+        );
+        assert!(!app.session.is_loading);
+        assert!(!app.session.stream_done);
+        assert_eq!(app.session.status_message, "Response failed.");
+        assert_eq!(effect, Effect::SaveSession);
+        // A follow-up message can be sent right away
+        assert_eq!(
+            update(&mut app, Action::Submit("Again".to_string())),
+            Effect::SpawnRequest
+        );
+    }
+
     fn make_tool_call(name: &str, call_id: &str) -> crate::inference::ToolCall {
         crate::inference::ToolCall {
             id: format!("fc_{call_id}"),
@@ -470,6 +1006,184 @@ mod tests {
         assert!(app.session.error.as_ref().unwrap().contains("loop"));
     }
 
+    #[test]
+    fn test_summary_remaps_pins() {
+        let mut app = test_app();
+        for text in ["one", "1", "two", "2", "three"] {
+            app.session.context.add_user_message(text.to_string());
+        }
+        app.session.summarizing = true;
+        // "one" is summarized away, "2" moves up from 4 to 3
+        app.session.pins = vec![1, 4];
+
+        update(
+            &mut app,
+            Action::ContextSummarized {
+                range: 1..3,
+                summary: "Asked for one, got 1.".to_string(),
+            },
+        );
+
+        assert_eq!(app.session.pins, vec![3]);
+    }
+
+    #[test]
+    fn test_regenerate_then_cycle_variants() {
+        let mut app = test_app();
+        assert_eq!(update(&mut app, Action::Regenerate), Effect::Render);
+        assert!(app.session.status_message.contains("Nothing"));
+
+        update(&mut app, Action::Submit("Hi".to_string()));
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Hello!".to_string(),
+                item_id: None,
+            },
+        );
+        update(&mut app, Action::ResponseDone(None));
+
+        let effect = update(&mut app, Action::Regenerate);
+        assert_eq!(effect, Effect::SpawnRequest);
+        assert!(app.session.is_loading);
+        assert_eq!(app.session.context.items.len(), 2); // Directive + "Hi"
+        // Can't flip answers while one is streaming
+        assert_eq!(update(&mut app, Action::CycleVariant(true)), Effect::None);
+
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Hey there.".to_string(),
+                item_id: None,
+            },
+        );
+        update(&mut app, Action::ResponseDone(None));
+        let effect = update(&mut app, Action::CycleVariant(false));
+        assert_eq!(effect, Effect::Render);
+        assert_eq!(app.session.status_message, "Answer 1 of 2");
+        assert!(
+            matches!(app.session.context.items.last(), Some(ContextItem::Message(seg)) if seg.content == "Hello!")
+        );
+    }
+
+    /// Submit `question` and stream back `answer` as a complete turn.
+    fn complete_turn(app: &mut App, question: &str, answer: &str) {
+        update(app, Action::Submit(question.to_string()));
+        update(
+            app,
+            Action::ResponseChunk {
+                text: answer.to_string(),
+                item_id: None,
+            },
+        );
+        update(app, Action::ResponseDone(None));
+    }
+
+    #[test]
+    fn test_regenerate_without_credentials_keeps_the_answer() {
+        let mut app = test_app();
+        complete_turn(&mut app, "Hi", "Hello!");
+        app.model.provider = "openrouter".to_string();
+        app.config.openrouter_api_key = None;
+
+        let effect = update(&mut app, Action::Regenerate);
+
+        assert_eq!(effect, Effect::Render);
+        assert!(!app.session.is_loading);
+        assert_eq!(
+            app.session.status_message,
+            crate::inference::credentials_hint("openrouter")
+        );
+        // The answer is still there, followed by the guidance
+        assert!(matches!(
+            &app.session.context.items[2],
+            ContextItem::Message(seg) if seg.content == "Hello!"
+        ));
+    }
+
+    #[test]
+    fn test_regenerate_summarizes_first_when_context_is_full() {
+        let mut app = test_app();
+        app.available_models = vec![ModelEntry {
+            name: app.model.name.clone(),
+            provider: app.model.provider.clone(),
+            context_window: Some(100), // The directive alone fills it
+            ..Default::default()
+        }];
+        complete_turn(&mut app, "one", "1");
+        complete_turn(&mut app, "two", "2");
+
+        let effect = update(&mut app, Action::Regenerate);
+
+        assert_eq!(effect, Effect::SummarizeContext(1..3));
+        assert!(app.session.summarizing);
+        assert_eq!(app.session.context.items.len(), 4); // Directive, "one", "1", "two"
+    }
+
+    #[test]
+    fn test_regenerate_and_cycle_drop_pins_on_the_answer() {
+        let mut app = test_app();
+        update(&mut app, Action::Submit("Hi".to_string()));
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Hello!".to_string(),
+                item_id: None,
+            },
+        );
+        update(&mut app, Action::ResponseDone(None));
+
+        app.session.pins = vec![1, 2];
+        update(&mut app, Action::Regenerate);
+        assert_eq!(app.session.pins, vec![1]);
+
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Hey there.".to_string(),
+                item_id: None,
+            },
+        );
+        update(&mut app, Action::ResponseDone(None));
+        app.session.pins = vec![1, 2];
+        update(&mut app, Action::CycleVariant(true));
+        assert_eq!(app.session.pins, vec![1]);
+    }
+
+    #[test]
+    fn test_message_stats_keep_the_rates_they_were_priced_at() {
+        let mut app = test_app();
+        app.config.models = vec![ModelEntry {
+            name: app.model.name.clone(),
+            provider: app.model.provider.clone(),
+            input_price: Some(3.0),
+            output_price: Some(15.0),
+            ..Default::default()
+        }];
+        update(&mut app, Action::Submit("Hi".to_string()));
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Hello!".to_string(),
+                item_id: None,
+            },
+        );
+        update(
+            &mut app,
+            Action::ResponseDone(Some(UsageStats {
+                output_tokens: Some(10),
+                ..Default::default()
+            })),
+        );
+
+        // A later switch to an unpriced model doesn't reprice the answer
+        app.model.name = "unpriced-model".to_string();
+        assert!(app.pricing().is_none());
+        let usage = &app.session.message_stats[&2];
+        assert_eq!(usage.stats.output_tokens, Some(10));
+        assert_eq!(usage.pricing.map(|p| p.output_per_million), Some(15.0));
+    }
+
     #[test]
     fn test_agentic_rounds_reset_on_submit() {
         let mut app = test_app();
@@ -553,6 +1267,24 @@ mod tests {
         assert_eq!(app.session.agentic_rounds, 0);
     }
 
+    #[test]
+    fn test_truncated_response_shows_hint() {
+        let mut app = test_app();
+        app.session.is_loading = true;
+
+        let stats = UsageStats {
+            output_tokens: Some(16384),
+            finish_reason: Some("max_output_tokens".to_string()),
+            ..Default::default()
+        };
+        let effect = update(&mut app, Action::ResponseDone(Some(stats)));
+
+        assert!(!app.session.is_loading);
+        assert!(app.session.status_message.starts_with("Response truncated"));
+        assert!(app.session.status_message.contains("/continue"));
+        assert_eq!(effect, Effect::SaveSession);
+    }
+
     #[test]
     fn test_response_done_with_stats_updates_status() {
         let mut app = test_app();
@@ -575,6 +1307,40 @@ mod tests {
         assert_eq!(effect, Effect::SaveSession);
     }
 
+    #[test]
+    fn test_inline_usage_appends_status_not_sent_to_provider() {
+        let mut app = test_app();
+        app.config.inline_usage = true;
+        update(&mut app, Action::Submit("Hi".to_string()));
+        update(
+            &mut app,
+            Action::ResponseChunk {
+                text: "Hello".to_string(),
+                item_id: None,
+            },
+        );
+
+        let stats = UsageStats {
+            input_tokens: Some(150),
+            output_tokens: Some(42),
+            tokens_per_sec: Some(28.0),
+            generation_duration_ms: Some(1500),
+            ..Default::default()
+        };
+        update(&mut app, Action::ResponseDone(Some(stats)));
+
+        let items = &app.session.context.items;
+        assert!(matches!(
+            items.last(),
+            Some(ContextItem::Message(seg))
+                if seg.source == Source::Status && seg.content == "150 in / 42 out | 28.0 tok/s | 1.5s"
+        ));
+        let body =
+            crate::inference::providers::responses_request_json(items, "m", Effort::Auto).unwrap();
+        assert!(body.contains("Hello"));
+        assert!(!body.contains("tok/s"));
+    }
+
     #[test]
     fn test_new_session_sets_title() {
         let mut app = test_app();
@@ -676,9 +1442,143 @@ mod tests {
         assert_eq!(effect, Effect::SwitchProvider);
     }
 
-    fn make_session_data(model_name: &str, provider_name: &str) -> crate::core::session::SessionData {
-        use crate::core::session::{SessionData, SessionMeta};
+    #[test]
+    fn test_switch_model_applies_per_model_overrides() {
+        let mut app = test_app();
+        app.config.models = vec![ModelEntry {
+            name: "qwen".to_string(),
+            provider: "lmstudio".to_string(),
+            effort: Some(Effort::Low),
+            temperature: Some(0.2),
+            max_output_tokens: Some(2048),
+            ..Default::default()
+        }];
+
+        update(
+            &mut app,
+            Action::SwitchModel(ActiveModel::new("qwen", "lmstudio")),
+        );
+        assert_eq!(app.effort, Effort::Low);
+        assert_eq!(app.temperature, Some(0.2));
+        assert_eq!(app.max_output_tokens, 2048);
+
+        // Switching to an unlisted model falls back to [general]
+        update(
+            &mut app,
+            Action::SwitchModel(ActiveModel::new("gpt-4", "openrouter")),
+        );
+        assert_eq!(app.effort, app.config.effort);
+        assert_eq!(app.temperature, None);
+        assert_eq!(app.max_output_tokens, app.config.max_output_tokens);
+    }
+
+    #[test]
+    fn test_config_reloaded_applies_hot_settings_and_keeps_session() {
+        let mut app = test_app();
+        app.session.context.add_user_message("hello".to_string());
+        let mut new_config = app.config.clone();
+        new_config.effort = Effort::High;
+        new_config.system_prompt = "Reloaded prompt.".to_string();
+        new_config.max_agentic_rounds = 3;
+        new_config.provider = "lmstudio".to_string();
+        new_config.fetch_allowlist = vec!["docs.rs".to_string()];
+
+        let effect = update(&mut app, Action::ConfigReloaded(Box::new(new_config)));
+
+        // The provider is rebuilt for the new connection settings
+        assert_eq!(effect, Effect::SwitchProvider);
+        // The tools are rebuilt from the new config
+        assert!(app.tool_definitions().iter().any(|t| t.name == "fetch_url"));
+        assert_eq!(app.effort, Effort::High);
+        assert_eq!(app.max_agentic_rounds, 3);
+        assert_eq!(app.system_prompt, "Reloaded prompt.");
+        assert_eq!(app.session.context.items.len(), 2);
+        match &app.session.context.items[0] {
+            ContextItem::Message(seg) => assert_eq!(seg.content, "Reloaded prompt."),
+            other => panic!("Expected directive, got {:?}", other),
+        }
+        // Active provider is not switched by a reload
+        assert_ne!(app.config.provider, "lmstudio");
+        assert_eq!(app.session.status_message, "Config reloaded.");
+    }
+
+    #[test]
+    fn test_config_reloaded_keeps_cli_effort() {
+        let mut app = test_app();
+        app.effort = Effort::Low;
+        app.effort_override = Some(Effort::Low);
+        let mut new_config = app.config.clone();
+        new_config.effort = Effort::High;
+
+        update(&mut app, Action::ConfigReloaded(Box::new(new_config)));
+
+        assert_eq!(app.effort, Effort::Low);
+        assert_eq!(app.config.effort, Effort::Low);
+    }
+
+    #[test]
+    fn test_switch_model_keeps_cli_effort() {
+        let mut app = test_app();
+        app.effort = Effort::Low;
+        app.effort_override = Some(Effort::Low);
+        app.config.effort = Effort::High;
+
+        update(
+            &mut app,
+            Action::SwitchModel(ActiveModel::new("gpt-4", "openrouter")),
+        );
+
+        assert_eq!(app.effort, Effort::Low);
+    }
+
+    #[test]
+    fn test_config_reload_failed_keeps_previous_config() {
+        let mut app = test_app();
+        let effort_before = app.effort;
+        let prompt_before = app.system_prompt.clone();
+
+        update(
+            &mut app,
+            Action::ConfigReloadFailed("config parse error: bad".to_string()),
+        );
+
+        assert_eq!(app.effort, effort_before);
+        assert_eq!(app.system_prompt, prompt_before);
+        assert!(app.session.status_message.contains("config parse error"));
+    }
+
+    #[test]
+    fn test_staged_image_sent_with_next_message() {
+        let mut app = test_app();
+        let image = crate::inference::ImageAttachment {
+            data_base64: "AAAA".to_string(),
+            mime: "image/png".to_string(),
+            caption: None,
+        };
+
+        update(&mut app, Action::StageImage(image.clone()));
+        // Held back from the context until the message is sent
+        assert_eq!(app.session.context.items.len(), 1);
+
+        let effect = update(&mut app, Action::Submit("What is this?".to_string()));
+
+        assert_eq!(effect, Effect::SpawnRequest);
+        assert!(app.session.pending_image.is_none());
+        let items = &app.session.context.items;
+        assert_eq!(items.len(), 3); // System + Image + User
+        assert!(matches!(&items[1], ContextItem::Image(i) if *i == image));
+        assert!(matches!(&items[2], ContextItem::Message(seg) if seg.content == "What is this?"));
+    }
+
+    fn make_session_data(
+        model_name: &str,
+        provider_name: &str,
+    ) -> crate::core::session::SessionData {
+        use crate::core::session::{
+            SESSION_SCHEMA_VERSION, ScrollPosition, SessionData, SessionMeta,
+        };
         SessionData {
+            schema_version: SESSION_SCHEMA_VERSION,
             meta: SessionMeta {
                 id: "sess-1".to_string(),
                 title: "Test Session".to_string(),
@@ -687,10 +1587,17 @@ mod tests {
                 message_count: 1,
                 model_name: model_name.to_string(),
                 provider_name: provider_name.to_string(),
+                tags: Vec::new(),
+                scroll: ScrollPosition {
+                    offset: 12,
+                    stick_to_bottom: false,
+                },
+                pins: vec![0],
             },
             items: vec![ContextItem::Message(crate::inference::ContextSegment {
                 source: Source::User,
                 content: "hello".to_string(),
+                timestamp: None,
             })],
         }
     }
@@ -708,6 +1615,19 @@ mod tests {
         assert_eq!(effect, Effect::SwitchProvider);
     }
 
+    #[test]
+    fn test_load_session_restores_scroll_position() {
+        let mut app = test_app();
+        update(
+            &mut app,
+            Action::LoadSession(make_session_data("m", "openrouter")),
+        );
+        assert_eq!(app.session.scroll.offset, 12);
+        assert!(!app.session.scroll.stick_to_bottom);
+        // The saved first item sits after the directive
+        assert_eq!(app.session.pins, vec![1]);
+    }
+
     #[test]
     fn test_load_session_same_provider_returns_render() {
         let mut app = test_app();
@@ -747,6 +1667,39 @@ mod tests {
         assert_eq!(effect, Effect::Render);
     }
 
+    #[test]
+    fn test_set_effort() {
+        let mut app = test_app();
+        let effect = update(&mut app, Action::SetEffort(Effort::High));
+
+        assert_eq!(app.effort, Effort::High);
+        assert_eq!(app.session.status_message, "Reasoning: High");
+        assert_eq!(effect, Effect::Render);
+    }
+
+    #[test]
+    fn test_turn_directive_applies_to_next_turn_only() {
+        let mut app = test_app();
+        update(
+            &mut app,
+            Action::SetTurnDirective("Answer in French.".to_string()),
+        );
+        assert_eq!(app.session.turn_directive, None);
+
+        update(&mut app, Action::Submit("hello".to_string()));
+        assert_eq!(
+            app.session.turn_directive.as_deref(),
+            Some("Answer in French.")
+        );
+        assert!(app.session.next_turn_directive.is_none());
+        // Never stored in the context
+        assert_eq!(app.session.context.items.len(), 2);
+
+        app.session.is_loading = false;
+        update(&mut app, Action::Submit("again".to_string()));
+        assert_eq!(app.session.turn_directive, None);
+    }
+
     #[test]
     fn test_submit_resets_usage_stats() {
         let mut app = test_app();
@@ -806,4 +1759,40 @@ mod tests {
         // Status should show the summary
         assert!(app.session.status_message.contains("250 in"));
     }
+
+    #[test]
+    fn test_session_usage_accumulates_across_rounds_and_turns() {
+        let mut app = test_app();
+        let round = |input, output| UsageStats {
+            input_tokens: Some(input),
+            output_tokens: Some(output),
+            total_tokens: Some(input + output),
+            ..Default::default()
+        };
+
+        // Turn 1: tool round + text round
+        update(&mut app, Action::Submit("1+1?".to_string()));
+        update(
+            &mut app,
+            Action::ToolCallReceived(make_tool_call("add", "call_1")),
+        );
+        update(&mut app, Action::ResponseDone(Some(round(100, 20))));
+        update(
+            &mut app,
+            Action::ToolResultReady {
+                call_id: "call_1".to_string(),
+                output: "2".to_string(),
+            },
+        );
+        update(&mut app, Action::ResponseDone(Some(round(150, 30))));
+        assert_eq!(app.session.session_usage.total(), Some(300));
+
+        // Turn 2: the per-turn stats reset, the session total keeps growing
+        update(&mut app, Action::Submit("thanks".to_string()));
+        update(&mut app, Action::ResponseDone(Some(round(200, 10))));
+        assert_eq!(app.session.usage_stats.input_tokens, Some(200));
+        assert_eq!(app.session.session_usage.input_tokens, Some(450));
+        assert_eq!(app.session.session_usage.output_tokens, Some(60));
+        assert_eq!(app.session.session_usage.total(), Some(510));
+    }
 }
@@ -8,17 +8,19 @@
 
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::inference::Effort;
+use crate::inference::prompt_vars;
+use crate::inference::{Effort, Pricing, ReasoningMode};
 
 // ============================================================================
 // Config Structs (all fields Option<T> for sparse TOML)
 // ============================================================================
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct NaviConfig {
     #[serde(default)]
     pub general: GeneralConfig,
@@ -27,36 +29,163 @@ pub struct NaviConfig {
     #[serde(default)]
     pub lmstudio: LmStudioConfig,
     #[serde(default)]
+    pub azure: AzureConfig,
+    #[serde(default)]
     pub models: Vec<ModelEntry>,
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct GeneralConfig {
     pub default_provider: Option<String>,
     pub default_model: Option<String>,
     pub max_agentic_rounds: Option<u8>,
     pub max_output_tokens: Option<u32>,
     pub reasoning_effort: Option<Effort>,
+    pub temperature: Option<f32>,
     pub system_prompt: Option<String>,
     pub system_prompt_file: Option<String>,
+    pub fallback_providers: Option<Vec<FallbackEntry>>,
+    pub show_input_counter: Option<bool>,
+    pub reasoning_mode: Option<ReasoningMode>,
+    pub spinner: Option<String>,
+    pub workspace_root: Option<String>,
+    pub dedupe_stream: Option<bool>,
+    pub prompt_cache: Option<bool>,
+    pub max_parallel_tools: Option<usize>,
+    pub max_tool_output_bytes: Option<usize>,
+    pub max_tool_args_bytes: Option<usize>,
+    pub stream_channel_capacity: Option<usize>,
+    pub landing_message: Option<String>,
+    pub landing_tips: Option<Vec<String>>,
+    pub stream_log: Option<String>,
+    pub fetch_allowlist: Option<Vec<String>>,
+    pub inline_usage: Option<bool>,
+    pub summarize_context: Option<bool>,
+    pub sessions_dir: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct OpenRouterConfig {
     pub api_key: Option<String>,
     pub base_url: Option<String>,
+    /// Upstream providers OpenRouter should try first, in order.
+    pub provider_order: Option<Vec<String>>,
+    /// Whether OpenRouter may use providers outside `provider_order`.
+    pub allow_fallbacks: Option<bool>,
+    /// Turn reasoning off with `enabled = false` instead of `effort = "none"`.
+    pub disable_reasoning_flag: Option<bool>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct LmStudioConfig {
     pub base_url: Option<String>,
+    /// Turn reasoning off with `enabled = false` instead of `effort = "none"`.
+    pub disable_reasoning_flag: Option<bool>,
+}
+
+/// `[azure]` settings for an Azure OpenAI deployment.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AzureConfig {
+    /// Resource URL, e.g. `https://my-resource.openai.azure.com`.
+    pub endpoint: Option<String>,
+    /// Deployment name; requests go to its Responses path.
+    pub deployment: Option<String>,
+    pub api_version: Option<String>,
+    pub api_key: Option<String>,
+}
+
+/// `[http]` settings for the HTTP client the providers share. Unset values
+/// keep reqwest's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct HttpConfig {
+    /// Idle connections kept open per host for reuse.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Seconds a connection sits idle before TCP keep-alive probes start.
+    pub tcp_keepalive_secs: Option<u64>,
+    /// true speaks HTTP/2 without negotiating it, false forces HTTP/1.1,
+    /// and unset lets each connection negotiate.
+    pub http2: Option<bool>,
+}
+
+/// Raw `[theme]` color names; parsed into colors by the TUI layer.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ThemeConfig {
+    /// "light" or "dark": which built-in palette the colors below override.
+    pub background: Option<String>,
+    pub user: Option<String>,
+    pub model: Option<String>,
+    pub system: Option<String>,
+    pub thinking: Option<String>,
+    pub tool: Option<String>,
+    pub border: Option<String>,
+    pub code_theme: Option<String>,
+    pub link_style: Option<String>,
 }
 
+/// A named set of overrides, selected with `--profile` or `NAVI_PROFILE`.
+///
+/// `[general]` keys go directly under `[profiles.<name>]`; provider sections
+/// nest as `[profiles.<name>.openrouter]`, `[profiles.<name>.lmstudio]` and
+/// `[profiles.<name>.azure]`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ProfileConfig {
+    #[serde(flatten)]
+    pub general: GeneralConfig,
+    #[serde(default)]
+    pub openrouter: OpenRouterConfig,
+    #[serde(default)]
+    pub lmstudio: LmStudioConfig,
+    #[serde(default)]
+    pub azure: AzureConfig,
+}
+
+/// A `fallback_providers` entry: a provider to try when the primary fails,
+/// and the model to ask it for, since model names differ between providers.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FallbackEntry {
+    pub provider: String,
+    pub model: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct ModelEntry {
     pub name: String,
     pub provider: String,
     pub description: Option<String>,
+    // Per-model overrides for `[general]` request parameters
+    pub effort: Option<Effort>,
+    pub temperature: Option<f32>,
+    pub max_output_tokens: Option<u32>,
+    /// False for models that reject a reasoning block; unset means supported
+    pub supports_reasoning: Option<bool>,
+    // Dollars per million tokens, used for cost estimates
+    pub input_price: Option<f64>,
+    pub output_price: Option<f64>,
+    pub cache_read_price: Option<f64>,
+    /// Context window in tokens, for the title bar usage gauge
+    pub context_window: Option<u32>,
+}
+
+impl ModelEntry {
+    /// True if this entry names the given model (overrides are ignored).
+    pub fn same_model(&self, name: &str, provider: &str) -> bool {
+        self.name == name && self.provider == provider
+    }
+
+    /// Token rates for this model, if both input and output prices are set.
+    pub fn pricing(&self) -> Option<Pricing> {
+        Some(Pricing {
+            input_per_million: self.input_price?,
+            output_per_million: self.output_price?,
+            cache_read_per_million: self.cache_read_price,
+        })
+    }
 }
 
 // ============================================================================
@@ -65,8 +194,13 @@ pub struct ModelEntry {
 
 pub const DEFAULT_MAX_AGENTIC_ROUNDS: u8 = 20;
 pub const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 16384;
+pub const DEFAULT_MAX_PARALLEL_TOOLS: usize = 4;
+pub const DEFAULT_MAX_TOOL_OUTPUT_BYTES: usize = 64 * 1024;
+pub const DEFAULT_MAX_TOOL_ARGS_BYTES: usize = 1024 * 1024;
+pub const DEFAULT_STREAM_CHANNEL_CAPACITY: usize = 100;
 pub const DEFAULT_OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
 pub const DEFAULT_LMSTUDIO_BASE_URL: &str = "http://localhost:1234/v1";
+pub const DEFAULT_AZURE_API_VERSION: &str = "2025-04-01-preview";
 
 pub const DEFAULT_SYSTEM_PROMPT: &str = "You are a helpful assistant.\n\n\
     TOOL USE: When a registered tool can fulfill a request - whether computation, file access, \
@@ -87,11 +221,110 @@ pub struct ResolvedConfig {
     pub max_agentic_rounds: u8,
     pub max_output_tokens: u32,
     pub effort: Effort,
+    pub temperature: Option<f32>,
     pub system_prompt: String,
     pub openrouter_api_key: Option<String>,
     pub openrouter_base_url: String,
+    /// Upstream provider routing sent with OpenRouter requests; empty lets
+    /// OpenRouter choose.
+    pub openrouter_provider_order: Vec<String>,
+    pub openrouter_allow_fallbacks: Option<bool>,
+    /// Whether `Effort::None` is sent as `reasoning.enabled = false`, for
+    /// backends that reject `effort = "none"`.
+    pub openrouter_disable_reasoning_flag: bool,
     pub lmstudio_base_url: String,
+    pub lmstudio_disable_reasoning_flag: bool,
+    /// Azure OpenAI resource URL; the provider needs it, the deployment and
+    /// the key.
+    pub azure_endpoint: Option<String>,
+    pub azure_deployment: Option<String>,
+    pub azure_api_version: String,
+    pub azure_api_key: Option<String>,
     pub models: Vec<ModelEntry>,
+    pub profile: Option<String>,
+    pub theme: ThemeConfig,
+    /// Connection settings for the providers' shared HTTP client.
+    pub http: HttpConfig,
+    /// Providers tried in order, each with its own model, when the primary
+    /// fails before streaming.
+    pub fallback_providers: Vec<FallbackEntry>,
+    /// Show the line/column and character counter in the input border.
+    pub show_input_counter: bool,
+    pub reasoning_mode: ReasoningMode,
+    /// Raw loading spinner style name; parsed by the TUI layer.
+    pub spinner: Option<String>,
+    /// Directory the file tools are confined to.
+    pub workspace_root: PathBuf,
+    /// Drop stream deltas that exactly repeat the previous one.
+    pub dedupe_stream: bool,
+    /// Mark the already-sent prefix with an explicit cache breakpoint.
+    pub prompt_cache: bool,
+    /// Tool calls allowed to execute at the same time.
+    pub max_parallel_tools: usize,
+    /// Tool output longer than this is cut before it is sent to the model.
+    pub max_tool_output_bytes: usize,
+    /// A streamed tool call whose arguments grow past this is abandoned.
+    pub max_tool_args_bytes: usize,
+    /// Stream chunks a provider can queue before it waits for the UI.
+    pub stream_channel_capacity: usize,
+    /// Greeting on the landing page; None keeps the built-in one.
+    pub landing_message: Option<String>,
+    /// Tips the landing page picks from; None uses the built-in list.
+    pub landing_tips: Option<Vec<String>>,
+    /// File the providers append every raw SSE line to, for debugging.
+    pub stream_log: Option<PathBuf>,
+    /// Host patterns the fetch_url tool may download from; empty disables it.
+    pub fetch_allowlist: Vec<String>,
+    /// Also add each turn's usage summary to the conversation as a status line.
+    pub inline_usage: bool,
+    /// Condense older turns into a summary when the context nears the
+    /// model's window.
+    pub summarize_context: bool,
+    /// Where sessions are saved; None keeps `~/.navi/sessions/`.
+    pub sessions_dir: Option<PathBuf>,
+}
+
+/// Request parameters that a `[[models]]` entry may override.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelParams {
+    pub effort: Effort,
+    pub temperature: Option<f32>,
+    pub max_output_tokens: u32,
+    pub supports_reasoning: bool,
+}
+
+/// Resolve the request parameters for `model`: its own overrides first,
+/// falling back to the `[general]` values in `general`.
+pub fn effective_params_for(model: &ModelEntry, general: &ResolvedConfig) -> ModelParams {
+    ModelParams {
+        effort: model.effort.unwrap_or(general.effort),
+        temperature: model.temperature.or(general.temperature),
+        max_output_tokens: model.max_output_tokens.unwrap_or(general.max_output_tokens),
+        supports_reasoning: model.supports_reasoning.unwrap_or(true),
+    }
+}
+
+/// Token rates for the model identified by `name`/`provider`, or None if it
+/// has no priced `[[models]]` entry.
+pub fn pricing_for_model(config: &ResolvedConfig, name: &str, provider: &str) -> Option<Pricing> {
+    config
+        .models
+        .iter()
+        .find(|m| m.same_model(name, provider))
+        .and_then(ModelEntry::pricing)
+}
+
+/// Resolve parameters for the model identified by `name`/`provider`. Models
+/// not listed in `[[models]]` (e.g. fetched from a provider API) get the
+/// `[general]` values.
+pub fn params_for_model(config: &ResolvedConfig, name: &str, provider: &str) -> ModelParams {
+    let entry = config
+        .models
+        .iter()
+        .find(|m| m.same_model(name, provider))
+        .cloned()
+        .unwrap_or_default();
+    effective_params_for(&entry, config)
 }
 
 // ============================================================================
@@ -115,10 +348,307 @@ impl fmt::Display for ConfigError {
 
 impl std::error::Error for ConfigError {}
 
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Provider names `build_provider` understands. Any other name silently
+/// falls back to OpenRouter, so it is reported by `validate`.
+pub const KNOWN_PROVIDERS: &[&str] = &["openrouter", "lmstudio", "azure"];
+
+/// A value that parses but won't behave as intended. Warnings never stop
+/// Navi from starting; they are shown in the status bar instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigWarning {
+    /// Where the value lives, e.g. `general.default_provider`
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigWarning {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Check a parsed config for unknown provider names, models that point at a
+/// provider that doesn't exist, and out-of-range numbers. Every problem is
+/// collected rather than stopping at the first.
+pub fn validate(config: &NaviConfig) -> Vec<ConfigWarning> {
+    let mut warnings = Vec::new();
+    validate_general("general", &config.general, &mut warnings);
+
+    let mut profile_names: Vec<&String> = config.profiles.keys().collect();
+    profile_names.sort();
+    for name in profile_names {
+        let section = format!("profiles.{name}");
+        validate_general(&section, &config.profiles[name].general, &mut warnings);
+    }
+
+    for model in &config.models {
+        let field = |key: &str| format!("models.{}.{key}", model.name);
+        if !is_known_provider(&model.provider) {
+            warnings.push(ConfigWarning::new(
+                field("provider"),
+                unknown_provider_message(&model.provider),
+            ));
+        }
+        check_temperature(field("temperature"), model.temperature, &mut warnings);
+        if model.max_output_tokens == Some(0) {
+            warnings.push(ConfigWarning::new(
+                field("max_output_tokens"),
+                "must be at least 1",
+            ));
+        }
+        if model.context_window == Some(0) {
+            warnings.push(ConfigWarning::new(
+                field("context_window"),
+                "must be at least 1",
+            ));
+        }
+        let prices = [
+            ("input_price", model.input_price),
+            ("output_price", model.output_price),
+            ("cache_read_price", model.cache_read_price),
+        ];
+        for (key, price) in prices {
+            if price.is_some_and(|p| p < 0.0) {
+                warnings.push(ConfigWarning::new(field(key), "must not be negative"));
+            }
+        }
+    }
+
+    // Loading already expanded every set variable; what's left wasn't set
+    let mut loaded = config.clone();
+    for (field, value) in env_fields(&mut loaded) {
+        let mut rest = value.as_deref().unwrap_or_default();
+        while let Some((_, name, after)) = next_env_var(rest) {
+            warnings.push(ConfigWarning::new(
+                field.clone(),
+                format!("environment variable ${{{name}}} is not set"),
+            ));
+            rest = after;
+        }
+    }
+    warnings
+}
+
+/// One status-bar line summarizing `warnings`, or None if there are none.
+pub fn warnings_status(warnings: &[ConfigWarning]) -> Option<String> {
+    if warnings.is_empty() {
+        return None;
+    }
+    let list: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    let noun = if warnings.len() == 1 {
+        "warning"
+    } else {
+        "warnings"
+    };
+    Some(format!("Config {noun}: {}", list.join("; ")))
+}
+
+/// Checks shared by `[general]` and each `[profiles.<name>]`.
+fn validate_general(section: &str, general: &GeneralConfig, warnings: &mut Vec<ConfigWarning>) {
+    let field = |key: &str| format!("{section}.{key}");
+    if let Some(provider) = &general.default_provider
+        && !is_known_provider(provider)
+    {
+        warnings.push(ConfigWarning::new(
+            field("default_provider"),
+            unknown_provider_message(provider),
+        ));
+    }
+    for fallback in general.fallback_providers.iter().flatten() {
+        if !is_known_provider(&fallback.provider) {
+            warnings.push(ConfigWarning::new(
+                field("fallback_providers"),
+                unknown_provider_message(&fallback.provider),
+            ));
+        }
+    }
+    if general.max_agentic_rounds == Some(0) {
+        warnings.push(ConfigWarning::new(
+            field("max_agentic_rounds"),
+            "must be at least 1",
+        ));
+    }
+    if general.max_output_tokens == Some(0) {
+        warnings.push(ConfigWarning::new(
+            field("max_output_tokens"),
+            "must be at least 1",
+        ));
+    }
+    if general.max_parallel_tools == Some(0) {
+        warnings.push(ConfigWarning::new(
+            field("max_parallel_tools"),
+            "must be at least 1",
+        ));
+    }
+    if general.max_tool_output_bytes == Some(0) {
+        warnings.push(ConfigWarning::new(
+            field("max_tool_output_bytes"),
+            "must be at least 1",
+        ));
+    }
+    if general.max_tool_args_bytes == Some(0) {
+        warnings.push(ConfigWarning::new(
+            field("max_tool_args_bytes"),
+            "must be at least 1",
+        ));
+    }
+    if general.stream_channel_capacity == Some(0) {
+        warnings.push(ConfigWarning::new(
+            field("stream_channel_capacity"),
+            "must be at least 1",
+        ));
+    }
+    check_temperature(field("temperature"), general.temperature, warnings);
+    let prompt = general.system_prompt.as_deref().unwrap_or_default();
+    for name in prompt_vars::unknown_placeholders(prompt) {
+        warnings.push(ConfigWarning::new(
+            field("system_prompt"),
+            format!(
+                "unknown placeholder {{{{{name}}}}} (expected one of: {})",
+                prompt_vars::PLACEHOLDERS.join(", ")
+            ),
+        ));
+    }
+}
+
+fn is_known_provider(name: &str) -> bool {
+    KNOWN_PROVIDERS.contains(&name)
+}
+
+fn unknown_provider_message(name: &str) -> String {
+    format!(
+        "unknown provider \"{name}\" (expected one of: {})",
+        KNOWN_PROVIDERS.join(", ")
+    )
+}
+
+fn check_temperature(field: String, temperature: Option<f32>, warnings: &mut Vec<ConfigWarning>) {
+    if temperature.is_some_and(|t| !(0.0..=2.0).contains(&t)) {
+        warnings.push(ConfigWarning::new(field, "must be between 0.0 and 2.0"));
+    }
+}
+
 // ============================================================================
 // Loading
 // ============================================================================
 
+/// Expand `${VAR}` in the settings that tend to hold secrets or per-machine
+/// values, so an API key can stay out of the file. A variable that isn't set
+/// is left as written; `validate` reports it.
+fn expand_env_vars(config: &mut NaviConfig, env: &dyn Fn(&str) -> Option<String>) {
+    for (field, value) in env_fields(config) {
+        if let Some(value) = value {
+            *value = expand_env(&field, value, env);
+        }
+    }
+}
+
+/// The settings `${VAR}` expansion applies to, with their field paths, in
+/// the order `validate` reports them.
+fn env_fields(config: &mut NaviConfig) -> Vec<(String, &mut Option<String>)> {
+    let mut fields = section_env_fields(
+        "general",
+        "",
+        &mut config.general,
+        &mut config.openrouter,
+        &mut config.lmstudio,
+        &mut config.azure,
+    );
+    let mut profiles: Vec<_> = config.profiles.iter_mut().collect();
+    profiles.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, profile) in profiles {
+        let section = format!("profiles.{name}");
+        fields.extend(section_env_fields(
+            &section,
+            &format!("{section}."),
+            &mut profile.general,
+            &mut profile.openrouter,
+            &mut profile.lmstudio,
+            &mut profile.azure,
+        ));
+    }
+    fields
+}
+
+/// `[general]` keys live under `general_section`; provider sections are
+/// prefixed with `provider_prefix`, matching the paths `validate` uses.
+fn section_env_fields<'a>(
+    general_section: &str,
+    provider_prefix: &str,
+    general: &'a mut GeneralConfig,
+    openrouter: &'a mut OpenRouterConfig,
+    lmstudio: &'a mut LmStudioConfig,
+    azure: &'a mut AzureConfig,
+) -> Vec<(String, &'a mut Option<String>)> {
+    vec![
+        (
+            format!("{general_section}.system_prompt"),
+            &mut general.system_prompt,
+        ),
+        (
+            format!("{provider_prefix}openrouter.api_key"),
+            &mut openrouter.api_key,
+        ),
+        (
+            format!("{provider_prefix}openrouter.base_url"),
+            &mut openrouter.base_url,
+        ),
+        (
+            format!("{provider_prefix}lmstudio.base_url"),
+            &mut lmstudio.base_url,
+        ),
+        (
+            format!("{provider_prefix}azure.endpoint"),
+            &mut azure.endpoint,
+        ),
+        (
+            format!("{provider_prefix}azure.api_key"),
+            &mut azure.api_key,
+        ),
+    ]
+}
+
+/// `value` with each `${NAME}` replaced by the variable's value.
+fn expand_env(field: &str, value: &str, env: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some((before, name, after)) = next_env_var(rest) {
+        out.push_str(before);
+        match env(name) {
+            Some(v) => out.push_str(&v),
+            None => {
+                warn!("{field}: environment variable ${{{name}}} is not set, left as is");
+                out.push_str(&rest[before.len()..rest.len() - after.len()]);
+            }
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Split `text` around its first `${NAME}`: the text before it, the name,
+/// and the text after it.
+fn next_env_var(text: &str) -> Option<(&str, &str, &str)> {
+    let start = text.find("${")?;
+    let len = text[start + 2..].find('}')?;
+    let name = &text[start + 2..start + 2 + len];
+    Some((&text[..start], name, &text[start + 2 + len + 1..]))
+}
+
 /// Returns the path to `~/.navi/config.toml`.
 pub fn config_path() -> Option<PathBuf> {
     dirs::home_dir().map(|h| h.join(".navi").join("config.toml"))
@@ -147,44 +677,113 @@ pub fn load_config() -> Result<NaviConfig, ConfigError> {
         return Ok(NaviConfig::default());
     }
 
-    let contents = fs::read_to_string(&path).map_err(ConfigError::Io)?;
-    let config: NaviConfig = toml::from_str(&contents).map_err(ConfigError::Parse)?;
+    let mut config = parse_config_file(&path)?;
     info!("Loaded config from {}", path.display());
+    // Logged before expansion, so secrets given as ${VAR} stay out of the log
     debug!("Config: {:?}", config);
+    expand_env_vars(&mut config, &|name| std::env::var(name).ok());
     Ok(config)
 }
 
+/// Read and parse an existing config file.
+pub fn parse_config_file(path: &Path) -> Result<NaviConfig, ConfigError> {
+    let contents = fs::read_to_string(path).map_err(ConfigError::Io)?;
+    toml::from_str(&contents).map_err(ConfigError::Parse)
+}
+
 /// Generates a commented-out default config file at the given path.
 fn generate_default_config(path: &PathBuf) {
     let default_content = r#"# Navi Configuration
 # All settings are optional — defaults are used for anything not specified.
 # Override hierarchy: defaults → this file → env vars → CLI flags.
+# api_key, base_url, endpoint and system_prompt may use ${VAR} to read an
+# environment variable, e.g. api_key = "${MY_OPENROUTER_KEY}".
 
 # [general]
-# default_provider = "openrouter"    # "openrouter" or "lmstudio"
+# default_provider = "openrouter"    # "openrouter", "lmstudio" or "azure"
 # default_model = "anthropic/claude-sonnet-4"
 # max_agentic_rounds = 20
 # max_output_tokens = 16384
 # reasoning_effort = "auto"          # "high", "medium", "low", "auto", "none"
+# temperature = 0.7                  # Omit to use the provider's default
 # system_prompt = "You are a helpful assistant."
 # system_prompt_file = "system.md"   # Path relative to ~/.navi/
+# fallback_providers = [{ provider = "lmstudio", model = "qwen3-8b" }]  # Tried in order if the provider fails before responding
+# reasoning_mode = "summary"        # "summary" or "full" reasoning shown as thinking
+# show_input_counter = true          # Line/column and character count in the input border
+# spinner = "logo"                  # "logo", "dots", "braille" or "line"
+# workspace_root = "~/code/project"  # read_file is confined here (default: launch directory)
+# dedupe_stream = false              # Drop deltas a proxy resends on reconnect (may hide real repeats)
+# prompt_cache = false               # Send cache_control breakpoints (Anthropic models via OpenRouter)
+# max_parallel_tools = 4             # Tool calls executed at the same time
+# max_tool_output_bytes = 65536      # Longer tool output is cut before the model sees it
+# max_tool_args_bytes = 1048576      # A tool call streaming longer arguments is abandoned
+# stream_channel_capacity = 100      # Chunks a provider can queue before waiting for the UI
+# landing_message = "Hey! Listen!"   # Greeting under the logo on an empty conversation
+# landing_tips = ["Ctrl+O opens saved sessions"]  # One is picked per launch; [] hides tips
+# stream_log = "/tmp/navi-stream.log"  # Append every raw SSE line here (debugging)
+# fetch_allowlist = ["docs.rs", "*.rust-lang.org"]  # Hosts fetch_url may read (default: none, tool off)
+# inline_usage = false               # Keep each turn's token/speed/cost summary in the conversation
+# summarize_context = true           # Summarize older turns near the context_window of [[models]]
+# sessions_dir = "~/Sync/navi"       # Save sessions here instead of ~/.navi/sessions
 
 # [openrouter]
 # api_key = "sk-or-..."              # Or set OPENROUTER_API_KEY env var
 # base_url = "https://openrouter.ai/api/v1"
+# provider_order = ["anthropic", "together"]  # Upstream providers to try first
+# allow_fallbacks = false            # Only use providers in provider_order
+# disable_reasoning_flag = false     # Send reasoning off as enabled = false, not effort = "none"
 
 # [lmstudio]
 # base_url = "http://localhost:1234/v1"
+# disable_reasoning_flag = false
+
+# [azure]
+# endpoint = "https://my-resource.openai.azure.com"  # Or set AZURE_OPENAI_ENDPOINT env var
+# deployment = "gpt-4o"              # Deployment name; it also picks the model
+# api_version = "2025-04-01-preview"
+# api_key = "..."                    # Or set AZURE_OPENAI_API_KEY env var
+
+# [http]
+# pool_max_idle_per_host = 8         # Idle connections kept open per host (default: unlimited)
+# tcp_keepalive_secs = 60            # Idle seconds before TCP keep-alive probes (default: 15)
+# http2 = true                       # true: HTTP/2 only, false: HTTP/1.1 only (default: negotiate)
+
+# [theme]
+# background = "dark"                # "light" or "dark" (default: detect from COLORFGBG)
+# user = "green"                     # Named, indexed (42), or hex (#00ff00)
+# model = "blue"
+# system = "yellow"
+# thinking = "dark gray"
+# tool = "yellow"
+# border = "dark gray"
+# code_theme = "base16-ocean.dark"   # Any syntect default theme
+# link_style = "inline"              # "inline" (url), "hidden", or "footnote" (numbered list)
+
+# Profiles override the sections above; select with --profile or NAVI_PROFILE.
+# [profiles.work]
+# default_model = "anthropic/claude-sonnet-4"
+# [profiles.work.openrouter]
+# api_key = "sk-or-..."
 
 # [[models]]
 # name = "anthropic/claude-sonnet-4"
 # provider = "openrouter"
 # description = "Fast, balanced reasoning"
+# input_price = 3.0                  # $ per 1M input tokens, shown as a cost estimate
+# output_price = 15.0                # $ per 1M output tokens
+# cache_read_price = 0.3             # $ per 1M cached input tokens (default: input_price)
+# context_window = 200000            # Tokens; shows a context usage gauge in the title bar
 
 # [[models]]
 # name = "qwen2.5-coder-32b"
 # provider = "lmstudio"
 # description = "Local coding model"
+# Optional per-model overrides (fall back to [general]):
+# effort = "low"
+# temperature = 0.2
+# max_output_tokens = 8192
+# supports_reasoning = false         # Omit the reasoning block; effort is ignored
 "#;
 
     if let Some(parent) = path.parent()
@@ -202,20 +801,44 @@ fn generate_default_config(path: &PathBuf) {
 // Resolution
 // ============================================================================
 
-/// Resolve the final config by collapsing: defaults → config file → env vars → CLI.
+/// Resolve the final config by collapsing:
+/// defaults → config file → profile → env vars → CLI.
 ///
-/// `cli_provider` and `cli_model` are from CLI flags (None = not specified).
-pub fn resolve(config: &NaviConfig, cli_provider: Option<&str>) -> ResolvedConfig {
+/// `cli_provider` and `cli_profile` are from CLI flags (None = not specified).
+pub fn resolve(
+    config: &NaviConfig,
+    cli_provider: Option<&str>,
+    cli_profile: Option<&str>,
+) -> ResolvedConfig {
+    resolve_with_env(config, cli_provider, cli_profile, &|key| {
+        std::env::var(key).ok()
+    })
+}
+
+/// `resolve` with an injectable environment lookup, so tests don't have to
+/// mutate the process environment.
+fn resolve_with_env(
+    config: &NaviConfig,
+    cli_provider: Option<&str>,
+    cli_profile: Option<&str>,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> ResolvedConfig {
+    // Profile: CLI → env
+    let profile = cli_profile
+        .map(|s| s.to_string())
+        .or_else(|| env("NAVI_PROFILE"));
+    let layered = apply_profile(config, profile.as_deref());
+    let config = &layered;
+
     // Provider: CLI → env → config → default
     let provider = cli_provider
         .map(|s| s.to_string())
-        .or_else(|| std::env::var("NAVI_PROVIDER").ok())
+        .or_else(|| env("NAVI_PROVIDER"))
         .or_else(|| config.general.default_provider.clone())
         .unwrap_or_else(|| "openrouter".to_string());
 
     // Model: env → config → default
-    let model_name = std::env::var("PRIMARY_MODEL_NAME")
-        .ok()
+    let model_name = env("PRIMARY_MODEL_NAME")
         .or_else(|| config.general.default_model.clone())
         .unwrap_or_else(|| "anthropic/claude-sonnet-4".to_string());
 
@@ -223,22 +846,33 @@ pub fn resolve(config: &NaviConfig, cli_provider: Option<&str>) -> ResolvedConfi
     let system_prompt = resolve_system_prompt(config);
 
     // OpenRouter API key: env → config
-    let openrouter_api_key = std::env::var("OPENROUTER_API_KEY")
-        .ok()
-        .or_else(|| config.openrouter.api_key.clone());
+    let openrouter_api_key =
+        env("OPENROUTER_API_KEY").or_else(|| config.openrouter.api_key.clone());
 
     // OpenRouter base URL: env → config → default
-    let openrouter_base_url = std::env::var("OPENROUTER_BASE_URL")
-        .ok()
+    let openrouter_base_url = env("OPENROUTER_BASE_URL")
         .or_else(|| config.openrouter.base_url.clone())
         .unwrap_or_else(|| DEFAULT_OPENROUTER_BASE_URL.to_string());
 
     // LM Studio base URL: env → config → default
-    let lmstudio_base_url = std::env::var("LM_STUDIO_BASE_URL")
-        .ok()
+    let lmstudio_base_url = env("LM_STUDIO_BASE_URL")
         .or_else(|| config.lmstudio.base_url.clone())
         .unwrap_or_else(|| DEFAULT_LMSTUDIO_BASE_URL.to_string());
 
+    // Azure OpenAI endpoint and key: env → config
+    let azure_endpoint = env("AZURE_OPENAI_ENDPOINT").or_else(|| config.azure.endpoint.clone());
+    let azure_api_key = env("AZURE_OPENAI_API_KEY").or_else(|| config.azure.api_key.clone());
+
+    // Stream transcript: env → config
+    let stream_log = env("NAVI_STREAM_LOG")
+        .or_else(|| config.general.stream_log.clone())
+        .map(PathBuf::from);
+
+    // Sessions directory: env → config → ~/.navi/sessions
+    let sessions_dir = env("NAVI_SESSIONS_DIR")
+        .or_else(|| config.general.sessions_dir.clone())
+        .map(|dir| expand_home(&dir));
+
     ResolvedConfig {
         provider,
         model_name,
@@ -251,15 +885,168 @@ pub fn resolve(config: &NaviConfig, cli_provider: Option<&str>) -> ResolvedConfi
             .max_output_tokens
             .unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS),
         effort: config.general.reasoning_effort.unwrap_or_default(),
+        temperature: config.general.temperature,
         system_prompt,
         openrouter_api_key,
         openrouter_base_url,
+        openrouter_provider_order: config.openrouter.provider_order.clone().unwrap_or_default(),
+        openrouter_allow_fallbacks: config.openrouter.allow_fallbacks,
+        openrouter_disable_reasoning_flag: config
+            .openrouter
+            .disable_reasoning_flag
+            .unwrap_or(false),
         lmstudio_base_url,
+        lmstudio_disable_reasoning_flag: config.lmstudio.disable_reasoning_flag.unwrap_or(false),
+        azure_endpoint,
+        azure_deployment: config.azure.deployment.clone(),
+        azure_api_version: config
+            .azure
+            .api_version
+            .clone()
+            .unwrap_or_else(|| DEFAULT_AZURE_API_VERSION.to_string()),
+        azure_api_key,
         models: config.models.clone(),
+        profile,
+        theme: config.theme.clone(),
+        http: config.http.clone(),
+        fallback_providers: config
+            .general
+            .fallback_providers
+            .clone()
+            .unwrap_or_default(),
+        show_input_counter: config.general.show_input_counter.unwrap_or(true),
+        reasoning_mode: config.general.reasoning_mode.unwrap_or_default(),
+        spinner: config.general.spinner.clone(),
+        workspace_root: resolve_workspace_root(config),
+        dedupe_stream: config.general.dedupe_stream.unwrap_or(false),
+        prompt_cache: config.general.prompt_cache.unwrap_or(false),
+        max_parallel_tools: config
+            .general
+            .max_parallel_tools
+            .unwrap_or(DEFAULT_MAX_PARALLEL_TOOLS),
+        max_tool_output_bytes: config
+            .general
+            .max_tool_output_bytes
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_MAX_TOOL_OUTPUT_BYTES),
+        max_tool_args_bytes: config
+            .general
+            .max_tool_args_bytes
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_MAX_TOOL_ARGS_BYTES),
+        stream_channel_capacity: config
+            .general
+            .stream_channel_capacity
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_STREAM_CHANNEL_CAPACITY),
+        landing_message: config.general.landing_message.clone(),
+        landing_tips: config.general.landing_tips.clone(),
+        stream_log,
+        fetch_allowlist: config.general.fetch_allowlist.clone().unwrap_or_default(),
+        inline_usage: config.general.inline_usage.unwrap_or(false),
+        summarize_context: config.general.summarize_context.unwrap_or(true),
+        sessions_dir,
+    }
+}
+
+/// Layer the named profile's sections over the base config. An unknown
+/// profile name is logged and ignored.
+fn apply_profile(config: &NaviConfig, name: Option<&str>) -> NaviConfig {
+    let mut layered = config.clone();
+    let Some(name) = name else {
+        return layered;
+    };
+    let Some(profile) = config.profiles.get(name) else {
+        warn!("Unknown profile '{}', using base config", name);
+        return layered;
+    };
+    info!("Applying profile '{}'", name);
+
+    let g = &mut layered.general;
+    let p = &profile.general;
+    overlay(&mut g.default_provider, &p.default_provider);
+    overlay(&mut g.default_model, &p.default_model);
+    overlay(&mut g.max_agentic_rounds, &p.max_agentic_rounds);
+    overlay(&mut g.max_output_tokens, &p.max_output_tokens);
+    overlay(&mut g.reasoning_effort, &p.reasoning_effort);
+    overlay(&mut g.temperature, &p.temperature);
+    overlay(&mut g.system_prompt, &p.system_prompt);
+    overlay(&mut g.system_prompt_file, &p.system_prompt_file);
+    overlay(&mut g.fallback_providers, &p.fallback_providers);
+    overlay(&mut g.show_input_counter, &p.show_input_counter);
+    overlay(&mut g.reasoning_mode, &p.reasoning_mode);
+    overlay(&mut g.spinner, &p.spinner);
+    overlay(&mut g.workspace_root, &p.workspace_root);
+    overlay(&mut g.dedupe_stream, &p.dedupe_stream);
+    overlay(&mut g.prompt_cache, &p.prompt_cache);
+    overlay(&mut g.max_parallel_tools, &p.max_parallel_tools);
+    overlay(&mut g.max_tool_output_bytes, &p.max_tool_output_bytes);
+    overlay(&mut g.max_tool_args_bytes, &p.max_tool_args_bytes);
+    overlay(&mut g.stream_channel_capacity, &p.stream_channel_capacity);
+    overlay(&mut g.landing_message, &p.landing_message);
+    overlay(&mut g.landing_tips, &p.landing_tips);
+    overlay(&mut g.stream_log, &p.stream_log);
+    overlay(&mut g.fetch_allowlist, &p.fetch_allowlist);
+    overlay(&mut g.inline_usage, &p.inline_usage);
+    overlay(&mut g.summarize_context, &p.summarize_context);
+    overlay(&mut g.sessions_dir, &p.sessions_dir);
+    overlay(&mut layered.openrouter.api_key, &profile.openrouter.api_key);
+    overlay(
+        &mut layered.openrouter.base_url,
+        &profile.openrouter.base_url,
+    );
+    overlay(
+        &mut layered.openrouter.provider_order,
+        &profile.openrouter.provider_order,
+    );
+    overlay(
+        &mut layered.openrouter.allow_fallbacks,
+        &profile.openrouter.allow_fallbacks,
+    );
+    overlay(
+        &mut layered.openrouter.disable_reasoning_flag,
+        &profile.openrouter.disable_reasoning_flag,
+    );
+    overlay(&mut layered.lmstudio.base_url, &profile.lmstudio.base_url);
+    overlay(
+        &mut layered.lmstudio.disable_reasoning_flag,
+        &profile.lmstudio.disable_reasoning_flag,
+    );
+    overlay(&mut layered.azure.endpoint, &profile.azure.endpoint);
+    overlay(&mut layered.azure.deployment, &profile.azure.deployment);
+    overlay(&mut layered.azure.api_version, &profile.azure.api_version);
+    overlay(&mut layered.azure.api_key, &profile.azure.api_key);
+    layered
+}
+
+/// Replace `base` with `over` when `over` is set.
+fn overlay<T: Clone>(base: &mut Option<T>, over: &Option<T>) {
+    if over.is_some() {
+        *base = over.clone();
+    }
+}
+
+/// Configured workspace root with a leading `~` expanded, else the
+/// directory Navi was launched from.
+fn resolve_workspace_root(config: &NaviConfig) -> PathBuf {
+    match config.general.workspace_root.as_deref() {
+        Some(root) => expand_home(root),
+        None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+    }
+}
+
+/// `path` with a leading `~/` replaced by the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
     }
 }
 
 /// Resolves the system prompt: inline wins over file, both win over default.
+///
+/// Placeholders such as `{{date}}` are kept here and expanded whenever a
+/// session's context is created, so each new conversation sees fresh values.
 fn resolve_system_prompt(config: &NaviConfig) -> String {
     // Inline system_prompt takes priority
     if let Some(ref prompt) = config.general.system_prompt {
@@ -307,7 +1094,7 @@ mod tests {
     #[test]
     fn test_resolve_uses_defaults_when_empty() {
         let config = NaviConfig::default();
-        let resolved = resolve(&config, None);
+        let resolved = resolve(&config, None, None);
         assert_eq!(resolved.max_agentic_rounds, DEFAULT_MAX_AGENTIC_ROUNDS);
         assert_eq!(resolved.max_output_tokens, DEFAULT_MAX_OUTPUT_TOKENS);
         assert_eq!(resolved.effort, Effort::default());
@@ -316,6 +1103,28 @@ mod tests {
                 .system_prompt
                 .starts_with("You are a helpful assistant")
         );
+        assert!(resolved.show_input_counter);
+        assert!(!resolved.dedupe_stream);
+        assert!(!resolved.prompt_cache);
+        assert_eq!(resolved.max_parallel_tools, DEFAULT_MAX_PARALLEL_TOOLS);
+        assert_eq!(
+            resolved.max_tool_output_bytes,
+            DEFAULT_MAX_TOOL_OUTPUT_BYTES
+        );
+        assert_eq!(resolved.max_tool_args_bytes, DEFAULT_MAX_TOOL_ARGS_BYTES);
+        assert_eq!(
+            resolved.stream_channel_capacity,
+            DEFAULT_STREAM_CHANNEL_CAPACITY
+        );
+        assert!(resolved.landing_message.is_none());
+        assert!(resolved.landing_tips.is_none());
+        assert!(resolved.openrouter_provider_order.is_empty());
+        assert_eq!(resolved.openrouter_allow_fallbacks, None);
+        assert!(!resolved.openrouter_disable_reasoning_flag);
+        assert!(!resolved.lmstudio_disable_reasoning_flag);
+        assert!(!resolved.inline_usage);
+        assert!(resolved.summarize_context);
+        assert!(resolved.sessions_dir.is_none());
     }
 
     #[test]
@@ -327,18 +1136,78 @@ mod tests {
                 max_agentic_rounds: Some(5),
                 max_output_tokens: Some(4096),
                 reasoning_effort: Some(Effort::High),
+                temperature: Some(0.5),
                 system_prompt: Some("Custom prompt.".to_string()),
                 system_prompt_file: None,
+                fallback_providers: Some(vec![FallbackEntry {
+                    provider: "lmstudio".to_string(),
+                    model: "qwen3-8b".to_string(),
+                }]),
+                show_input_counter: Some(false),
+                reasoning_mode: Some(ReasoningMode::Full),
+                spinner: Some("dots".to_string()),
+                workspace_root: Some("/srv/project".to_string()),
+                dedupe_stream: Some(true),
+                prompt_cache: Some(true),
+                max_parallel_tools: Some(2),
+                max_tool_output_bytes: Some(1000),
+                max_tool_args_bytes: Some(2000),
+                stream_channel_capacity: Some(8),
+                landing_message: Some("Welcome back.".to_string()),
+                landing_tips: Some(vec!["Stretch.".to_string()]),
+                stream_log: Some("/tmp/stream.log".to_string()),
+                fetch_allowlist: Some(vec!["docs.rs".to_string()]),
+                inline_usage: Some(true),
+                summarize_context: Some(false),
+                sessions_dir: Some("/srv/sessions".to_string()),
             },
             ..Default::default()
         };
-        let resolved = resolve(&config, None);
+        let resolved = resolve(&config, None, None);
         assert_eq!(resolved.provider, "lmstudio");
         assert_eq!(resolved.model_name, "my-model");
         assert_eq!(resolved.max_agentic_rounds, 5);
         assert_eq!(resolved.max_output_tokens, 4096);
         assert_eq!(resolved.effort, Effort::High);
+        assert_eq!(resolved.temperature, Some(0.5));
         assert_eq!(resolved.system_prompt, "Custom prompt.");
+        assert_eq!(resolved.fallback_providers[0].provider, "lmstudio");
+        assert_eq!(resolved.fallback_providers[0].model, "qwen3-8b");
+        assert!(!resolved.show_input_counter);
+        assert_eq!(resolved.reasoning_mode, ReasoningMode::Full);
+        assert_eq!(resolved.spinner.as_deref(), Some("dots"));
+        assert_eq!(resolved.workspace_root, PathBuf::from("/srv/project"));
+        assert!(resolved.dedupe_stream);
+        assert!(resolved.prompt_cache);
+        assert_eq!(resolved.max_parallel_tools, 2);
+        assert_eq!(resolved.max_tool_output_bytes, 1000);
+        assert_eq!(resolved.max_tool_args_bytes, 2000);
+        assert_eq!(resolved.stream_channel_capacity, 8);
+        assert_eq!(resolved.landing_message.as_deref(), Some("Welcome back."));
+        assert_eq!(resolved.landing_tips, Some(vec!["Stretch.".to_string()]));
+        assert_eq!(resolved.stream_log, Some(PathBuf::from("/tmp/stream.log")));
+        assert_eq!(resolved.fetch_allowlist, vec!["docs.rs"]);
+        assert!(resolved.inline_usage);
+        assert!(!resolved.summarize_context);
+        assert_eq!(resolved.sessions_dir, Some(PathBuf::from("/srv/sessions")));
+    }
+
+    #[test]
+    fn test_resolve_zero_caps_fall_back_to_defaults() {
+        let config = NaviConfig {
+            general: GeneralConfig {
+                max_tool_output_bytes: Some(0),
+                max_tool_args_bytes: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let resolved = resolve(&config, None, None);
+        assert_eq!(
+            resolved.max_tool_output_bytes,
+            DEFAULT_MAX_TOOL_OUTPUT_BYTES
+        );
+        assert_eq!(resolved.max_tool_args_bytes, DEFAULT_MAX_TOOL_ARGS_BYTES);
     }
 
     #[test]
@@ -350,7 +1219,7 @@ mod tests {
             },
             ..Default::default()
         };
-        let resolved = resolve(&config, Some("openrouter"));
+        let resolved = resolve(&config, Some("openrouter"), None);
         assert_eq!(resolved.provider, "openrouter");
     }
 
@@ -405,6 +1274,20 @@ default_model = "my-model"
         assert!(config.models.is_empty());
     }
 
+    #[test]
+    fn test_http_section_resolves() {
+        let toml_str = r#"
+[http]
+pool_max_idle_per_host = 4
+http2 = false
+"#;
+        let config: NaviConfig = toml::from_str(toml_str).unwrap();
+        let resolved = resolve(&config, None, None);
+        assert_eq!(resolved.http.pool_max_idle_per_host, Some(4));
+        assert_eq!(resolved.http.tcp_keepalive_secs, None);
+        assert_eq!(resolved.http.http2, Some(false));
+    }
+
     #[test]
     fn test_inline_system_prompt_wins_over_file() {
         let config = NaviConfig {
@@ -415,7 +1298,7 @@ default_model = "my-model"
             },
             ..Default::default()
         };
-        let resolved = resolve(&config, None);
+        let resolved = resolve(&config, None, None);
         assert_eq!(resolved.system_prompt, "Inline wins.");
     }
 
@@ -425,9 +1308,490 @@ default_model = "my-model"
             name: "test".to_string(),
             provider: "openrouter".to_string(),
             description: Some("desc".to_string()),
+            ..Default::default()
         };
         let cloned = entry.clone();
         assert_eq!(cloned.name, "test");
         assert_eq!(cloned.description.as_deref(), Some("desc"));
     }
+
+    #[test]
+    fn test_effective_params_uses_model_overrides() {
+        let general = resolve(&NaviConfig::default(), None, None);
+        let model = ModelEntry {
+            name: "qwen".to_string(),
+            provider: "lmstudio".to_string(),
+            effort: Some(Effort::Low),
+            temperature: Some(0.2),
+            max_output_tokens: Some(2048),
+            supports_reasoning: Some(false),
+            ..Default::default()
+        };
+        let params = effective_params_for(&model, &general);
+        assert_eq!(params.effort, Effort::Low);
+        assert_eq!(params.temperature, Some(0.2));
+        assert_eq!(params.max_output_tokens, 2048);
+        assert!(!params.supports_reasoning);
+    }
+
+    #[test]
+    fn test_effective_params_falls_back_to_general() {
+        let config = NaviConfig {
+            general: GeneralConfig {
+                reasoning_effort: Some(Effort::High),
+                temperature: Some(0.9),
+                max_output_tokens: Some(4096),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let general = resolve(&config, None, None);
+        let model = ModelEntry {
+            name: "qwen".to_string(),
+            provider: "lmstudio".to_string(),
+            ..Default::default()
+        };
+        let params = effective_params_for(&model, &general);
+        assert_eq!(params.effort, Effort::High);
+        assert_eq!(params.temperature, Some(0.9));
+        assert_eq!(params.max_output_tokens, 4096);
+        // Unlisted capability is assumed
+        assert!(params.supports_reasoning);
+    }
+
+    #[test]
+    fn test_params_for_model_matches_name_and_provider() {
+        let config = NaviConfig {
+            models: vec![ModelEntry {
+                name: "qwen".to_string(),
+                provider: "lmstudio".to_string(),
+                max_output_tokens: Some(2048),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let resolved = resolve(&config, None, None);
+        assert_eq!(
+            params_for_model(&resolved, "qwen", "lmstudio").max_output_tokens,
+            2048
+        );
+        // Same name under a different provider is a different model
+        assert_eq!(
+            params_for_model(&resolved, "qwen", "openrouter").max_output_tokens,
+            DEFAULT_MAX_OUTPUT_TOKENS
+        );
+    }
+
+    #[test]
+    fn test_model_entry_overrides_parse_from_toml() {
+        let toml_str = r#"
+            [[models]]
+            name = "qwen"
+            provider = "lmstudio"
+            effort = "low"
+            temperature = 0.2
+            max_output_tokens = 2048
+            supports_reasoning = false
+        "#;
+        let config: NaviConfig = toml::from_str(toml_str).unwrap();
+        let entry = &config.models[0];
+        assert_eq!(entry.effort, Some(Effort::Low));
+        assert_eq!(entry.temperature, Some(0.2));
+        assert_eq!(entry.max_output_tokens, Some(2048));
+        assert_eq!(entry.supports_reasoning, Some(false));
+    }
+
+    #[test]
+    fn test_pricing_for_model_from_toml() {
+        let toml_str = r#"
+            [[models]]
+            name = "anthropic/claude-sonnet-4"
+            provider = "openrouter"
+            input_price = 3.0
+            output_price = 15.0
+            cache_read_price = 0.3
+
+            [[models]]
+            name = "qwen"
+            provider = "lmstudio"
+            input_price = 1.0
+        "#;
+        let config: NaviConfig = toml::from_str(toml_str).unwrap();
+        let resolved = resolve(&config, None, None);
+
+        let pricing =
+            pricing_for_model(&resolved, "anthropic/claude-sonnet-4", "openrouter").unwrap();
+        assert_eq!(pricing.input_per_million, 3.0);
+        assert_eq!(pricing.output_per_million, 15.0);
+        assert_eq!(pricing.cache_read_per_million, Some(0.3));
+        // Partial rates are not enough to estimate a cost
+        assert!(pricing_for_model(&resolved, "qwen", "lmstudio").is_none());
+        assert!(pricing_for_model(&resolved, "unlisted", "openrouter").is_none());
+    }
+
+    #[test]
+    fn test_edited_config_file_resolves_new_values() {
+        let path = std::env::temp_dir().join("navi_test_config_reload.toml");
+        fs::write(&path, "[general]\nreasoning_effort = \"low\"\n").unwrap();
+        let before = resolve(&parse_config_file(&path).unwrap(), None, None);
+        assert_eq!(before.effort, Effort::Low);
+
+        fs::write(
+            &path,
+            "[general]\nreasoning_effort = \"high\"\nsystem_prompt = \"Edited.\"\n",
+        )
+        .unwrap();
+        let after = resolve(&parse_config_file(&path).unwrap(), None, None);
+        assert_eq!(after.effort, Effort::High);
+        assert_eq!(after.system_prompt, "Edited.");
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_malformed_config_file_is_parse_error() {
+        let path = std::env::temp_dir().join("navi_test_config_malformed.toml");
+        fs::write(&path, "[general\nreasoning_effort = ").unwrap();
+        assert!(matches!(
+            parse_config_file(&path),
+            Err(ConfigError::Parse(_))
+        ));
+        fs::remove_file(path).ok();
+    }
+
+    fn profile_config() -> NaviConfig {
+        let toml_str = r#"
+            [general]
+            default_model = "base-model"
+            max_agentic_rounds = 10
+
+            [profiles.work]
+            default_model = "work-model"
+            reasoning_effort = "high"
+
+            [profiles.work.openrouter]
+            api_key = "sk-or-work"
+        "#;
+        toml::from_str(toml_str).unwrap()
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_profiles_parse_from_toml() {
+        let config = profile_config();
+        let work = &config.profiles["work"];
+        assert_eq!(work.general.default_model.as_deref(), Some("work-model"));
+        assert_eq!(work.general.reasoning_effort, Some(Effort::High));
+        assert_eq!(work.openrouter.api_key.as_deref(), Some("sk-or-work"));
+    }
+
+    #[test]
+    fn test_profile_overrides_general() {
+        let resolved = resolve_with_env(&profile_config(), None, Some("work"), &no_env);
+        assert_eq!(resolved.model_name, "work-model");
+        assert_eq!(resolved.effort, Effort::High);
+        assert_eq!(resolved.openrouter_api_key.as_deref(), Some("sk-or-work"));
+        // Keys the profile doesn't set fall through to [general]
+        assert_eq!(resolved.max_agentic_rounds, 10);
+        assert_eq!(resolved.profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_openrouter_routing_from_toml_and_profile() {
+        let toml_str = r#"
+            [openrouter]
+            provider_order = ["anthropic", "together"]
+
+            [profiles.strict.openrouter]
+            allow_fallbacks = false
+        "#;
+        let config: NaviConfig = toml::from_str(toml_str).unwrap();
+
+        let resolved = resolve_with_env(&config, None, None, &no_env);
+        assert_eq!(
+            resolved.openrouter_provider_order,
+            ["anthropic", "together"]
+        );
+        assert_eq!(resolved.openrouter_allow_fallbacks, None);
+
+        let strict = resolve_with_env(&config, None, Some("strict"), &no_env);
+        assert_eq!(strict.openrouter_provider_order, ["anthropic", "together"]);
+        assert_eq!(strict.openrouter_allow_fallbacks, Some(false));
+    }
+
+    #[test]
+    fn test_disable_reasoning_flag_is_per_provider() {
+        let toml_str = r#"
+            [lmstudio]
+            disable_reasoning_flag = true
+
+            [profiles.routed.openrouter]
+            disable_reasoning_flag = true
+        "#;
+        let config: NaviConfig = toml::from_str(toml_str).unwrap();
+
+        let resolved = resolve_with_env(&config, None, None, &no_env);
+        assert!(resolved.lmstudio_disable_reasoning_flag);
+        assert!(!resolved.openrouter_disable_reasoning_flag);
+
+        let routed = resolve_with_env(&config, None, Some("routed"), &no_env);
+        assert!(routed.openrouter_disable_reasoning_flag);
+    }
+
+    #[test]
+    fn test_azure_section_resolves() {
+        let toml_str = r#"
+            [general]
+            default_provider = "azure"
+
+            [azure]
+            endpoint = "https://config.openai.azure.com"
+            deployment = "gpt-4o"
+            api_key = "config-key"
+        "#;
+        let config: NaviConfig = toml::from_str(toml_str).unwrap();
+        assert!(validate(&config).is_empty());
+
+        let resolved = resolve_with_env(&config, None, None, &no_env);
+        assert_eq!(resolved.provider, "azure");
+        assert_eq!(
+            resolved.azure_endpoint.as_deref(),
+            Some("https://config.openai.azure.com")
+        );
+        assert_eq!(resolved.azure_deployment.as_deref(), Some("gpt-4o"));
+        assert_eq!(resolved.azure_api_version, DEFAULT_AZURE_API_VERSION);
+        assert_eq!(resolved.azure_api_key.as_deref(), Some("config-key"));
+
+        let env = |key: &str| (key == "AZURE_OPENAI_API_KEY").then(|| "env-key".to_string());
+        let resolved = resolve_with_env(&config, None, None, &env);
+        assert_eq!(resolved.azure_api_key.as_deref(), Some("env-key"));
+    }
+
+    #[test]
+    fn test_profile_selected_by_env() {
+        let env = |key: &str| (key == "NAVI_PROFILE").then(|| "work".to_string());
+        let resolved = resolve_with_env(&profile_config(), None, None, &env);
+        assert_eq!(resolved.model_name, "work-model");
+    }
+
+    #[test]
+    fn test_env_stream_log_wins_over_config() {
+        let config = NaviConfig {
+            general: GeneralConfig {
+                stream_log: Some("config.log".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let env = |key: &str| (key == "NAVI_STREAM_LOG").then(|| "env.log".to_string());
+        let resolved = resolve_with_env(&config, None, None, &env);
+        assert_eq!(resolved.stream_log, Some(PathBuf::from("env.log")));
+    }
+
+    #[test]
+    fn test_env_sessions_dir_wins_over_config() {
+        let config = NaviConfig {
+            general: GeneralConfig {
+                sessions_dir: Some("/srv/config-sessions".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let env = |key: &str| (key == "NAVI_SESSIONS_DIR").then(|| "/srv/env-sessions".to_string());
+        let resolved = resolve_with_env(&config, None, None, &env);
+        assert_eq!(
+            resolved.sessions_dir,
+            Some(PathBuf::from("/srv/env-sessions"))
+        );
+    }
+
+    #[test]
+    fn test_env_model_wins_over_profile() {
+        let env = |key: &str| (key == "PRIMARY_MODEL_NAME").then(|| "env-model".to_string());
+        let resolved = resolve_with_env(&profile_config(), None, Some("work"), &env);
+        assert_eq!(resolved.model_name, "env-model");
+    }
+
+    #[test]
+    fn test_validate_accepts_default_and_sample_config() {
+        assert!(validate(&NaviConfig::default()).is_empty());
+        assert!(validate(&profile_config()).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_provider() {
+        let config: NaviConfig = toml::from_str(
+            r#"
+            [general]
+            default_provider = "openruoter"
+            fallback_providers = [
+                { provider = "lmstudio", model = "qwen3-8b" },
+                { provider = "ollama", model = "llama3" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let warnings = validate(&config);
+        let fields: Vec<&str> = warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["general.default_provider", "general.fallback_providers"]
+        );
+        assert!(warnings[0].message.contains("\"openruoter\""));
+        assert!(warnings[1].message.contains("\"ollama\""));
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_prompt_placeholder() {
+        let config = NaviConfig {
+            general: GeneralConfig {
+                system_prompt: Some("Today is {{date}}. Call me {{name}}.".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let warnings = validate(&config);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "general.system_prompt");
+        assert!(warnings[0].message.contains("{{name}}"));
+    }
+
+    #[test]
+    fn test_env_var_expands_in_api_key() {
+        let mut config: NaviConfig = toml::from_str(
+            r#"
+            [openrouter]
+            api_key = "${MY_KEY}"
+            base_url = "http://${HOST}:8080/v1"
+
+            [profiles.work.azure]
+            api_key = "${MY_KEY}"
+            "#,
+        )
+        .unwrap();
+        let env = |name: &str| match name {
+            "MY_KEY" => Some("sk-secret".to_string()),
+            "HOST" => Some("gateway".to_string()),
+            _ => None,
+        };
+        expand_env_vars(&mut config, &env);
+
+        assert_eq!(config.openrouter.api_key.as_deref(), Some("sk-secret"));
+        assert_eq!(
+            config.openrouter.base_url.as_deref(),
+            Some("http://gateway:8080/v1")
+        );
+        assert_eq!(
+            config.profiles["work"].azure.api_key.as_deref(),
+            Some("sk-secret")
+        );
+        assert!(validate(&config).is_empty());
+    }
+
+    #[test]
+    fn test_undefined_env_var_left_literal_with_warning() {
+        let mut config: NaviConfig = toml::from_str(
+            r#"
+            [general]
+            system_prompt = "Costs are in ${CURRENCY}."
+
+            [openrouter]
+            api_key = "${MISSING_KEY}"
+            "#,
+        )
+        .unwrap();
+        expand_env_vars(&mut config, &|_| None);
+
+        assert_eq!(config.openrouter.api_key.as_deref(), Some("${MISSING_KEY}"));
+        assert_eq!(
+            config.general.system_prompt.as_deref(),
+            Some("Costs are in ${CURRENCY}.")
+        );
+        let warnings = validate(&config);
+        let fields: Vec<&str> = warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, ["general.system_prompt", "openrouter.api_key"]);
+        assert!(warnings[1].message.contains("${MISSING_KEY}"));
+    }
+
+    #[test]
+    fn test_validate_reports_model_with_nonexistent_provider() {
+        let config: NaviConfig = toml::from_str(
+            r#"
+            [[models]]
+            name = "anthropic/claude-sonnet-4"
+            provider = "openrouter"
+
+            [[models]]
+            name = "llama3"
+            provider = "ollama"
+            "#,
+        )
+        .unwrap();
+
+        let warnings = validate(&config);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "models.llama3.provider");
+        assert_eq!(
+            warnings[0].to_string(),
+            "models.llama3.provider: unknown provider \"ollama\" (expected one of: openrouter, lmstudio, azure)"
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_every_numeric_problem() {
+        let config: NaviConfig = toml::from_str(
+            r#"
+            [general]
+            max_agentic_rounds = 0
+            temperature = 3.5
+
+            [profiles.fast]
+            max_output_tokens = 0
+            max_parallel_tools = 0
+
+            [[models]]
+            name = "m"
+            provider = "lmstudio"
+            input_price = -1.0
+            context_window = 0
+            "#,
+        )
+        .unwrap();
+
+        let fields: Vec<String> = validate(&config).into_iter().map(|w| w.field).collect();
+        assert_eq!(
+            fields,
+            [
+                "general.max_agentic_rounds",
+                "general.temperature",
+                "profiles.fast.max_output_tokens",
+                "profiles.fast.max_parallel_tools",
+                "models.m.context_window",
+                "models.m.input_price",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_warnings_status_joins_all_warnings() {
+        assert_eq!(warnings_status(&[]), None);
+        let warnings = vec![
+            ConfigWarning::new("general.temperature", "must be between 0.0 and 2.0"),
+            ConfigWarning::new("general.max_agentic_rounds", "must be at least 1"),
+        ];
+        assert_eq!(
+            warnings_status(&warnings).unwrap(),
+            "Config warnings: general.temperature: must be between 0.0 and 2.0; \
+             general.max_agentic_rounds: must be at least 1"
+        );
+    }
+
+    #[test]
+    fn test_unknown_profile_uses_base_config() {
+        let resolved = resolve_with_env(&profile_config(), None, Some("nope"), &no_env);
+        assert_eq!(resolved.model_name, "base-model");
+    }
 }
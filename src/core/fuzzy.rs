@@ -0,0 +1,158 @@
+//! # Fuzzy Matching
+//!
+//! Scores how well a short query matches a title when its characters appear
+//! in order but not necessarily next to each other, so "clm" finds "claude
+//! model". Matches that start words or run together score higher, and every
+//! skipped character costs a little, so tighter matches rank first.
+//!
+//! Among all the ways the query could line up with the text, the best one
+//! is found with a small dynamic program over (query char, text position).
+
+/// Score for each matched character.
+const MATCH: i32 = 16;
+/// Extra for a match at the start of a word (or a camelCase hump).
+const WORD_START_BONUS: i32 = 8;
+/// Extra for a match right after the previous one.
+const CONSECUTIVE_BONUS: i32 = 8;
+
+/// A successful match: higher scores are better matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Character (not byte) indices of the matched characters in the text.
+    pub positions: Vec<usize>,
+}
+
+/// Match `query` against `text`, ignoring case and whitespace in the query.
+/// None when the query's characters don't all appear in order. An empty
+/// query matches everything with a score of 0.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let n = chars.len();
+
+    // best[j][i]: top score with query[j] matched at text[i], and from[j][i]
+    // the position query[j - 1] was matched at to get it
+    let mut best = vec![vec![None::<i32>; n]; query.len()];
+    let mut from = vec![vec![0usize; n]; query.len()];
+    for (j, &qc) in query.iter().enumerate() {
+        // Best `score + position` of the previous query char at least two
+        // positions back, so its gap penalty can be applied in O(1)
+        let mut running: Option<(i32, usize)> = None;
+        for i in 0..n {
+            if j > 0
+                && i >= 2
+                && let Some(prev) = best[j - 1][i - 2]
+                && running.is_none_or(|(r, _)| prev + (i - 2) as i32 > r)
+            {
+                running = Some((prev + (i - 2) as i32, i - 2));
+            }
+            if lower[i] != qc {
+                continue;
+            }
+            let gain = MATCH + word_start_bonus(&chars, i);
+            if j == 0 {
+                // Characters skipped before the first match count as a gap
+                best[j][i] = Some(gain - i as i32);
+                continue;
+            }
+            let consecutive = (i >= 1)
+                .then(|| best[j - 1][i - 1])
+                .flatten()
+                .map(|prev| (prev + CONSECUTIVE_BONUS, i - 1));
+            let gapped = running.map(|(r, k)| (r - (i as i32 - 1), k));
+            let Some((prev, k)) = consecutive.into_iter().chain(gapped).max_by_key(|c| c.0) else {
+                continue;
+            };
+            best[j][i] = Some(prev + gain);
+            from[j][i] = k;
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut i, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| s.map(|s| (i, s)))
+        .max_by_key(|&(_, s)| s)?;
+    let mut positions = vec![0; query.len()];
+    for j in (0..query.len()).rev() {
+        positions[j] = i;
+        i = from[j][i];
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+fn word_start_bonus(chars: &[char], i: usize) -> i32 {
+    let starts_word = match i.checked_sub(1).map(|p| chars[p]) {
+        None => true,
+        Some(prev) => !prev.is_alphanumeric() || (prev.is_lowercase() && chars[i].is_uppercase()),
+    };
+    if starts_word { WORD_START_BONUS } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_titles_ranked_by_score() {
+        let titles = [
+            "Cool film ideas",
+            "Rust lifetimes",
+            "claude model comparison",
+            "Calm morning routine",
+        ];
+        let mut ranked: Vec<(&str, i32)> = titles
+            .iter()
+            .filter_map(|t| fuzzy_match("clm", t).map(|m| (*t, m.score)))
+            .collect();
+        ranked.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        let order: Vec<&str> = ranked.iter().map(|&(t, _)| t).collect();
+        assert_eq!(
+            order,
+            [
+                "claude model comparison",
+                "Calm morning routine",
+                "Cool film ideas"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_positions_prefer_word_starts() {
+        let m = fuzzy_match("clm", "claude model").unwrap();
+        assert_eq!(m.positions, [0, 1, 7]);
+        let m = fuzzy_match("fm", "aFixMe").unwrap();
+        assert_eq!(m.positions, [1, 4]);
+    }
+
+    #[test]
+    fn test_no_match_and_empty_query() {
+        assert!(fuzzy_match("xyz", "claude model").is_none());
+        assert!(fuzzy_match("ledo", "claude model").is_none());
+        let m = fuzzy_match("  ", "anything").unwrap();
+        assert_eq!(m.score, 0);
+        assert!(m.positions.is_empty());
+    }
+
+    #[test]
+    fn test_positions_are_char_indices() {
+        let m = fuzzy_match("ca", "école café").unwrap();
+        assert_eq!(m.positions, [6, 7]);
+    }
+}
@@ -31,9 +31,11 @@
 
 pub mod action;
 pub mod config;
+pub mod fuzzy;
 pub mod session;
 pub mod state;
 pub mod tools;
+pub mod usage;
 
 // Re-export commonly used types for convenience
 // pub use action::Action;
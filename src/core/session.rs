@@ -1,23 +1,33 @@
 //! # Session Persistence
 //!
-//! Save/load conversations to `~/.navi/sessions/`.
+//! Save/load conversations to `~/.navi/sessions/`, or to the directory set
+//! by `[general] sessions_dir` / `NAVI_SESSIONS_DIR`.
 //!
 //! Each session is a JSON file (`<uuid>.json`) plus a lightweight index
 //! (`sessions.json`) that avoids loading all files just to render a list.
 //!
 //! All writes use atomic rename (write `.tmp`, then `rename()`) for crash safety.
+//!
+//! Session files carry a `schema_version`. Older files are upgraded when
+//! read and written back in the current format the next time they're
+//! opened; files from a newer navi load best-effort with a warning.
+//!
+//! `export_archive` bundles every session and the index into one JSON file
+//! for backup; `import_archive` restores one alongside existing sessions.
 
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
 
 use chrono::Utc;
 use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 
 use crate::core::state::{ActiveModel, App};
-use crate::inference::{ContextItem, Source};
+use crate::inference::providers::responses_request_json;
+use crate::inference::{ContextItem, Effort, Source};
 
 /// Summary metadata for a session (stored in the index file).
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -30,11 +40,53 @@ pub struct SessionMeta {
     pub model_name: String,
     #[serde(default)]
     pub provider_name: String,
+    /// User-assigned labels for organizing sessions by topic.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Where the conversation was scrolled; older sessions open at the bottom.
+    #[serde(default)]
+    pub scroll: ScrollPosition,
+    /// Indices of pinned items among the saved items, in order.
+    #[serde(default)]
+    pub pins: Vec<usize>,
+}
+
+/// The message list's vertical scroll position, restored when the session
+/// is reopened.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScrollPosition {
+    /// Rows scrolled down from the top of the conversation
+    pub offset: u16,
+    /// Following the newest content; `offset` is ignored while set
+    pub stick_to_bottom: bool,
 }
 
+impl Default for ScrollPosition {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            stick_to_bottom: true,
+        }
+    }
+}
+
+impl SessionMeta {
+    /// Whether the session carries `tag` (case-insensitive).
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Version of the session file format written by this build. Bump it
+/// alongside a new step in `migrate` when the format changes.
+pub const SESSION_SCHEMA_VERSION: u32 = 1;
+
 /// Full session data: metadata + conversation items.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SessionData {
+    /// Format version; files saved before versioning read as 0.
+    #[serde(default)]
+    pub schema_version: u32,
     pub meta: SessionMeta,
     pub items: Vec<ContextItem>,
 }
@@ -45,11 +97,42 @@ pub struct SessionIndex {
     pub sessions: Vec<SessionMeta>,
 }
 
-/// Returns `~/.navi/sessions/`, creating it if needed.
+/// Every session in one file, as written by `export_archive`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SessionArchive {
+    pub exported_at: i64,
+    pub index: SessionIndex,
+    pub sessions: Vec<SessionData>,
+}
+
+/// How often the TUI saves conversations with unsaved changes.
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The `sessions_dir` setting, recorded once at startup by `set_sessions_dir`.
+static CONFIGURED_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Save and load sessions in `dir` instead of `~/.navi/sessions/` for the
+/// rest of the process. Only the first call has any effect.
+pub fn set_sessions_dir(dir: PathBuf) {
+    if CONFIGURED_DIR.set(dir).is_err() {
+        warn!("Sessions directory already set; keeping the first one");
+    }
+}
+
+/// Returns the configured sessions directory, else `~/.navi/sessions/`,
+/// creating it if needed.
 pub fn sessions_dir() -> io::Result<PathBuf> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
-    let dir = home.join(".navi").join("sessions");
+    sessions_dir_from(CONFIGURED_DIR.get().map(PathBuf::as_path))
+}
+
+fn sessions_dir_from(configured: Option<&Path>) -> io::Result<PathBuf> {
+    let dir = match configured {
+        Some(dir) => dir.to_path_buf(),
+        None => dirs::home_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?
+            .join(".navi")
+            .join("sessions"),
+    };
     fs::create_dir_all(&dir)?;
     Ok(dir)
 }
@@ -78,20 +161,54 @@ pub fn next_session_number() -> u32 {
 
 /// Rename a session's title on disk (both session file and index).
 pub fn rename_session(id: &str, new_title: &str) -> io::Result<()> {
-    let dir = sessions_dir()?;
+    update_meta(&sessions_dir()?, id, |meta| {
+        meta.title = new_title.to_string();
+    })
+}
+
+/// Tag a session on disk (both session file and index). Adding a tag the
+/// session already has is a no-op.
+pub fn add_tag(id: &str, tag: &str) -> io::Result<()> {
+    add_tag_in(&sessions_dir()?, id, tag)
+}
+
+/// Remove a tag from a session on disk (both session file and index).
+pub fn remove_tag(id: &str, tag: &str) -> io::Result<()> {
+    remove_tag_in(&sessions_dir()?, id, tag)
+}
+
+fn add_tag_in(dir: &Path, id: &str, tag: &str) -> io::Result<()> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty tag"));
+    }
+    update_meta(dir, id, |meta| {
+        if !meta.has_tag(tag) {
+            meta.tags.push(tag.to_string());
+        }
+    })
+}
+
+fn remove_tag_in(dir: &Path, id: &str, tag: &str) -> io::Result<()> {
+    let tag = tag.trim();
+    update_meta(dir, id, |meta| {
+        meta.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+    })
+}
+
+/// Apply `edit` to a session's metadata in both its file and the index.
+fn update_meta(dir: &Path, id: &str, edit: impl Fn(&mut SessionMeta)) -> io::Result<()> {
     let path = dir.join(format!("{}.json", id));
 
     // Update session file
-    let json = fs::read_to_string(&path)?;
-    let mut data: SessionData =
-        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-    data.meta.title = new_title.to_string();
+    let (mut data, _) = read_session_file(&path)?;
+    edit(&mut data.meta);
     atomic_write_json(&path, &data)?;
 
     // Update index
-    let mut index = load_index().unwrap_or_default();
+    let mut index = read_index(dir).unwrap_or_default();
     if let Some(entry) = index.sessions.iter_mut().find(|s| s.id == id) {
-        entry.title = new_title.to_string();
+        edit(entry);
     }
     let index_path = dir.join("sessions.json");
     atomic_write_json(&index_path, &index)?;
@@ -113,13 +230,29 @@ fn count_messages(items: &[ContextItem]) -> usize {
 fn persistable_items(items: &[ContextItem]) -> Vec<ContextItem> {
     items
         .iter()
-        .filter(|item| {
-            !matches!(item, ContextItem::Message(seg) if matches!(seg.source, Source::Directive | Source::Status))
-        })
+        .filter(|item| is_persistable(item))
         .cloned()
         .collect()
 }
 
+fn is_persistable(item: &ContextItem) -> bool {
+    !matches!(item, ContextItem::Message(seg) if matches!(seg.source, Source::Directive | Source::Status))
+}
+
+/// Pinned `items` indices as indices among the persisted items. Pins on
+/// items that aren't saved are dropped.
+fn persisted_pins(items: &[ContextItem], pins: &[usize]) -> Vec<usize> {
+    pins.iter()
+        .filter(|&&pin| items.get(pin).is_some_and(is_persistable))
+        .map(|&pin| {
+            items[..pin]
+                .iter()
+                .filter(|item| is_persistable(item))
+                .count()
+        })
+        .collect()
+}
+
 /// Touch a file to update its mtime to now.
 fn touch(path: &Path) -> io::Result<()> {
     let file = fs::OpenOptions::new().write(true).open(path)?;
@@ -164,6 +297,8 @@ pub fn save_session(
     model: &ActiveModel,
     title: &str,
     existing_meta: Option<&SessionMeta>,
+    scroll: ScrollPosition,
+    pins: &[usize],
 ) -> io::Result<()> {
     let dir = sessions_dir()?;
     let now = Utc::now().timestamp();
@@ -184,9 +319,13 @@ pub fn save_session(
         message_count,
         model_name: model.name.clone(),
         provider_name: model.provider.clone(),
+        tags: existing_meta.map(|m| m.tags.clone()).unwrap_or_default(),
+        scroll,
+        pins: persisted_pins(items, pins),
     };
 
     let data = SessionData {
+        schema_version: SESSION_SCHEMA_VERSION,
         meta: meta.clone(),
         items: persistable,
     };
@@ -210,11 +349,17 @@ pub fn save_session(
 /// Load a session from disk by ID.
 /// Touches the file to update mtime so it sorts as most-recently-opened.
 pub fn load_session(id: &str) -> io::Result<SessionData> {
-    let dir = sessions_dir()?;
+    load_session_in(&sessions_dir()?, id)
+}
+
+fn load_session_in(dir: &Path, id: &str) -> io::Result<SessionData> {
     let path = dir.join(format!("{}.json", id));
-    let json = fs::read_to_string(&path)?;
-    let data: SessionData =
-        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let (data, migrated) = read_session_file(&path)?;
+
+    // Write an upgraded file back so the migration only runs once
+    if migrated && let Err(e) = atomic_write_json(&path, &data) {
+        warn!("Failed to re-save migrated session {}: {}", id, e);
+    }
 
     // Touch file so mtime reflects "last opened", not just "last saved"
     let _ = touch(&path);
@@ -222,6 +367,52 @@ pub fn load_session(id: &str) -> io::Result<SessionData> {
     Ok(data)
 }
 
+/// Read a session from disk by ID without marking it as opened.
+/// Used for previews, which shouldn't reorder the session list.
+pub fn read_session(id: &str) -> io::Result<SessionData> {
+    let path = sessions_dir()?.join(format!("{}.json", id));
+    read_session_file(&path).map(|(data, _)| data)
+}
+
+/// Parse a session file and upgrade it to the current schema in memory.
+/// The flag says whether a migration ran.
+fn read_session_file(path: &Path) -> io::Result<(SessionData, bool)> {
+    let json = fs::read_to_string(path)?;
+    let mut data: SessionData =
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let migrated = migrate(&mut data);
+    Ok((data, migrated))
+}
+
+/// Upgrade `data` to `SESSION_SCHEMA_VERSION`, one version at a time.
+/// Returns whether anything changed. Files from a newer version are left
+/// as they are: the fields this build knows about loaded, the rest didn't.
+fn migrate(data: &mut SessionData) -> bool {
+    let from = data.schema_version;
+    if from > SESSION_SCHEMA_VERSION {
+        warn!(
+            "Session {} has schema version {} (this build knows {}), loading best-effort",
+            data.meta.id, from, SESSION_SCHEMA_VERSION
+        );
+        return false;
+    }
+
+    if data.schema_version == 0 {
+        // Unversioned files may lack the provider, tags and scroll position;
+        // serde has already filled those with their defaults.
+        data.schema_version = 1;
+    }
+
+    if data.schema_version == from {
+        return false;
+    }
+    debug!(
+        "Migrated session {} from schema version {} to {}",
+        data.meta.id, from, data.schema_version
+    );
+    true
+}
+
 /// Delete a session from disk and update the index.
 pub fn delete_session(id: &str) -> io::Result<()> {
     let dir = sessions_dir()?;
@@ -238,9 +429,60 @@ pub fn delete_session(id: &str) -> io::Result<()> {
     Ok(())
 }
 
+/// Write every saved session, plus the index, to a single JSON file at
+/// `path`. A session whose file can't be read is skipped with a warning.
+pub fn export_archive(path: &Path) -> io::Result<()> {
+    export_archive_from(&sessions_dir()?, path)
+}
+
+fn export_archive_from(dir: &Path, path: &Path) -> io::Result<()> {
+    let index = read_index(dir)?;
+    let mut sessions = Vec::with_capacity(index.sessions.len());
+    for meta in &index.sessions {
+        match read_session_file(&dir.join(format!("{}.json", meta.id))) {
+            Ok((data, _)) => sessions.push(data),
+            Err(e) => warn!("Leaving session {} out of the archive: {}", meta.id, e),
+        }
+    }
+    let archive = SessionArchive {
+        exported_at: Utc::now().timestamp(),
+        index,
+        sessions,
+    };
+    atomic_write_json(path, &archive)
+}
+
+/// Restore the sessions in the archive at `path`. Each gets a fresh id, so
+/// importing never overwrites a session, even one it was exported from.
+/// Returns how many sessions were imported.
+pub fn import_archive(path: &Path) -> io::Result<usize> {
+    import_archive_into(&sessions_dir()?, path)
+}
+
+fn import_archive_into(dir: &Path, path: &Path) -> io::Result<usize> {
+    let json = fs::read_to_string(path)?;
+    let archive: SessionArchive =
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut index = read_index(dir).unwrap_or_default();
+    let count = archive.sessions.len();
+    for mut data in archive.sessions {
+        migrate(&mut data);
+        data.meta.id = new_session_id();
+        atomic_write_json(&dir.join(format!("{}.json", data.meta.id)), &data)?;
+        index.sessions.push(data.meta);
+    }
+    sort_index_by_mtime(&mut index, dir);
+    atomic_write_json(&dir.join("sessions.json"), &index)?;
+    Ok(count)
+}
+
 /// Load the session index from disk.
 pub fn load_index() -> io::Result<SessionIndex> {
-    let dir = sessions_dir()?;
+    read_index(&sessions_dir()?)
+}
+
+fn read_index(dir: &Path) -> io::Result<SessionIndex> {
     let path = dir.join("sessions.json");
     if !path.exists() {
         return Ok(SessionIndex::default());
@@ -252,11 +494,13 @@ pub fn load_index() -> io::Result<SessionIndex> {
 /// Save the current app session to disk. Generates a session ID if needed.
 /// Skips empty sessions (no user/model messages). This is the single entry
 /// point for session persistence — call from the TUI on SaveSession effect or quit.
+/// Clears the session's dirty flag unless the write fails.
 pub fn save_current_session(app: &mut App) {
     let has_messages = app.session.context.items.iter().any(|item| {
         matches!(item, ContextItem::Message(seg) if matches!(seg.source, Source::User | Source::Model))
     });
     if !has_messages {
+        app.session.dirty = false;
         return;
     }
 
@@ -280,22 +524,175 @@ pub fn save_current_session(app: &mut App) {
         &app.model,
         &app.session.session_title,
         existing_meta.as_ref(),
+        app.session.scroll,
+        &app.session.pins,
     ) {
         warn!("Failed to save session: {}", e);
     } else {
         debug!("Session saved: {}", id);
+        app.session.dirty = false;
+    }
+}
+
+/// Save the current session if it changed since it was last saved, so a
+/// crash loses at most one autosave interval. Returns whether it saved.
+pub fn autosave(app: &mut App) -> bool {
+    if !app.session.dirty {
+        return false;
+    }
+    save_current_session(app);
+    true
+}
+
+/// Drop tool calls whose results were cut off, so a truncated conversation
+/// can still be replayed to the provider (every call needs its output).
+fn drop_unanswered_tool_calls(items: &mut Vec<ContextItem>) {
+    let answered: Vec<String> = items
+        .iter()
+        .filter_map(|item| match item {
+            ContextItem::ToolResult(result) => Some(result.call_id.clone()),
+            _ => None,
+        })
+        .collect();
+    items.retain(|item| match item {
+        ContextItem::ToolCall(call) => answered.contains(&call.call_id),
+        _ => true,
+    });
+}
+
+/// Copy `data` truncated after `index` under a new id. Tool calls left
+/// without a result by the cut are dropped; the original is not modified.
+pub fn fork_session(data: &SessionData, index: usize) -> SessionData {
+    let end = (index + 1).min(data.items.len());
+    let mut items = data.items[..end].to_vec();
+    drop_unanswered_tool_calls(&mut items);
+
+    let now = Utc::now().timestamp();
+    SessionData {
+        schema_version: SESSION_SCHEMA_VERSION,
+        meta: SessionMeta {
+            id: new_session_id(),
+            title: format!("{} (fork)", data.meta.title),
+            created_at: now,
+            updated_at: now,
+            message_count: count_messages(&items),
+            scroll: ScrollPosition::default(),
+            pins: data
+                .meta
+                .pins
+                .iter()
+                .copied()
+                .filter(|&p| p < end)
+                .collect(),
+            ..data.meta.clone()
+        },
+        items,
+    }
+}
+
+/// Fork the current conversation at context item `index`, save the fork,
+/// and return it for loading. The current session is saved first so its
+/// file stays intact alongside the fork.
+pub fn fork_current_session(app: &mut App, index: usize) -> io::Result<SessionData> {
+    let items = &app.session.context.items;
+    if index >= items.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no message at fork point",
+        ));
+    }
+    // Position among the persisted items (directive and status aren't saved)
+    let Some(persisted_index) = persistable_items(&items[..=index]).len().checked_sub(1) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "nothing to fork before this message",
+        ));
+    };
+
+    save_current_session(app);
+    let id = app
+        .session
+        .current_session_id
+        .clone()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "session has no messages"))?;
+    let current = load_session(&id)?;
+
+    let fork = fork_session(&current, persisted_index);
+    save_session(
+        &fork.meta.id,
+        &fork.items,
+        &app.model,
+        &fork.meta.title,
+        Some(&fork.meta),
+        fork.meta.scroll,
+        &fork.meta.pins,
+    )?;
+    Ok(fork)
+}
+
+/// Render the conversation's user and model messages as markdown.
+pub fn conversation_markdown(title: &str, items: &[ContextItem]) -> String {
+    let mut out = format!("# {}\n", title);
+    for item in items {
+        let ContextItem::Message(seg) = item else {
+            continue;
+        };
+        let heading = match seg.source {
+            Source::User => "User",
+            Source::Model => "Assistant",
+            _ => continue,
+        };
+        out.push_str(&format!("\n## {}\n\n{}\n", heading, seg.content.trim()));
     }
+    out
+}
+
+/// Write the current conversation to `~/.navi/exports/<session id>.md`.
+/// Returns the path written.
+pub fn export_current_session(app: &App) -> io::Result<PathBuf> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+    let dir = home.join(".navi").join("exports");
+    fs::create_dir_all(&dir)?;
+
+    let id = app
+        .session
+        .current_session_id
+        .clone()
+        .unwrap_or_else(new_session_id);
+    let title = if app.session.session_title.is_empty() {
+        "Untitled session"
+    } else {
+        app.session.session_title.as_str()
+    };
+    let path = dir.join(format!("{}.md", id));
+    fs::write(
+        &path,
+        conversation_markdown(title, &app.session.context.items),
+    )?;
+    Ok(path)
+}
+
+/// The JSON request body sending `items` to `model` would produce, without
+/// making the request. Backs `navi --dump-request <session id>`.
+pub fn build_request_json(
+    items: &[ContextItem],
+    model: &str,
+    effort: Effort,
+) -> serde_json::Result<String> {
+    responses_request_json(items, model, effort)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::inference::ContextSegment;
+    use crate::inference::{ContextSegment, ToolCall, ToolResult};
 
     fn user_msg(text: &str) -> ContextItem {
         ContextItem::Message(ContextSegment {
             source: Source::User,
             content: text.to_string(),
+            timestamp: None,
         })
     }
 
@@ -303,6 +700,7 @@ mod tests {
         ContextItem::Message(ContextSegment {
             source: Source::Model,
             content: text.to_string(),
+            timestamp: None,
         })
     }
 
@@ -310,6 +708,7 @@ mod tests {
         ContextItem::Message(ContextSegment {
             source: Source::Directive,
             content: "system prompt".to_string(),
+            timestamp: None,
         })
     }
 
@@ -321,6 +720,7 @@ mod tests {
             ContextItem::Message(ContextSegment {
                 source: Source::Status,
                 content: "Loading...".to_string(),
+                timestamp: None,
             }),
             model_msg("hi"),
         ];
@@ -340,4 +740,374 @@ mod tests {
         ];
         assert_eq!(count_messages(&items), 3); // 2 user + 1 model
     }
+
+    fn tool_call(call_id: &str) -> ContextItem {
+        ContextItem::ToolCall(ToolCall {
+            id: format!("fc_{}", call_id),
+            call_id: call_id.to_string(),
+            name: "add".to_string(),
+            arguments: "{}".to_string(),
+        })
+    }
+
+    fn tool_result(call_id: &str) -> ContextItem {
+        ContextItem::ToolResult(ToolResult {
+            call_id: call_id.to_string(),
+            output: "2".to_string(),
+        })
+    }
+
+    fn session_data(items: Vec<ContextItem>) -> SessionData {
+        SessionData {
+            schema_version: SESSION_SCHEMA_VERSION,
+            meta: SessionMeta {
+                id: "original".to_string(),
+                title: "Session #1".to_string(),
+                created_at: 0,
+                updated_at: 0,
+                message_count: count_messages(&items),
+                model_name: "test-model".to_string(),
+                provider_name: "openrouter".to_string(),
+                tags: Vec::new(),
+                scroll: ScrollPosition::default(),
+                pins: Vec::new(),
+            },
+            items,
+        }
+    }
+
+    #[test]
+    fn test_fork_session_keeps_items_through_index() {
+        let items = vec![
+            user_msg("1+1?"),
+            tool_call("call_1"),
+            tool_result("call_1"),
+            model_msg("2"),
+            user_msg("thanks"),
+            model_msg("welcome"),
+        ];
+        let data = session_data(items.clone());
+
+        for k in [0, 2, 3, 5] {
+            let fork = fork_session(&data, k);
+            assert_eq!(fork.items, items[..=k].to_vec(), "fork at {}", k);
+            assert_ne!(fork.meta.id, data.meta.id);
+            assert_eq!(fork.meta.title, "Session #1 (fork)");
+            assert_eq!(fork.meta.model_name, "test-model");
+        }
+        // Source data is untouched
+        assert_eq!(data.items, items);
+    }
+
+    #[test]
+    fn test_fork_session_drops_unanswered_tool_calls() {
+        let data = session_data(vec![
+            user_msg("1+1 and 2+2?"),
+            tool_call("call_1"),
+            tool_call("call_2"),
+            tool_result("call_1"),
+            tool_result("call_2"),
+            model_msg("2 and 4"),
+        ]);
+
+        let fork = fork_session(&data, 3);
+        assert_eq!(
+            fork.items,
+            vec![
+                user_msg("1+1 and 2+2?"),
+                tool_call("call_1"),
+                tool_result("call_1")
+            ]
+        );
+        assert_eq!(fork.meta.message_count, 1);
+    }
+
+    #[test]
+    fn test_fork_session_ids_are_distinct() {
+        let data = session_data(vec![user_msg("hello"), model_msg("hi")]);
+        let a = fork_session(&data, 1);
+        let b = fork_session(&data, 1);
+        assert_ne!(a.meta.id, b.meta.id);
+    }
+
+    #[test]
+    fn test_configured_sessions_dir_is_used_and_created() {
+        let dir = std::env::temp_dir().join("navi_test_sessions_configured/nested");
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+
+        assert_eq!(sessions_dir_from(Some(&dir)).unwrap(), dir);
+        assert!(dir.is_dir());
+        fs::remove_dir_all(dir.parent().unwrap()).ok();
+    }
+
+    /// Helper: create a fresh sessions directory for one test.
+    fn temp_sessions_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("navi_test_sessions_{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Helper: write a session file and an index containing it.
+    fn write_session(dir: &Path, data: &SessionData) {
+        atomic_write_json(&dir.join(format!("{}.json", data.meta.id)), data).unwrap();
+        let index = SessionIndex {
+            sessions: vec![data.meta.clone()],
+        };
+        atomic_write_json(&dir.join("sessions.json"), &index).unwrap();
+    }
+
+    fn read_session(dir: &Path, id: &str) -> SessionData {
+        let json = fs::read_to_string(dir.join(format!("{}.json", id))).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_tags_round_trip_through_file_and_index() {
+        let dir = temp_sessions_dir("tags");
+        write_session(&dir, &session_data(vec![user_msg("hello")]));
+
+        add_tag_in(&dir, "original", "rust").unwrap();
+        add_tag_in(&dir, "original", " work ").unwrap();
+        add_tag_in(&dir, "original", "Rust").unwrap(); // duplicate, ignored
+        assert!(add_tag_in(&dir, "original", "  ").is_err());
+
+        assert_eq!(
+            read_session(&dir, "original").meta.tags,
+            vec!["rust", "work"]
+        );
+        assert_eq!(
+            read_index(&dir).unwrap().sessions[0].tags,
+            vec!["rust", "work"]
+        );
+
+        remove_tag_in(&dir, "original", "RUST").unwrap();
+        assert_eq!(read_session(&dir, "original").meta.tags, vec!["work"]);
+        assert_eq!(read_index(&dir).unwrap().sessions[0].tags, vec!["work"]);
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_autosave_follows_dirty_flag() {
+        use crate::core::action::{Action, update};
+        use crate::test_support::test_app;
+
+        let mut app = test_app();
+        assert!(!app.session.dirty);
+        assert!(!autosave(&mut app), "clean session must not save");
+
+        update(&mut app, Action::Submit("hello".to_string()));
+        assert!(app.session.dirty);
+
+        // Only the directive is left after the cancel, so the save skips
+        // the disk but still counts as done
+        let mut app = test_app();
+        update(&mut app, Action::CancelGeneration);
+        assert!(app.session.dirty);
+        assert!(autosave(&mut app));
+        assert!(!app.session.dirty);
+        assert!(!autosave(&mut app));
+    }
+
+    #[test]
+    fn test_meta_without_tags_deserializes() {
+        let json = r#"{"id":"a","title":"Old","created_at":0,"updated_at":0,"message_count":1,"model_name":"m"}"#;
+        let meta: SessionMeta = serde_json::from_str(json).unwrap();
+        assert!(meta.tags.is_empty());
+        assert_eq!(meta.scroll, ScrollPosition::default());
+    }
+
+    #[test]
+    fn test_unversioned_session_migrated_and_resaved() {
+        let dir = temp_sessions_dir("migrate_v0");
+        let json = r#"{
+            "meta": {"id":"old","title":"Old","created_at":0,"updated_at":0,"message_count":1,"model_name":"m"},
+            "items": [{"Message":{"role":"user","content":"hello"}}]
+        }"#;
+        fs::write(dir.join("old.json"), json).unwrap();
+
+        let data = load_session_in(&dir, "old").unwrap();
+        assert_eq!(data.schema_version, SESSION_SCHEMA_VERSION);
+        assert_eq!(data.meta.provider_name, "");
+        assert!(data.meta.tags.is_empty());
+        assert_eq!(data.meta.scroll, ScrollPosition::default());
+        assert_eq!(data.items, vec![user_msg("hello")]);
+
+        let saved = read_session(&dir, "old");
+        assert_eq!(saved.schema_version, SESSION_SCHEMA_VERSION);
+        assert_eq!(saved.items, data.items);
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_newer_session_loads_best_effort() {
+        let dir = temp_sessions_dir("migrate_future");
+        let mut data = session_data(vec![user_msg("hello")]);
+        data.schema_version = SESSION_SCHEMA_VERSION + 1;
+        let mut json = serde_json::to_value(&data).unwrap();
+        json["meta"]["pinned"] = serde_json::json!(true);
+        fs::write(dir.join("original.json"), json.to_string()).unwrap();
+
+        let loaded = load_session_in(&dir, "original").unwrap();
+        assert_eq!(loaded.schema_version, SESSION_SCHEMA_VERSION + 1);
+        assert_eq!(loaded.items, data.items);
+        // Not rewritten, so the unknown field survives
+        let on_disk = fs::read_to_string(dir.join("original.json")).unwrap();
+        assert!(on_disk.contains("pinned"));
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_archive_round_trip_imports_under_fresh_ids() {
+        let source = temp_sessions_dir("archive_source");
+        let mut first = session_data(vec![user_msg("hello"), model_msg("hi")]);
+        first.meta.id = "first".to_string();
+        first.meta.tags = vec!["rust".to_string()];
+        let mut second = session_data(vec![user_msg("bye")]);
+        second.meta.id = "second".to_string();
+        second.meta.title = "Session #2".to_string();
+        atomic_write_json(&source.join("first.json"), &first).unwrap();
+        atomic_write_json(&source.join("second.json"), &second).unwrap();
+        let index = SessionIndex {
+            sessions: vec![first.meta.clone(), second.meta.clone()],
+        };
+        atomic_write_json(&source.join("sessions.json"), &index).unwrap();
+
+        let archive = source.join("backup.json");
+        export_archive_from(&source, &archive).unwrap();
+
+        // Importing into a directory that already has a session keeps it
+        let target = temp_sessions_dir("archive_target");
+        write_session(&target, &session_data(vec![user_msg("existing")]));
+        assert_eq!(import_archive_into(&target, &archive).unwrap(), 2);
+
+        let index = read_index(&target).unwrap();
+        assert_eq!(index.sessions.len(), 3);
+        assert!(index.sessions.iter().any(|m| m.id == "original"));
+        for original in [&first, &second] {
+            let meta = index
+                .sessions
+                .iter()
+                .find(|m| m.title == original.meta.title && m.id != "original")
+                .unwrap();
+            assert_ne!(meta.id, original.meta.id);
+            let restored = read_session(&target, &meta.id);
+            assert_eq!(restored.items, original.items);
+            assert_eq!(restored.meta.tags, original.meta.tags);
+        }
+        assert_eq!(
+            read_session(&target, "original").items,
+            vec![user_msg("existing")]
+        );
+
+        // A second import adds copies rather than replacing the first
+        assert_eq!(import_archive_into(&target, &archive).unwrap(), 2);
+        assert_eq!(read_index(&target).unwrap().sessions.len(), 5);
+
+        fs::remove_dir_all(source).ok();
+        fs::remove_dir_all(target).ok();
+    }
+
+    #[test]
+    fn test_scroll_position_round_trips_through_file() {
+        let dir = temp_sessions_dir("scroll");
+        let mut data = session_data(vec![user_msg("hello")]);
+        data.meta.scroll = ScrollPosition {
+            offset: 42,
+            stick_to_bottom: false,
+        };
+        write_session(&dir, &data);
+
+        assert_eq!(read_session(&dir, "original").meta.scroll, data.meta.scroll);
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_pins_saved_as_persisted_indices() {
+        let items = vec![
+            directive_msg(),
+            user_msg("hello"),
+            ContextItem::Message(ContextSegment {
+                source: Source::Status,
+                content: "12 in / 3 out".to_string(),
+                timestamp: None,
+            }),
+            model_msg("hi"),
+        ];
+        // The directive and the status line aren't saved, so neither are their pins
+        assert_eq!(persisted_pins(&items, &[0, 1, 2, 3]), vec![0, 1]);
+
+        let dir = temp_sessions_dir("pins");
+        let mut data = session_data(vec![user_msg("hello"), model_msg("hi")]);
+        data.meta.pins = vec![1];
+        write_session(&dir, &data);
+        assert_eq!(read_session(&dir, "original").meta.pins, vec![1]);
+        assert!(fork_session(&data, 0).meta.pins.is_empty());
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_conversation_markdown_skips_non_chat_items() {
+        let items = vec![directive_msg(), user_msg("hello"), model_msg("hi there\n")];
+        assert_eq!(
+            conversation_markdown("Session #1", &items),
+            "# Session #1\n\n## User\n\nhello\n\n## Assistant\n\nhi there\n"
+        );
+    }
+    #[test]
+    fn test_build_request_json_matches_input_array() {
+        let items = vec![
+            directive_msg(),
+            user_msg("list files"),
+            ContextItem::Message(ContextSegment {
+                source: Source::Thinking,
+                content: "use the tool".to_string(),
+                timestamp: None,
+            }),
+            ContextItem::ToolCall(ToolCall {
+                id: "fc_1".to_string(),
+                call_id: "call_1".to_string(),
+                name: "list_dir".to_string(),
+                arguments: "{}".to_string(),
+            }),
+            ContextItem::ToolResult(ToolResult {
+                call_id: "call_1".to_string(),
+                output: "a.txt".to_string(),
+            }),
+            model_msg("One file: a.txt"),
+        ];
+
+        let json = build_request_json(&items, "test-model", Effort::Low).unwrap();
+        let request: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(request["model"], "test-model");
+        assert_eq!(request["reasoning"], serde_json::json!({ "effort": "low" }));
+        assert_eq!(
+            request["input"],
+            serde_json::json!([
+                { "type": "message", "role": "system", "content": "system prompt" },
+                { "type": "message", "role": "user", "content": "list files" },
+                {
+                    "type": "function_call",
+                    "id": "fc_1",
+                    "call_id": "call_1",
+                    "name": "list_dir",
+                    "arguments": "{}"
+                },
+                {
+                    "type": "function_call_output",
+                    "id": "fco_1",
+                    "call_id": "call_1",
+                    "output": "a.txt"
+                },
+                { "type": "message", "role": "assistant", "content": "One file: a.txt" }
+            ])
+        );
+        // Same session, same bytes
+        assert_eq!(
+            build_request_json(&items, "test-model", Effort::Low).unwrap(),
+            json
+        );
+    }
 }
@@ -9,7 +9,10 @@
 //! ├── session: SessionState                  // per-conversation state
 //! ├── effort: Effort                         // reasoning effort level
 //! ├── registry: Arc<ToolRegistry>            // tool registry
+//! ├── dispatcher: ToolDispatcher             // bounded pool running tool calls
 //! ├── model: ActiveModel                     // model name + provider
+//! ├── rate_limit: Option<RateLimitInfo>      // latest quota headers from the provider
+//! ├── input_max_lines: Option<u16>           // input box height cap set by the user
 //! ├── config: ResolvedConfig                 // connection details (URLs, keys)
 //! └── ... mutable overrides ...
 //! ```
@@ -17,11 +20,16 @@
 //! State changes only happen through `update(state, action)` in action.rs.
 //! This keeps things predictable, so no surprise mutations.
 
+use crate::core::config::{self, ModelEntry, ModelParams, ResolvedConfig};
 #[cfg(test)]
-use crate::core::config::{self, DEFAULT_MAX_AGENTIC_ROUNDS, DEFAULT_MAX_OUTPUT_TOKENS};
-use crate::core::config::{ModelEntry, ResolvedConfig};
+use crate::core::config::{DEFAULT_MAX_AGENTIC_ROUNDS, DEFAULT_MAX_OUTPUT_TOKENS};
+use crate::core::session::ScrollPosition;
 use crate::core::tools::ToolRegistry;
-use crate::inference::{CompletionProvider, Context, Effort, ToolDefinition, UsageStats};
+use crate::core::tools::dispatch::ToolDispatcher;
+use crate::inference::{
+    self, CompletionProvider, Context, Effort, ImageAttachment, LiveRate, Pricing, RateLimitInfo,
+    ToolDefinition, TurnUsage, UsageStats,
+};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
@@ -46,20 +54,56 @@ impl ActiveModel {
 ///
 /// Extracting this from App means session resets are a single assignment
 /// instead of resetting 13 fields individually.
+/// What the model has streamed so far in the current round, so the UI can
+/// tell reasoning apart from writing the response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamPhase {
+    /// Request sent, nothing received yet
+    #[default]
+    Waiting,
+    /// Only reasoning has arrived
+    Thinking,
+    /// Response text or tool calls have started
+    Content,
+}
+
 pub struct SessionState {
     pub context: Context,
     pub current_session_id: Option<String>,
     pub session_title: String,
     pub is_loading: bool,
+    pub stream_phase: StreamPhase,
     pub pending_tool_calls: HashSet<String>,
     pub stream_done: bool,
     pub had_tool_calls: bool,
     pub agentic_rounds: u8,
     pub usage_stats: UsageStats,
-    pub message_stats: HashMap<usize, UsageStats>,
-    pub session_total_tokens: u32,
+    /// Estimated tok/s of the answer streaming now, reset each turn
+    pub live_rate: LiveRate,
+    /// Usage of each model message, keyed by item index
+    pub message_stats: HashMap<usize, TurnUsage>,
+    /// Running total across every turn and round of the conversation
+    pub session_usage: UsageStats,
     pub error: Option<String>,
     pub status_message: String,
+    /// One-off instruction queued for the next submitted message (`/once`)
+    pub next_turn_directive: Option<String>,
+    /// Instruction sent with every request of the current turn, never stored
+    /// in the context
+    pub turn_directive: Option<String>,
+    /// Last scroll position of the conversation, saved with the session
+    pub scroll: ScrollPosition,
+    /// Context indices of pinned items, ascending, saved with the session
+    pub pins: Vec<usize>,
+    /// The context changed since the session was last saved; periodic
+    /// autosave skips the session while this is false
+    pub dirty: bool,
+    /// Image pasted from the clipboard, added to the context just before
+    /// the next submitted message
+    pub pending_image: Option<ImageAttachment>,
+    /// Older turns are being summarized; the submitted message is sent
+    /// once the summary arrives
+    pub summarizing: bool,
 }
 
 impl SessionState {
@@ -69,15 +113,24 @@ impl SessionState {
             current_session_id: None,
             session_title: String::new(),
             is_loading: false,
+            stream_phase: StreamPhase::Waiting,
             pending_tool_calls: HashSet::new(),
             stream_done: false,
             had_tool_calls: false,
             agentic_rounds: 0,
             usage_stats: UsageStats::default(),
+            live_rate: LiveRate::default(),
             message_stats: HashMap::new(),
-            session_total_tokens: 0,
+            session_usage: UsageStats::default(),
             error: None,
             status_message: String::from("Welcome to Navi!"),
+            next_turn_directive: None,
+            turn_directive: None,
+            scroll: ScrollPosition::default(),
+            pins: Vec::new(),
+            dirty: false,
+            pending_image: None,
+            summarizing: false,
         }
     }
 }
@@ -86,13 +139,26 @@ pub struct App {
     pub provider: Arc<dyn CompletionProvider>,
     pub session: SessionState,
     pub effort: Effort,
+    /// Effort from the `--effort` flag, kept across model switches and reloads
+    pub effort_override: Option<Effort>,
     pub registry: Arc<ToolRegistry>,
+    /// Runs tool calls concurrently over `registry`, up to `max_parallel_tools`
+    pub dispatcher: ToolDispatcher,
     pub model: ActiveModel,
+    /// Quota and credit headers from the provider's most recent response
+    pub rate_limit: Option<RateLimitInfo>,
+    /// Lines the input box shows before scrolling, as grown or shrunk with
+    /// Alt+= / Alt+-; None keeps the default
+    pub input_max_lines: Option<u16>,
 
     // --- Config-driven fields ---
     pub config: ResolvedConfig,
     pub max_agentic_rounds: u8,
     pub max_output_tokens: u32,
+    pub temperature: Option<f32>,
+    /// False when the active model rejects a reasoning block, so requests
+    /// omit it and the effort setting is ignored
+    pub supports_reasoning: bool,
     pub system_prompt: String,
     pub available_models: Vec<ModelEntry>,
 }
@@ -101,16 +167,26 @@ impl App {
     /// Creates an App with default settings. Used by tests via `test_app()`.
     #[cfg(test)]
     pub fn new(provider: Arc<dyn CompletionProvider>, model_name: String) -> Self {
-        let resolved = config::resolve(&config::NaviConfig::default(), None);
+        let resolved = config::resolve(&config::NaviConfig::default(), None, None);
+        let registry = Arc::new(crate::core::tools::default_registry(
+            &resolved.workspace_root,
+            &resolved.fetch_allowlist,
+        ));
         Self {
             provider,
             session: SessionState::new(config::DEFAULT_SYSTEM_PROMPT),
             model: ActiveModel::new(model_name, ""),
+            rate_limit: None,
+            input_max_lines: None,
             effort: Effort::default(),
-            registry: Arc::new(crate::core::tools::default_registry()),
+            effort_override: None,
+            dispatcher: ToolDispatcher::new(registry.clone(), resolved.max_parallel_tools),
+            registry,
             config: resolved,
             max_agentic_rounds: DEFAULT_MAX_AGENTIC_ROUNDS,
             max_output_tokens: DEFAULT_MAX_OUTPUT_TOKENS,
+            temperature: None,
+            supports_reasoning: true,
             system_prompt: config::DEFAULT_SYSTEM_PROMPT.to_string(),
             available_models: Vec::new(),
         }
@@ -118,27 +194,92 @@ impl App {
 
     /// Creates an App from resolved config values.
     pub fn from_config(provider: Arc<dyn CompletionProvider>, config: ResolvedConfig) -> Self {
+        let params = config::params_for_model(&config, &config.model_name, &config.provider);
+        let registry = Arc::new(crate::core::tools::default_registry(
+            &config.workspace_root,
+            &config.fetch_allowlist,
+        ));
         Self {
             provider,
             session: SessionState::new(&config.system_prompt),
             model: ActiveModel::new(config.model_name.clone(), config.provider.clone()),
-            effort: config.effort,
-            registry: Arc::new(crate::core::tools::default_registry()),
+            rate_limit: None,
+            input_max_lines: None,
+            effort: params.effort,
+            effort_override: None,
+            dispatcher: ToolDispatcher::new(registry.clone(), config.max_parallel_tools),
+            registry,
             max_agentic_rounds: config.max_agentic_rounds,
-            max_output_tokens: config.max_output_tokens,
+            max_output_tokens: params.max_output_tokens,
+            temperature: params.temperature,
+            supports_reasoning: params.supports_reasoning,
             system_prompt: config.system_prompt.clone(),
             available_models: config.models.clone(),
             config,
         }
     }
 
+    /// Apply per-model request parameters (effort, temperature, output cap,
+    /// reasoning support). `--effort` outranks the model's own effort.
+    pub fn apply_model_params(&mut self, params: ModelParams) {
+        self.effort = self.effort_override.unwrap_or(params.effort);
+        self.temperature = params.temperature;
+        self.max_output_tokens = params.max_output_tokens;
+        self.supports_reasoning = params.supports_reasoning;
+    }
+
+    /// Rebuild the tool registry and dispatcher from `config`, e.g. after a
+    /// reload changed the workspace root, fetch allowlist or parallelism.
+    pub fn rebuild_tools(&mut self) {
+        self.registry = Arc::new(crate::core::tools::default_registry(
+            &self.config.workspace_root,
+            &self.config.fetch_allowlist,
+        ));
+        self.dispatcher =
+            ToolDispatcher::new(self.registry.clone(), self.config.max_parallel_tools);
+    }
+
+    /// Start over in place: a fresh context holding only the current directive,
+    /// no session id, and no accumulated usage. The saved session file (if any)
+    /// is left on disk; the next save creates a new session.
+    pub fn clear_conversation(&mut self) {
+        self.session = SessionState::new(&self.system_prompt);
+        self.session.status_message = String::from("Conversation cleared.");
+    }
+
+    /// Token rates for the active model, if its `[[models]]` entry is priced.
+    pub fn pricing(&self) -> Option<Pricing> {
+        config::pricing_for_model(&self.config, &self.model.name, &self.model.provider)
+    }
+
+    /// Context window of the active model, if its `[[models]]` entry sets one.
+    pub fn context_window(&self) -> Option<u32> {
+        self.available_models
+            .iter()
+            .find(|m| m.same_model(&self.model.name, &self.model.provider))
+            .and_then(|m| m.context_window)
+    }
+
     pub fn tool_definitions(&self) -> Vec<ToolDefinition> {
         self.registry.definitions()
     }
+
+    /// What to tell the user instead of sending, when neither the active
+    /// provider nor any fallback can authenticate: OpenRouter without an API
+    /// key would only fail with a 401 once the stream starts, and Azure can't
+    /// be built at all.
+    pub fn missing_credentials(&self) -> Option<&'static str> {
+        let fallbacks = self.config.fallback_providers.iter();
+        let usable = std::iter::once(self.model.provider.as_str())
+            .chain(fallbacks.map(|fallback| fallback.provider.as_str()))
+            .any(|name| inference::has_credentials(name, &self.config));
+        (!usable).then(|| inference::credentials_hint(&self.model.provider))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::inference::{ContextItem, Source};
     use crate::test_support::test_app;
 
     #[test]
@@ -148,4 +289,29 @@ mod tests {
         assert!(!app.session.is_loading);
         assert_eq!(app.model.name, "test-model");
     }
+
+    #[test]
+    fn test_clear_conversation_keeps_only_directive() {
+        let mut app = test_app();
+        app.system_prompt = "Be terse.".to_string();
+        app.session.context.add_user_message("hello".to_string());
+        app.session.current_session_id = Some("sess-1".to_string());
+        app.session.usage_stats.input_tokens = Some(500);
+        app.session.message_stats.insert(1, Default::default());
+        app.session.session_usage.total_tokens = Some(500);
+
+        app.clear_conversation();
+
+        let items = &app.session.context.items;
+        assert_eq!(items.len(), 1);
+        let ContextItem::Message(directive) = &items[0] else {
+            panic!("expected the directive message");
+        };
+        assert_eq!(directive.source, Source::Directive);
+        assert_eq!(directive.content, "Be terse.");
+        assert!(app.session.current_session_id.is_none());
+        assert!(app.session.usage_stats.input_tokens.is_none());
+        assert!(app.session.message_stats.is_empty());
+        assert!(app.session.session_usage.total().is_none());
+    }
 }
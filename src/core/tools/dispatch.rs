@@ -0,0 +1,173 @@
+//! # Tool Dispatcher
+//!
+//! Runs tool calls on a bounded worker pool. Every call gets its own tokio
+//! task, so independent calls from one response execute concurrently, but a
+//! semaphore caps how many run at once; the rest wait for a free slot.
+//!
+//! Results come back in whatever order the tools finish. The context puts
+//! them back in call order (see `Context::add_tool_result`), so scheduling
+//! never changes what the model sees.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::warn;
+use tokio::sync::Semaphore;
+
+use super::ToolRegistry;
+use crate::inference::types::{ToolCall, ToolResult};
+
+/// A tool still running after this long is abandoned with an error result.
+const TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+pub struct ToolDispatcher {
+    registry: Arc<ToolRegistry>,
+    slots: Arc<Semaphore>,
+}
+
+impl ToolDispatcher {
+    /// At most `max_parallel` calls execute at once (minimum 1).
+    pub fn new(registry: Arc<ToolRegistry>, max_parallel: usize) -> Self {
+        Self {
+            registry,
+            slots: Arc::new(Semaphore::new(max_parallel.max(1))),
+        }
+    }
+
+    /// Execute `call` once a worker slot is free. Timeouts and failures are
+    /// reported as an error result rather than dropped, so every call gets
+    /// an answer.
+    pub async fn execute(&self, call: &ToolCall) -> ToolResult {
+        // The semaphore is never closed, so acquiring can't fail
+        let _slot = self.slots.acquire().await.expect("tool semaphore closed");
+        let output = match tokio::time::timeout(TOOL_TIMEOUT, self.registry.execute(call)).await {
+            Ok(output) => output,
+            Err(_) => {
+                warn!(
+                    "Tool '{}' timed out after {}s (call_id={})",
+                    call.name,
+                    TOOL_TIMEOUT.as_secs(),
+                    call.call_id
+                );
+                serde_json::json!({
+                    "error": format!("Tool execution timed out after {}s", TOOL_TIMEOUT.as_secs())
+                })
+                .to_string()
+            }
+        };
+        ToolResult {
+            call_id: call.call_id.clone(),
+            output,
+        }
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tools::{Tool, ToolError};
+    use async_trait::async_trait;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use std::time::Instant;
+
+    /// Test tool that sleeps, then echoes its label.
+    struct Sleepy;
+
+    #[derive(Deserialize, JsonSchema)]
+    struct SleepyArgs {
+        ms: u64,
+        label: String,
+    }
+
+    #[derive(Serialize)]
+    struct SleepyOutput {
+        label: String,
+    }
+
+    #[async_trait]
+    impl Tool for Sleepy {
+        const NAME: &'static str = "sleepy";
+        const DESCRIPTION: &'static str = "Sleeps for `ms` milliseconds.";
+        type Args = SleepyArgs;
+        type Output = SleepyOutput;
+
+        async fn call(&self, args: SleepyArgs) -> Result<SleepyOutput, ToolError> {
+            tokio::time::sleep(Duration::from_millis(args.ms)).await;
+            Ok(SleepyOutput { label: args.label })
+        }
+    }
+
+    fn dispatcher(max_parallel: usize) -> ToolDispatcher {
+        let mut registry = ToolRegistry::new();
+        registry.register(Sleepy);
+        ToolDispatcher::new(Arc::new(registry), max_parallel)
+    }
+
+    fn sleepy_call(call_id: &str, ms: u64) -> ToolCall {
+        ToolCall {
+            id: format!("fc_{call_id}"),
+            call_id: call_id.into(),
+            name: "sleepy".into(),
+            arguments: serde_json::json!({ "ms": ms, "label": call_id }).to_string(),
+        }
+    }
+
+    /// Spawn each call as its own task, the way the TUI does, and join them
+    /// in call order.
+    async fn run_all(dispatcher: &ToolDispatcher, calls: &[ToolCall]) -> Vec<ToolResult> {
+        let handles: Vec<_> = calls
+            .iter()
+            .cloned()
+            .map(|call| {
+                let dispatcher = dispatcher.clone();
+                tokio::spawn(async move { dispatcher.execute(&call).await })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+        results
+    }
+
+    #[tokio::test]
+    async fn test_independent_calls_run_concurrently() {
+        let calls = [sleepy_call("call_a", 200), sleepy_call("call_b", 200)];
+        let started = Instant::now();
+        let results = run_all(&dispatcher(4), &calls).await;
+        let elapsed = started.elapsed();
+
+        assert!(elapsed < Duration::from_millis(400), "took {elapsed:?}");
+        // Each result maps back to the call that produced it
+        for (call, result) in calls.iter().zip(&results) {
+            assert_eq!(result.call_id, call.call_id);
+            let output: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+            assert_eq!(output["label"], call.call_id.as_str());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pool_size_bounds_concurrency() {
+        let calls = [sleepy_call("call_a", 100), sleepy_call("call_b", 100)];
+        let started = Instant::now();
+        let results = run_all(&dispatcher(1), &calls).await;
+
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tool_still_answers() {
+        let call = ToolCall {
+            name: "nonexistent".into(),
+            ..sleepy_call("call_x", 0)
+        };
+        let result = dispatcher(1).execute(&call).await;
+        assert_eq!(result.call_id, "call_x");
+        assert!(result.output.contains("Unknown tool"));
+    }
+}
@@ -0,0 +1,471 @@
+//! # Fetch Tool
+//!
+//! `fetch_url` downloads a web page for the model. Only hosts matching
+//! `[general] fetch_allowlist` can be fetched, and the tool isn't offered at
+//! all while that list is empty.
+//!
+//! Hosts that resolve to loopback, private or link-local addresses are
+//! refused unless the allowlist names them exactly (a wildcard never lets
+//! one through), so a prompt can't steer the tool at services on the local
+//! network. The checked address is the one connected to, and redirects are
+//! reported rather than followed, so every hop goes through the same checks.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Url;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{Tool, ToolError};
+
+/// Response bodies are cut off after this many bytes.
+pub const DEFAULT_MAX_FETCH_BYTES: usize = 256 * 1024;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+// ── Fetch URL ───────────────────────────────────────────────────────────────
+
+pub struct FetchUrlTool {
+    allowlist: Vec<String>,
+    max_bytes: usize,
+}
+
+impl FetchUrlTool {
+    /// `allowlist` holds host patterns: `docs.rs` matches that host only,
+    /// `*.rust-lang.org` matches its subdomains, and `*` matches any host.
+    pub fn new(allowlist: Vec<String>) -> Self {
+        Self {
+            allowlist,
+            max_bytes: DEFAULT_MAX_FETCH_BYTES,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Parse `url` and check its host against the allowlist. Returns the
+    /// URL, its host, and whether the allowlist names that host exactly.
+    fn check_url(&self, url: &str) -> Result<(Url, String, bool), ToolError> {
+        let parsed =
+            Url::parse(url).map_err(|e| ToolError(format!("Invalid URL '{}': {}", url, e)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(ToolError(format!(
+                "Unsupported URL scheme '{}': only http and https can be fetched",
+                parsed.scheme()
+            )));
+        }
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| ToolError(format!("URL '{}' has no host", url)))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        if !self.allowlist.iter().any(|p| host_matches(p, &host)) {
+            return Err(ToolError(format!(
+                "Access denied: '{}' is not in fetch_allowlist",
+                host
+            )));
+        }
+        let named = self
+            .allowlist
+            .iter()
+            .any(|p| p.trim().eq_ignore_ascii_case(&host));
+        Ok((parsed, host, named))
+    }
+
+    /// Resolve `host` and refuse internal addresses unless it was named
+    /// explicitly. Returns the address to connect to.
+    async fn resolve(&self, url: &Url, host: &str, named: bool) -> Result<SocketAddr, ToolError> {
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| ToolError(format!("Failed to resolve '{}': {}", host, e)))?
+            .collect();
+        if !named && let Some(addr) = addrs.iter().find(|a| is_internal(a.ip())) {
+            return Err(ToolError(format!(
+                "Access denied: '{}' resolves to the private address {}; \
+                 list the host exactly in fetch_allowlist to allow it",
+                host,
+                addr.ip()
+            )));
+        }
+        addrs
+            .into_iter()
+            .next()
+            .ok_or_else(|| ToolError(format!("Failed to resolve '{}': no addresses", host)))
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct FetchUrlArgs {
+    /// The http or https URL to fetch.
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FetchUrlResult {
+    pub status: u16,
+    pub text: String,
+}
+
+#[async_trait]
+impl Tool for FetchUrlTool {
+    const NAME: &'static str = "fetch_url";
+    const DESCRIPTION: &'static str = "\
+        Fetches a web page over http or https and returns its HTTP status and text. HTML is \
+        reduced to plain text; other text responses are returned as is. Only hosts on the \
+        user's allowlist can be fetched, and local or private network addresses are refused \
+        unless explicitly allowed. Redirects are not followed: the text names the new location, \
+        which you can fetch if its host is allowed. Large responses are truncated, with a note \
+        saying so.";
+    type Args = FetchUrlArgs;
+    type Output = FetchUrlResult;
+
+    async fn call(&self, args: FetchUrlArgs) -> Result<FetchUrlResult, ToolError> {
+        let (url, host, named) = self.check_url(&args.url)?;
+        let addr = self.resolve(&url, &host, named).await?;
+        let fetch_err = |e: reqwest::Error| ToolError(format!("Failed to fetch '{}': {}", url, e));
+
+        // Pin the connection to the address that passed the check, so a
+        // second DNS lookup can't swap in a different one.
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(FETCH_TIMEOUT)
+            .resolve(&host, addr)
+            // A proxy would make its own lookup and connection, bypassing
+            // the pinned address.
+            .no_proxy()
+            .build()
+            .map_err(fetch_err)?;
+        let mut response = client.get(url.clone()).send().await.map_err(fetch_err)?;
+        let status = response.status();
+
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|loc| url.join(loc).ok());
+            let text = match location {
+                Some(target) => format!("Redirected to {}", target),
+                None => "Redirected without a location".to_string(),
+            };
+            return Ok(FetchUrlResult {
+                status: status.as_u16(),
+                text,
+            });
+        }
+
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("html"));
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response.chunk().await.map_err(fetch_err)? {
+            let room = self.max_bytes - body.len();
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let raw = String::from_utf8_lossy(&body);
+        let mut text = if is_html {
+            html_to_text(&raw)
+        } else {
+            raw.into_owned()
+        };
+        if truncated {
+            text.push_str(&format!(
+                "\n\n[truncated: response exceeded {} bytes]",
+                self.max_bytes
+            ));
+        }
+        Ok(FetchUrlResult {
+            status: status.as_u16(),
+            text,
+        })
+    }
+}
+
+/// Whether `host` (lowercase) matches an allowlist `pattern`.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+        None => pattern == host,
+    }
+}
+
+/// Loopback, private, link-local and other addresses that don't belong to
+/// the public internet.
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                // "This network", 0.0.0.0/8
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (b & 0xc0) == 64)
+        }
+        IpAddr::V6(v6) => {
+            // IPv4-mapped ::ffff:a.b.c.d and IPv4-compatible ::a.b.c.d
+            // (which also covers :: and ::1) stand for the IPv4 address.
+            if let Some(v4) = v6.to_ipv4() {
+                return is_internal(IpAddr::V4(v4));
+            }
+            let segments = v6.segments();
+            // NAT64, 64:ff9b::/96, reaches the embedded IPv4 address.
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [.., a, b, c, d] = v6.octets();
+                return is_internal(IpAddr::V4(Ipv4Addr::new(a, b, c, d)));
+            }
+            let first = segments[0];
+            v6.is_multicast()
+                // Unique local, fc00::/7
+                || (first & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// Elements whose contents are never shown as text.
+const HIDDEN_ELEMENTS: &[&str] = &["script", "style", "noscript", "head"];
+
+/// Reduce an HTML document to readable text: tags and hidden elements are
+/// dropped, common entities decoded, and whitespace collapsed, with block
+/// elements starting new lines.
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        if !tag.starts_with('/') && HIDDEN_ELEMENTS.contains(&name.as_str()) {
+            let close = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(&close) {
+                Some(end) => &rest[end..],
+                None => "",
+            };
+        } else if is_block(&name) {
+            text.push('\n');
+        } else {
+            text.push(' ');
+        }
+    }
+    text.push_str(rest);
+    collapse_whitespace(&decode_entities(&text))
+}
+
+fn is_block(name: &str) -> bool {
+    matches!(
+        name,
+        "p" | "div"
+            | "br"
+            | "li"
+            | "tr"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "pre"
+            | "section"
+            | "article"
+            | "header"
+            | "footer"
+            | "blockquote"
+            | "table"
+            | "ul"
+            | "ol"
+    )
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Collapse runs of spaces within lines and drop blank lines.
+fn collapse_whitespace(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(allowlist: &[&str]) -> FetchUrlTool {
+        FetchUrlTool::new(allowlist.iter().map(|p| p.to_string()).collect())
+    }
+
+    async fn fetch(tool: &FetchUrlTool, url: &str) -> Result<FetchUrlResult, ToolError> {
+        tool.call(FetchUrlArgs { url: url.into() }).await
+    }
+
+    #[test]
+    fn test_host_patterns() {
+        assert!(host_matches("docs.rs", "docs.rs"));
+        assert!(host_matches("Docs.RS", "docs.rs"));
+        assert!(!host_matches("docs.rs", "evil-docs.rs"));
+        assert!(host_matches("*.rust-lang.org", "doc.rust-lang.org"));
+        assert!(!host_matches("*.rust-lang.org", "rust-lang.org"));
+        assert!(!host_matches("*.rust-lang.org", "evilrust-lang.org"));
+        assert!(host_matches("*", "example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_host_outside_allowlist_refused() {
+        let err = fetch(&tool(&["docs.rs"]), "https://example.com/")
+            .await
+            .unwrap_err();
+        assert!(err.0.contains("not in fetch_allowlist"), "got: {err}");
+
+        let err = fetch(&tool(&[]), "https://docs.rs/").await.unwrap_err();
+        assert!(err.0.contains("not in fetch_allowlist"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_non_http_scheme_refused() {
+        let err = fetch(&tool(&["*"]), "file:///etc/passwd")
+            .await
+            .unwrap_err();
+        assert!(err.0.contains("Unsupported URL scheme"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_private_addresses_blocked_by_wildcards() {
+        let wildcard = tool(&["*"]);
+        for url in [
+            "http://127.0.0.1:9/",
+            "http://10.1.2.3/",
+            "http://192.168.0.1/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[::1]/",
+            "http://localhost:9/",
+        ] {
+            let err = fetch(&wildcard, url).await.unwrap_err();
+            assert!(err.0.contains("private address"), "{url}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_is_internal() {
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.5.4",
+            "100.64.0.1",
+            "0.1.2.3",
+            "224.0.0.1",
+            "::1",
+            "::",
+            "fd00::1",
+            "ff02::1",
+            "::127.0.0.1",
+            "::10.0.0.1",
+            "64:ff9b::7f00:1",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(is_internal(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "93.184.216.34",
+            "1.1.1.1",
+            "2606:4700::1111",
+            "::ffff:8.8.8.8",
+            "::8.8.8.8",
+            "64:ff9b::808:808",
+        ] {
+            assert!(!is_internal(ip.parse().unwrap()), "{ip}");
+        }
+        assert!(is_internal("::ffff:192.168.1.1".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_explicitly_allowed_local_host_fetched_as_text() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "<html><head><title>T</title><style>p{}</style></head>\
+                 <body><h1>Hello</h1><p>Fish &amp; chips</p></body></html>",
+                "text/html; charset=utf-8",
+            ))
+            .mount(&server)
+            .await;
+
+        let result = fetch(&tool(&["127.0.0.1"]), &server.uri()).await.unwrap();
+        assert_eq!(result.status, 200);
+        assert_eq!(result.text, "Hello\nFish & chips");
+    }
+
+    #[tokio::test]
+    async fn test_large_response_truncated() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(100)))
+            .mount(&server)
+            .await;
+
+        let small = tool(&["127.0.0.1"]).with_max_bytes(10);
+        let result = fetch(&small, &server.uri()).await.unwrap();
+        assert!(result.text.starts_with("xxxxxxxxxx\n\n[truncated"));
+    }
+
+    #[test]
+    fn test_html_to_text_drops_scripts_and_tags() {
+        let html = "<div>One<script>alert('<b>')</script></div><ul><li>Two</li><li>Three</li></ul>";
+        assert_eq!(html_to_text(html), "One\nTwo\nThree");
+    }
+}
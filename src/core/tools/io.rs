@@ -1,7 +1,14 @@
 //! # IO Tools
 //!
 //! IO-related tool implementations. (Read, Write, etc.)
-//! Each tool is a unit struct implementing the `Tool` trait.
+//! Each tool is a struct implementing the `Tool` trait.
+//!
+//! File access is sandboxed to a workspace root: paths are canonicalized
+//! (resolving `..` and symlinks) and anything that lands outside the root
+//! is refused.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 use async_trait::async_trait;
 use schemars::JsonSchema;
@@ -9,14 +16,62 @@ use serde::{Deserialize, Serialize};
 
 use super::{Tool, ToolError};
 
+/// Files larger than this are truncated, with a note appended.
+pub const DEFAULT_MAX_READ_BYTES: usize = 64 * 1024;
+
 // ── Read File ─────────────────────────────────────────────────────────────────────
 
-pub struct ReadFileTool;
+pub struct ReadFileTool {
+    root: PathBuf,
+    max_bytes: usize,
+}
+
+impl ReadFileTool {
+    /// Reads are confined to `root` (relative paths resolve against it).
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_bytes: DEFAULT_MAX_READ_BYTES,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Canonicalize `path` and ensure it stays inside the workspace root.
+    fn resolve(&self, path: &str) -> Result<PathBuf, ToolError> {
+        let root = self.root.canonicalize().map_err(|e| {
+            ToolError(format!(
+                "Workspace root '{}' is unavailable: {}",
+                self.root.display(),
+                e
+            ))
+        })?;
+        // Canonicalizing follows symlinks, so a link pointing out of the
+        // root resolves to its real target and fails the prefix check.
+        let resolved = root
+            .join(Path::new(path))
+            .canonicalize()
+            .map_err(|e| ToolError(format!("Failed to read file '{}': {}", path, e)))?;
+        if !resolved.starts_with(&root) {
+            return Err(ToolError(format!(
+                "Access denied: '{}' is outside the workspace root '{}'",
+                path,
+                root.display()
+            )));
+        }
+        Ok(resolved)
+    }
+}
 
 #[derive(Deserialize, JsonSchema)]
 pub struct ReadFileArgs {
-    /// Absolute or relative path to the file to read.
-    pub file_path: String,
+    /// Path to the file, relative to the workspace root (or absolute within it).
+    #[serde(alias = "file_path")]
+    pub path: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -28,18 +83,52 @@ pub struct ReadFileResult {
 impl Tool for ReadFileTool {
     const NAME: &'static str = "read_file";
     const DESCRIPTION: &'static str = "\
-        Reads the full content of a text file at the given path and returns it as a string. \
+        Reads the content of a text file in the workspace and returns it as a string. \
         Use this tool whenever you need to inspect a file's contents - for example, to answer \
-        questions about code, configuration, logs, or data files. The file_path can be absolute \
-        or relative to the working directory. Returns an error if the file does not exist, is not \
-        readable, or is not valid UTF-8 text. This tool does not support binary files.";
+        questions about code, configuration, logs, or data files. The path is relative to the \
+        workspace root; paths outside the workspace are refused. Large files are truncated, \
+        with a note saying how much was shown. Returns an error if the file does not exist, is \
+        not readable, or is not valid UTF-8 text. This tool does not support binary files.";
     type Args = ReadFileArgs;
     type Output = ReadFileResult;
 
     async fn call(&self, args: ReadFileArgs) -> Result<ReadFileResult, ToolError> {
-        let content = std::fs::read_to_string(&args.file_path)
-            .map_err(|e| ToolError(format!("Failed to read file '{}': {}", args.file_path, e)))?;
+        let path = self.resolve(&args.path)?;
+        let read_err =
+            |e: std::io::Error| ToolError(format!("Failed to read file '{}': {}", args.path, e));
+
+        let file = std::fs::File::open(&path).map_err(read_err)?;
+        let total_bytes = file.metadata().map_err(read_err)?.len();
+        let mut bytes = Vec::new();
+        file.take(self.max_bytes as u64)
+            .read_to_end(&mut bytes)
+            .map_err(read_err)?;
+        let truncated = total_bytes > bytes.len() as u64;
 
+        let mut content = match String::from_utf8(bytes) {
+            Ok(text) => text,
+            // The cut may split a multi-byte character; drop the partial tail
+            Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+                let valid = e.utf8_error().valid_up_to();
+                let mut bytes = e.into_bytes();
+                bytes.truncate(valid);
+                String::from_utf8(bytes).expect("truncated to valid UTF-8")
+            }
+            Err(_) => {
+                return Err(ToolError(format!(
+                    "Failed to read file '{}': not valid UTF-8 text",
+                    args.path
+                )));
+            }
+        };
+
+        if truncated {
+            content.push_str(&format!(
+                "\n\n[truncated: showing first {} of {} bytes]",
+                content.len(),
+                total_bytes
+            ));
+        }
         Ok(ReadFileResult { content })
     }
 }
@@ -49,38 +138,129 @@ impl Tool for ReadFileTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-
-    /// Helper: create a temp file with the given content, return its path.
-    fn temp_file(name: &str, content: &str) -> std::path::PathBuf {
-        let path = std::env::temp_dir().join(format!("navi_test_{name}"));
-        let mut f = std::fs::File::create(&path).unwrap();
-        f.write_all(content.as_bytes()).unwrap();
-        path
+
+    /// Helper: create a fresh workspace directory for one test.
+    fn temp_workspace(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("navi_test_ws_{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    async fn read(tool: &ReadFileTool, path: &str) -> Result<String, ToolError> {
+        tool.call(ReadFileArgs {
+            path: path.to_string(),
+        })
+        .await
+        .map(|r| r.content)
     }
 
     #[tokio::test]
-    async fn read_file_returns_content() {
-        let path = temp_file("read_ok", "hello world\n");
-        let result = ReadFileTool
-            .call(ReadFileArgs {
-                file_path: path.to_string_lossy().into(),
-            })
-            .await
-            .unwrap();
-        assert_eq!(result.content, "hello world\n");
-        std::fs::remove_file(path).ok();
+    async fn read_file_in_root_returns_content() {
+        let root = temp_workspace("read_ok");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        let tool = ReadFileTool::new(&root);
+
+        assert_eq!(read(&tool, "src/main.rs").await.unwrap(), "fn main() {}\n");
+        // Absolute paths inside the root are fine too
+        let absolute = root.join("src/main.rs");
+        assert_eq!(
+            read(&tool, &absolute.to_string_lossy()).await.unwrap(),
+            "fn main() {}\n"
+        );
+        std::fs::remove_dir_all(root).ok();
     }
 
     #[tokio::test]
     async fn read_file_missing_returns_error() {
-        let result = ReadFileTool
-            .call(ReadFileArgs {
-                file_path: "/tmp/navi_test_does_not_exist_xyz".into(),
-            })
-            .await;
-        let err = result.unwrap_err();
+        let root = temp_workspace("read_missing");
+        let err = read(&ReadFileTool::new(&root), "does_not_exist.txt")
+            .await
+            .unwrap_err();
         assert!(err.0.contains("Failed to read file"), "got: {err}");
+        std::fs::remove_dir_all(root).ok();
+    }
+
+    #[tokio::test]
+    async fn read_file_outside_root_is_refused() {
+        let root = temp_workspace("read_escape");
+        let outside = temp_workspace("read_escape_outside");
+        std::fs::write(outside.join("secret.txt"), "secret").unwrap();
+        let tool = ReadFileTool::new(&root);
+
+        let relative = read(&tool, "../navi_test_ws_read_escape_outside/secret.txt")
+            .await
+            .unwrap_err();
+        assert!(
+            relative.0.contains("outside the workspace"),
+            "got: {relative}"
+        );
+
+        let absolute = outside.join("secret.txt");
+        let err = read(&tool, &absolute.to_string_lossy()).await.unwrap_err();
+        assert!(err.0.contains("outside the workspace"), "got: {err}");
+
+        std::fs::remove_dir_all(root).ok();
+        std::fs::remove_dir_all(outside).ok();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn read_file_symlink_out_of_root_is_refused() {
+        let root = temp_workspace("read_symlink");
+        let outside = temp_workspace("read_symlink_outside");
+        std::fs::write(outside.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.txt"), root.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("linkdir")).unwrap();
+        let tool = ReadFileTool::new(&root);
+
+        for path in ["link.txt", "linkdir/secret.txt"] {
+            let err = read(&tool, path).await.unwrap_err();
+            assert!(err.0.contains("outside the workspace"), "{path}: {err}");
+        }
+
+        std::fs::remove_dir_all(root).ok();
+        std::fs::remove_dir_all(outside).ok();
+    }
+
+    #[tokio::test]
+    async fn read_file_large_file_is_truncated_with_marker() {
+        let root = temp_workspace("read_large");
+        std::fs::write(root.join("big.txt"), "a".repeat(100)).unwrap();
+        let tool = ReadFileTool::new(&root).with_max_bytes(10);
+
+        let content = read(&tool, "big.txt").await.unwrap();
+        assert!(content.starts_with(&"a".repeat(10)));
+        assert!(!content.starts_with(&"a".repeat(11)));
+        assert!(
+            content.ends_with("[truncated: showing first 10 of 100 bytes]"),
+            "got: {content}"
+        );
+        std::fs::remove_dir_all(root).ok();
+    }
+
+    #[tokio::test]
+    async fn read_file_truncation_respects_char_boundaries() {
+        let root = temp_workspace("read_utf8");
+        std::fs::write(root.join("accents.txt"), "ééééé").unwrap(); // 2 bytes each
+        let tool = ReadFileTool::new(&root).with_max_bytes(5);
+
+        let content = read(&tool, "accents.txt").await.unwrap();
+        assert!(content.starts_with("éé\n\n[truncated: showing first 4 of 10 bytes]"));
+        std::fs::remove_dir_all(root).ok();
+    }
+
+    #[tokio::test]
+    async fn read_file_binary_returns_error() {
+        let root = temp_workspace("read_binary");
+        std::fs::write(root.join("blob.bin"), [0xff, 0xfe, 0x00, 0x80]).unwrap();
+
+        let err = read(&ReadFileTool::new(&root), "blob.bin")
+            .await
+            .unwrap_err();
+        assert!(err.0.contains("not valid UTF-8"), "got: {err}");
+        std::fs::remove_dir_all(root).ok();
     }
 
     #[tokio::test]
@@ -88,21 +268,18 @@ mod tests {
         use crate::core::tools::default_registry;
         use crate::inference::types::ToolCall;
 
-        let path = temp_file("read_reg", "registry test");
-        let registry = default_registry();
+        let root = temp_workspace("read_reg");
+        std::fs::write(root.join("notes.txt"), "registry test").unwrap();
+        let registry = default_registry(&root, &[]);
         let tc = ToolCall {
             id: "fc_io".into(),
             call_id: "call_io".into(),
             name: "read_file".into(),
-            arguments: serde_json::json!({ "file_path": path.to_string_lossy() }).to_string(),
+            arguments: serde_json::json!({ "path": "notes.txt" }).to_string(),
         };
         let result = registry.execute(&tc).await;
         let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(parsed["content"], "registry test");
-        std::fs::remove_file(path).ok();
+        std::fs::remove_dir_all(root).ok();
     }
-
-    // TODO(human): Add a test for reading a binary/non-UTF-8 file.
-    // The tool claims it doesn't support binary files - verify it returns
-    // a clear error with an appropriate message.
 }
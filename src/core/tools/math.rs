@@ -2,6 +2,8 @@
 //!
 //! Math-related tool implementations.
 //! Each tool is a unit struct implementing the `Tool` trait.
+//! `MathOperation` applies one binary operation; `Calculator` evaluates a
+//! whole expression.
 
 use async_trait::async_trait;
 use schemars::JsonSchema;
@@ -76,6 +78,189 @@ impl Tool for MathOperation {
     }
 }
 
+// ── Calculator ──────────────────────────────────────────────────────────────
+
+pub struct Calculator;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CalculatorArgs {
+    /// Arithmetic expression using numbers, + - * / % ^ and parentheses, e.g. "(3 + 3) * 5 / 2"
+    pub expression: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CalculatorOutput {
+    pub result: f64,
+}
+
+#[async_trait]
+impl Tool for Calculator {
+    const NAME: &'static str = "calculator";
+    const DESCRIPTION: &'static str = "Evaluates a whole arithmetic expression in one call. Supports + - * / % ^ (power), unary minus, decimals, and parentheses. Returns the numeric result or an error for invalid expressions or division by zero.";
+    type Args = CalculatorArgs;
+    type Output = CalculatorOutput;
+
+    async fn call(&self, args: CalculatorArgs) -> Result<CalculatorOutput, ToolError> {
+        let result = evaluate(&args.expression)?;
+        if !result.is_finite() {
+            return Err(ToolError(format!(
+                "Result is not a finite number for '{}'",
+                args.expression
+            )));
+        }
+        Ok(CalculatorOutput { result })
+    }
+}
+
+/// Evaluate an arithmetic expression with a small recursive-descent parser.
+///
+/// ```text
+/// expr    = term (('+' | '-') term)*
+/// term    = unary (('*' | '/' | '%') unary)*
+/// unary   = ('-' | '+') unary | power
+/// power   = primary ('^' unary)?        right-associative, binds tighter than unary minus
+/// primary = number | '(' expr ')'
+/// ```
+///
+/// Every level of recursion passes through `unary`, which refuses to go
+/// deeper than `MAX_NESTING` so hostile input can't overflow the stack.
+fn evaluate(expression: &str) -> Result<f64, ToolError> {
+    let mut parser = Parser {
+        chars: expression.chars().collect(),
+        pos: 0,
+        depth: 0,
+    };
+    if expression.trim().is_empty() {
+        return Err(ToolError("Empty expression".into()));
+    }
+    let value = parser.expr()?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(c) => Err(ToolError(format!(
+            "Unexpected '{}' at position {}",
+            c, parser.pos
+        ))),
+    }
+}
+
+/// Deepest nesting of parentheses, signs and exponents `evaluate` accepts.
+const MAX_NESTING: usize = 64;
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    /// Next non-whitespace character, without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> Result<f64, ToolError> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<f64, ToolError> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err(ToolError("Division by zero".into()));
+                }
+                value /= divisor;
+            } else if self.eat('%') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err(ToolError("Division by zero".into()));
+                }
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, ToolError> {
+        if self.depth == MAX_NESTING {
+            return Err(ToolError(format!(
+                "Expression nested more than {MAX_NESTING} levels deep"
+            )));
+        }
+        self.depth += 1;
+        let value = if self.eat('-') {
+            self.unary().map(|v| -v)
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.power()
+        };
+        self.depth -= 1;
+        value
+    }
+
+    fn power(&mut self) -> Result<f64, ToolError> {
+        let base = self.primary()?;
+        if self.eat('^') {
+            let exponent = self.unary()?;
+            Ok(base.powf(exponent))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn primary(&mut self) -> Result<f64, ToolError> {
+        if self.eat('(') {
+            let value = self.expr()?;
+            if !self.eat(')') {
+                return Err(ToolError(format!("Expected ')' at position {}", self.pos)));
+            }
+            return Ok(value);
+        }
+
+        self.peek();
+        let start = self.pos;
+        while matches!(self.chars.get(self.pos), Some(c) if c.is_ascii_digit() || *c == '.') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(match self.peek() {
+                Some(c) => ToolError(format!("Unexpected '{}' at position {}", c, self.pos)),
+                None => ToolError("Unexpected end of expression".into()),
+            });
+        }
+        let literal: String = self.chars[start..self.pos].iter().collect();
+        literal
+            .parse()
+            .map_err(|_| ToolError(format!("Invalid number '{}'", literal)))
+    }
+}
+
 // ── Tests ───────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -177,4 +362,62 @@ mod tests {
         let json = r#"{"operation": "modulo", "a": 1, "b": 2}"#;
         assert!(serde_json::from_str::<MathOperationArgs>(json).is_err());
     }
+
+    // ── Calculator ──────────────────────────────────────────────────────────
+
+    async fn calc(expression: &str) -> Result<f64, ToolError> {
+        Calculator
+            .call(CalculatorArgs {
+                expression: expression.to_string(),
+            })
+            .await
+            .map(|out| out.result)
+    }
+
+    #[tokio::test]
+    async fn calculator_evaluates_expressions() {
+        assert_eq!(calc("1 + 2").await.unwrap(), 3.0);
+        assert_eq!(calc("((3+3) * 5) / 2").await.unwrap(), 15.0);
+        assert_eq!(calc("2 + 3 * 4").await.unwrap(), 14.0);
+        assert_eq!(calc("10 - 4 - 3").await.unwrap(), 3.0);
+        assert_eq!(calc("2 ^ 3 ^ 2").await.unwrap(), 512.0);
+        assert_eq!(calc("-2 ^ 2").await.unwrap(), -4.0);
+        assert_eq!(calc("-(1.5 + 0.5) * -3").await.unwrap(), 6.0);
+        assert_eq!(calc("7 % 3").await.unwrap(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn calculator_division_by_zero_returns_error() {
+        let err = calc("1 / (2 - 2)").await.unwrap_err();
+        assert!(err.0.contains("Division by zero"), "got: {err}");
+        let err = calc("5 % 0").await.unwrap_err();
+        assert!(err.0.contains("Division by zero"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn calculator_parse_failures_return_error() {
+        for bad in ["", "1 +", "(1 + 2", "1 + 2)", "2 * x", "1..2", "3 4"] {
+            assert!(calc(bad).await.is_err(), "expected error for {bad:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn calculator_deep_nesting_returns_error() {
+        let parens = format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000));
+        let signs = format!("{}1", "-".repeat(10_000));
+        let powers = format!("1{}", "^1".repeat(10_000));
+        for deep in [parens, signs, powers] {
+            let err = calc(&deep).await.unwrap_err();
+            assert!(err.0.contains("nested"), "got: {err}");
+        }
+
+        let nested = format!("{}1{}", "(".repeat(20), ")".repeat(20));
+        assert_eq!(calc(&nested).await.unwrap(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn calculator_non_finite_result_returns_error() {
+        let err = calc("(-1) ^ 0.5").await.unwrap_err();
+        assert!(err.0.contains("finite"), "got: {err}");
+    }
 }
@@ -9,6 +9,10 @@
 
 pub mod math;
 pub mod io;
+pub mod fetch;
+pub mod dispatch;
+
+use std::path::Path;
 
 use async_trait::async_trait;
 use schemars::JsonSchema;
@@ -116,11 +120,17 @@ impl ToolRegistry {
     }
 }
 
-/// Creates a registry with all built-in tools.
-pub fn default_registry() -> ToolRegistry {
+/// Creates a registry with all built-in tools. File tools are confined to
+/// `workspace_root`; `fetch_url` is only registered when `fetch_allowlist`
+/// names at least one host.
+pub fn default_registry(workspace_root: &Path, fetch_allowlist: &[String]) -> ToolRegistry {
     let mut registry = ToolRegistry::new();
     registry.register(math::MathOperation);
-    registry.register(io::ReadFileTool);
+    registry.register(math::Calculator);
+    registry.register(io::ReadFileTool::new(workspace_root));
+    if !fetch_allowlist.is_empty() {
+        registry.register(fetch::FetchUrlTool::new(fetch_allowlist.to_vec()));
+    }
     registry
 }
 
@@ -132,7 +142,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_registry_execute() {
-        let registry = default_registry();
+        let registry = default_registry(Path::new("."), &[]);
         let tc = ToolCall {
             id: "fc_1".into(),
             call_id: "call_1".into(),
@@ -143,9 +153,29 @@ mod tests {
         assert_eq!(result, r#"{"result":10.0}"#);
     }
 
+    #[tokio::test]
+    async fn test_registry_execute_calculator() {
+        let registry = default_registry(Path::new("."), &[]);
+        let tc = ToolCall {
+            id: "fc_4".into(),
+            call_id: "call_4".into(),
+            name: "calculator".into(),
+            arguments: r#"{"expression": "(3 + 3) * 5 / 2"}"#.into(),
+        };
+        let result = registry.execute(&tc).await;
+        assert_eq!(result, r#"{"result":15.0}"#);
+
+        let tc = ToolCall {
+            arguments: r#"{"expression": "1 / 0"}"#.into(),
+            ..tc
+        };
+        let result = registry.execute(&tc).await;
+        assert!(result.contains("Division by zero"));
+    }
+
     #[tokio::test]
     async fn test_registry_bad_args() {
-        let registry = default_registry();
+        let registry = default_registry(Path::new("."), &[]);
         let tc = ToolCall {
             id: "fc_2".into(),
             call_id: "call_2".into(),
@@ -158,7 +188,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_registry_unknown_tool() {
-        let registry = default_registry();
+        let registry = default_registry(Path::new("."), &[]);
         let tc = ToolCall {
             id: "fc_3".into(),
             call_id: "call_3".into(),
@@ -171,17 +201,26 @@ mod tests {
 
     #[test]
     fn test_definitions_lists_all_tools() {
-        let registry = default_registry();
+        let registry = default_registry(Path::new("."), &[]);
         let defs = registry.definitions();
-        assert_eq!(defs.len(), 2);
+        assert_eq!(defs.len(), 3);
         let names: Vec<&str> = defs.iter().map(|d| d.name.as_str()).collect();
         assert!(names.contains(&"math_operation"));
+        assert!(names.contains(&"calculator"));
         assert!(names.contains(&"read_file"));
     }
 
+    #[test]
+    fn test_fetch_tool_registered_only_with_allowlist() {
+        let registry = default_registry(Path::new("."), &["docs.rs".to_string()]);
+        let defs = registry.definitions();
+        assert_eq!(defs.len(), 4);
+        assert!(defs.iter().any(|d| d.name == "fetch_url"));
+    }
+
     #[test]
     fn test_math_schema_has_properties_and_required() {
-        let registry = default_registry();
+        let registry = default_registry(Path::new("."), &[]);
         let defs = registry.definitions();
         let math_def = defs.iter().find(|d| d.name == "math_operation").unwrap();
         let params = &math_def.parameters;
@@ -0,0 +1,143 @@
+//! # Usage Log
+//!
+//! Appends one CSV row per completed turn to `~/.navi/usage.csv`, for
+//! analyzing token spend over time in a spreadsheet. The header is written
+//! when the file is created; missing values are left as empty cells.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+
+use crate::inference::{Pricing, UsageStats};
+
+const CSV_HEADER: &str =
+    "timestamp,model,input_tokens,output_tokens,total_tokens,ttft_ms,tokens_per_sec,cost_usd";
+
+/// Returns `~/.navi/usage.csv`, creating `~/.navi/` if needed.
+pub fn usage_csv_path() -> io::Result<PathBuf> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+    let dir = home.join(".navi");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join("usage.csv"))
+}
+
+/// Append a row for one completed turn to `~/.navi/usage.csv`.
+/// `pricing` fills the cost column; without it the cell stays empty.
+pub fn append_csv_row(
+    stats: &UsageStats,
+    model: &str,
+    pricing: Option<&Pricing>,
+) -> io::Result<()> {
+    append_row_to(&usage_csv_path()?, stats, model, pricing)
+}
+
+fn append_row_to(
+    path: &Path,
+    stats: &UsageStats,
+    model: &str,
+    pricing: Option<&Pricing>,
+) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", CSV_HEADER)?;
+    }
+    writeln!(file, "{}", csv_row(stats, model, pricing))
+}
+
+/// Format one row matching `CSV_HEADER`.
+fn csv_row(stats: &UsageStats, model: &str, pricing: Option<&Pricing>) -> String {
+    fn cell<T: ToString>(value: Option<T>) -> String {
+        value.map(|v| v.to_string()).unwrap_or_default()
+    }
+    let cost = pricing.and_then(|p| stats.cost(p));
+    [
+        Utc::now().to_rfc3339(),
+        csv_escape(model),
+        cell(stats.input_tokens),
+        cell(stats.output_tokens),
+        cell(stats.total()),
+        cell(stats.ttft_ms),
+        cell(stats.tokens_per_sec.map(|tps| format!("{tps:.1}"))),
+        cell(cost.map(|c| format!("{c:.6}"))),
+    ]
+    .join(",")
+}
+
+/// Quote a field if it contains a comma, quote, or newline (RFC 4180).
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_writes_header_once_and_rows_parse() {
+        let path = std::env::temp_dir().join("navi_test_usage.csv");
+        let _ = fs::remove_file(&path);
+        let pricing = Pricing {
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+            cache_read_per_million: None,
+        };
+        let first = UsageStats {
+            input_tokens: Some(1000),
+            output_tokens: Some(200),
+            ttft_ms: Some(340),
+            tokens_per_sec: Some(28.54),
+            ..Default::default()
+        };
+        let second = UsageStats {
+            output_tokens: Some(5),
+            ..Default::default()
+        };
+
+        append_row_to(&path, &first, "anthropic/claude-sonnet-4", Some(&pricing)).unwrap();
+        append_row_to(&path, &second, "local, model", None).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(content.matches("timestamp,").count(), 1);
+
+        let row: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(row.len(), 8);
+        assert!(chrono::DateTime::parse_from_rfc3339(row[0]).is_ok());
+        assert_eq!(
+            &row[1..],
+            [
+                "anthropic/claude-sonnet-4",
+                "1000",
+                "200",
+                "1200",
+                "340",
+                "28.5",
+                "0.006000"
+            ]
+        );
+
+        // Quoted model name keeps the column count intact
+        let rest = lines[2].split_once(',').unwrap().1;
+        assert_eq!(rest, "\"local, model\",,5,5,,,");
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("plain/model"), "plain/model");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
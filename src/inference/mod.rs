@@ -1,33 +1,209 @@
 pub mod model_discovery;
+pub mod prompt_vars;
 pub mod provider;
 pub mod providers;
 pub mod types;
 
 use std::sync::Arc;
 
+use log::warn;
+
 use crate::core::config::ResolvedConfig;
 
 pub use provider::{CompletionProvider, CompletionRequest, ProviderError};
-pub use providers::{LmStudioProvider, OpenRouterProvider};
+pub use providers::{
+    AzureOpenAiProvider, FallbackProvider, LmStudioProvider, OpenRouterProvider, build_client,
+};
 pub use types::{
-    Context, ContextItem, ContextSegment, Effort, Source, StreamChunk, ToolCall, ToolDefinition,
-    ToolResult, UsageStats,
+    Context, ContextItem, ContextSegment, Effort, ImageAttachment, LiveRate, Pricing,
+    RateLimitInfo, ReasoningMode, Source, StreamChunk, ToolCall, ToolDefinition, ToolResult,
+    TurnUsage, UsageStats,
 };
 
 /// Build a provider from a resolved config's provider name and credentials.
-pub fn build_provider(config: &ResolvedConfig) -> Arc<dyn CompletionProvider> {
-    match config.provider.as_str() {
-        "lmstudio" => Arc::new(LmStudioProvider::new(config.lmstudio_base_url.clone())),
+/// With `fallback_providers` configured, the primary is wrapped in a
+/// `FallbackProvider` followed by each distinct fallback and its model. All
+/// of them share one HTTP client built from the `[http]` settings.
+///
+/// Entries without credentials are skipped with a warning. If none is left,
+/// the error is the guidance for configuring the primary.
+pub fn build_provider(
+    config: &ResolvedConfig,
+) -> Result<Arc<dyn CompletionProvider>, &'static str> {
+    let client = build_client(&config.http);
+    let primary = build_named_provider(&config.provider, config, &client);
+    if primary.is_none() {
+        warn!(
+            "Skipping provider '{}': credentials not configured",
+            config.provider
+        );
+    }
+
+    let mut fallbacks = Vec::new();
+    let mut seen = Vec::new();
+    for fallback in &config.fallback_providers {
+        if seen.contains(&fallback) {
+            continue;
+        }
+        seen.push(fallback);
+        match build_named_provider(&fallback.provider, config, &client) {
+            Some(provider) => fallbacks.push((provider, fallback.model.clone())),
+            None => warn!(
+                "Skipping fallback provider '{}': credentials not configured",
+                fallback.provider
+            ),
+        }
+    }
+
+    let mut fallbacks = fallbacks.into_iter();
+    let mut chain = match (primary, fallbacks.next()) {
+        (Some(primary), None) => return Ok(Arc::from(primary)),
+        (None, None) => return Err(credentials_hint(&config.provider)),
+        (Some(primary), Some((provider, model))) => {
+            FallbackProvider::new(primary).with_fallback(provider, model)
+        }
+        (None, Some((provider, model))) => FallbackProvider::from_fallback(provider, model),
+    };
+    for (provider, model) in fallbacks {
+        chain = chain.with_fallback(provider, model);
+    }
+    Ok(Arc::new(chain))
+}
+
+/// Stand-in for when `build_provider` fails at startup: a keyless OpenRouter
+/// provider. Sends are refused with guidance until credentials are set
+/// (`App::missing_credentials`), so it never makes a request.
+pub fn unconfigured_provider(config: &ResolvedConfig) -> Arc<dyn CompletionProvider> {
+    Arc::from(openrouter_provider(
+        String::new(),
+        config,
+        &build_client(&config.http),
+    ))
+}
+
+/// Whether `name` has what it needs to authenticate: an API key for
+/// OpenRouter (the default for unknown names), an endpoint, deployment and
+/// key for Azure. LM Studio needs nothing.
+pub fn has_credentials(name: &str, config: &ResolvedConfig) -> bool {
+    let set = |value: &Option<String>| value.as_deref().is_some_and(|v| !v.trim().is_empty());
+    match name {
+        "lmstudio" => true,
+        "azure" => {
+            set(&config.azure_api_key)
+                && set(&config.azure_endpoint)
+                && set(&config.azure_deployment)
+        }
+        _ => set(&config.openrouter_api_key),
+    }
+}
+
+/// What to tell the user when `name` lacks credentials.
+pub fn credentials_hint(name: &str) -> &'static str {
+    match name {
+        "azure" => {
+            "Set [azure] endpoint, deployment and api_key (or AZURE_OPENAI_ENDPOINT and AZURE_OPENAI_API_KEY)"
+        }
+        _ => "Set OPENROUTER_API_KEY or [openrouter] api_key",
+    }
+}
+
+/// Construct a single provider by name, or None if `has_credentials` says
+/// it can't authenticate.
+fn build_named_provider(
+    name: &str,
+    config: &ResolvedConfig,
+    client: &reqwest::Client,
+) -> Option<Box<dyn CompletionProvider>> {
+    if !has_credentials(name, config) {
+        return None;
+    }
+    match name {
+        "lmstudio" => Some(Box::new(
+            LmStudioProvider::new(config.lmstudio_base_url.clone())
+                .with_disable_reasoning_flag(config.lmstudio_disable_reasoning_flag)
+                .with_stream_log(config.stream_log.clone())
+                .with_max_tool_args_bytes(config.max_tool_args_bytes)
+                .with_client(client.clone()),
+        )),
+        "azure" => Some(Box::new(
+            AzureOpenAiProvider::new(
+                config.azure_api_key.clone()?,
+                config.azure_endpoint.clone()?,
+                config.azure_deployment.clone()?,
+                config.azure_api_version.clone(),
+            )
+            .with_stream_log(config.stream_log.clone())
+            .with_max_tool_args_bytes(config.max_tool_args_bytes)
+            .with_client(client.clone()),
+        )),
         _ => {
             // Default to openrouter
-            let api_key = config
-                .openrouter_api_key
-                .clone()
-                .expect("OpenRouter API key must be set (config file, OPENROUTER_API_KEY env var, or --provider lmstudio)");
-            Arc::new(OpenRouterProvider::new(
-                api_key,
-                Some(config.openrouter_base_url.clone()),
-            ))
+            let api_key = config.openrouter_api_key.clone()?;
+            Some(openrouter_provider(api_key, config, client))
         }
     }
 }
+
+fn openrouter_provider(
+    api_key: String,
+    config: &ResolvedConfig,
+    client: &reqwest::Client,
+) -> Box<dyn CompletionProvider> {
+    Box::new(
+        OpenRouterProvider::new(api_key, Some(config.openrouter_base_url.clone()))
+            .with_routing(
+                config.openrouter_provider_order.clone(),
+                config.openrouter_allow_fallbacks,
+            )
+            .with_disable_reasoning_flag(config.openrouter_disable_reasoning_flag)
+            .with_stream_log(config.stream_log.clone())
+            .with_max_tool_args_bytes(config.max_tool_args_bytes)
+            .with_client(client.clone()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::FallbackEntry;
+    use crate::test_support::test_app;
+
+    fn azure_config() -> ResolvedConfig {
+        let mut config = test_app().config;
+        config.provider = "azure".to_string();
+        config.azure_api_key = Some("az-key".to_string());
+        config.azure_endpoint = None;
+        config.azure_deployment = Some("gpt-4o".to_string());
+        config
+    }
+
+    #[test]
+    fn test_build_provider_without_credentials_returns_guidance() {
+        let Err(hint) = build_provider(&azure_config()) else {
+            panic!("expected missing credentials");
+        };
+        assert_eq!(hint, credentials_hint("azure"));
+    }
+
+    #[test]
+    fn test_build_provider_skips_primary_without_credentials() {
+        let mut config = azure_config();
+        config.fallback_providers = vec![FallbackEntry {
+            provider: "lmstudio".to_string(),
+            model: "qwen3-8b".to_string(),
+        }];
+        assert!(build_provider(&config).is_ok());
+    }
+
+    #[test]
+    fn test_has_credentials_ignores_blank_values() {
+        let mut config = azure_config();
+        assert!(!has_credentials("azure", &config));
+        config.azure_endpoint = Some("https://example.openai.azure.com".to_string());
+        assert!(has_credentials("azure", &config));
+
+        config.openrouter_api_key = Some("  ".to_string());
+        assert!(!has_credentials("openrouter", &config));
+        assert!(has_credentials("lmstudio", &config));
+    }
+}
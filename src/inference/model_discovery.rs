@@ -30,6 +30,9 @@ struct OpenRouterModel {
     /// Human-readable display name (e.g. "Anthropic: Claude Sonnet 4").
     #[serde(default)]
     name: String,
+    /// Maximum context length in tokens, when the catalog reports it.
+    #[serde(default)]
+    context_length: Option<u32>,
 }
 
 /// Fetches all models from the OpenRouter API.
@@ -80,6 +83,8 @@ pub async fn fetch_openrouter_models(
                 name: m.id,
                 provider: "openrouter".to_string(),
                 description,
+                context_window: m.context_length,
+                ..Default::default()
             }
         })
         .collect();
@@ -153,6 +158,7 @@ pub async fn fetch_lmstudio_models(base_url: &str) -> Result<Vec<ModelEntry>, St
             name: m.id,
             provider: "lmstudio".to_string(),
             description: None,
+            ..Default::default()
         })
         .collect();
 
@@ -184,6 +190,14 @@ mod tests {
         let json = r#"{"data": [{"id": "some/model"}]}"#;
         let response: OpenRouterModelsResponse = serde_json::from_str(json).unwrap();
         assert_eq!(response.data[0].name, "");
+        assert_eq!(response.data[0].context_length, None);
+    }
+
+    #[test]
+    fn test_openrouter_model_context_length_deserializes() {
+        let json = r#"{"data": [{"id": "some/model", "context_length": 200000}]}"#;
+        let response: OpenRouterModelsResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.data[0].context_length, Some(200000));
     }
 
     #[test]
@@ -204,11 +218,13 @@ mod tests {
         let model = OpenRouterModel {
             id: "anthropic/claude-sonnet-4".to_string(),
             name: "Anthropic: Claude Sonnet 4".to_string(),
+            context_length: None,
         };
         let entry = ModelEntry {
             name: model.id,
             provider: "openrouter".to_string(),
             description: Some(model.name),
+            ..Default::default()
         };
         assert_eq!(entry.name, "anthropic/claude-sonnet-4");
         assert_eq!(
@@ -222,6 +238,7 @@ mod tests {
         let model = OpenRouterModel {
             id: "some/model".to_string(),
             name: String::new(),
+            context_length: None,
         };
         let description = if model.name.is_empty() {
             None
@@ -240,6 +257,7 @@ mod tests {
             name: model.id,
             provider: "lmstudio".to_string(),
             description: None,
+            ..Default::default()
         };
         assert_eq!(entry.name, "qwen2.5-coder-32b");
         assert_eq!(entry.provider, "lmstudio");
@@ -0,0 +1,110 @@
+//! # System Prompt Variables
+//!
+//! The system prompt may contain `{{date}}`, `{{os}}` and `{{cwd}}`. They're
+//! expanded each time a session's context is created, so a conversation
+//! started tomorrow sees tomorrow's date. Anything else in double braces is
+//! left as written, with a warning in the log.
+
+use chrono::Local;
+use log::warn;
+
+/// Placeholder names `expand` substitutes.
+pub const PLACEHOLDERS: &[&str] = &["date", "os", "cwd"];
+
+/// `template` with every known placeholder replaced by its current value.
+pub fn expand(template: &str) -> String {
+    expand_with(template, &current_value)
+}
+
+/// Names in double braces that `expand` would leave untouched.
+pub fn unknown_placeholders(template: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for_each_placeholder(template, |name| {
+        if !PLACEHOLDERS.contains(&name) {
+            unknown.push(name.to_string());
+        }
+    });
+    unknown
+}
+
+/// The value a placeholder stands for right now, or None for unknown names.
+fn current_value(name: &str) -> Option<String> {
+    match name {
+        "date" => Some(Local::now().format("%Y-%m-%d").to_string()),
+        "os" => Some(std::env::consts::OS.to_string()),
+        "cwd" => std::env::current_dir()
+            .ok()
+            .map(|dir| dir.display().to_string()),
+        _ => None,
+    }
+}
+
+/// `expand` with an injectable lookup, so tests get fixed values.
+fn expand_with(template: &str, value: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some((before, name, after)) = next_placeholder(rest) {
+        out.push_str(before);
+        match value(name) {
+            Some(v) => out.push_str(&v),
+            None => {
+                warn!("Unknown system prompt placeholder \"{}\", left as is", name);
+                out.push_str(&rest[before.len()..rest.len() - after.len()]);
+            }
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+fn for_each_placeholder(template: &str, mut f: impl FnMut(&str)) {
+    let mut rest = template;
+    while let Some((_, name, after)) = next_placeholder(rest) {
+        f(name);
+        rest = after;
+    }
+}
+
+/// Split `text` around its first `{{name}}`: the text before it, the
+/// trimmed name, and the text after it.
+fn next_placeholder(text: &str) -> Option<(&str, &str, &str)> {
+    let start = text.find("{{")?;
+    let len = text[start + 2..].find("}}")?;
+    let name = text[start + 2..start + 2 + len].trim();
+    Some((&text[..start], name, &text[start + 2 + len + 2..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_expands_to_iso_date() {
+        let expanded = expand("Today is {{date}}.");
+        let date = expanded
+            .strip_prefix("Today is ")
+            .and_then(|s| s.strip_suffix('.'))
+            .unwrap();
+        assert!(chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok());
+        assert_eq!(date.len(), 10);
+    }
+
+    #[test]
+    fn test_unknown_placeholders_preserved() {
+        let value = |name: &str| (name == "os").then(|| "linux".to_string());
+        assert_eq!(
+            expand_with("On {{ os }}, ask {{user}} {{", &value),
+            "On linux, ask {{user}} {{"
+        );
+        assert_eq!(
+            unknown_placeholders("{{date}} {{user}} {{ shell }}"),
+            ["user", "shell"]
+        );
+    }
+
+    #[test]
+    fn test_text_without_placeholders_unchanged() {
+        assert_eq!(expand("Be terse. {not one}"), "Be terse. {not one}");
+    }
+}
@@ -1,18 +1,33 @@
+use std::error::Error;
 use std::fmt;
 
 use async_trait::async_trait;
 use tokio::sync::mpsc::Sender;
 
-use super::types::{Context, Effort, StreamChunk, ToolDefinition};
+use super::types::{Context, Effort, ReasoningMode, StreamChunk, ToolDefinition};
 
 /// Errors that can occur during provider operations.
-/// Variants carry enough info to determine retryability (future use).
+/// Variants carry enough info to determine retryability.
 #[derive(Debug)]
 pub enum ProviderError {
-    /// Network-level failure (timeout, DNS, connection refused). Retryable.
+    /// The server couldn't be reached (DNS, connection refused). Retryable.
+    Connect(String),
+    /// The request or response took too long. Retryable.
+    Timeout(String),
+    /// TLS handshake or certificate failure. Not retryable: it fails the
+    /// same way until the setup changes.
+    Tls(String),
+    /// The response body broke off or couldn't be read. Retryable.
+    Decode(String),
+    /// Any other transport failure. Retryable.
     Network(String),
     /// API returned an error response. Retryable if status >= 500 or 429.
-    Api { status: u16, message: String },
+    /// `request_id` is the provider's id for the request, for support tickets.
+    Api {
+        status: u16,
+        message: String,
+        request_id: Option<String>,
+    },
     /// The mpsc channel was closed (TUI dropped the receiver). Not retryable.
     ChannelClosed,
 }
@@ -20,24 +35,108 @@ pub enum ProviderError {
 impl fmt::Display for ProviderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            ProviderError::Connect(msg) => write!(
+                f,
+                "could not connect: {msg} (check the base URL and that the server is running)"
+            ),
+            ProviderError::Timeout(msg) => write!(
+                f,
+                "request timed out: {msg} (the provider may be overloaded; try again)"
+            ),
+            ProviderError::Tls(msg) => write!(
+                f,
+                "TLS error: {msg} (check the endpoint's certificate, or a proxy intercepting HTTPS)"
+            ),
+            ProviderError::Decode(msg) => write!(f, "could not read the response: {msg}"),
             ProviderError::Network(msg) => write!(f, "network error: {msg}"),
-            ProviderError::Api { status, message } => {
-                write!(f, "API error (HTTP {status}): {message}")
+            ProviderError::Api {
+                status,
+                message,
+                request_id,
+            } => {
+                write!(f, "API error (HTTP {status}): {message}")?;
+                if let Some(id) = request_id {
+                    write!(f, " (request id: {id})")?;
+                }
+                Ok(())
             }
             ProviderError::ChannelClosed => write!(f, "channel closed"),
         }
     }
 }
 
-impl std::error::Error for ProviderError {}
+impl ProviderError {
+    /// Transient failures worth trying again (or on another provider).
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ProviderError::Connect(_)
+            | ProviderError::Timeout(_)
+            | ProviderError::Decode(_)
+            | ProviderError::Network(_) => true,
+            ProviderError::Api { status, .. } => *status >= 500 || *status == 429,
+            ProviderError::Tls(_) | ProviderError::ChannelClosed => false,
+        }
+    }
+}
+
+impl Error for ProviderError {}
+
+/// Sorts a failed request or body read by what went wrong, keeping the
+/// whole cause chain as the message; reqwest's own message is just "error
+/// sending request".
+impl From<reqwest::Error> for ProviderError {
+    fn from(e: reqwest::Error) -> Self {
+        let message = cause_chain(&e);
+        if e.is_timeout() {
+            ProviderError::Timeout(message)
+        } else if e.is_connect() && is_tls_failure(&message) {
+            ProviderError::Tls(message)
+        } else if e.is_connect() {
+            ProviderError::Connect(message)
+        } else if e.is_decode() || e.is_body() {
+            ProviderError::Decode(message)
+        } else {
+            ProviderError::Network(message)
+        }
+    }
+}
+
+/// `e` and each error under it, joined with ": ".
+fn cause_chain(e: &dyn Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// reqwest reports TLS failures as connect errors; only the causes tell
+/// them apart.
+fn is_tls_failure(chain: &str) -> bool {
+    let chain = chain.to_ascii_lowercase();
+    ["certificate", "tls", "ssl", "handshake"]
+        .iter()
+        .any(|word| chain.contains(word))
+}
 
 /// Everything a provider needs to fulfill a completion request.
 pub struct CompletionRequest<'a> {
     pub context: &'a Context,
     pub model: &'a str,
     pub effort: Effort,
+    pub reasoning: bool, // false = omit the reasoning block; the model has none
     pub tools: &'a [ToolDefinition], // empty slice = no tools
     pub max_output_tokens: Option<u32>,
+    pub temperature: Option<f32>, // None = provider default
+    pub reasoning_mode: ReasoningMode,
+    pub dedupe_stream: bool, // drop exact immediate repeats of a delta
+    pub prompt_cache: bool,  // mark the context's cache watermark where supported
+    /// One-off instruction sent as an extra system message for this request
+    /// only; it is never stored in the `Context`.
+    pub turn_directive: Option<&'a str>,
 }
 
 #[async_trait]
@@ -49,3 +148,70 @@ pub trait CompletionProvider: Send + Sync {
         sender: Sender<StreamChunk>,
     ) -> Result<(), ProviderError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn request_error(client: reqwest::Client, url: &str) -> ProviderError {
+        client.get(url).send().await.unwrap_err().into()
+    }
+
+    #[tokio::test]
+    async fn test_reqwest_errors_are_classified() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Nothing listens on a port just released
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let refused =
+            request_error(reqwest::Client::new(), &format!("http://127.0.0.1:{port}")).await;
+        assert!(
+            matches!(refused, ProviderError::Connect(_)),
+            "got: {refused:?}"
+        );
+        assert!(refused.is_retryable());
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("not json")
+                    .set_delay(std::time::Duration::from_millis(500)),
+            )
+            .mount(&server)
+            .await;
+
+        let impatient = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let timeout = request_error(impatient, &server.uri()).await;
+        assert!(
+            matches!(timeout, ProviderError::Timeout(_)),
+            "got: {timeout:?}"
+        );
+
+        // A TLS handshake against a plain HTTP server
+        let https = server.uri().replace("http://", "https://");
+        let tls = request_error(reqwest::Client::new(), &https).await;
+        assert!(matches!(tls, ProviderError::Tls(_)), "got: {tls:?}");
+        assert!(!tls.is_retryable());
+
+        let body = reqwest::get(server.uri()).await.unwrap();
+        let decode: ProviderError = body.json::<serde_json::Value>().await.unwrap_err().into();
+        assert!(
+            matches!(decode, ProviderError::Decode(_)),
+            "got: {decode:?}"
+        );
+        assert!(
+            decode
+                .to_string()
+                .starts_with("could not read the response")
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Azure OpenAI provider.
+//!
+//! Azure serves the same Responses API as OpenRouter, so request building
+//! and SSE parsing are shared with `OpenRouterProvider`. Only the endpoint
+//! shape differs: each deployment has its own path,
+//! `{endpoint}/openai/deployments/{deployment}/responses?api-version=...`,
+//! and the key goes in an `api-key` header rather than a bearer token.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc::Sender;
+
+use super::openrouter::OpenRouterProvider;
+use crate::inference::{CompletionProvider, CompletionRequest, ProviderError, StreamChunk};
+
+/// Azure OpenAI provider for a single deployment.
+pub struct AzureOpenAiProvider {
+    inner: OpenRouterProvider,
+}
+
+impl AzureOpenAiProvider {
+    /// Creates a provider for `deployment` on the Azure resource at
+    /// `endpoint` (e.g. `https://my-resource.openai.azure.com`).
+    pub fn new(api_key: String, endpoint: String, deployment: String, api_version: String) -> Self {
+        Self {
+            inner: OpenRouterProvider::new(api_key, Some(endpoint))
+                .with_azure_deployment(deployment, api_version),
+        }
+    }
+
+    /// Append every raw SSE line of each stream to `path`, if set.
+    pub fn with_stream_log(mut self, path: Option<PathBuf>) -> Self {
+        self.inner = self.inner.with_stream_log(path);
+        self
+    }
+
+    /// Send requests through `client`, sharing its connection pool.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.inner = self.inner.with_client(client);
+        self
+    }
+
+    /// Abandon a tool call whose streamed arguments pass `bytes`.
+    pub fn with_max_tool_args_bytes(mut self, bytes: usize) -> Self {
+        self.inner = self.inner.with_max_tool_args_bytes(bytes);
+        self
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for AzureOpenAiProvider {
+    async fn stream_completion(
+        &self,
+        request: CompletionRequest<'_>,
+        sender: Sender<StreamChunk>,
+    ) -> Result<(), ProviderError> {
+        self.inner.stream_completion(request, sender).await
+    }
+}
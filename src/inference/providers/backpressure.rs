@@ -0,0 +1,79 @@
+//! # Chunk Backpressure
+//!
+//! Providers hand stream chunks to the UI over a bounded channel. When the
+//! UI falls behind, the channel fills and the provider has to wait. That
+//! wait used to be silent; `send_chunk` tries a non-blocking send first and
+//! only awaits space, with a log line, when the channel is full.
+
+use std::time::Instant;
+
+use log::info;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::mpsc::error::{SendError, TrySendError};
+
+use crate::inference::StreamChunk;
+
+/// Send `chunk`, waiting for space only when the channel is full. Fails
+/// only when the receiver has been dropped.
+pub(super) async fn send_chunk(
+    sender: &Sender<StreamChunk>,
+    chunk: StreamChunk,
+) -> Result<(), SendError<StreamChunk>> {
+    match sender.try_send(chunk) {
+        Ok(()) => Ok(()),
+        Err(TrySendError::Closed(chunk)) => Err(SendError(chunk)),
+        Err(TrySendError::Full(chunk)) => {
+            let started = Instant::now();
+            let sent = sender.send(chunk).await;
+            info!(
+                "Stream channel full ({} slots), waited {:?} for the consumer",
+                sender.max_capacity(),
+                started.elapsed()
+            );
+            sent
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn content(n: usize) -> StreamChunk {
+        StreamChunk::Content {
+            text: n.to_string(),
+            item_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_receives_every_chunk_in_order() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let producer = tokio::spawn(async move {
+            for n in 0..20 {
+                send_chunk(&tx, content(n)).await.unwrap();
+            }
+        });
+
+        let mut received = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            tokio::time::sleep(Duration::from_millis(2)).await;
+            if let StreamChunk::Content { text, .. } = chunk {
+                received.push(text);
+            }
+        }
+        producer.await.unwrap();
+
+        let expected: Vec<String> = (0..20).map(|n| n.to_string()).collect();
+        assert_eq!(received, expected);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_receiver_is_an_error() {
+        let (tx, rx) = mpsc::channel(1);
+        drop(rx);
+        assert!(send_chunk(&tx, content(0)).await.is_err());
+    }
+}
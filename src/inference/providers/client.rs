@@ -0,0 +1,131 @@
+//! # HTTP Client
+//!
+//! Providers share one `reqwest::Client` built from the `[http]` config, so
+//! its pool of open connections carries over between agentic rounds, and
+//! between a provider and its fallbacks, instead of every round paying for
+//! a fresh TCP and TLS handshake. Unset options keep reqwest's defaults.
+
+use std::time::Duration;
+
+use log::warn;
+
+use crate::core::config::HttpConfig;
+
+/// The builder settings `configure` applies, split out so tests can record
+/// them (a built `reqwest::Client` doesn't expose its pool settings).
+trait ClientOptions: Sized {
+    fn pool_max_idle_per_host(self, max: usize) -> Self;
+    fn tcp_keepalive(self, interval: Duration) -> Self;
+    fn http1_only(self) -> Self;
+    fn http2_prior_knowledge(self) -> Self;
+}
+
+impl ClientOptions for reqwest::ClientBuilder {
+    fn pool_max_idle_per_host(self, max: usize) -> Self {
+        reqwest::ClientBuilder::pool_max_idle_per_host(self, max)
+    }
+
+    fn tcp_keepalive(self, interval: Duration) -> Self {
+        reqwest::ClientBuilder::tcp_keepalive(self, interval)
+    }
+
+    fn http1_only(self) -> Self {
+        reqwest::ClientBuilder::http1_only(self)
+    }
+
+    fn http2_prior_knowledge(self) -> Self {
+        reqwest::ClientBuilder::http2_prior_knowledge(self)
+    }
+}
+
+fn configure<B: ClientOptions>(mut builder: B, http: &HttpConfig) -> B {
+    if let Some(max) = http.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max);
+    }
+    if let Some(secs) = http.tcp_keepalive_secs {
+        builder = builder.tcp_keepalive(Duration::from_secs(secs));
+    }
+    match http.http2 {
+        Some(true) => builder.http2_prior_knowledge(),
+        Some(false) => builder.http1_only(),
+        None => builder,
+    }
+}
+
+/// Build the client the providers share. If the settings can't be applied,
+/// a warning is logged and a default client is used instead.
+pub fn build_client(http: &HttpConfig) -> reqwest::Client {
+    configure(reqwest::Client::builder(), http)
+        .build()
+        .unwrap_or_else(|e| {
+            warn!(
+                "Failed to build HTTP client from [http] config, using defaults: {}",
+                e
+            );
+            reqwest::Client::new()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the builder calls instead of making them.
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl ClientOptions for Recorder {
+        fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+            self.0.push(format!("pool_max_idle_per_host({max})"));
+            self
+        }
+
+        fn tcp_keepalive(mut self, interval: Duration) -> Self {
+            self.0.push(format!("tcp_keepalive({interval:?})"));
+            self
+        }
+
+        fn http1_only(mut self) -> Self {
+            self.0.push("http1_only".to_string());
+            self
+        }
+
+        fn http2_prior_knowledge(mut self) -> Self {
+            self.0.push("http2_prior_knowledge".to_string());
+            self
+        }
+    }
+
+    #[test]
+    fn test_configured_values_applied() {
+        let http = HttpConfig {
+            pool_max_idle_per_host: Some(8),
+            tcp_keepalive_secs: Some(30),
+            http2: Some(true),
+        };
+        let calls = configure(Recorder::default(), &http).0;
+        assert_eq!(
+            calls,
+            [
+                "pool_max_idle_per_host(8)",
+                "tcp_keepalive(30s)",
+                "http2_prior_knowledge"
+            ]
+        );
+
+        let http1 = HttpConfig {
+            http2: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(configure(Recorder::default(), &http1).0, ["http1_only"]);
+    }
+
+    #[test]
+    fn test_unset_values_keep_defaults() {
+        assert!(
+            configure(Recorder::default(), &HttpConfig::default())
+                .0
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! # Delta Deduplication
+//!
+//! Some proxies resend the last delta after reconnecting, which doubles text
+//! in the transcript. `DeltaDeduper` drops a delta that exactly repeats the
+//! one right before it (same kind, item, and text). It's opt-in via
+//! `[general] dedupe_stream` because models can legitimately repeat a token.
+
+use log::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum DeltaKind {
+    Content,
+    Thinking,
+}
+
+/// Remembers the last delta of a single stream.
+pub(super) struct DeltaDeduper {
+    enabled: bool,
+    last: Option<(DeltaKind, String, String)>,
+}
+
+impl DeltaDeduper {
+    pub(super) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            last: None,
+        }
+    }
+
+    /// True if this delta is an immediate repeat and should be dropped.
+    /// Always false when deduplication is disabled.
+    pub(super) fn is_repeat(&mut self, kind: DeltaKind, item_id: &str, text: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let repeat = self
+            .last
+            .as_ref()
+            .is_some_and(|(k, id, t)| *k == kind && id == item_id && t == text);
+        if repeat {
+            debug!("Dropping repeated {:?} delta ({} bytes)", kind, text.len());
+        } else {
+            self.last = Some((kind, item_id.to_string(), text.to_string()));
+        }
+        repeat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drops_only_immediate_repeats() {
+        let mut dedupe = DeltaDeduper::new(true);
+        assert!(!dedupe.is_repeat(DeltaKind::Content, "msg_1", "Hello"));
+        assert!(dedupe.is_repeat(DeltaKind::Content, "msg_1", "Hello"));
+        assert!(!dedupe.is_repeat(DeltaKind::Content, "msg_1", " world"));
+        // Not adjacent to the first "Hello" anymore
+        assert!(!dedupe.is_repeat(DeltaKind::Content, "msg_1", "Hello"));
+    }
+
+    #[test]
+    fn test_kind_and_item_distinguish_deltas() {
+        let mut dedupe = DeltaDeduper::new(true);
+        assert!(!dedupe.is_repeat(DeltaKind::Thinking, "rs_1", "ok"));
+        assert!(!dedupe.is_repeat(DeltaKind::Content, "rs_1", "ok"));
+        assert!(!dedupe.is_repeat(DeltaKind::Content, "msg_2", "ok"));
+    }
+
+    #[test]
+    fn test_disabled_keeps_everything() {
+        let mut dedupe = DeltaDeduper::new(false);
+        assert!(!dedupe.is_repeat(DeltaKind::Content, "", "la"));
+        assert!(!dedupe.is_repeat(DeltaKind::Content, "", "la"));
+    }
+}
@@ -0,0 +1,105 @@
+//! # Error Event Payloads
+//!
+//! Both Responses API providers report a failed stream the same way, with an
+//! `error` or `response.failed` SSE event. `parse_error_payload` turns either
+//! payload into the message shown to the user.
+
+use log::debug;
+use serde::Deserialize;
+
+use super::sse::SseData;
+
+/// Payload of the `error` and `response.failed` SSE events.
+/// `error` carries the details at the top level:
+/// `{"type":"error","code":"...","message":"..."}`
+/// while `response.failed` nests them under the response:
+/// `{"type":"response.failed","response":{"status":"failed","error":{"code":"...","message":"..."}}}`
+#[derive(Deserialize, Debug)]
+struct ErrorEventPayload {
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    code: Option<serde_json::Value>,
+    #[serde(default)]
+    response: Option<FailedResponse>,
+}
+
+/// The inner `response` object from the failed event.
+#[derive(Deserialize, Debug)]
+struct FailedResponse {
+    #[serde(default)]
+    error: Option<ErrorDetail>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ErrorDetail {
+    #[serde(default)]
+    message: Option<String>,
+    /// Usually a string (e.g. "rate_limit_exceeded"), occasionally an HTTP status number.
+    #[serde(default)]
+    code: Option<serde_json::Value>,
+}
+
+/// Extracts a human-readable message from an `error` / `response.failed` SSE payload.
+/// Falls back to the raw data when the payload doesn't have the expected shape.
+pub(super) fn parse_error_payload(data: &SseData) -> String {
+    let payload: ErrorEventPayload = match data.parse() {
+        Ok(p) => p,
+        Err(e) => {
+            debug!("Failed to parse error event payload: {}", e);
+            return format!("Provider error: {}", data);
+        }
+    };
+    let detail = match payload.response.and_then(|r| r.error) {
+        Some(nested) => nested,
+        None => ErrorDetail {
+            message: payload.message,
+            code: payload.code,
+        },
+    };
+    let code = detail.code.map(|c| match c {
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    });
+    match (detail.message, code) {
+        (Some(message), Some(code)) => format!("{} ({})", message, code),
+        (Some(message), None) => message,
+        (None, Some(code)) => format!("Provider error ({})", code),
+        (None, None) => String::from("Provider reported an error"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_payload_top_level() {
+        let data = r#"{"type":"error","code":"rate_limit_exceeded","message":"Slow down"}"#;
+        assert_eq!(
+            parse_error_payload(&SseData::from(data)),
+            "Slow down (rate_limit_exceeded)"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_payload_response_failed() {
+        let data = r#"{"type":"response.failed","response":{"status":"failed","error":{"code":502,"message":"Upstream died"}}}"#;
+        assert_eq!(
+            parse_error_payload(&SseData::from(data)),
+            "Upstream died (502)"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_payload_fallbacks() {
+        assert_eq!(
+            parse_error_payload(&SseData::from(r#"{"type":"error"}"#)),
+            "Provider reported an error"
+        );
+        assert_eq!(
+            parse_error_payload(&SseData::from("not json")),
+            "Provider error: not json"
+        );
+    }
+}
@@ -0,0 +1,432 @@
+//! Provider that tries an ordered chain of providers until one responds.
+//!
+//! A provider is abandoned only if it fails with a retryable error (network,
+//! 5xx, 429) *before* emitting its first chunk. Once any chunk has been
+//! forwarded the stream is committed to that provider — switching mid-stream
+//! would interleave two different responses.
+//!
+//! Each attempt's `Completed` chunk is held back until the attempt returns
+//! `Ok`, so the usage it carries is only counted for the attempt that
+//! actually answered. Stats from a failed attempt are dropped, and since
+//! `Completed` alone doesn't commit the stream, such an attempt can still
+//! fall back.
+//!
+//! Model names differ between providers, so each fallback is asked for its
+//! own configured model instead of the one the primary was sent.
+
+use async_trait::async_trait;
+use log::{debug, warn};
+use tokio::sync::mpsc::{self, Sender};
+
+use super::backpressure::send_chunk;
+use crate::inference::{CompletionProvider, CompletionRequest, ProviderError, StreamChunk};
+
+/// Buffer for the per-attempt channel between a provider and the forwarder.
+const ATTEMPT_CHANNEL_SIZE: usize = 100;
+
+pub struct FallbackProvider {
+    /// Each provider with the model to request from it; None (the primary)
+    /// keeps the request's own model.
+    providers: Vec<(Box<dyn CompletionProvider>, Option<String>)>,
+}
+
+impl FallbackProvider {
+    /// `primary` is tried first, with the request's model.
+    pub fn new(primary: Box<dyn CompletionProvider>) -> Self {
+        Self {
+            providers: vec![(primary, None)],
+        }
+    }
+
+    /// A chain that starts at a fallback, for when the primary can't be
+    /// built: every provider is asked for its own model.
+    pub fn from_fallback(provider: Box<dyn CompletionProvider>, model: String) -> Self {
+        Self {
+            providers: vec![(provider, Some(model))],
+        }
+    }
+
+    /// Try `provider` with `model` after the providers added so far.
+    pub fn with_fallback(mut self, provider: Box<dyn CompletionProvider>, model: String) -> Self {
+        self.providers.push((provider, Some(model)));
+        self
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for FallbackProvider {
+    async fn stream_completion(
+        &self,
+        request: CompletionRequest<'_>,
+        sender: Sender<StreamChunk>,
+    ) -> Result<(), ProviderError> {
+        let mut last_error = ProviderError::Network("no providers configured".to_string());
+
+        for (index, (provider, model)) in self.providers.iter().enumerate() {
+            let attempt = CompletionRequest {
+                context: request.context,
+                model: model.as_deref().unwrap_or(request.model),
+                effort: request.effort,
+                reasoning: request.reasoning,
+                tools: request.tools,
+                max_output_tokens: request.max_output_tokens,
+                temperature: request.temperature,
+                reasoning_mode: request.reasoning_mode,
+                dedupe_stream: request.dedupe_stream,
+                prompt_cache: request.prompt_cache,
+                turn_directive: request.turn_directive,
+            };
+
+            // Route through an intermediate channel so we know whether this
+            // attempt emitted anything before it failed.
+            let (attempt_tx, mut attempt_rx) = mpsc::channel(ATTEMPT_CHANNEL_SIZE);
+            let forward = async {
+                let mut emitted = false;
+                let mut completed = None;
+                while let Some(chunk) = attempt_rx.recv().await {
+                    if let StreamChunk::Completed(stats) = chunk {
+                        completed = Some(stats);
+                        continue;
+                    }
+                    // Rate limit headers are metadata, not part of the response
+                    emitted |= !matches!(chunk, StreamChunk::RateLimit(_));
+                    if send_chunk(&sender, chunk).await.is_err() {
+                        // Dropping attempt_rx surfaces ChannelClosed to the provider
+                        break;
+                    }
+                }
+                (emitted, completed)
+            };
+
+            let (result, (emitted, completed)) =
+                tokio::join!(provider.stream_completion(attempt, attempt_tx), forward);
+
+            match result {
+                Ok(()) => {
+                    if let Some(stats) = completed
+                        && send_chunk(&sender, StreamChunk::Completed(stats))
+                            .await
+                            .is_err()
+                    {
+                        return Err(ProviderError::ChannelClosed);
+                    }
+                    return Ok(());
+                }
+                Err(e) if !emitted && e.is_retryable() && index + 1 < self.providers.len() => {
+                    warn!("Provider {} failed ({}), falling back to next", index, e);
+                    if completed.is_some() {
+                        debug!("Discarding usage reported by failed provider {}", index);
+                    }
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::{Context, Effort, ReasoningMode, UsageStats};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Scripted provider: emits `chunks`, then `Completed` carrying
+    /// `usage` if set, then returns `error` if set.
+    struct MockProvider {
+        chunks: Vec<&'static str>,
+        usage: Option<UsageStats>,
+        error: Option<fn() -> ProviderError>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl MockProvider {
+        fn boxed(
+            chunks: Vec<&'static str>,
+            error: Option<fn() -> ProviderError>,
+        ) -> (Box<dyn CompletionProvider>, Arc<AtomicUsize>) {
+            Self::boxed_with_usage(chunks, None, error)
+        }
+
+        fn boxed_with_usage(
+            chunks: Vec<&'static str>,
+            usage: Option<UsageStats>,
+            error: Option<fn() -> ProviderError>,
+        ) -> (Box<dyn CompletionProvider>, Arc<AtomicUsize>) {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let provider = MockProvider {
+                chunks,
+                usage,
+                error,
+                calls: calls.clone(),
+            };
+            (Box::new(provider), calls)
+        }
+    }
+
+    #[async_trait]
+    impl CompletionProvider for MockProvider {
+        async fn stream_completion(
+            &self,
+            _request: CompletionRequest<'_>,
+            sender: Sender<StreamChunk>,
+        ) -> Result<(), ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            for text in &self.chunks {
+                sender
+                    .send(StreamChunk::Content {
+                        text: text.to_string(),
+                        item_id: None,
+                    })
+                    .await
+                    .map_err(|_| ProviderError::ChannelClosed)?;
+            }
+            if let Some(usage) = &self.usage {
+                sender
+                    .send(StreamChunk::Completed(Some(usage.clone())))
+                    .await
+                    .map_err(|_| ProviderError::ChannelClosed)?;
+            }
+            match self.error {
+                Some(error) => Err(error()),
+                None => Ok(()),
+            }
+        }
+    }
+
+    /// `primary` backed by `secondary`, which is asked for "fallback-model".
+    fn chain(
+        primary: Box<dyn CompletionProvider>,
+        secondary: Box<dyn CompletionProvider>,
+    ) -> FallbackProvider {
+        FallbackProvider::new(primary).with_fallback(secondary, "fallback-model".to_string())
+    }
+
+    /// Records the model each request asks for, then fails before streaming.
+    struct ModelRecorder {
+        models: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl CompletionProvider for ModelRecorder {
+        async fn stream_completion(
+            &self,
+            request: CompletionRequest<'_>,
+            _sender: Sender<StreamChunk>,
+        ) -> Result<(), ProviderError> {
+            self.models.lock().unwrap().push(request.model.to_string());
+            Err(server_error())
+        }
+    }
+
+    fn server_error() -> ProviderError {
+        ProviderError::Api {
+            status: 503,
+            message: "unavailable".to_string(),
+            request_id: None,
+        }
+    }
+
+    fn usage(output_tokens: u32) -> UsageStats {
+        UsageStats {
+            input_tokens: Some(100),
+            output_tokens: Some(output_tokens),
+            ..Default::default()
+        }
+    }
+
+    async fn run(provider: &FallbackProvider) -> (Result<(), ProviderError>, Vec<String>) {
+        let (result, chunks) = run_chunks(provider).await;
+        let texts = chunks
+            .into_iter()
+            .filter_map(|chunk| match chunk {
+                StreamChunk::Content { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect();
+        (result, texts)
+    }
+
+    /// Usage summed over every `Completed` chunk, as the TUI accumulates it.
+    fn accumulated_usage(chunks: &[StreamChunk]) -> UsageStats {
+        let mut total = UsageStats::default();
+        for chunk in chunks {
+            if let StreamChunk::Completed(Some(stats)) = chunk {
+                total.accumulate(stats);
+            }
+        }
+        total
+    }
+
+    async fn run_chunks(
+        provider: &FallbackProvider,
+    ) -> (Result<(), ProviderError>, Vec<StreamChunk>) {
+        let context = Context::new();
+        let request = CompletionRequest {
+            context: &context,
+            model: "test-model",
+            effort: Effort::None,
+            reasoning: true,
+            tools: &[],
+            max_output_tokens: None,
+            temperature: None,
+            reasoning_mode: ReasoningMode::default(),
+            dedupe_stream: false,
+            prompt_cache: false,
+            turn_directive: None,
+        };
+        let (tx, mut rx) = mpsc::channel(100);
+        let result = provider.stream_completion(request, tx).await;
+
+        let mut chunks = Vec::new();
+        while let Ok(chunk) = rx.try_recv() {
+            chunks.push(chunk);
+        }
+        (result, chunks)
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_when_primary_fails_before_streaming() {
+        let (primary, primary_calls) = MockProvider::boxed(vec![], Some(server_error));
+        let (secondary, secondary_calls) = MockProvider::boxed(vec!["Hello", " there"], None);
+        let provider = chain(primary, secondary);
+
+        let (result, texts) = run(&provider).await;
+
+        assert!(result.is_ok());
+        assert_eq!(texts, vec!["Hello", " there"]);
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_each_fallback_gets_its_own_model() {
+        let models = Arc::new(Mutex::new(Vec::new()));
+        let recorder = || {
+            Box::new(ModelRecorder {
+                models: models.clone(),
+            })
+        };
+        let provider = FallbackProvider::new(recorder())
+            .with_fallback(recorder(), "qwen3-8b".to_string())
+            .with_fallback(recorder(), "llama3".to_string());
+
+        let (result, _) = run(&provider).await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            *models.lock().unwrap(),
+            ["test-model", "qwen3-8b", "llama3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chain_without_primary_uses_fallback_models() {
+        let models = Arc::new(Mutex::new(Vec::new()));
+        let recorder = || {
+            Box::new(ModelRecorder {
+                models: models.clone(),
+            })
+        };
+        let provider = FallbackProvider::from_fallback(recorder(), "qwen3-8b".to_string())
+            .with_fallback(recorder(), "llama3".to_string());
+
+        let (result, _) = run(&provider).await;
+
+        assert!(result.is_err());
+        assert_eq!(*models.lock().unwrap(), ["qwen3-8b", "llama3"]);
+    }
+
+    #[tokio::test]
+    async fn test_no_fallback_after_first_chunk() {
+        let (primary, _) = MockProvider::boxed(vec!["partial"], Some(server_error));
+        let (secondary, secondary_calls) = MockProvider::boxed(vec!["other"], None);
+        let provider = chain(primary, secondary);
+
+        let (result, texts) = run(&provider).await;
+
+        assert!(matches!(
+            result,
+            Err(ProviderError::Api { status: 503, .. })
+        ));
+        assert_eq!(texts, vec!["partial"]);
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_no_fallback_on_client_error() {
+        let (primary, _) = MockProvider::boxed(
+            vec![],
+            Some(|| ProviderError::Api {
+                status: 401,
+                message: "bad key".to_string(),
+                request_id: None,
+            }),
+        );
+        let (secondary, secondary_calls) = MockProvider::boxed(vec!["other"], None);
+        let provider = chain(primary, secondary);
+
+        let (result, _) = run(&provider).await;
+
+        assert!(matches!(
+            result,
+            Err(ProviderError::Api { status: 401, .. })
+        ));
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_returns_last_error_when_all_fail() {
+        let (primary, _) = MockProvider::boxed(vec![], Some(server_error));
+        let (secondary, _) = MockProvider::boxed(
+            vec![],
+            Some(|| ProviderError::Network("refused".to_string())),
+        );
+        let provider = chain(primary, secondary);
+
+        let (result, texts) = run(&provider).await;
+
+        assert!(matches!(result, Err(ProviderError::Network(_))));
+        assert!(texts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_usage_counts_only_successful_attempt() {
+        let (primary, _) = MockProvider::boxed(vec![], Some(server_error));
+        let (secondary, _) = MockProvider::boxed_with_usage(vec!["Hi"], Some(usage(20)), None);
+        let provider = chain(primary, secondary);
+
+        let (result, chunks) = run_chunks(&provider).await;
+
+        assert!(result.is_ok());
+        let total = accumulated_usage(&chunks);
+        assert_eq!(total.input_tokens, Some(100));
+        assert_eq!(total.output_tokens, Some(20));
+    }
+
+    #[tokio::test]
+    async fn test_failed_attempt_usage_discarded() {
+        // Reports usage, then fails: its stats must not reach the caller
+        let (primary, _) =
+            MockProvider::boxed_with_usage(vec![], Some(usage(7)), Some(server_error));
+        let (secondary, secondary_calls) =
+            MockProvider::boxed_with_usage(vec!["Hi"], Some(usage(20)), None);
+        let provider = chain(primary, secondary);
+
+        let (result, chunks) = run_chunks(&provider).await;
+
+        assert!(result.is_ok());
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 1);
+        let completed = chunks
+            .iter()
+            .filter(|chunk| matches!(chunk, StreamChunk::Completed(_)))
+            .count();
+        assert_eq!(completed, 1);
+        let total = accumulated_usage(&chunks);
+        assert_eq!(total.input_tokens, Some(100));
+        assert_eq!(total.output_tokens, Some(20));
+    }
+}
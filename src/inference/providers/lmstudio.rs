@@ -1,16 +1,27 @@
 //! LM Studio provider implementation using the Responses API.
 //!
 //! LM Studio v0.3.29+ supports the /v1/responses endpoint with:
-//! - Reasoning support with effort parameter
+//! - Reasoning support with effort parameter, with `ThinkingStart` /
+//!   `ThinkingEnd` at the reasoning item's boundaries
 //! - Streaming with SSE events
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use async_trait::async_trait;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 
+use super::backpressure::send_chunk;
+use super::dedupe::{DeltaDeduper, DeltaKind};
+use super::error_event::parse_error_payload;
+use super::malformed::DropCounter;
+use super::sse::{SseData, SseLineReader};
+use super::stream_log::{self, StreamLog};
+use super::tool_args::{oversized_arguments, validated_arguments};
+use super::utf8::Utf8Decoder;
+use crate::core::config::DEFAULT_MAX_TOOL_ARGS_BYTES;
 use crate::inference::{
     CompletionProvider, CompletionRequest, ContextItem, Effort, ProviderError, Source, StreamChunk,
     ToolDefinition, UsageStats,
@@ -76,11 +87,15 @@ struct ResponsesRequest {
     input: Vec<InputItem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
-    reasoning: Reasoning,
+    /// Omitted entirely for models that reject a reasoning block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning: Option<Reasoning>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<ApiToolDefinition>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
 }
 
 /// SSE event for delta content (used for both text and reasoning)
@@ -96,9 +111,9 @@ fn non_empty(s: String) -> Option<String> {
     if s.is_empty() { None } else { Some(s) }
 }
 
-/// SSE event for response.output_item.added
+/// SSE event for response.output_item.added and response.output_item.done
 #[derive(Deserialize, Debug)]
-struct OutputItemAddedEvent {
+struct OutputItemEvent {
     item: OutputItemData,
 }
 
@@ -136,7 +151,7 @@ struct PendingToolCall {
     args_buffer: String, // Accumulates argument deltas
 }
 
-/// Payload of the `response.completed` SSE event.
+/// Payload of the `response.completed` and `response.incomplete` SSE events.
 /// The real structure nests data under a `response` key:
 /// `{"type":"response.completed","response":{"id":"...","usage":{...},"status":"completed"}}`
 #[derive(Deserialize, Debug)]
@@ -148,10 +163,21 @@ struct CompletedResponsePayload {
 /// The inner `response` object from the completed event.
 #[derive(Deserialize, Debug)]
 struct CompletedResponse {
+    #[serde(default)]
+    id: Option<String>,
     #[serde(default)]
     usage: Option<CompletedUsage>,
     #[serde(default)]
     status: Option<String>,
+    /// Why an `incomplete` response stopped, e.g. `max_output_tokens`
+    #[serde(default)]
+    incomplete_details: Option<IncompleteDetails>,
+}
+
+#[derive(Deserialize, Debug)]
+struct IncompleteDetails {
+    #[serde(default)]
+    reason: Option<String>,
 }
 
 /// Token usage breakdown from the completed response.
@@ -177,7 +203,8 @@ struct CompletedUsage {
 ///
 /// Accepts a slice of ContextItems (full or partial) to support prompt caching.
 /// Produces a polymorphic input array: messages, function calls, and function call outputs.
-/// Filters out Thinking segments (reasoning is model-generated, not input).
+/// Filters out Thinking segments (reasoning is model-generated, not input) and
+/// image attachments (not supported by this provider).
 fn context_to_input(items: &[ContextItem]) -> Vec<InputItem> {
     let mut fco_counter = 0usize;
     items
@@ -207,10 +234,37 @@ fn context_to_input(items: &[ContextItem]) -> Vec<InputItem> {
                     output: tr.output.clone(),
                 })
             }
+            ContextItem::Image(image) => {
+                // Image input is only wired up for vision-capable providers (OpenRouter)
+                warn!(
+                    "LM Studio does not accept image input; dropping attachment ({})",
+                    image.label()
+                );
+                None
+            }
         })
         .collect()
 }
 
+/// Inserts a one-off turn directive as a second system message, right after
+/// the leading system prompt. It only exists in this request's input.
+fn insert_turn_directive(input: &mut Vec<InputItem>, directive: &str) {
+    let has_system_prompt = matches!(
+        input.first(),
+        Some(InputItem::Message {
+            role: Role::System,
+            ..
+        })
+    );
+    input.insert(
+        usize::from(has_system_prompt),
+        InputItem::Message {
+            role: Role::System,
+            content: directive.to_string(),
+        },
+    );
+}
+
 /// Converts tool definitions to API format. Returns None if empty (omitted from JSON).
 fn tools_to_api(tools: &[ToolDefinition]) -> Option<Vec<ApiToolDefinition>> {
     if tools.is_empty() {
@@ -230,12 +284,19 @@ fn tools_to_api(tools: &[ToolDefinition]) -> Option<Vec<ApiToolDefinition>> {
 }
 
 /// Maps our Effort enum to a Reasoning config for the Responses API.
-fn effort_to_reasoning(effort: Effort) -> Reasoning {
+/// With `disable_flag`, `Effort::None` turns reasoning off with
+/// `enabled: false` rather than `effort: "none"`, for backends that
+/// only accept the former.
+fn effort_to_reasoning(effort: Effort, disable_flag: bool) -> Reasoning {
     match effort {
         Effort::Auto => Reasoning {
             effort: None,
             enabled: Some(true),
         },
+        Effort::None if disable_flag => Reasoning {
+            effort: None,
+            enabled: Some(false),
+        },
         other => {
             let effort = match other {
                 Effort::High => "high",
@@ -252,10 +313,25 @@ fn effort_to_reasoning(effort: Effort) -> Reasoning {
     }
 }
 
+/// The reasoning block for `request`, or None when the model doesn't support
+/// reasoning; some backends reject any reasoning block for such models.
+fn request_reasoning(request: &CompletionRequest<'_>, disable_flag: bool) -> Option<Reasoning> {
+    if request.reasoning {
+        return Some(effort_to_reasoning(request.effort, disable_flag));
+    }
+    if request.effort != Effort::None {
+        info!(
+            "{} does not support reasoning; ignoring effort={:?}",
+            request.model, request.effort
+        );
+    }
+    None
+}
+
 /// Parses the `response.completed` SSE data into `UsageStats`.
 /// Returns `None` if parsing fails — we never want to crash over missing metrics.
-fn parse_completed_payload(data: &str) -> Option<UsageStats> {
-    let payload: CompletedResponsePayload = match serde_json::from_str(data) {
+fn parse_completed_payload(data: &SseData) -> Option<UsageStats> {
+    let payload: CompletedResponsePayload = match data.parse() {
         Ok(p) => p,
         Err(e) => {
             debug!("Failed to parse response.completed payload: {}", e);
@@ -270,7 +346,11 @@ fn parse_completed_payload(data: &str) -> Option<UsageStats> {
         total_tokens: usage.total_tokens,
         cache_creation_input_tokens: usage.cache_creation_input_tokens,
         cache_read_input_tokens: usage.cache_read_input_tokens,
-        finish_reason: response.status,
+        finish_reason: response
+            .incomplete_details
+            .and_then(|d| d.reason)
+            .or(response.status),
+        request_id: response.id,
         ..Default::default()
     })
 }
@@ -283,6 +363,9 @@ fn parse_completed_payload(data: &str) -> Option<UsageStats> {
 pub struct LmStudioProvider {
     base_url: String,
     client: reqwest::Client,
+    stream_log: Option<PathBuf>,
+    disable_reasoning_flag: bool,
+    max_tool_args_bytes: usize,
 }
 
 impl LmStudioProvider {
@@ -290,9 +373,37 @@ impl LmStudioProvider {
         Self {
             base_url,
             client: reqwest::Client::new(),
+            stream_log: None,
+            disable_reasoning_flag: false,
+            max_tool_args_bytes: DEFAULT_MAX_TOOL_ARGS_BYTES,
         }
     }
 
+    /// Append every raw SSE line of each stream to `path`, if set.
+    pub fn with_stream_log(mut self, path: Option<PathBuf>) -> Self {
+        self.stream_log = path;
+        self
+    }
+
+    /// Send requests through `client`, sharing its connection pool.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Turn reasoning off with `reasoning.enabled = false` instead of
+    /// `effort = "none"`, for models whose backend requires it.
+    pub fn with_disable_reasoning_flag(mut self, enabled: bool) -> Self {
+        self.disable_reasoning_flag = enabled;
+        self
+    }
+
+    /// Abandon a tool call whose accumulated arguments pass `bytes`.
+    pub fn with_max_tool_args_bytes(mut self, bytes: usize) -> Self {
+        self.max_tool_args_bytes = bytes;
+        self
+    }
+
     /// Sends a request to the Responses endpoint and returns the response.
     async fn send_request(
         &self,
@@ -304,7 +415,7 @@ impl LmStudioProvider {
             .json(request)
             .send()
             .await
-            .map_err(|e| ProviderError::Network(e.to_string()))?;
+            .map_err(ProviderError::from)?;
 
         debug!("LM Studio response status: {}", response.status());
 
@@ -318,6 +429,7 @@ impl LmStudioProvider {
             return Err(ProviderError::Api {
                 status,
                 message: err_body,
+                request_id: None,
             });
         }
 
@@ -332,9 +444,12 @@ impl CompletionProvider for LmStudioProvider {
         request: CompletionRequest<'_>,
         sender: Sender<StreamChunk>,
     ) -> Result<(), ProviderError> {
-        let reasoning = effort_to_reasoning(request.effort);
+        let reasoning = request_reasoning(&request, self.disable_reasoning_flag);
 
-        let input = context_to_input(&request.context.items);
+        let mut input = context_to_input(&request.context.items);
+        if let Some(directive) = request.turn_directive {
+            insert_turn_directive(&mut input, directive);
+        }
 
         let responses_request = ResponsesRequest {
             model: request.model.to_string(),
@@ -343,6 +458,7 @@ impl CompletionProvider for LmStudioProvider {
             reasoning,
             tools: tools_to_api(request.tools),
             max_output_tokens: request.max_output_tokens,
+            temperature: request.temperature,
         };
 
         info!(
@@ -355,98 +471,92 @@ impl CompletionProvider for LmStudioProvider {
         let response = self.send_request(&responses_request).await?;
 
         // Process the SSE stream with typed events
-        let mut buffer = String::new();
-        let mut current_event_type: Option<String> = None;
+        let mut events = SseLineReader::new();
         let mut total_content_len = 0usize;
         let mut chunk_count = 0usize;
+        let mut dedupe = DeltaDeduper::new(request.dedupe_stream);
+        let mut malformed = DropCounter::new();
+        let mut transcript = StreamLog::open(self.stream_log.as_deref(), "lmstudio");
+        let mut decoder = Utf8Decoder::new();
         let mut response = response;
 
         // Tool call state: tracks concurrent tool calls by item_id
         let mut pending_tools: HashMap<String, PendingToolCall> = HashMap::new();
 
-        while let Some(chunk) = response
-            .chunk()
-            .await
-            .map_err(|e| ProviderError::Network(e.to_string()))?
-        {
-            let s = String::from_utf8_lossy(&chunk);
+        while let Some(chunk) = response.chunk().await.map_err(ProviderError::from)? {
             debug!("Raw chunk received: {} bytes", chunk.len());
-            buffer.push_str(&s);
-
-            // Process complete lines from buffer
-            while let Some(pos) = buffer.find('\n') {
-                let line = buffer[..pos].to_string();
-                buffer.drain(..pos + 1);
-
-                let line = line.trim();
-
-                // Log all non-empty lines for debugging
-                if !line.is_empty() {
-                    debug!("SSE line: {}", line);
-                }
-
-                // Parse SSE event type
-                if let Some(event_type) = line.strip_prefix("event: ") {
-                    debug!("SSE event type: {}", event_type);
-                    current_event_type = Some(event_type.to_string());
-                    continue;
-                }
-
-                // Parse SSE data
-                if let Some(data) = line.strip_prefix("data: ") {
-                    debug!(
-                        "SSE data for event {:?}: {} bytes",
-                        current_event_type,
-                        data.len()
-                    );
-                    match current_event_type.as_deref() {
-                        Some("response.output_text.delta") => {
-                            if let Ok(event) = serde_json::from_str::<DeltaEvent>(data)
-                                && !event.delta.is_empty()
+            events.push(&decoder.decode(&chunk));
+
+            while let Some((event_type, data)) =
+                events.next_event(&mut |line| stream_log::record(&mut transcript, line))
+            {
+                match event_type.as_deref() {
+                    Some("response.output_text.delta") => {
+                        if let Some(event) =
+                            malformed.parse::<DeltaEvent>("response.output_text.delta", &data)
+                            && !event.delta.is_empty()
+                            && !dedupe.is_repeat(DeltaKind::Content, &event.item_id, &event.delta)
+                        {
+                            chunk_count += 1;
+                            total_content_len += event.delta.len();
+                            debug!(
+                                "Sending Content chunk (len={}, total={})",
+                                event.delta.len(),
+                                total_content_len
+                            );
+                            if send_chunk(
+                                &sender,
+                                StreamChunk::Content {
+                                    text: event.delta,
+                                    item_id: non_empty(event.item_id),
+                                },
+                            )
+                            .await
+                            .is_err()
                             {
-                                chunk_count += 1;
-                                total_content_len += event.delta.len();
-                                debug!(
-                                    "Sending Content chunk (len={}, total={})",
-                                    event.delta.len(),
-                                    total_content_len
-                                );
-                                if sender
-                                    .send(StreamChunk::Content {
-                                        text: event.delta,
-                                        item_id: non_empty(event.item_id),
-                                    })
-                                    .await
-                                    .is_err()
-                                {
-                                    warn!("Content chunk send failed: receiver dropped");
-                                    return Err(ProviderError::ChannelClosed);
-                                }
+                                warn!("Content chunk send failed: receiver dropped");
+                                return Err(ProviderError::ChannelClosed);
                             }
                         }
-                        Some("response.reasoning_text.delta") => {
-                            if let Ok(event) = serde_json::from_str::<DeltaEvent>(data)
-                                && !event.delta.is_empty()
+                    }
+                    // LM Studio emits no reasoning summary, so `reasoning_mode` doesn't apply
+                    Some("response.reasoning_text.delta") => {
+                        if let Some(event) =
+                            malformed.parse::<DeltaEvent>("response.reasoning_text.delta", &data)
+                            && !event.delta.is_empty()
+                            && !dedupe.is_repeat(DeltaKind::Thinking, &event.item_id, &event.delta)
+                        {
+                            chunk_count += 1;
+                            debug!("Sending Thinking chunk (len={})", event.delta.len());
+                            if send_chunk(
+                                &sender,
+                                StreamChunk::Thinking {
+                                    text: event.delta,
+                                    item_id: non_empty(event.item_id),
+                                },
+                            )
+                            .await
+                            .is_err()
                             {
-                                chunk_count += 1;
-                                debug!("Sending Thinking chunk (len={})", event.delta.len());
-                                if sender
-                                    .send(StreamChunk::Thinking {
-                                        text: event.delta,
-                                        item_id: non_empty(event.item_id),
-                                    })
-                                    .await
-                                    .is_err()
-                                {
-                                    warn!("Thinking chunk send failed: receiver dropped");
+                                warn!("Thinking chunk send failed: receiver dropped");
+                                return Err(ProviderError::ChannelClosed);
+                            }
+                        }
+                    }
+                    Some("response.output_item.added") => {
+                        let event = data.parse::<OutputItemEvent>().ok();
+                        match event {
+                            Some(event) if event.item.item_type == "reasoning" => {
+                                debug!("Reasoning started (item_id={})", event.item.id);
+                                let chunk = StreamChunk::ThinkingStart {
+                                    item_id: non_empty(event.item.id),
+                                };
+                                if send_chunk(&sender, chunk).await.is_err() {
+                                    warn!("ThinkingStart send failed: receiver dropped");
                                     return Err(ProviderError::ChannelClosed);
                                 }
                             }
-                        }
-                        Some("response.output_item.added") => {
-                            if let Ok(event) = serde_json::from_str::<OutputItemAddedEvent>(data)
-                                && event.item.item_type == "function_call"
-                            {
+                            Some(event) if event.item.item_type == "function_call" => {
                                 debug!(
                                     "Tool call started: {} (item_id={}, call_id={})",
                                     event.item.name, event.item.id, event.item.call_id
@@ -461,96 +571,140 @@ impl CompletionProvider for LmStudioProvider {
                                     },
                                 );
                             }
+                            _ => {}
                         }
-                        Some("response.function_call_arguments.delta") => {
-                            if let Ok(event) = serde_json::from_str::<DeltaEvent>(data) {
-                                // Append to the matching pending tool's args buffer.
-                                // item_id on DeltaEvent may be empty for some LM Studio
-                                // versions, so fall back to single-entry heuristic.
-                                let entry = if !event.item_id.is_empty() {
-                                    pending_tools.get_mut(&event.item_id)
-                                } else {
-                                    pending_tools.values_mut().next()
-                                };
-                                if let Some(pending) = entry {
-                                    pending.args_buffer.push_str(&event.delta);
-                                } else {
-                                    warn!("Argument delta for unknown tool call");
-                                }
+                    }
+                    Some("response.output_item.done") => {
+                        if let Ok(event) = data.parse::<OutputItemEvent>()
+                            && event.item.item_type == "reasoning"
+                        {
+                            debug!("Reasoning finished (item_id={})", event.item.id);
+                            let chunk = StreamChunk::ThinkingEnd {
+                                item_id: non_empty(event.item.id),
+                            };
+                            if send_chunk(&sender, chunk).await.is_err() {
+                                warn!("ThinkingEnd send failed: receiver dropped");
+                                return Err(ProviderError::ChannelClosed);
                             }
                         }
-                        Some("response.function_call_arguments.done") => {
-                            if let Ok(event) =
-                                serde_json::from_str::<FunctionCallArgsDoneEvent>(data)
-                            {
-                                // Look up by item_id; fall back to single-entry heuristic
-                                // if LM Studio omits item_id.
-                                let pending = if !event.item_id.is_empty() {
-                                    pending_tools.remove(&event.item_id)
-                                } else {
-                                    let key = pending_tools.keys().next().cloned();
-                                    key.and_then(|k| pending_tools.remove(&k))
-                                };
-                                let (id, call_id, name) = match pending {
-                                    Some(p) => {
-                                        // LM Studio omits `name` from arguments.done —
-                                        // use the name from output_item.added.
-                                        let name = if event.name.is_empty() {
-                                            p.name
-                                        } else {
-                                            event.name
-                                        };
-                                        (p.id, p.call_id, name)
-                                    }
-                                    None => {
-                                        warn!(
-                                            "arguments.done for unknown item_id: {}, skipping",
-                                            event.item_id
-                                        );
-                                        continue;
+                    }
+                    Some("response.function_call_arguments.delta") => {
+                        if let Ok(event) = data.parse::<DeltaEvent>() {
+                            // Append to the matching pending tool's args buffer.
+                            // item_id on DeltaEvent may be empty for some LM Studio
+                            // versions, so fall back to single-entry heuristic.
+                            let entry = if !event.item_id.is_empty() {
+                                pending_tools.get_mut(&event.item_id)
+                            } else {
+                                pending_tools.values_mut().next()
+                            };
+                            if let Some(pending) = entry {
+                                let len = pending.args_buffer.len() + event.delta.len();
+                                if let Some(message) = oversized_arguments(
+                                    &pending.name,
+                                    len,
+                                    self.max_tool_args_bytes,
+                                ) {
+                                    if send_chunk(&sender, StreamChunk::Error(message))
+                                        .await
+                                        .is_err()
+                                    {
+                                        warn!("Error send failed: receiver dropped");
+                                        return Err(ProviderError::ChannelClosed);
                                     }
-                                };
-                                let tool_call = crate::inference::ToolCall {
-                                    id,
-                                    call_id,
-                                    name: name.clone(),
-                                    arguments: event.arguments,
-                                };
-                                debug!(
-                                    "Tool call complete: {} (call_id={})",
-                                    name, tool_call.call_id
-                                );
-                                chunk_count += 1;
-                                if sender.send(StreamChunk::ToolCall(tool_call)).await.is_err() {
-                                    warn!("ToolCall send failed: receiver dropped");
-                                    return Err(ProviderError::ChannelClosed);
+                                    return Ok(());
                                 }
+                                pending.args_buffer.push_str(&event.delta);
+                            } else {
+                                warn!("Argument delta for unknown tool call");
                             }
                         }
-                        Some("response.completed") => {
-                            info!(
-                                "Stream complete: {} chunks, {} content bytes",
-                                chunk_count, total_content_len
+                    }
+                    Some("response.function_call_arguments.done") => {
+                        if let Ok(event) = data.parse::<FunctionCallArgsDoneEvent>() {
+                            // Look up by item_id; fall back to single-entry heuristic
+                            // if LM Studio omits item_id.
+                            let pending = if !event.item_id.is_empty() {
+                                pending_tools.remove(&event.item_id)
+                            } else {
+                                let key = pending_tools.keys().next().cloned();
+                                key.and_then(|k| pending_tools.remove(&k))
+                            };
+                            let (id, call_id, name) = match pending {
+                                Some(p) => {
+                                    // LM Studio omits `name` from arguments.done —
+                                    // use the name from output_item.added.
+                                    let name = if event.name.is_empty() {
+                                        p.name
+                                    } else {
+                                        event.name
+                                    };
+                                    (p.id, p.call_id, name)
+                                }
+                                None => {
+                                    warn!(
+                                        "arguments.done for unknown item_id: {}, skipping",
+                                        event.item_id
+                                    );
+                                    continue;
+                                }
+                            };
+                            let tool_call = crate::inference::ToolCall {
+                                id,
+                                call_id,
+                                name: name.clone(),
+                                arguments: validated_arguments(&name, event.arguments),
+                            };
+                            debug!(
+                                "Tool call complete: {} (call_id={})",
+                                name, tool_call.call_id
                             );
-                            debug!("response.completed data: {}", data);
-                            let stats = parse_completed_payload(data);
-                            if sender.send(StreamChunk::Completed(stats)).await.is_err() {
-                                warn!("Completed send failed: receiver dropped");
+                            chunk_count += 1;
+                            if send_chunk(&sender, StreamChunk::ToolCall(tool_call))
+                                .await
+                                .is_err()
+                            {
+                                warn!("ToolCall send failed: receiver dropped");
                                 return Err(ProviderError::ChannelClosed);
                             }
-                            return Ok(());
                         }
-                        Some(other) => {
-                            debug!("Unrecognized event type '{}' with data: {}", other, data);
+                    }
+                    Some("response.completed") | Some("response.incomplete") => {
+                        info!(
+                            "Stream complete: {} chunks, {} content bytes, {} dropped",
+                            chunk_count,
+                            total_content_len,
+                            malformed.dropped()
+                        );
+                        debug!("response.completed data: {}", data);
+                        let stats = malformed.report(parse_completed_payload(&data));
+                        if send_chunk(&sender, StreamChunk::Completed(stats))
+                            .await
+                            .is_err()
+                        {
+                            warn!("Completed send failed: receiver dropped");
+                            return Err(ProviderError::ChannelClosed);
                         }
-                        None => {
-                            // Data without event type - log it
-                            debug!("Data without event type: {}", data);
+                        return Ok(());
+                    }
+                    Some("error") | Some("response.failed") => {
+                        let message = parse_error_payload(&data);
+                        warn!("Stream error event: {}", message);
+                        if send_chunk(&sender, StreamChunk::Error(message))
+                            .await
+                            .is_err()
+                        {
+                            warn!("Error send failed: receiver dropped");
+                            return Err(ProviderError::ChannelClosed);
                         }
+                        return Ok(());
+                    }
+                    Some(other) => {
+                        debug!("Unrecognized event type '{}' with data: {}", other, data);
+                    }
+                    None => {
+                        debug!("Data without event type: {}", data);
                     }
-
-                    // Reset event type after processing data
-                    current_event_type = None;
                 }
             }
         }
@@ -563,8 +717,10 @@ impl CompletionProvider for LmStudioProvider {
             );
         }
         info!(
-            "Stream ended: {} chunks processed, {} total content bytes",
-            chunk_count, total_content_len
+            "Stream ended: {} chunks processed, {} total content bytes, {} dropped",
+            chunk_count,
+            total_content_len,
+            malformed.dropped()
         );
         Ok(())
     }
@@ -573,7 +729,7 @@ impl CompletionProvider for LmStudioProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::inference::{Context, ContextSegment, Effort, Source};
+    use crate::inference::{Context, ContextSegment, Effort, ImageAttachment, Source};
 
     #[test]
     fn test_context_to_input_filters_thinking() {
@@ -581,14 +737,17 @@ mod tests {
         context.add(ContextSegment {
             source: Source::User,
             content: "Hello".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::Thinking,
             content: "Internal thought".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::Model,
             content: "Response".to_string(),
+            timestamp: None,
         });
 
         let input = context_to_input(&context.items);
@@ -617,14 +776,17 @@ mod tests {
         context.add(ContextSegment {
             source: Source::Directive,
             content: "System message".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::User,
             content: "User message".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::Model,
             content: "Model message".to_string(),
+            timestamp: None,
         });
 
         let input = context_to_input(&context.items);
@@ -641,17 +803,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_context_to_input_drops_images() {
+        let mut context = Context::new();
+        context.add(ContextSegment {
+            source: Source::User,
+            content: "What is this?".to_string(),
+            timestamp: None,
+        });
+        context.add_image(ImageAttachment {
+            data_base64: "iVBORw0KGgo=".to_string(),
+            mime: "image/png".to_string(),
+            caption: Some("cat.png".to_string()),
+        });
+
+        let input = context_to_input(&context.items);
+
+        assert_eq!(input.len(), 2);
+        assert!(
+            matches!(&input[1], InputItem::Message { role: Role::User, content } if content == "What is this?")
+        );
+    }
+
     #[test]
     fn test_effort_to_reasoning_returns_correct_values() {
-        assert_eq!(effort_to_reasoning(Effort::High).effort, Some("high"));
-        assert_eq!(effort_to_reasoning(Effort::Medium).effort, Some("medium"));
-        assert_eq!(effort_to_reasoning(Effort::Low).effort, Some("low"));
-        assert_eq!(effort_to_reasoning(Effort::None).effort, Some("none"));
-        assert_eq!(effort_to_reasoning(Effort::Auto).effort, None);
+        assert_eq!(
+            effort_to_reasoning(Effort::High, false).effort,
+            Some("high")
+        );
+        assert_eq!(
+            effort_to_reasoning(Effort::Medium, false).effort,
+            Some("medium")
+        );
+        assert_eq!(effort_to_reasoning(Effort::Low, false).effort, Some("low"));
+        assert_eq!(
+            effort_to_reasoning(Effort::None, false).effort,
+            Some("none")
+        );
+        assert_eq!(effort_to_reasoning(Effort::Auto, false).effort, None);
 
         // Auto uses enabled flag, explicit efforts don't
-        assert_eq!(effort_to_reasoning(Effort::Auto).enabled, Some(true));
-        assert_eq!(effort_to_reasoning(Effort::High).enabled, None);
+        assert_eq!(effort_to_reasoning(Effort::Auto, false).enabled, Some(true));
+        assert_eq!(effort_to_reasoning(Effort::High, false).enabled, None);
+
+        // Backends that need it get reasoning turned off with the flag
+        let off = effort_to_reasoning(Effort::None, true);
+        assert_eq!((off.effort, off.enabled), (None, Some(false)));
     }
 
     #[test]
@@ -699,9 +896,10 @@ mod tests {
             model: "test".to_string(),
             input: vec![],
             stream: None,
-            reasoning: effort_to_reasoning(Effort::Auto),
+            reasoning: Some(effort_to_reasoning(Effort::Auto, false)),
             tools: None,
             max_output_tokens: None,
+            temperature: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -717,9 +915,10 @@ mod tests {
             model: "test".to_string(),
             input: vec![],
             stream: Some(true),
-            reasoning: effort_to_reasoning(Effort::Medium),
+            reasoning: Some(effort_to_reasoning(Effort::Medium, false)),
             tools: None,
             max_output_tokens: None,
+            temperature: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -746,15 +945,18 @@ mod tests {
         context.add(ContextSegment {
             source: Source::User,
             content: "Turn 1".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::Model,
             content: "Response 1".to_string(),
+            timestamp: None,
         });
         // Watermark = 3 (system + user + model)
         context.add(ContextSegment {
             source: Source::User,
             content: "Turn 2".to_string(),
+            timestamp: None,
         });
 
         // Partial slice: only the new user message
@@ -772,7 +974,7 @@ mod tests {
     #[test]
     fn test_parse_completed_payload_with_usage() {
         let data = r#"{"type":"response.completed","response":{"id":"resp_1","usage":{"input_tokens":100,"output_tokens":30,"total_tokens":130},"status":"completed"}}"#;
-        let stats = parse_completed_payload(data).unwrap();
+        let stats = parse_completed_payload(&SseData::from(data)).unwrap();
         assert_eq!(stats.input_tokens, Some(100));
         assert_eq!(stats.output_tokens, Some(30));
         assert_eq!(stats.total_tokens, Some(130));
@@ -783,20 +985,20 @@ mod tests {
     fn test_parse_completed_payload_without_usage() {
         let data =
             r#"{"type":"response.completed","response":{"id":"resp_1","status":"completed"}}"#;
-        let stats = parse_completed_payload(data);
+        let stats = parse_completed_payload(&SseData::from(data));
         assert!(stats.is_none());
     }
 
     #[test]
     fn test_parse_completed_payload_no_response_object() {
         let data = r#"{"type":"response.completed"}"#;
-        let stats = parse_completed_payload(data);
+        let stats = parse_completed_payload(&SseData::from(data));
         assert!(stats.is_none());
     }
 
     #[test]
     fn test_parse_completed_payload_invalid_json() {
-        let stats = parse_completed_payload("not json");
+        let stats = parse_completed_payload(&SseData::from("not json"));
         assert!(stats.is_none());
     }
 }
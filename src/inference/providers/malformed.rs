@@ -0,0 +1,123 @@
+//! # Malformed Delta Recovery
+//!
+//! A content or thinking delta whose JSON doesn't parse used to vanish
+//! without a trace, leaving a hole in the transcript. `DropCounter` parses
+//! those events instead: a failure is logged with the start of the raw data
+//! and counted, the stream carries on, and the total is reported with the
+//! usage stats when the stream completes.
+
+use log::warn;
+use serde::de::DeserializeOwned;
+
+use super::sse::SseData;
+use crate::inference::UsageStats;
+
+/// Bytes of a malformed data line included in the warning.
+const RAW_PREVIEW_BYTES: usize = 200;
+
+/// Counts the deltas of a single stream that were skipped as malformed.
+pub(super) struct DropCounter {
+    dropped: u32,
+}
+
+impl DropCounter {
+    pub(super) fn new() -> Self {
+        Self { dropped: 0 }
+    }
+
+    /// Parse `data` from an `event_type` event, or log and count it when
+    /// it doesn't parse.
+    pub(super) fn parse<T: DeserializeOwned>(
+        &mut self,
+        event_type: &str,
+        data: &SseData,
+    ) -> Option<T> {
+        match data.parse() {
+            Ok(event) => Some(event),
+            Err(e) => {
+                self.dropped += 1;
+                warn!(
+                    "Skipping malformed {} data ({}), dropped {} chunk(s) so far: {}",
+                    event_type,
+                    e,
+                    self.dropped,
+                    preview(data.raw())
+                );
+                None
+            }
+        }
+    }
+
+    pub(super) fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Add the drop count to the stream's final stats. `stats` is returned
+    /// unchanged when nothing was dropped.
+    pub(super) fn report(&self, stats: Option<UsageStats>) -> Option<UsageStats> {
+        if self.dropped == 0 {
+            return stats;
+        }
+        warn!(
+            "Stream completed with {} malformed chunk(s) dropped",
+            self.dropped
+        );
+        let mut stats = stats.unwrap_or_default();
+        stats.dropped_chunks = Some(self.dropped);
+        Some(stats)
+    }
+}
+
+/// The first `RAW_PREVIEW_BYTES` of `data`, cut at a character boundary.
+fn preview(data: &str) -> &str {
+    if data.len() <= RAW_PREVIEW_BYTES {
+        return data;
+    }
+    let end = (0..=RAW_PREVIEW_BYTES)
+        .rev()
+        .find(|&i| data.is_char_boundary(i))
+        .unwrap_or(0);
+    &data[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    struct Delta {
+        delta: String,
+    }
+
+    #[test]
+    fn test_parse_counts_only_failures() {
+        let mut counter = DropCounter::new();
+        let ok: Option<Delta> = counter.parse("delta", &SseData::from(r#"{"delta":"hi"}"#));
+        assert_eq!(ok.unwrap().delta, "hi");
+        assert!(
+            counter
+                .parse::<Delta>("delta", &SseData::from(r#"{"delta":"#))
+                .is_none()
+        );
+        assert_eq!(counter.dropped(), 1);
+    }
+
+    #[test]
+    fn test_report_adds_count_only_when_something_dropped() {
+        let mut counter = DropCounter::new();
+        assert!(counter.report(None).is_none());
+
+        counter.parse::<Delta>("delta", &SseData::from("not json"));
+        let stats = counter.report(None).unwrap();
+        assert_eq!(stats.dropped_chunks, Some(1));
+    }
+
+    #[test]
+    fn test_preview_truncates_on_char_boundary() {
+        let long = "é".repeat(RAW_PREVIEW_BYTES);
+        let cut = preview(&long);
+        assert!(cut.len() <= RAW_PREVIEW_BYTES);
+        assert!(long.starts_with(cut));
+        assert_eq!(preview("short"), "short");
+    }
+}
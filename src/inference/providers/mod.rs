@@ -1,5 +1,19 @@
+mod azure;
+mod backpressure;
+mod client;
+mod dedupe;
+mod error_event;
+mod fallback;
 mod lmstudio;
+mod malformed;
 mod openrouter;
+mod sse;
+mod stream_log;
+mod tool_args;
+mod utf8;
 
+pub use azure::AzureOpenAiProvider;
+pub use client::build_client;
+pub use fallback::FallbackProvider;
 pub use lmstudio::LmStudioProvider;
-pub use openrouter::OpenRouterProvider;
+pub use openrouter::{OpenRouterProvider, responses_request_json};
@@ -3,18 +3,31 @@
 //! This module uses OpenAI Responses API terminology:
 //! - "input" (array of messages, not "context")
 //! - "role" (not "source")
-//! - SSE events: response.output_text.delta, response.reasoning_summary_text.delta
+//! - SSE events: response.output_text.delta, response.reasoning_summary_text.delta,
+//!   response.reasoning_text.delta (only the one selected by `ReasoningMode` is surfaced)
+//! - Reasoning items opening and closing (response.output_item.added / .done)
+//!   are surfaced as `ThinkingStart` / `ThinkingEnd`
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use async_trait::async_trait;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 
+use super::backpressure::send_chunk;
+use super::dedupe::{DeltaDeduper, DeltaKind};
+use super::error_event::parse_error_payload;
+use super::malformed::DropCounter;
+use super::sse::{SseData, SseLineReader};
+use super::stream_log::{self, StreamLog};
+use super::tool_args::{oversized_arguments, validated_arguments};
+use super::utf8::Utf8Decoder;
+use crate::core::config::DEFAULT_MAX_TOOL_ARGS_BYTES;
 use crate::inference::{
-    CompletionProvider, CompletionRequest, ContextItem, Effort, ProviderError, Source, StreamChunk,
-    ToolDefinition, UsageStats,
+    CompletionProvider, CompletionRequest, ContextItem, Effort, ImageAttachment, ProviderError,
+    RateLimitInfo, ReasoningMode, Source, StreamChunk, ToolDefinition, UsageStats,
 };
 
 // ============================================================================
@@ -37,6 +50,12 @@ enum Role {
 enum InputItem {
     #[serde(rename = "message")]
     Message { role: Role, content: String },
+    /// A message whose content is a list of typed parts (used for images).
+    #[serde(rename = "message")]
+    MessageParts {
+        role: Role,
+        content: Vec<ContentPart>,
+    },
     #[serde(rename = "function_call")]
     FunctionCall {
         id: String,
@@ -52,6 +71,33 @@ enum InputItem {
     },
 }
 
+/// A typed content part inside a `MessageParts` input item.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type")]
+enum ContentPart {
+    #[serde(rename = "input_text")]
+    InputText {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    #[serde(rename = "input_image")]
+    InputImage {
+        image_url: String,
+        detail: &'static str,
+    },
+}
+
+/// Anthropic-style prompt cache breakpoint; OpenRouter passes it through to
+/// providers that support explicit caching and ignores it elsewhere.
+#[derive(Serialize, Debug, Clone)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+const EPHEMERAL_CACHE: CacheControl = CacheControl { kind: "ephemeral" };
+
 /// Configuration for reasoning tokens
 #[derive(Serialize, Debug)]
 struct Reasoning {
@@ -78,19 +124,35 @@ struct ResponsesRequest {
     input: Vec<InputItem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
-    reasoning: Reasoning,
+    /// Omitted entirely for models that reject a reasoning block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning: Option<Reasoning>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<ApiToolDefinition>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    /// Upstream provider routing; omitted to let OpenRouter choose
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<ProviderPreferences>,
+}
+
+/// OpenRouter's `provider` routing object
+#[derive(Serialize, Debug, Clone, PartialEq)]
+struct ProviderPreferences {
+    /// Upstream providers to try first, in order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<Vec<String>>,
+    /// Whether OpenRouter may route elsewhere when `order` is exhausted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allow_fallbacks: Option<bool>,
 }
 
-/// Generic SSE event wrapper to extract the type field
-/// OpenRouter embeds the event type inside the JSON, not in SSE event: lines
+/// Generic SSE event payload for text and reasoning deltas. The event type
+/// comes from `SseLineReader`, which reads it from the JSON's `type` field.
 #[derive(Deserialize, Debug)]
 struct SseEvent {
-    #[serde(rename = "type")]
-    event_type: String,
     #[serde(default)]
     delta: String,
     #[serde(default)]
@@ -102,9 +164,10 @@ fn non_empty(s: String) -> Option<String> {
     if s.is_empty() { None } else { Some(s) }
 }
 
-/// SSE event for response.output_item.added (detects function_call output items)
+/// SSE event for response.output_item.added and response.output_item.done
+/// (detects function_call and reasoning output items)
 #[derive(Deserialize, Debug)]
-struct OutputItemAddedEvent {
+struct OutputItemEvent {
     item: OutputItemData,
 }
 
@@ -124,7 +187,6 @@ struct OutputItemData {
 #[derive(Deserialize, Debug)]
 struct FunctionCallArgsDeltaEvent {
     item_id: String,
-    #[allow(dead_code)]
     delta: String,
 }
 
@@ -141,9 +203,11 @@ struct FunctionCallArgsDoneEvent {
 struct PendingToolCall {
     id: String,      // API object ID (e.g. "fc_abc123")
     call_id: String, // Correlation ID (e.g. "call_xyz789")
+    name: String,    // Function name, for the size guard's error
+    args_len: usize, // Argument bytes streamed so far
 }
 
-/// Payload of the `response.completed` SSE event.
+/// Payload of the `response.completed` and `response.incomplete` SSE events.
 /// The real structure nests data under a `response` key:
 /// `{"type":"response.completed","response":{"id":"...","usage":{...},"status":"completed"}}`
 #[derive(Deserialize, Debug)]
@@ -155,10 +219,21 @@ struct CompletedResponsePayload {
 /// The inner `response` object from the completed event.
 #[derive(Deserialize, Debug)]
 struct CompletedResponse {
+    #[serde(default)]
+    id: Option<String>,
     #[serde(default)]
     usage: Option<CompletedUsage>,
     #[serde(default)]
     status: Option<String>,
+    /// Why an `incomplete` response stopped, e.g. `max_output_tokens`
+    #[serde(default)]
+    incomplete_details: Option<IncompleteDetails>,
+}
+
+#[derive(Deserialize, Debug)]
+struct IncompleteDetails {
+    #[serde(default)]
+    reason: Option<String>,
 }
 
 /// Token usage breakdown from the completed response.
@@ -214,10 +289,115 @@ fn context_to_input(items: &[ContextItem]) -> Vec<InputItem> {
                     output: tr.output.clone(),
                 })
             }
+            ContextItem::Image(image) => Some(image_to_input(image)),
         })
         .collect()
 }
 
+/// The request body `stream_completion` would send for `items`, as pretty
+/// JSON. Tools, routing and output limits are left out, and nothing is sent.
+pub fn responses_request_json(
+    items: &[ContextItem],
+    model: &str,
+    effort: Effort,
+) -> serde_json::Result<String> {
+    let request = ResponsesRequest {
+        model: model.to_string(),
+        input: context_to_input(items),
+        stream: Some(true),
+        reasoning: Some(effort_to_reasoning(effort, false)),
+        tools: None,
+        max_output_tokens: None,
+        temperature: None,
+        provider: None,
+    };
+    serde_json::to_string_pretty(&request)
+}
+
+/// Inserts a one-off turn directive as a second system message, right after
+/// the leading system prompt. It only exists in this request's input.
+fn insert_turn_directive(input: &mut Vec<InputItem>, directive: &str) {
+    let has_system_prompt = matches!(
+        input.first(),
+        Some(InputItem::Message {
+            role: Role::System,
+            ..
+        })
+    );
+    input.insert(
+        usize::from(has_system_prompt),
+        InputItem::Message {
+            role: Role::System,
+            content: directive.to_string(),
+        },
+    );
+}
+
+/// Converts an image attachment into a user message with content parts:
+/// the caption (if any) as `input_text`, then the image as a `data:` URL.
+fn image_to_input(image: &ImageAttachment) -> InputItem {
+    let mut content = Vec::with_capacity(2);
+    if let Some(caption) = &image.caption {
+        content.push(ContentPart::InputText {
+            text: caption.clone(),
+            cache_control: None,
+        });
+    }
+    content.push(ContentPart::InputImage {
+        image_url: image.data_url(),
+        detail: "auto",
+    });
+    InputItem::MessageParts {
+        role: Role::User,
+        content,
+    }
+}
+
+/// Places a cache breakpoint at the end of the stable prefix: the last user
+/// or system message among the input produced from `items[..watermark]`.
+///
+/// The full input is still sent (the API is stateless); the marker tells the
+/// provider it may reuse the cached prefix up to that point.
+fn mark_cached_prefix(input: &mut [InputItem], items: &[ContextItem], watermark: usize) {
+    // Mirrors the filtering in `context_to_input`
+    let prefix_len = items[..watermark.min(items.len())]
+        .iter()
+        .filter(|item| {
+            !matches!(item, ContextItem::Message(seg)
+                if matches!(seg.source, Source::Thinking | Source::Status))
+        })
+        .count();
+
+    for item in input[..prefix_len].iter_mut().rev() {
+        match item {
+            // Plain string content has nowhere to carry the marker, so it
+            // becomes a single text part. Assistant content can't be sent as
+            // `input_text`, so those messages are passed over.
+            InputItem::Message { role, content } if !matches!(role, Role::Assistant) => {
+                *item = InputItem::MessageParts {
+                    role: role.clone(),
+                    content: vec![ContentPart::InputText {
+                        text: std::mem::take(content),
+                        cache_control: Some(EPHEMERAL_CACHE),
+                    }],
+                };
+                return;
+            }
+            InputItem::MessageParts { content, .. } => {
+                let last_text = content.iter_mut().rev().find_map(|part| match part {
+                    ContentPart::InputText { cache_control, .. } => Some(cache_control),
+                    ContentPart::InputImage { .. } => None,
+                });
+                if let Some(cache_control) = last_text {
+                    *cache_control = Some(EPHEMERAL_CACHE);
+                    return;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 /// Converts tool definitions to API format. Returns None if empty (omitted from JSON).
 fn tools_to_api(tools: &[ToolDefinition]) -> Option<Vec<ApiToolDefinition>> {
     if tools.is_empty() {
@@ -236,13 +416,28 @@ fn tools_to_api(tools: &[ToolDefinition]) -> Option<Vec<ApiToolDefinition>> {
     )
 }
 
+/// The SSE event type carrying the reasoning stream selected by `mode`.
+fn reasoning_event_type(mode: ReasoningMode) -> &'static str {
+    match mode {
+        ReasoningMode::Summary => "response.reasoning_summary_text.delta",
+        ReasoningMode::Full => "response.reasoning_text.delta",
+    }
+}
+
 /// Maps our Effort enum to a Reasoning config for the Responses API.
-fn effort_to_reasoning(effort: Effort) -> Reasoning {
+/// With `disable_flag`, `Effort::None` turns reasoning off with
+/// `enabled: false` rather than `effort: "none"`, for backends that
+/// only accept the former.
+fn effort_to_reasoning(effort: Effort, disable_flag: bool) -> Reasoning {
     match effort {
         Effort::Auto => Reasoning {
             effort: None,
             enabled: Some(true),
         },
+        Effort::None if disable_flag => Reasoning {
+            effort: None,
+            enabled: Some(false),
+        },
         other => {
             let effort = match other {
                 Effort::High => "high",
@@ -259,10 +454,25 @@ fn effort_to_reasoning(effort: Effort) -> Reasoning {
     }
 }
 
+/// The reasoning block for `request`, or None when the model doesn't support
+/// reasoning; some backends reject any reasoning block for such models.
+fn request_reasoning(request: &CompletionRequest<'_>, disable_flag: bool) -> Option<Reasoning> {
+    if request.reasoning {
+        return Some(effort_to_reasoning(request.effort, disable_flag));
+    }
+    if request.effort != Effort::None {
+        info!(
+            "{} does not support reasoning; ignoring effort={:?}",
+            request.model, request.effort
+        );
+    }
+    None
+}
+
 /// Parses the `response.completed` SSE data into `UsageStats`.
 /// Returns `None` if parsing fails — we never want to crash over missing metrics.
-fn parse_completed_payload(data: &str) -> Option<UsageStats> {
-    let payload: CompletedResponsePayload = match serde_json::from_str(data) {
+fn parse_completed_payload(data: &SseData) -> Option<UsageStats> {
+    let payload: CompletedResponsePayload = match data.parse() {
         Ok(p) => p,
         Err(e) => {
             debug!("Failed to parse response.completed payload: {}", e);
@@ -277,11 +487,29 @@ fn parse_completed_payload(data: &str) -> Option<UsageStats> {
         total_tokens: usage.total_tokens,
         cache_creation_input_tokens: usage.cache_creation_input_tokens,
         cache_read_input_tokens: usage.cache_read_input_tokens,
-        finish_reason: response.status,
+        finish_reason: response
+            .incomplete_details
+            .and_then(|d| d.reason)
+            .or(response.status),
+        request_id: response.id,
         ..Default::default()
     })
 }
 
+/// The `response.id` carried by `response.created` and later events, if any.
+fn parse_response_id(data: &SseData) -> Option<String> {
+    data.parse::<CompletedResponsePayload>().ok()?.response?.id
+}
+
+/// `message` with the provider's request id appended, for quoting in a
+/// support ticket.
+fn with_request_id(message: String, request_id: Option<&str>) -> String {
+    match request_id {
+        Some(id) => format!("{message} (request id: {id})"),
+        None => message,
+    }
+}
+
 // ============================================================================
 // Provider Implementation
 // ============================================================================
@@ -291,6 +519,18 @@ pub struct OpenRouterProvider {
     api_key: String,
     base_url: String,
     client: reqwest::Client,
+    routing: Option<ProviderPreferences>,
+    stream_log: Option<PathBuf>,
+    azure: Option<AzureDeployment>,
+    disable_reasoning_flag: bool,
+    max_tool_args_bytes: usize,
+}
+
+/// Azure OpenAI deployment targeted instead of OpenRouter's endpoint; see
+/// `AzureOpenAiProvider`.
+struct AzureDeployment {
+    name: String,
+    api_version: String,
 }
 
 impl OpenRouterProvider {
@@ -304,29 +544,102 @@ impl OpenRouterProvider {
             api_key,
             base_url: base_url.unwrap_or_else(|| "https://openrouter.ai/api/v1".to_string()),
             client: reqwest::Client::new(),
+            routing: None,
+            stream_log: None,
+            azure: None,
+            disable_reasoning_flag: false,
+            max_tool_args_bytes: DEFAULT_MAX_TOOL_ARGS_BYTES,
         }
     }
 
-    /// Sends a request to the Responses endpoint and returns the response.
+    /// Send requests to an Azure OpenAI deployment under `base_url`: the
+    /// deployment's Responses path with `api_version` as a query parameter,
+    /// the key in an `api-key` header, and the deployment as the model.
+    pub(super) fn with_azure_deployment(mut self, deployment: String, api_version: String) -> Self {
+        self.azure = Some(AzureDeployment {
+            name: deployment,
+            api_version,
+        });
+        self
+    }
+
+    /// Append every raw SSE line of each stream to `path`, if set.
+    pub fn with_stream_log(mut self, path: Option<PathBuf>) -> Self {
+        self.stream_log = path;
+        self
+    }
+
+    /// Send requests through `client`, sharing its connection pool.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Turn reasoning off with `reasoning.enabled = false` instead of
+    /// `effort = "none"`, for models whose backend requires it.
+    pub fn with_disable_reasoning_flag(mut self, enabled: bool) -> Self {
+        self.disable_reasoning_flag = enabled;
+        self
+    }
+
+    /// Abandon a tool call whose streamed arguments pass `bytes`.
+    pub fn with_max_tool_args_bytes(mut self, bytes: usize) -> Self {
+        self.max_tool_args_bytes = bytes;
+        self
+    }
+
+    /// Ask OpenRouter to route through the upstream providers in `order`,
+    /// optionally forbidding it from falling back to others. With no order
+    /// and no fallback preference, the routing object is left out entirely.
+    pub fn with_routing(mut self, order: Vec<String>, allow_fallbacks: Option<bool>) -> Self {
+        let order = (!order.is_empty()).then_some(order);
+        if order.is_some() || allow_fallbacks.is_some() {
+            self.routing = Some(ProviderPreferences {
+                order,
+                allow_fallbacks,
+            });
+        }
+        self
+    }
+
+    /// Sends a request to the Responses endpoint and returns the response,
+    /// with the rate limit and credit headers it carried.
     async fn send_request(
         &self,
         request: &ResponsesRequest,
-    ) -> Result<reqwest::Response, ProviderError> {
+    ) -> Result<(reqwest::Response, Option<RateLimitInfo>), ProviderError> {
         let json_body = serde_json::to_string(request)
             .map_err(|e| ProviderError::Network(format!("Request serialization failed: {e}")))?;
         info!("Raw OpenRouter Request: {}", json_body);
 
-        let response = self
-            .client
-            .post(format!("{}/responses", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
+        let builder = match &self.azure {
+            None => self
+                .client
+                .post(format!("{}/responses", self.base_url))
+                .header("Authorization", format!("Bearer {}", self.api_key)),
+            Some(azure) => self
+                .client
+                .post(format!(
+                    "{}/openai/deployments/{}/responses",
+                    self.base_url.trim_end_matches('/'),
+                    azure.name
+                ))
+                .query(&[("api-version", &azure.api_version)])
+                .header("api-key", &self.api_key),
+        };
+        let response = builder
             .header("Content-Type", "application/json")
             .body(json_body)
             .send()
             .await
-            .map_err(|e| ProviderError::Network(e.to_string()))?;
+            .map_err(ProviderError::from)?;
 
         debug!("OpenRouter response status: {}", response.status());
+        let rate_limit = rate_limit_info(response.headers());
+        let request_id = request_id_header(response.headers());
+        if let Some(info) = &rate_limit {
+            debug!("OpenRouter rate limit: {:?}", info);
+        }
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -338,11 +651,33 @@ impl OpenRouterProvider {
             return Err(ProviderError::Api {
                 status,
                 message: err_body,
+                request_id,
             });
         }
 
-        Ok(response)
+        Ok((response, rate_limit))
+    }
+}
+
+/// The `X-Request-Id` header, which identifies the request to support.
+fn request_id_header(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let id = headers.get("x-request-id")?.to_str().ok()?.trim();
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// Read OpenRouter's `X-RateLimit-*` and `X-Credits-Remaining` headers.
+/// None when the response carried none of them.
+fn rate_limit_info(headers: &reqwest::header::HeaderMap) -> Option<RateLimitInfo> {
+    fn parse<T: std::str::FromStr>(headers: &reqwest::header::HeaderMap, name: &str) -> Option<T> {
+        headers.get(name)?.to_str().ok()?.trim().parse().ok()
     }
+    let info = RateLimitInfo {
+        limit: parse(headers, "x-ratelimit-limit"),
+        remaining: parse(headers, "x-ratelimit-remaining"),
+        reset_ms: parse(headers, "x-ratelimit-reset"),
+        credits_remaining: parse(headers, "x-credits-remaining"),
+    };
+    (info != RateLimitInfo::default()).then_some(info)
 }
 
 #[async_trait]
@@ -352,21 +687,38 @@ impl CompletionProvider for OpenRouterProvider {
         request: CompletionRequest<'_>,
         sender: Sender<StreamChunk>,
     ) -> Result<(), ProviderError> {
-        let reasoning = effort_to_reasoning(request.effort);
+        let reasoning = request_reasoning(&request, self.disable_reasoning_flag);
+        let reasoning_event = reasoning_event_type(request.reasoning_mode);
 
         // Always send full context. OpenRouter's Responses API is stateless —
         // it does not persist conversation state between requests. Prompt
         // caching happens transparently via KV cache prefix reuse when the
-        // prompt prefix stays stable across turns.
-        let input = context_to_input(&request.context.items);
+        // prompt prefix stays stable across turns; providers that need an
+        // explicit marker (Anthropic) get one at the cache watermark.
+        let mut input = context_to_input(&request.context.items);
+        if request.prompt_cache {
+            let watermark = request.context.cache_watermark();
+            mark_cached_prefix(&mut input, &request.context.items, watermark);
+        }
+        // After cache marking, which indexes input by context position
+        if let Some(directive) = request.turn_directive {
+            insert_turn_directive(&mut input, directive);
+        }
 
+        // An Azure deployment serves one model, named by the deployment
+        let model = self
+            .azure
+            .as_ref()
+            .map_or(request.model, |a| a.name.as_str());
         let responses_request = ResponsesRequest {
-            model: request.model.to_string(),
+            model: model.to_string(),
             input,
             stream: Some(true),
             reasoning,
             tools: tools_to_api(request.tools),
             max_output_tokens: request.max_output_tokens,
+            temperature: request.temperature,
+            provider: self.routing.clone(),
         };
 
         info!(
@@ -376,112 +728,115 @@ impl CompletionProvider for OpenRouterProvider {
             request.effort,
         );
 
-        let response = self.send_request(&responses_request).await?;
+        let (response, rate_limit) = self.send_request(&responses_request).await?;
+        // Replaced by the response's own id if the header is missing
+        let mut request_id = request_id_header(response.headers());
+        if let Some(info) = rate_limit
+            && send_chunk(&sender, StreamChunk::RateLimit(info))
+                .await
+                .is_err()
+        {
+            return Err(ProviderError::ChannelClosed);
+        }
 
         // Process the SSE stream with typed events
-        let mut buffer = String::new();
-        let mut current_event_type: Option<String> = None;
+        let mut events = SseLineReader::new();
         let mut total_content_len = 0usize;
         let mut chunk_count = 0usize;
+        let mut dedupe = DeltaDeduper::new(request.dedupe_stream);
+        let mut malformed = DropCounter::new();
+        let provider_name = if self.azure.is_some() {
+            "azure"
+        } else {
+            "openrouter"
+        };
+        let mut transcript = StreamLog::open(self.stream_log.as_deref(), provider_name);
+        let mut decoder = Utf8Decoder::new();
         let mut response = response;
 
         // Tool call state: tracks concurrent tool calls by item_id
         let mut pending_tools: HashMap<String, PendingToolCall> = HashMap::new();
 
-        while let Some(chunk) = response
-            .chunk()
-            .await
-            .map_err(|e| ProviderError::Network(e.to_string()))?
-        {
-            let s = String::from_utf8_lossy(&chunk);
+        while let Some(chunk) = response.chunk().await.map_err(ProviderError::from)? {
             debug!("Raw chunk received: {} bytes", chunk.len());
-            buffer.push_str(&s);
-
-            // Process complete lines from buffer
-            while let Some(pos) = buffer.find('\n') {
-                let line = buffer[..pos].to_string();
-                buffer.drain(..pos + 1);
-
-                let line = line.trim();
-
-                // Log all non-empty lines for debugging
-                if !line.is_empty() {
-                    debug!("SSE line: {}", line);
-                }
-
-                // Parse SSE event type
-                if let Some(event_type) = line.strip_prefix("event: ") {
-                    debug!("SSE event type: {}", event_type);
-                    current_event_type = Some(event_type.to_string());
-                    continue;
-                }
-
-                // Parse SSE data
-                if let Some(data) = line.strip_prefix("data: ") {
-                    // Skip [DONE] marker
-                    if data == "[DONE]" {
-                        debug!("Received [DONE] marker");
-                        continue;
-                    }
-
-                    // Parse the JSON to extract the type field
-                    let event_type = current_event_type.clone().or_else(|| {
-                        serde_json::from_str::<SseEvent>(data)
-                            .ok()
-                            .map(|e| e.event_type)
-                    });
-
-                    debug!("SSE data for event {:?}: {} bytes", event_type, data.len());
-
-                    match event_type.as_deref() {
-                        Some("response.output_text.delta") => {
-                            if let Ok(event) = serde_json::from_str::<SseEvent>(data)
-                                && !event.delta.is_empty()
+            events.push(&decoder.decode(&chunk));
+
+            while let Some((event_type, data)) =
+                events.next_event(&mut |line| stream_log::record(&mut transcript, line))
+            {
+                match event_type.as_deref() {
+                    Some("response.output_text.delta") => {
+                        if let Some(event) =
+                            malformed.parse::<SseEvent>("response.output_text.delta", &data)
+                            && !event.delta.is_empty()
+                            && !dedupe.is_repeat(DeltaKind::Content, &event.item_id, &event.delta)
+                        {
+                            chunk_count += 1;
+                            total_content_len += event.delta.len();
+                            debug!(
+                                "Sending Content chunk (len={}, total={})",
+                                event.delta.len(),
+                                total_content_len
+                            );
+                            if send_chunk(
+                                &sender,
+                                StreamChunk::Content {
+                                    text: event.delta,
+                                    item_id: non_empty(event.item_id),
+                                },
+                            )
+                            .await
+                            .is_err()
                             {
-                                chunk_count += 1;
-                                total_content_len += event.delta.len();
-                                debug!(
-                                    "Sending Content chunk (len={}, total={})",
-                                    event.delta.len(),
-                                    total_content_len
-                                );
-                                if sender
-                                    .send(StreamChunk::Content {
-                                        text: event.delta,
-                                        item_id: non_empty(event.item_id),
-                                    })
-                                    .await
-                                    .is_err()
-                                {
-                                    warn!("Content chunk send failed: receiver dropped");
-                                    return Err(ProviderError::ChannelClosed);
-                                }
+                                warn!("Content chunk send failed: receiver dropped");
+                                return Err(ProviderError::ChannelClosed);
                             }
                         }
-                        Some("response.reasoning_summary_text.delta")
-                        | Some("response.reasoning_text.delta") => {
-                            if let Ok(event) = serde_json::from_str::<SseEvent>(data)
-                                && !event.delta.is_empty()
+                    }
+                    Some(event_type) if event_type == reasoning_event => {
+                        if let Some(event) = malformed.parse::<SseEvent>(event_type, &data)
+                            && !event.delta.is_empty()
+                            && !dedupe.is_repeat(DeltaKind::Thinking, &event.item_id, &event.delta)
+                        {
+                            chunk_count += 1;
+                            debug!("Sending Thinking chunk (len={})", event.delta.len());
+                            if send_chunk(
+                                &sender,
+                                StreamChunk::Thinking {
+                                    text: event.delta,
+                                    item_id: non_empty(event.item_id),
+                                },
+                            )
+                            .await
+                            .is_err()
                             {
-                                chunk_count += 1;
-                                debug!("Sending Thinking chunk (len={})", event.delta.len());
-                                if sender
-                                    .send(StreamChunk::Thinking {
-                                        text: event.delta,
-                                        item_id: non_empty(event.item_id),
-                                    })
-                                    .await
-                                    .is_err()
-                                {
-                                    warn!("Thinking chunk send failed: receiver dropped");
+                                warn!("Thinking chunk send failed: receiver dropped");
+                                return Err(ProviderError::ChannelClosed);
+                            }
+                        }
+                    }
+                    Some("response.reasoning_summary_text.delta")
+                    | Some("response.reasoning_text.delta") => {
+                        // The other reasoning stream — surfacing both duplicates content
+                        debug!(
+                            "Dropping {:?} (reasoning_mode={:?})",
+                            event_type, request.reasoning_mode
+                        );
+                    }
+                    Some("response.output_item.added") => {
+                        let event = data.parse::<OutputItemEvent>().ok();
+                        match event {
+                            Some(event) if event.item.item_type == "reasoning" => {
+                                debug!("Reasoning started (item_id={})", event.item.id);
+                                let chunk = StreamChunk::ThinkingStart {
+                                    item_id: non_empty(event.item.id),
+                                };
+                                if send_chunk(&sender, chunk).await.is_err() {
+                                    warn!("ThinkingStart send failed: receiver dropped");
                                     return Err(ProviderError::ChannelClosed);
                                 }
                             }
-                        }
-                        Some("response.output_item.added") => {
-                            if let Ok(event) = serde_json::from_str::<OutputItemAddedEvent>(data)
-                                && event.item.item_type == "function_call"
-                            {
+                            Some(event) if event.item.item_type == "function_call" => {
                                 debug!(
                                     "Tool call started: {} (item_id={}, call_id={})",
                                     event.item.name, event.item.id, event.item.call_id
@@ -491,76 +846,139 @@ impl CompletionProvider for OpenRouterProvider {
                                     PendingToolCall {
                                         id: event.item.id,
                                         call_id: event.item.call_id,
+                                        name: event.item.name,
+                                        args_len: 0,
                                     },
                                 );
                             }
+                            _ => {}
                         }
-                        Some("response.function_call_arguments.delta") => {
-                            // Delta events are ignored — the done event contains the full arguments.
-                            // We parse the event only to validate the item_id correlation.
-                            if let Ok(event) =
-                                serde_json::from_str::<FunctionCallArgsDeltaEvent>(data)
-                                && !pending_tools.contains_key(&event.item_id)
-                            {
-                                warn!("Argument delta for unknown item_id: {}", event.item_id);
+                    }
+                    Some("response.output_item.done") => {
+                        if let Ok(event) = data.parse::<OutputItemEvent>()
+                            && event.item.item_type == "reasoning"
+                        {
+                            debug!("Reasoning finished (item_id={})", event.item.id);
+                            let chunk = StreamChunk::ThinkingEnd {
+                                item_id: non_empty(event.item.id),
+                            };
+                            if send_chunk(&sender, chunk).await.is_err() {
+                                warn!("ThinkingEnd send failed: receiver dropped");
+                                return Err(ProviderError::ChannelClosed);
                             }
                         }
-                        Some("response.function_call_arguments.done") => {
-                            if let Ok(event) =
-                                serde_json::from_str::<FunctionCallArgsDoneEvent>(data)
-                            {
-                                let pending = pending_tools.remove(&event.item_id);
-                                let (id, call_id) = match pending {
-                                    Some(p) => (p.id, p.call_id),
-                                    None => {
-                                        warn!(
-                                            "arguments.done for unknown item_id: {}, skipping",
-                                            event.item_id
-                                        );
-                                        continue;
-                                    }
-                                };
-                                let tool_call = crate::inference::ToolCall {
-                                    id,
-                                    call_id,
-                                    name: event.name.clone(),
-                                    arguments: event.arguments,
-                                };
-                                debug!(
-                                    "Tool call complete: {} (item_id={}, call_id={})",
-                                    event.name, event.item_id, tool_call.call_id
-                                );
-                                chunk_count += 1;
-                                if sender.send(StreamChunk::ToolCall(tool_call)).await.is_err() {
-                                    warn!("ToolCall send failed: receiver dropped");
+                    }
+                    Some("response.function_call_arguments.delta") => {
+                        // The done event contains the full arguments, so deltas are
+                        // only counted, to abandon a call whose arguments run away.
+                        if let Ok(event) = data.parse::<FunctionCallArgsDeltaEvent>() {
+                            let Some(pending) = pending_tools.get_mut(&event.item_id) else {
+                                warn!("Argument delta for unknown item_id: {}", event.item_id);
+                                continue;
+                            };
+                            pending.args_len += event.delta.len();
+                            if let Some(message) = oversized_arguments(
+                                &pending.name,
+                                pending.args_len,
+                                self.max_tool_args_bytes,
+                            ) {
+                                if send_chunk(&sender, StreamChunk::Error(message))
+                                    .await
+                                    .is_err()
+                                {
+                                    warn!("Error send failed: receiver dropped");
                                     return Err(ProviderError::ChannelClosed);
                                 }
+                                return Ok(());
                             }
                         }
-                        Some("response.completed") => {
-                            info!(
-                                "Stream complete: {} chunks, {} content bytes",
-                                chunk_count, total_content_len
+                    }
+                    Some("response.function_call_arguments.done") => {
+                        if let Ok(event) = data.parse::<FunctionCallArgsDoneEvent>() {
+                            let pending = pending_tools.remove(&event.item_id);
+                            let (id, call_id) = match pending {
+                                Some(p) => (p.id, p.call_id),
+                                None => {
+                                    warn!(
+                                        "arguments.done for unknown item_id: {}, skipping",
+                                        event.item_id
+                                    );
+                                    continue;
+                                }
+                            };
+                            let tool_call = crate::inference::ToolCall {
+                                id,
+                                call_id,
+                                name: event.name.clone(),
+                                arguments: validated_arguments(&event.name, event.arguments),
+                            };
+                            debug!(
+                                "Tool call complete: {} (item_id={}, call_id={})",
+                                event.name, event.item_id, tool_call.call_id
                             );
-                            debug!("response.completed data: {}", data);
-                            let stats = parse_completed_payload(data);
-                            if sender.send(StreamChunk::Completed(stats)).await.is_err() {
-                                warn!("Completed send failed: receiver dropped");
+                            chunk_count += 1;
+                            if send_chunk(&sender, StreamChunk::ToolCall(tool_call))
+                                .await
+                                .is_err()
+                            {
+                                warn!("ToolCall send failed: receiver dropped");
                                 return Err(ProviderError::ChannelClosed);
                             }
-                            return Ok(());
                         }
-                        Some(other) => {
-                            // Ignore other event types (response.created, response.in_progress, etc.)
-                            debug!("Ignoring event type '{}': {} bytes", other, data.len());
+                    }
+                    Some("response.completed") | Some("response.incomplete") => {
+                        info!(
+                            "Stream complete: {} chunks, {} content bytes, {} dropped",
+                            chunk_count,
+                            total_content_len,
+                            malformed.dropped()
+                        );
+                        debug!("response.completed data: {}", data);
+                        let mut stats = malformed.report(parse_completed_payload(&data));
+                        if let Some(stats) = stats.as_mut()
+                            && stats.request_id.is_none()
+                        {
+                            stats.request_id = request_id.clone();
                         }
-                        None => {
-                            debug!("Could not parse event type from data: {}", data);
+                        if send_chunk(&sender, StreamChunk::Completed(stats))
+                            .await
+                            .is_err()
+                        {
+                            warn!("Completed send failed: receiver dropped");
+                            return Err(ProviderError::ChannelClosed);
                         }
+                        return Ok(());
+                    }
+                    Some("response.created") => {
+                        if request_id.is_none() {
+                            request_id = parse_response_id(&data);
+                        }
+                        debug!("Response created (request id {:?})", request_id);
+                    }
+                    Some("error") | Some("response.failed") => {
+                        let id = parse_response_id(&data).or_else(|| request_id.clone());
+                        let message = with_request_id(parse_error_payload(&data), id.as_deref());
+                        warn!("Stream error event: {}", message);
+                        if send_chunk(&sender, StreamChunk::Error(message))
+                            .await
+                            .is_err()
+                        {
+                            warn!("Error send failed: receiver dropped");
+                            return Err(ProviderError::ChannelClosed);
+                        }
+                        return Ok(());
+                    }
+                    Some(other) => {
+                        // Ignore other event types (response.created, response.in_progress, etc.)
+                        debug!(
+                            "Ignoring event type '{}': {} bytes",
+                            other,
+                            data.raw().len()
+                        );
+                    }
+                    None => {
+                        debug!("Could not parse event type from data: {}", data);
                     }
-
-                    // Reset event type after processing data
-                    current_event_type = None;
                 }
             }
         }
@@ -573,8 +991,10 @@ impl CompletionProvider for OpenRouterProvider {
             );
         }
         info!(
-            "Stream ended: {} chunks processed, {} total content bytes",
-            chunk_count, total_content_len
+            "Stream ended: {} chunks processed, {} total content bytes, {} dropped",
+            chunk_count,
+            total_content_len,
+            malformed.dropped()
         );
         Ok(())
     }
@@ -591,14 +1011,17 @@ mod tests {
         context.add(ContextSegment {
             source: Source::User,
             content: "Hello".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::Thinking,
             content: "Internal thought".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::Model,
             content: "Response".to_string(),
+            timestamp: None,
         });
 
         let input = context_to_input(&context.items);
@@ -621,6 +1044,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_context_to_input_sends_the_shown_variant() {
+        let mut context = Context::new();
+        context.add_user_message("Hello".to_string());
+        context.append_to_last_model_message("First answer", None);
+        context.begin_variant();
+        context.append_to_last_model_message("Second answer", None);
+
+        let model_text = |context: &Context| {
+            let input = context_to_input(&context.items);
+            assert_eq!(input.len(), 3); // Only one answer is ever sent
+            match &input[2] {
+                InputItem::Message {
+                    role: Role::Assistant,
+                    content,
+                } => content.clone(),
+                other => panic!("expected an assistant message, got {:?}", other),
+            }
+        };
+        assert_eq!(model_text(&context), "Second answer");
+        context.cycle_variant(true);
+        assert_eq!(model_text(&context), "First answer");
+        context.cycle_variant(true);
+        assert_eq!(model_text(&context), "Second answer");
+    }
+
     #[test]
     fn test_context_to_input_translates_roles_correctly() {
         let mut context = Context::new();
@@ -629,14 +1078,17 @@ mod tests {
         context.add(ContextSegment {
             source: Source::Directive,
             content: "System message".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::User,
             content: "User message".to_string(),
+            timestamp: None,
         });
         context.add(ContextSegment {
             source: Source::Model,
             content: "Model message".to_string(),
+            timestamp: None,
         });
 
         let input = context_to_input(&context.items);
@@ -655,15 +1107,123 @@ mod tests {
 
     #[test]
     fn test_effort_to_reasoning_returns_correct_values() {
-        assert_eq!(effort_to_reasoning(Effort::High).effort, Some("high"));
-        assert_eq!(effort_to_reasoning(Effort::Medium).effort, Some("medium"));
-        assert_eq!(effort_to_reasoning(Effort::Low).effort, Some("low"));
-        assert_eq!(effort_to_reasoning(Effort::None).effort, Some("none"));
-        assert_eq!(effort_to_reasoning(Effort::Auto).effort, None);
+        assert_eq!(
+            effort_to_reasoning(Effort::High, false).effort,
+            Some("high")
+        );
+        assert_eq!(
+            effort_to_reasoning(Effort::Medium, false).effort,
+            Some("medium")
+        );
+        assert_eq!(effort_to_reasoning(Effort::Low, false).effort, Some("low"));
+        assert_eq!(
+            effort_to_reasoning(Effort::None, false).effort,
+            Some("none")
+        );
+        assert_eq!(effort_to_reasoning(Effort::Auto, false).effort, None);
 
         // Auto uses enabled flag, explicit efforts don't
-        assert_eq!(effort_to_reasoning(Effort::Auto).enabled, Some(true));
-        assert_eq!(effort_to_reasoning(Effort::High).enabled, None);
+        assert_eq!(effort_to_reasoning(Effort::Auto, false).enabled, Some(true));
+        assert_eq!(effort_to_reasoning(Effort::High, false).enabled, None);
+    }
+
+    #[test]
+    fn test_context_to_input_serializes_image_as_content_parts() {
+        let mut context = Context::new();
+        context.add_image(ImageAttachment {
+            data_base64: "iVBORw0KGgo=".to_string(),
+            mime: "image/png".to_string(),
+            caption: Some("cat.png".to_string()),
+        });
+
+        let input = context_to_input(&context.items);
+        assert_eq!(input.len(), 2);
+
+        let json = serde_json::to_value(&input[1]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "message",
+                "role": "user",
+                "content": [
+                    {"type": "input_text", "text": "cat.png"},
+                    {
+                        "type": "input_image",
+                        "image_url": "data:image/png;base64,iVBORw0KGgo=",
+                        "detail": "auto"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_mark_cached_prefix_marks_last_user_message_before_watermark() {
+        let mut context = Context::new();
+        context.add_user_message("first".to_string());
+        context.add(ContextSegment {
+            source: Source::Thinking,
+            content: "hmm".to_string(),
+            timestamp: None,
+        });
+        context.add(ContextSegment {
+            source: Source::Model,
+            content: "reply".to_string(),
+            timestamp: None,
+        });
+        context.advance_cache_watermark();
+        context.add_user_message("second".to_string());
+
+        let mut input = context_to_input(&context.items);
+        mark_cached_prefix(&mut input, &context.items, context.cache_watermark());
+        let json = serde_json::to_value(&input).unwrap();
+
+        // The breakpoint lands on the user message, skipping the assistant reply
+        assert_eq!(
+            json[1]["content"],
+            serde_json::json!([{
+                "type": "input_text",
+                "text": "first",
+                "cache_control": {"type": "ephemeral"}
+            }])
+        );
+        assert_eq!(json[2]["content"], "reply");
+        // Items past the watermark are sent as usual
+        assert_eq!(json[3]["content"], "second");
+    }
+
+    #[test]
+    fn test_mark_cached_prefix_without_watermark_is_noop() {
+        let mut context = Context::new();
+        context.add_user_message("hello".to_string());
+
+        let mut input = context_to_input(&context.items);
+        mark_cached_prefix(&mut input, &context.items, context.cache_watermark());
+        let json = serde_json::to_value(&input).unwrap();
+
+        assert!(json[0]["content"].is_string());
+        assert_eq!(json[1]["content"], "hello");
+        assert!(!json.to_string().contains("cache_control"));
+    }
+
+    #[test]
+    fn test_insert_turn_directive_follows_system_prompt() {
+        let mut context = Context::new();
+        context.add_user_message("hello".to_string());
+
+        let mut input = context_to_input(&context.items);
+        insert_turn_directive(&mut input, "Answer in French.");
+        let json = serde_json::to_value(&input).unwrap();
+
+        assert_eq!(json[0]["role"], "system");
+        assert_eq!(json[1]["role"], "system");
+        assert_eq!(json[1]["content"], "Answer in French.");
+        assert_eq!(json[2]["content"], "hello");
+
+        // Without a system prompt the directive goes first
+        let mut input = Vec::new();
+        insert_turn_directive(&mut input, "Answer in French.");
+        assert_eq!(input.len(), 1);
     }
 
     #[test]
@@ -697,9 +1257,11 @@ mod tests {
             model: "test".to_string(),
             input: vec![],
             stream: None,
-            reasoning: effort_to_reasoning(Effort::Auto),
+            reasoning: Some(effort_to_reasoning(Effort::Auto, false)),
             tools: None,
             max_output_tokens: None,
+            temperature: None,
+            provider: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -715,9 +1277,11 @@ mod tests {
             model: "test".to_string(),
             input: vec![],
             stream: Some(true),
-            reasoning: effort_to_reasoning(Effort::High),
+            reasoning: Some(effort_to_reasoning(Effort::High, false)),
             tools: None,
             max_output_tokens: None,
+            temperature: None,
+            provider: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -731,50 +1295,192 @@ mod tests {
             model: "test".to_string(),
             input: vec![],
             stream: Some(true),
-            reasoning: effort_to_reasoning(Effort::None),
+            reasoning: Some(effort_to_reasoning(Effort::None, false)),
             tools: None,
             max_output_tokens: None,
+            temperature: None,
+            provider: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains(r#""effort":"none"#));
+        assert!(!json.contains("enabled"));
+    }
+
+    #[test]
+    fn test_responses_request_reasoning_off_with_disable_flag() {
+        let request = ResponsesRequest {
+            model: "test".to_string(),
+            input: vec![],
+            stream: Some(true),
+            reasoning: Some(effort_to_reasoning(Effort::None, true)),
+            tools: None,
+            max_output_tokens: None,
+            temperature: None,
+            provider: None,
+        };
+
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains(r#""reasoning":{"enabled":false}"#));
+
+        // Only Effort::None changes shape
+        let high = effort_to_reasoning(Effort::High, true);
+        assert_eq!((high.effort, high.enabled), (Some("high"), None));
+        let auto = effort_to_reasoning(Effort::Auto, true);
+        assert_eq!((auto.effort, auto.enabled), (None, Some(true)));
+    }
+
+    #[test]
+    fn test_responses_request_omits_reasoning_for_non_reasoning_model() {
+        let context = Context::new();
+        let mut request = CompletionRequest {
+            context: &context,
+            model: "test",
+            effort: Effort::High,
+            reasoning: true,
+            tools: &[],
+            max_output_tokens: None,
+            temperature: None,
+            reasoning_mode: ReasoningMode::default(),
+            dedupe_stream: false,
+            prompt_cache: false,
+            turn_directive: None,
+        };
+        assert!(request_reasoning(&request, false).is_some());
+
+        request.reasoning = false;
+        let body = ResponsesRequest {
+            model: "test".to_string(),
+            input: vec![],
+            stream: Some(true),
+            reasoning: request_reasoning(&request, false),
+            tools: None,
+            max_output_tokens: None,
+            temperature: None,
+            provider: None,
+        };
+        let json = serde_json::to_string(&body).unwrap();
+        assert!(!json.contains("reasoning"));
+        assert!(!json.contains("effort"));
+    }
+
+    #[test]
+    fn test_responses_request_temperature_serialized_when_set() {
+        let mut request = ResponsesRequest {
+            model: "test".to_string(),
+            input: vec![],
+            stream: Some(true),
+            reasoning: Some(effort_to_reasoning(Effort::Auto, false)),
+            tools: None,
+            max_output_tokens: None,
+            temperature: None,
+            provider: None,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(!json.contains("temperature"));
+
+        request.temperature = Some(0.5);
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains(r#""temperature":0.5"#));
+    }
+
+    /// Serialize a minimal request routed the way `provider` is configured.
+    fn request_json(provider: &OpenRouterProvider) -> serde_json::Value {
+        let request = ResponsesRequest {
+            model: "test".to_string(),
+            input: vec![],
+            stream: Some(true),
+            reasoning: Some(effort_to_reasoning(Effort::Auto, false)),
+            tools: None,
+            max_output_tokens: None,
+            temperature: None,
+            provider: provider.routing.clone(),
+        };
+        serde_json::to_value(&request).unwrap()
+    }
+
+    #[test]
+    fn test_responses_request_routing_absent_by_default() {
+        let provider = OpenRouterProvider::new("key".to_string(), None);
+        assert!(request_json(&provider).get("provider").is_none());
+
+        let unset = OpenRouterProvider::new("key".to_string(), None).with_routing(vec![], None);
+        assert!(request_json(&unset).get("provider").is_none());
+    }
+
+    #[test]
+    fn test_responses_request_routing_serialized_with_order() {
+        let provider = OpenRouterProvider::new("key".to_string(), None)
+            .with_routing(vec!["anthropic".into(), "together".into()], Some(false));
+        assert_eq!(
+            request_json(&provider)["provider"],
+            serde_json::json!({ "order": ["anthropic", "together"], "allow_fallbacks": false })
+        );
+
+        // Only the keys that were configured are sent
+        let provider = OpenRouterProvider::new("key".to_string(), None)
+            .with_routing(vec!["deepinfra".into()], None);
+        assert_eq!(
+            request_json(&provider)["provider"],
+            serde_json::json!({ "order": ["deepinfra"] })
+        );
     }
 
     #[test]
     fn test_sse_event_deserialization_with_embedded_type() {
         let json = r#"{"type":"response.output_text.delta","delta":"Hello"}"#;
-        let event: SseEvent = serde_json::from_str(json).unwrap();
+        let (event_type, data) = read_data_line(json);
+        let event: SseEvent = data.parse().unwrap();
 
-        assert_eq!(event.event_type, "response.output_text.delta");
+        assert_eq!(event_type.as_deref(), Some("response.output_text.delta"));
+        assert_eq!(data.raw(), json);
         assert_eq!(event.delta, "Hello");
     }
 
     #[test]
     fn test_sse_event_deserialization_missing_delta() {
         let json = r#"{"type":"response.created"}"#;
-        let event: SseEvent = serde_json::from_str(json).unwrap();
+        let (event_type, data) = read_data_line(json);
+        let event: SseEvent = data.parse().unwrap();
 
-        assert_eq!(event.event_type, "response.created");
+        assert_eq!(event_type.as_deref(), Some("response.created"));
         assert_eq!(event.delta, ""); // Default is empty string
     }
 
+    /// The `(event_type, data)` pair `SseLineReader` yields for a single
+    /// OpenRouter `data:` line, which carries its type inside the JSON.
+    fn read_data_line(json: &str) -> (Option<String>, SseData) {
+        let mut events = SseLineReader::new();
+        events.push(&format!("data: {json}\n"));
+        events.next_event(&mut |_| {}).unwrap()
+    }
+
     #[test]
     fn test_parse_completed_payload_with_usage() {
         let data = r#"{"type":"response.completed","response":{"id":"resp_1","usage":{"input_tokens":150,"output_tokens":42,"total_tokens":192,"cache_read_input_tokens":80},"status":"completed"}}"#;
-        let stats = parse_completed_payload(data).unwrap();
+        let stats = parse_completed_payload(&SseData::from(data)).unwrap();
         assert_eq!(stats.input_tokens, Some(150));
         assert_eq!(stats.output_tokens, Some(42));
         assert_eq!(stats.total_tokens, Some(192));
         assert_eq!(stats.cache_read_input_tokens, Some(80));
         assert_eq!(stats.cache_creation_input_tokens, None);
         assert_eq!(stats.finish_reason.as_deref(), Some("completed"));
+        assert_eq!(stats.request_id.as_deref(), Some("resp_1"));
+    }
+
+    #[test]
+    fn test_parse_incomplete_payload_reports_reason() {
+        let data = r#"{"type":"response.incomplete","response":{"id":"resp_1","usage":{"input_tokens":10,"output_tokens":64},"status":"incomplete","incomplete_details":{"reason":"max_output_tokens"}}}"#;
+        let stats = parse_completed_payload(&SseData::from(data)).unwrap();
+        assert_eq!(stats.output_tokens, Some(64));
+        assert_eq!(stats.finish_reason.as_deref(), Some("max_output_tokens"));
     }
 
     #[test]
     fn test_parse_completed_payload_without_usage() {
         let data =
             r#"{"type":"response.completed","response":{"id":"resp_1","status":"completed"}}"#;
-        let stats = parse_completed_payload(data);
+        let stats = parse_completed_payload(&SseData::from(data));
         assert!(stats.is_none());
     }
 
@@ -782,21 +1488,39 @@ mod tests {
     fn test_parse_completed_payload_no_response_object() {
         // Gracefully handle missing response key entirely
         let data = r#"{"type":"response.completed"}"#;
-        let stats = parse_completed_payload(data);
+        let stats = parse_completed_payload(&SseData::from(data));
         assert!(stats.is_none());
     }
 
     #[test]
     fn test_parse_completed_payload_with_cache_creation() {
         let data = r#"{"type":"response.completed","response":{"usage":{"input_tokens":200,"output_tokens":50,"total_tokens":250,"cache_creation_input_tokens":100,"cache_read_input_tokens":0}}}"#;
-        let stats = parse_completed_payload(data).unwrap();
+        let stats = parse_completed_payload(&SseData::from(data)).unwrap();
         assert_eq!(stats.cache_creation_input_tokens, Some(100));
         assert_eq!(stats.cache_read_input_tokens, Some(0));
     }
 
     #[test]
     fn test_parse_completed_payload_invalid_json() {
-        let stats = parse_completed_payload("not json");
+        let stats = parse_completed_payload(&SseData::from("not json"));
         assert!(stats.is_none());
     }
+
+    #[test]
+    fn test_rate_limit_info_partial_and_absent_headers() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(rate_limit_info(&headers), None);
+
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("3"));
+        headers.insert(
+            "x-credits-remaining",
+            HeaderValue::from_static("not a number"),
+        );
+        let info = rate_limit_info(&headers).unwrap();
+        assert_eq!(info.remaining, Some(3));
+        assert_eq!(info.limit, None);
+        assert_eq!(info.credits_remaining, None);
+    }
 }
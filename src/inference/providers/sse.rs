@@ -0,0 +1,228 @@
+//! # SSE Line Reading
+//!
+//! Both Responses API providers receive the same server-sent event framing.
+//! `SseLineReader` splits a streamed body into `(event_type, data)` pairs,
+//! leaving each provider only the mapping from events to `StreamChunk`s.
+//!
+//! Each `data:` payload is parsed as JSON once, here: the reader needs it to
+//! find an embedded event type, and the provider deserializes its typed
+//! event from the same `SseData`.
+
+use std::fmt;
+
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// The payload of one `data:` line, kept both raw (for logs and error text)
+/// and parsed.
+#[derive(Debug)]
+pub(super) struct SseData {
+    raw: String,
+    json: Option<Value>, // None when the payload isn't JSON
+}
+
+impl SseData {
+    pub(super) fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Deserialize the payload as `T` from the JSON parsed on arrival.
+    pub(super) fn parse<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        match &self.json {
+            Some(json) => T::deserialize(json),
+            // Reproduces the syntax error for the caller to report
+            None => serde_json::from_str(&self.raw),
+        }
+    }
+
+    /// The `type` field of a JSON payload, for streams without `event:` lines.
+    fn embedded_type(&self) -> Option<String> {
+        let event_type = self.json.as_ref()?.get("type")?.as_str()?;
+        Some(event_type.to_string())
+    }
+}
+
+impl From<&str> for SseData {
+    fn from(raw: &str) -> Self {
+        Self {
+            raw: raw.to_string(),
+            json: serde_json::from_str(raw).ok(),
+        }
+    }
+}
+
+impl fmt::Display for SseData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// Splits a streamed SSE body into `(event_type, data)` pairs.
+///
+/// Providers name events one of two ways: an `event:` line before the
+/// `data:` line (LM Studio), or just a `type` field inside the JSON
+/// (OpenRouter). The pair carries whichever is there, the `event:` line
+/// winning. Every `data:` line is an event of its own; comments, blank lines
+/// and the `[DONE]` marker yield nothing.
+///
+/// Text can be pushed in pieces of any size. A line is read once its `\n`
+/// has arrived, and a `\r` before it is dropped.
+#[derive(Debug, Default)]
+pub(super) struct SseLineReader {
+    buffer: String,
+    event_type: Option<String>, // from the last `event:` line, until its data
+}
+
+impl SseLineReader {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append decoded text from the stream.
+    pub(super) fn push(&mut self, text: &str) {
+        self.buffer.push_str(text);
+    }
+
+    /// The next event among the complete lines pushed so far, or None
+    /// until more text arrives. `on_line` sees every raw line read on the
+    /// way, for the stream transcript.
+    pub(super) fn next_event(
+        &mut self,
+        on_line: &mut impl FnMut(&str),
+    ) -> Option<(Option<String>, SseData)> {
+        while let Some(pos) = self.buffer.find('\n') {
+            let raw: String = self.buffer.drain(..=pos).collect();
+            let raw = raw[..pos].trim_end_matches('\r');
+            on_line(raw);
+
+            let line = raw.trim();
+            if !line.is_empty() {
+                debug!("SSE line: {}", line);
+            }
+
+            if let Some(event_type) = field(line, "event") {
+                self.event_type = Some(event_type.to_string());
+                continue;
+            }
+            let Some(data) = field(line, "data") else {
+                continue;
+            };
+            let event_type = self.event_type.take();
+            if data == "[DONE]" {
+                debug!("Received [DONE] marker");
+                continue;
+            }
+            let data = SseData::from(data);
+            let event_type = event_type.or_else(|| data.embedded_type());
+            debug!(
+                "SSE data for event {:?}: {} bytes",
+                event_type,
+                data.raw().len()
+            );
+            return Some((event_type, data));
+        }
+        None
+    }
+}
+
+/// The value of an SSE `name:` line, without the one optional space after
+/// the colon.
+fn field<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let value = line.strip_prefix(name)?.strip_prefix(':')?;
+    Some(value.strip_prefix(' ').unwrap_or(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    fn events(reader: &mut SseLineReader) -> Vec<(Option<String>, String)> {
+        std::iter::from_fn(|| reader.next_event(&mut |_| {}))
+            .map(|(event_type, data)| (event_type, data.raw().to_string()))
+            .collect()
+    }
+
+    fn event(event_type: &str, data: &str) -> (Option<String>, String) {
+        (Some(event_type.to_string()), data.to_string())
+    }
+
+    #[test]
+    fn test_event_line_and_embedded_type() {
+        let mut reader = SseLineReader::new();
+        reader.push(concat!(
+            "event: response.created\n",
+            "data: {\"id\":\"resp_1\"}\n",
+            "\n",
+            "data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hi\"}\n",
+            ": keep-alive comment\n",
+            "data: not json\n",
+        ));
+        assert_eq!(
+            events(&mut reader),
+            [
+                event("response.created", r#"{"id":"resp_1"}"#),
+                event(
+                    "response.output_text.delta",
+                    r#"{"type":"response.output_text.delta","delta":"Hi"}"#
+                ),
+                (None, "not json".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_line_waits_for_newline() {
+        let mut reader = SseLineReader::new();
+        reader.push("event: response.compl");
+        assert!(events(&mut reader).is_empty());
+        reader.push("eted\ndata: {\"a\"");
+        assert!(events(&mut reader).is_empty());
+        reader.push(":1}\n");
+        assert_eq!(
+            events(&mut reader),
+            [event("response.completed", r#"{"a":1}"#)]
+        );
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        let mut reader = SseLineReader::new();
+        let mut lines = Vec::new();
+        reader.push("event:error\r\ndata: {}\r\n\r\n");
+        let (event_type, data) = reader
+            .next_event(&mut |line| lines.push(line.to_string()))
+            .unwrap();
+        assert_eq!((event_type, data.raw()), (Some("error".to_string()), "{}"));
+        assert!(
+            reader
+                .next_event(&mut |line| lines.push(line.to_string()))
+                .is_none()
+        );
+        assert_eq!(lines, ["event:error", "data: {}", ""]);
+    }
+
+    #[test]
+    fn test_done_marker_is_skipped() {
+        let mut reader = SseLineReader::new();
+        reader.push("event: response.done\ndata: [DONE]\ndata: {\"type\":\"x\"}\n");
+        // The event line applied to [DONE], not to the data after it
+        assert_eq!(events(&mut reader), [event("x", r#"{"type":"x"}"#)]);
+    }
+
+    #[test]
+    fn test_data_parses_from_the_stored_json() {
+        #[derive(Deserialize)]
+        struct Delta {
+            delta: String,
+        }
+        let data = SseData::from(r#"{"type":"t","delta":"Hi"}"#);
+        assert_eq!(data.parse::<Delta>().unwrap().delta, "Hi");
+
+        let bad = SseData::from("not json");
+        assert!(bad.parse::<Delta>().is_err());
+        assert_eq!(bad.to_string(), "not json");
+    }
+}
@@ -0,0 +1,87 @@
+//! # Stream Transcript
+//!
+//! With `NAVI_STREAM_LOG` (or `[general] stream_log`) set, every raw SSE
+//! line a provider reads is appended to that file before it's parsed, so a
+//! misbehaving stream can be replayed exactly as it arrived. Each stream
+//! starts with an SSE comment line naming the provider, and every line is
+//! flushed as it's written so the file stays useful after a crash.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use log::warn;
+
+/// An open transcript file for one stream.
+pub(super) struct StreamLog {
+    file: File,
+}
+
+impl StreamLog {
+    /// Open `path` for appending and mark the start of a `provider` stream.
+    /// None when no path is configured or the file can't be opened.
+    pub(super) fn open(path: Option<&Path>, provider: &str) -> Option<Self> {
+        let path = path?;
+        let opened = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(|file| Self { file });
+        let mut log = match opened {
+            Ok(log) => log,
+            Err(e) => {
+                warn!("Failed to open stream log {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        log.write_line(&format!(": navi {} stream", provider))
+            .map_err(|e| warn!("Failed to write stream log {}: {}", path.display(), e))
+            .ok()?;
+        Some(log)
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()
+    }
+}
+
+/// Append `line` to the transcript, if there is one. A failed write is
+/// logged and closes the transcript rather than the stream.
+pub(super) fn record(log: &mut Option<StreamLog>, line: &str) {
+    if let Some(open) = log
+        && let Err(e) = open.write_line(line)
+    {
+        warn!("Stream log write failed, logging stopped: {}", e);
+        *log = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_appended_after_stream_marker() {
+        let path = std::env::temp_dir().join("navi_test_stream_log_unit.log");
+        let _ = std::fs::remove_file(&path);
+
+        let mut log = StreamLog::open(Some(&path), "test");
+        record(&mut log, "event: response.created");
+        record(&mut log, "");
+        let mut log = StreamLog::open(Some(&path), "test");
+        record(&mut log, "data: [DONE]");
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            text,
+            ": navi test stream\nevent: response.created\n\n: navi test stream\ndata: [DONE]\n"
+        );
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_no_path_no_log() {
+        assert!(StreamLog::open(None, "test").is_none());
+    }
+}
@@ -0,0 +1,68 @@
+//! # Tool Argument Validation
+//!
+//! Both providers buffer a tool call's arguments and emit them whole on
+//! `response.function_call_arguments.done`. Models occasionally produce
+//! malformed JSON there. Rather than let it fail later inside the tool
+//! registry with an opaque parse error, it is logged and replaced with `{}`,
+//! so the tool reports which arguments it is missing.
+//!
+//! A runaway model can also stream arguments without end. Each provider
+//! counts a call's argument deltas and abandons the stream with an error
+//! once they pass `max_tool_args_bytes`.
+
+use log::warn;
+
+/// Arguments to emit for a finished tool call: unchanged when they parse as
+/// JSON, otherwise `{}`.
+pub(super) fn validated_arguments(tool_name: &str, arguments: String) -> String {
+    match serde_json::from_str::<serde_json::Value>(&arguments) {
+        Ok(_) => arguments,
+        Err(e) => {
+            warn!(
+                "Malformed arguments for tool '{}' ({}), using {{}}: {:?}",
+                tool_name, e, arguments
+            );
+            String::from("{}")
+        }
+    }
+}
+
+/// The error to end the stream with once a tool call's streamed arguments
+/// reach `len` bytes, or None while that is within `limit`.
+pub(super) fn oversized_arguments(tool_name: &str, len: usize, limit: usize) -> Option<String> {
+    if len <= limit {
+        return None;
+    }
+    warn!(
+        "Arguments for tool '{}' passed {} bytes, abandoning the call",
+        tool_name, limit
+    );
+    Some(format!(
+        "Tool call '{tool_name}' abandoned: its arguments passed {limit} bytes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_arguments_pass_through() {
+        let args = r#"{"a": 1, "b": 2}"#.to_string();
+        assert_eq!(validated_arguments("add", args.clone()), args);
+    }
+
+    #[test]
+    fn test_malformed_arguments_become_empty_object() {
+        assert_eq!(validated_arguments("add", r#"{"a": 1,"#.to_string()), "{}");
+        assert_eq!(validated_arguments("add", String::new()), "{}");
+    }
+
+    #[test]
+    fn test_oversized_arguments_trip_past_limit() {
+        assert!(oversized_arguments("add", 100, 100).is_none());
+        let message = oversized_arguments("add", 101, 100).unwrap();
+        assert!(message.contains("'add'"), "got: {message}");
+        assert!(message.contains("100 bytes"), "got: {message}");
+    }
+}
@@ -0,0 +1,76 @@
+//! # Chunk Decoding
+//!
+//! Network chunks don't respect character boundaries, so a multi-byte UTF-8
+//! character can arrive half in one chunk and half in the next. Decoding
+//! each chunk on its own turns both halves into U+FFFD. `Utf8Decoder` holds
+//! back an incomplete trailing sequence and prepends it to the next chunk.
+
+/// Decodes one stream's chunks, carrying split characters between them.
+pub(super) struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    pub(super) fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+        }
+    }
+
+    /// Decode `chunk` after any bytes held back from the previous one.
+    /// Invalid bytes still become U+FFFD; only an unfinished character at
+    /// the end is kept for later.
+    pub(super) fn decode(&mut self, chunk: &[u8]) -> String {
+        self.pending.extend_from_slice(chunk);
+        let split = self.pending.len() - incomplete_tail(&self.pending);
+        let text = String::from_utf8_lossy(&self.pending[..split]).into_owned();
+        self.pending.drain(..split);
+        text
+    }
+}
+
+/// Length of the unfinished multi-byte sequence at the end of `bytes`, or 0
+/// when the last character is complete.
+fn incomplete_tail(bytes: &[u8]) -> usize {
+    // A sequence is at most 4 bytes, so its lead byte is in the last 3
+    for i in (bytes.len().saturating_sub(3)..bytes.len()).rev() {
+        let needed = match bytes[i] {
+            0x80..=0xbf => continue,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 1,
+        };
+        let have = bytes.len() - i;
+        return if have < needed { have } else { 0 };
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_character_split_across_chunks() {
+        let bytes = "data: héllo 🦀\n".as_bytes();
+        // Inside the 2-byte é, then inside the 4-byte crab
+        let (first, rest) = bytes.split_at(8);
+        let (second, third) = rest.split_at(8);
+
+        let mut decoder = Utf8Decoder::new();
+        let mut text = decoder.decode(first);
+        assert_eq!(text, "data: h");
+        text.push_str(&decoder.decode(second));
+        text.push_str(&decoder.decode(third));
+        assert_eq!(text, "data: héllo 🦀\n");
+    }
+
+    #[test]
+    fn test_invalid_bytes_still_replaced() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(b"a\xffb"), "a\u{fffd}b");
+        assert_eq!(decoder.decode(b"\xe2\x82"), "");
+        assert_eq!(decoder.decode(b"\xac!"), "€!");
+    }
+}
@@ -1,5 +1,10 @@
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::inference::prompt_vars;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub enum Source {
@@ -21,6 +26,10 @@ pub struct ContextSegment {
     #[serde(rename = "role")]
     pub source: Source,
     pub content: String,
+    /// When the message was added, in Unix seconds. None for the directive
+    /// and for messages saved before timestamps were kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
 }
 
 impl ContextSegment {
@@ -31,6 +40,7 @@ impl ContextSegment {
         ContextSegment {
             source: self.source.clone(),
             content: replace_typography(&self.content).trim().to_string(),
+            timestamp: None,
         }
     }
 }
@@ -43,15 +53,104 @@ fn replace_typography(text: &str) -> String {
         .replace('…', "...") // Ellipsis
 }
 
-/// A single item in the context — either a message, tool call, or tool result.
+/// A single item in the context — a message, tool call, tool result, or image.
 /// The Responses API input array is polymorphic; this enum mirrors that structure.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ContextItem {
     Message(ContextSegment),
     ToolCall(ToolCall),
     ToolResult(ToolResult),
+    Image(ImageAttachment),
+}
+
+/// A user-attached image, sent to vision-capable providers as a content part.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImageAttachment {
+    pub data_base64: String,
+    pub mime: String,
+    pub caption: Option<String>,
+}
+
+impl ImageAttachment {
+    /// Load an image file, inferring the MIME type from its extension.
+    /// The file name becomes the caption.
+    pub fn from_file(path: &std::path::Path) -> Result<Self, String> {
+        let mime = image_mime_for_path(path)
+            .ok_or_else(|| format!("Unsupported image type: {}", path.display()))?;
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let caption = path.file_name().map(|n| n.to_string_lossy().into_owned());
+        Ok(Self::from_bytes(&bytes, mime, caption))
+    }
+
+    /// Wrap raw image bytes of type `mime`, e.g. read from the clipboard.
+    pub fn from_bytes(bytes: &[u8], mime: &str, caption: Option<String>) -> Self {
+        use base64::Engine;
+
+        Self {
+            data_base64: base64::engine::general_purpose::STANDARD.encode(bytes),
+            mime: mime.to_string(),
+            caption,
+        }
+    }
+
+    /// Parse a base64 `data:image/...` URL, the form some apps paste image
+    /// content in. None for anything else.
+    pub fn from_data_url(url: &str) -> Option<Self> {
+        use base64::Engine;
+        use base64::engine::general_purpose::STANDARD;
+
+        let (header, data) = url.trim().strip_prefix("data:")?.split_once(',')?;
+        let mime = header.strip_suffix(";base64")?;
+        if !mime.starts_with("image/") {
+            return None;
+        }
+        let data: String = data.split_whitespace().collect();
+        let bytes = STANDARD.decode(data).ok()?;
+        Some(Self::from_bytes(&bytes, mime, None))
+    }
+
+    /// `data:` URL form accepted by the Responses API `input_image` part.
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime, self.data_base64)
+    }
+
+    /// Decoded size in bytes (approximate — ignores padding).
+    pub fn size_bytes(&self) -> usize {
+        self.data_base64.len() / 4 * 3
+    }
+
+    /// Short one-line description for display, e.g. "cat.png · image/png · 12 KB".
+    pub fn label(&self) -> String {
+        let kb = self.size_bytes().div_ceil(1024);
+        match &self.caption {
+            Some(caption) => format!("{caption} · {} · {kb} KB", self.mime),
+            None => format!("{} · {kb} KB", self.mime),
+        }
+    }
 }
 
+/// MIME type for an image path based on its extension, or None if not an image.
+pub fn image_mime_for_path(path: &std::path::Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Appended to a model message whose stream was cancelled part-way.
+const CANCELLED_MARKER: &str = "\n\n[cancelled]";
+
+/// Output given to a tool call whose result was lost (see `repair_tool_pairs`).
+const MISSING_RESULT_OUTPUT: &str = r#"{"error":"result missing"}"#;
+
+/// First line of the message that stands in for summarized turns.
+const SUMMARY_HEADER: &str = "[Summary of the earlier conversation]";
+
 /// Represents the model input context, holding a collection of context items.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Context {
@@ -61,6 +160,57 @@ pub struct Context {
     /// Transient — only meaningful during an active stream.
     #[serde(skip)]
     active_streams: HashMap<String, usize>,
+    /// Items before this index were sent in a completed turn and haven't
+    /// changed since, so providers can mark them as a cacheable prefix.
+    #[serde(skip)]
+    cache_watermark: usize,
+    /// Alternative answers to the latest user message, from regenerating it.
+    #[serde(skip)]
+    variants: Option<TurnVariants>,
+    /// Untruncated tool results by call_id, for those whose copy in `items`
+    /// was cut by `add_tool_result_capped`. Only the UI reads these.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    full_tool_results: HashMap<String, ToolResult>,
+}
+
+/// Answers to one user message kept by regenerating it. The one shown is
+/// the tail of `Context::items`, so it is what gets sent; the others wait
+/// here until switched to.
+#[derive(Debug, Clone, PartialEq)]
+struct TurnVariants {
+    /// Index of the user message the answers follow
+    anchor: usize,
+    /// Each answer's items. The active slot is only refreshed from
+    /// `Context::items` when switching away from it.
+    variants: Vec<Vec<ContextItem>>,
+    active: usize,
+}
+
+/// The model's messages in an answer, joined by blank lines.
+fn answer_text(items: &[ContextItem]) -> String {
+    let parts: Vec<&str> = items
+        .iter()
+        .filter_map(|item| match item {
+            ContextItem::Message(seg) if seg.source == Source::Model => Some(seg.content.as_str()),
+            _ => None,
+        })
+        .collect();
+    parts.join("\n\n")
+}
+
+/// `output` cut to at most `max_bytes` on a character boundary, followed by
+/// a note telling the model how much it isn't seeing.
+fn truncate_tool_output(output: &str, max_bytes: usize) -> String {
+    let mut end = max_bytes;
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}\n[output truncated: first {} of {} bytes shown]",
+        &output[..end],
+        end,
+        output.len()
+    )
 }
 
 impl Default for Context {
@@ -83,27 +233,53 @@ impl Context {
                  Your text responses should only interpret and present tool results, never substitute for them. \
                  Be direct, be honest about uncertainty, and prefer clarity over hedging.",
             ),
+            timestamp: None,
         };
         Context {
             items: vec![ContextItem::Message(sys_directive)],
             active_streams: HashMap::new(),
+            cache_watermark: 0,
+            variants: None,
+            full_tool_results: HashMap::new(),
         }
     }
 
-    /// Creates a new Context with a custom system directive.
+    /// Creates a new Context with a custom system directive, its
+    /// `{{date}}`-style placeholders expanded.
     pub fn with_system_prompt(prompt: String) -> Self {
         let sys_directive = ContextSegment {
             source: Source::Directive,
-            content: prompt,
+            content: prompt_vars::expand(&prompt),
+            timestamp: None,
         };
         Context {
             items: vec![ContextItem::Message(sys_directive)],
             active_streams: HashMap::new(),
+            cache_watermark: 0,
+            variants: None,
+            full_tool_results: HashMap::new(),
+        }
+    }
+
+    /// Replaces the leading system directive, if present. Used when the
+    /// system prompt changes mid-session so the next turn picks it up.
+    pub fn set_system_prompt(&mut self, prompt: String) {
+        let prompt = prompt_vars::expand(&prompt);
+        if let Some(ContextItem::Message(seg)) = self.items.first_mut()
+            && seg.source == Source::Directive
+            && seg.content != prompt
+        {
+            seg.content = prompt;
+            self.invalidate_cache_from(0);
         }
     }
 
     /// Adds a new ContextSegment (wrapped in ContextItem::Message) and returns a reference to it.
-    pub fn add(&mut self, segment: ContextSegment) -> &ContextSegment {
+    /// A segment without a timestamp is stamped with the current time.
+    pub fn add(&mut self, mut segment: ContextSegment) -> &ContextSegment {
+        segment
+            .timestamp
+            .get_or_insert_with(|| Utc::now().timestamp());
         self.items.push(ContextItem::Message(segment));
         match self.items.last().expect("just pushed") {
             ContextItem::Message(seg) => seg,
@@ -111,10 +287,14 @@ impl Context {
         }
     }
 
+    /// Adds a user message. The answer shown for the previous user message
+    /// becomes final, and its other variants are dropped.
     pub fn add_user_message(&mut self, content: String) -> &ContextSegment {
+        self.variants = None;
         let segment = ContextSegment {
             source: Source::User,
             content,
+            timestamp: None,
         };
         self.add(segment)
     }
@@ -124,9 +304,52 @@ impl Context {
         self.items.push(ContextItem::ToolCall(tc));
     }
 
-    /// Adds a tool result to the context.
+    /// Adds a tool result to the context. Tools run concurrently and finish
+    /// in any order, so the result is placed among the trailing results by
+    /// the position of its call, keeping the context the same however the
+    /// calls were scheduled.
     pub fn add_tool_result(&mut self, tr: ToolResult) {
-        self.items.push(ContextItem::ToolResult(tr));
+        let call_index = |call_id: &str| {
+            self.items
+                .iter()
+                .position(|item| matches!(item, ContextItem::ToolCall(tc) if tc.call_id == call_id))
+        };
+        let mut at = self.items.len();
+        if let Some(own) = call_index(&tr.call_id) {
+            while let Some(ContextItem::ToolResult(prev)) =
+                at.checked_sub(1).map(|i| &self.items[i])
+                && call_index(&prev.call_id).is_some_and(|prev_call| prev_call > own)
+            {
+                at -= 1;
+            }
+        }
+        if at < self.items.len() {
+            self.invalidate_cache_from(at);
+        }
+        self.items.insert(at, ContextItem::ToolResult(tr));
+    }
+
+    /// Adds a tool result, with its output cut to `max_bytes` and a marker
+    /// saying so, since that copy is what the model is sent. The full result
+    /// stays available through `full_tool_result`.
+    pub fn add_tool_result_capped(&mut self, mut tr: ToolResult, max_bytes: usize) {
+        if tr.output.len() > max_bytes {
+            self.full_tool_results
+                .insert(tr.call_id.clone(), tr.clone());
+            tr.output = truncate_tool_output(&tr.output, max_bytes);
+        }
+        self.add_tool_result(tr);
+    }
+
+    /// The untruncated result for `call_id`, if its output was cut when it
+    /// was added.
+    pub fn full_tool_result(&self, call_id: &str) -> Option<&ToolResult> {
+        self.full_tool_results.get(call_id)
+    }
+
+    /// Adds an image attachment to the context.
+    pub fn add_image(&mut self, image: ImageAttachment) {
+        self.items.push(ContextItem::Image(image));
     }
 
     /// Appends content to the last message if it is from the model.
@@ -168,6 +391,7 @@ impl Context {
         self.add(ContextSegment {
             source: Source::Model,
             content: normalized,
+            timestamp: None,
         });
         if let Some(id) = item_id {
             self.active_streams
@@ -208,6 +432,7 @@ impl Context {
         self.add(ContextSegment {
             source: Source::Thinking,
             content: normalized,
+            timestamp: None,
         });
         if let Some(id) = item_id {
             self.active_streams
@@ -215,6 +440,84 @@ impl Context {
         }
     }
 
+    /// Sets the answer to the latest user message aside as a variant and
+    /// removes it, so a new answer can stream in its place. Returns false,
+    /// changing nothing, when there is no answer to regenerate.
+    pub fn begin_variant(&mut self) -> bool {
+        let Some(anchor) = self.items.iter().rposition(
+            |item| matches!(item, ContextItem::Message(seg) if seg.source == Source::User),
+        ) else {
+            return false;
+        };
+        if anchor + 1 >= self.items.len() {
+            return false;
+        }
+        let answer = self.items.split_off(anchor + 1);
+        let mut turn = match self.variants.take() {
+            Some(mut turn) if turn.anchor == anchor => {
+                turn.variants[turn.active] = answer;
+                turn
+            }
+            _ => TurnVariants {
+                anchor,
+                variants: vec![answer],
+                active: 0,
+            },
+        };
+        // The new answer's slot is filled when switching away from it
+        turn.variants.push(Vec::new());
+        turn.active = turn.variants.len() - 1;
+        self.variants = Some(turn);
+        self.active_streams.clear();
+        self.invalidate_cache_from(anchor + 1);
+        true
+    }
+
+    /// Shows the next (or previous) answer to the latest user message,
+    /// wrapping around. Returns the 0-based position shown and the number of
+    /// answers, or None if there is only one.
+    pub fn cycle_variant(&mut self, forward: bool) -> Option<(usize, usize)> {
+        let v = self.variants.as_mut()?;
+        let count = v.variants.len();
+        if count < 2 || v.anchor >= self.items.len() {
+            return None;
+        }
+        v.variants[v.active] = self.items.split_off(v.anchor + 1);
+        v.active = if forward {
+            (v.active + 1) % count
+        } else {
+            (v.active + count - 1) % count
+        };
+        self.items.extend(v.variants[v.active].iter().cloned());
+        let anchor = v.anchor;
+        let active = v.active;
+        self.active_streams.clear();
+        self.invalidate_cache_from(anchor + 1);
+        Some((active, count))
+    }
+
+    /// Model text of the answer listed before the one shown, and of the one
+    /// shown, for comparing regenerated answers. None with only one answer.
+    pub fn variant_texts(&self) -> Option<(String, String)> {
+        let v = self.variants.as_ref()?;
+        let count = v.variants.len();
+        if count < 2 || v.anchor >= self.items.len() {
+            return None;
+        }
+        // The active slot is stale; the shown answer is the tail of `items`
+        let previous = &v.variants[(v.active + count - 1) % count];
+        Some((
+            answer_text(previous),
+            answer_text(&self.items[v.anchor + 1..]),
+        ))
+    }
+
+    /// Index of the user message whose answers are kept as variants, if a
+    /// turn has been regenerated.
+    pub fn variant_anchor(&self) -> Option<usize> {
+        self.variants.as_ref().map(|v| v.anchor)
+    }
+
     /// Returns true if any User or Model messages exist in the context.
     pub fn has_visible_messages(&self) -> bool {
         self.items.iter().any(|item| {
@@ -226,6 +529,193 @@ impl Context {
     pub fn clear_active_streams(&mut self) {
         self.active_streams.clear();
     }
+
+    /// Stops routing deltas for `item_id`, e.g. when its reasoning item
+    /// closes. The segment it pointed at keeps its content.
+    pub fn end_stream(&mut self, item_id: &str) {
+        self.active_streams.remove(item_id);
+    }
+
+    /// Settles a model message cut off by cancellation: an empty partial is
+    /// removed, anything else keeps its text with a `[cancelled]` marker.
+    /// Also ends stream routing, since no more deltas will arrive.
+    pub fn cancel_partial_model_message(&mut self) {
+        self.active_streams.clear();
+        let last_idx = self.items.len().wrapping_sub(1);
+        let Some(ContextItem::Message(seg)) = self.items.last_mut() else {
+            return;
+        };
+        if seg.source != Source::Model {
+            return;
+        }
+        if seg.content.trim().is_empty() {
+            self.items.pop();
+        } else {
+            seg.content.push_str(CANCELLED_MARKER);
+        }
+        self.invalidate_cache_from(last_idx);
+    }
+
+    /// Completes broken ToolCall/ToolResult pairs, e.g. in a session saved
+    /// mid-turn. A call whose result was lost gets an error result right
+    /// after it, so it shows as finished instead of pending forever. A result
+    /// with no matching call is dropped, since providers reject it.
+    pub fn repair_tool_pairs(&mut self) {
+        let mut call_ids = HashSet::new();
+        let mut result_ids = HashSet::new();
+        for item in &self.items {
+            match item {
+                ContextItem::ToolCall(tc) => {
+                    call_ids.insert(tc.call_id.clone());
+                }
+                ContextItem::ToolResult(tr) => {
+                    result_ids.insert(tr.call_id.clone());
+                }
+                _ => {}
+            }
+        }
+
+        let mut first_change = None;
+        let mut repaired = Vec::with_capacity(self.items.len());
+        for item in self.items.drain(..) {
+            match &item {
+                ContextItem::ToolResult(tr) if !call_ids.contains(&tr.call_id) => {
+                    first_change.get_or_insert(repaired.len());
+                }
+                ContextItem::ToolCall(tc) if !result_ids.contains(&tc.call_id) => {
+                    let missing = ToolResult {
+                        call_id: tc.call_id.clone(),
+                        output: MISSING_RESULT_OUTPUT.to_string(),
+                    };
+                    repaired.push(item);
+                    first_change.get_or_insert(repaired.len());
+                    repaired.push(ContextItem::ToolResult(missing));
+                }
+                _ => repaired.push(item),
+            }
+        }
+        self.items = repaired;
+        if let Some(idx) = first_change {
+            self.invalidate_cache_from(idx);
+        }
+    }
+
+    /// The older turns a summary could replace: everything after the
+    /// directive up to the last completed turn, which is kept verbatim.
+    /// Only whole turns are included, so no tool call is split from its
+    /// result. None if there is less than one turn to condense.
+    pub fn summarizable_range(&self) -> Option<Range<usize>> {
+        let start = match self.items.first() {
+            Some(ContextItem::Message(seg)) if seg.source == Source::Directive => 1,
+            _ => 0,
+        };
+        let mut end = self.items[..self.cache_watermark()].iter().rposition(
+            |item| matches!(item, ContextItem::Message(seg) if seg.source == Source::User),
+        )?;
+        // An image sent with the kept turn's message belongs to that turn
+        while end > start && matches!(self.items[end - 1], ContextItem::Image(_)) {
+            end -= 1;
+        }
+        // A lone earlier summary isn't worth condensing again
+        (end >= start + 2).then_some(start..end)
+    }
+
+    /// Replaces the items in `range` with a single user message holding
+    /// `summary`. A user message rather than a directive, since directives
+    /// aren't saved with the session. Returns false, changing nothing, if
+    /// `range` no longer fits the context.
+    pub fn replace_with_summary(&mut self, range: Range<usize>, summary: &str) -> bool {
+        if range.is_empty() || range.end > self.items.len() {
+            return false;
+        }
+        let segment = ContextSegment {
+            source: Source::User,
+            content: format!("{SUMMARY_HEADER}\n\n{}", summary.trim()),
+            timestamp: None,
+        };
+        let removed: Vec<ContextItem> = self
+            .items
+            .splice(range.clone(), [ContextItem::Message(segment)])
+            .collect();
+        for item in &removed {
+            if let ContextItem::ToolResult(tr) = item {
+                self.full_tool_results.remove(&tr.call_id);
+            }
+        }
+
+        // Later items moved up by the number removed, less the summary
+        let shift = removed.len() - 1;
+        for idx in self.active_streams.values_mut() {
+            if *idx >= range.end {
+                *idx -= shift;
+            }
+        }
+        if let Some(turn) = self.variants.as_mut()
+            && turn.anchor >= range.end
+        {
+            turn.anchor -= shift;
+        } else {
+            self.variants = None;
+        }
+        self.invalidate_cache_from(range.start);
+        true
+    }
+
+    /// Number of leading items that form a stable, already-sent prefix.
+    /// Clamped to the current length, so trimming items never leaves it
+    /// pointing past the end.
+    pub fn cache_watermark(&self) -> usize {
+        self.cache_watermark.min(self.items.len())
+    }
+
+    /// Marks everything currently in the context as a stable prefix.
+    /// Called when a turn completes.
+    pub fn advance_cache_watermark(&mut self) {
+        self.cache_watermark = self.items.len();
+    }
+
+    /// Pulls the watermark back to `index` after an item at or past it was
+    /// edited or removed, so the changed prefix isn't marked as cached.
+    pub fn invalidate_cache_from(&mut self, index: usize) {
+        self.cache_watermark = self.cache_watermark.min(index);
+    }
+
+    /// Rough token count of what the next request would send, at ~4 characters
+    /// per token. Thinking and status messages are excluded, as providers drop them.
+    pub fn estimate_tokens(&self) -> u32 {
+        let chars: usize = self
+            .items
+            .iter()
+            .map(|item| match item {
+                ContextItem::Message(seg) => match seg.source {
+                    Source::Thinking | Source::Status => 0,
+                    _ => seg.content.chars().count(),
+                },
+                ContextItem::ToolCall(tc) => tc.name.len() + tc.arguments.chars().count(),
+                ContextItem::ToolResult(tr) => tr.output.chars().count(),
+                ContextItem::Image(_) => IMAGE_TOKEN_ESTIMATE * CHARS_PER_TOKEN,
+            })
+            .sum();
+        chars.div_ceil(CHARS_PER_TOKEN) as u32
+    }
+}
+
+/// Average characters per token for English text, used by `estimate_tokens`.
+const CHARS_PER_TOKEN: usize = 4;
+/// Flat per-image charge; actual image costs vary by provider and resolution.
+const IMAGE_TOKEN_ESTIMATE: usize = 1_000;
+
+/// Which reasoning stream to surface as Thinking when a provider emits both
+/// a summary and the full reasoning text. Surfacing both duplicates content.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReasoningMode {
+    /// `response.reasoning_summary_text.delta` only
+    #[serde(rename = "summary")]
+    #[default]
+    Summary,
+    /// `response.reasoning_text.delta` only
+    #[serde(rename = "full")]
+    Full,
 }
 
 /// Effort level for reasoning tokens
@@ -274,6 +764,25 @@ impl Effort {
     }
 }
 
+/// Parses the same names the config file uses, case-insensitively, plus
+/// "off" as the label shown for `None`.
+impl std::str::FromStr for Effort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "high" => Ok(Effort::High),
+            "medium" => Ok(Effort::Medium),
+            "low" => Ok(Effort::Low),
+            "auto" => Ok(Effort::Auto),
+            "none" | "off" => Ok(Effort::None),
+            _ => Err(format!(
+                "unknown effort level '{s}' (expected off, auto, low, medium or high)"
+            )),
+        }
+    }
+}
+
 /// A tool the model can call.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolDefinition {
@@ -309,10 +818,47 @@ pub enum StreamChunk {
         text: String,
         item_id: Option<String>,
     },
+    /// A reasoning output item opened; its `Thinking` deltas follow.
+    ThinkingStart {
+        item_id: Option<String>,
+    },
+    /// A reasoning output item closed, so content after it is the answer.
+    ThinkingEnd {
+        item_id: Option<String>,
+    },
     ToolCall(ToolCall), // Complete tool call (arguments buffered by provider)
     /// Signals stream completion. Providers send this as their final chunk before returning Ok(()).
     /// Carries usage statistics parsed from the `response.completed` payload, if available.
     Completed(Option<UsageStats>),
+    /// The provider reported an error mid-stream (`error` / `response.failed` events).
+    /// Like `Completed`, this is the final chunk: providers return Ok(()) right after.
+    Error(String),
+    /// Quota and credit headers from the response, sent before any content.
+    RateLimit(RateLimitInfo),
+}
+
+/// Request quota and credit balance a provider reported with its response.
+/// Fields are None when the corresponding header was absent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitInfo {
+    /// Requests allowed in the current window
+    pub limit: Option<u32>,
+    /// Requests left in the current window
+    pub remaining: Option<u32>,
+    /// When the window resets, in Unix milliseconds
+    pub reset_ms: Option<u64>,
+    /// Account credit left, in dollars
+    pub credits_remaining: Option<f64>,
+}
+
+impl RateLimitInfo {
+    /// A tenth or less of the window's requests remain.
+    pub fn is_low(&self) -> bool {
+        match (self.remaining, self.limit) {
+            (Some(remaining), Some(limit)) => remaining as u64 * 10 <= limit as u64,
+            _ => false,
+        }
+    }
 }
 
 /// Token usage and timing statistics from a single inference round.
@@ -330,6 +876,60 @@ pub struct UsageStats {
     pub ttft_ms: Option<u64>,
     pub tokens_per_sec: Option<f32>,
     pub generation_duration_ms: Option<u64>,
+    /// Stream deltas skipped because their JSON didn't parse
+    pub dropped_chunks: Option<u32>,
+    /// The provider's id for the request, to quote when reporting a problem
+    pub request_id: Option<String>,
+}
+
+/// Running tok/s estimate while an answer streams, shown until the final
+/// `UsageStats` arrive. Tokens are estimated from characters, since the
+/// provider only reports real counts at the end.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LiveRate {
+    chars: usize,
+    elapsed: Duration,
+}
+
+impl LiveRate {
+    /// Count a content chunk of `chars` characters that arrived `elapsed`
+    /// after its request was sent. Each tool round sends a new request with
+    /// its own clock, so an earlier `elapsed` starts the count over.
+    pub fn record(&mut self, chars: usize, elapsed: Duration) {
+        if elapsed < self.elapsed {
+            self.chars = 0;
+        }
+        self.chars += chars;
+        self.elapsed = elapsed;
+    }
+
+    /// Estimated tokens per second, None until content has arrived.
+    pub fn tokens_per_sec(&self) -> Option<f32> {
+        if self.chars == 0 || self.elapsed.is_zero() {
+            return None;
+        }
+        let tokens = self.chars as f32 / CHARS_PER_TOKEN as f32;
+        Some(tokens / self.elapsed.as_secs_f32())
+    }
+}
+
+/// Per-model token rates in dollars per million tokens.
+///
+/// `cache_read_per_million` prices cache hits; when unset, cached tokens
+/// are billed at the regular input rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    pub cache_read_per_million: Option<f64>,
+}
+
+/// One response's usage with the rates of the model that produced it, so a
+/// later model switch doesn't reprice it.
+#[derive(Debug, Clone, Default)]
+pub struct TurnUsage {
+    pub stats: UsageStats,
+    pub pricing: Option<Pricing>,
 }
 
 /// Adds two `Option<u32>` values: None + None = None, otherwise sum.
@@ -371,15 +971,50 @@ impl UsageStats {
             self.tokens_per_sec = Some(tokens as f32 / (duration_ms as f32 / 1000.0));
         }
 
-        // Last finish_reason wins
+        self.dropped_chunks = add_opt(self.dropped_chunks, other.dropped_chunks);
+
+        // Last finish_reason and request id win
         if other.finish_reason.is_some() {
             self.finish_reason.clone_from(&other.finish_reason);
         }
+        if other.request_id.is_some() {
+            self.request_id.clone_from(&other.request_id);
+        }
+    }
+
+    /// Total tokens: the reported total, else input + output.
+    pub fn total(&self) -> Option<u32> {
+        self.total_tokens
+            .or_else(|| add_opt(self.input_tokens, self.output_tokens))
+    }
+
+    /// Estimated dollar cost of this usage, or None if no token counts were reported.
+    /// `input_tokens` includes cache hits, which are billed at the cache rate.
+    pub fn cost(&self, pricing: &Pricing) -> Option<f64> {
+        if self.input_tokens.is_none() && self.output_tokens.is_none() {
+            return None;
+        }
+        let input = self.input_tokens.unwrap_or(0);
+        let cached = self.cache_read_input_tokens.unwrap_or(0).min(input);
+        let output = self.output_tokens.unwrap_or(0);
+        let cache_rate = pricing
+            .cache_read_per_million
+            .unwrap_or(pricing.input_per_million);
+
+        let dollars = (input - cached) as f64 * pricing.input_per_million
+            + cached as f64 * cache_rate
+            + output as f64 * pricing.output_per_million;
+        Some(dollars / 1_000_000.0)
+    }
+
+    /// Status-bar hint for how the last round ended, if it needs attention.
+    pub fn finish_hint(&self) -> Option<&'static str> {
+        self.finish_reason.as_deref().and_then(finish_reason_hint)
     }
 
     /// Formats a human-readable summary for the status bar.
-    /// e.g. "150 in / 42 out (80 cached) | TTFT 340ms | 28.5 tok/s | 1.2s"
-    pub fn display_summary(&self) -> String {
+    /// e.g. "150 in / 42 out (80 cached) | TTFT 340ms | 28.5 tok/s | 1.2s | $0.0123"
+    pub fn display_summary(&self, pricing: Option<&Pricing>) -> String {
         let mut parts = Vec::new();
 
         // Token counts
@@ -418,6 +1053,18 @@ impl UsageStats {
             parts.push(format!("{secs:.1}s"));
         }
 
+        // Estimated cost, when the model's rates are known
+        if let Some(cost) = pricing.and_then(|p| self.cost(p)) {
+            parts.push(format!("${cost:.4}"));
+        }
+
+        // Malformed deltas the provider skipped
+        if let Some(dropped) = self.dropped_chunks
+            && dropped > 0
+        {
+            parts.push(format!("{dropped} dropped"));
+        }
+
         if parts.is_empty() {
             "Response complete.".to_string()
         } else {
@@ -426,6 +1073,19 @@ impl UsageStats {
     }
 }
 
+/// User-facing hint for a response that ended for `reason`, or None when
+/// the model simply finished. Accepts Responses API statuses and
+/// `incomplete_details` reasons as well as Chat Completions finish reasons.
+pub fn finish_reason_hint(reason: &str) -> Option<&'static str> {
+    match reason {
+        "length" | "max_tokens" | "max_output_tokens" | "incomplete" => {
+            Some("Response truncated — increase max_output_tokens, or /continue")
+        }
+        "content_filter" => Some("Response stopped by the provider's content filter"),
+        _ => None,
+    }
+}
+
 /// Adds two `Option<u64>` values: None + None = None, otherwise sum.
 fn add_opt_u64(a: Option<u64>, b: Option<u64>) -> Option<u64> {
     match (a, b) {
@@ -452,6 +1112,7 @@ mod tests {
                     let segment = ContextSegment {
                         source: Source::User,
                         content: $input.to_string(),
+                        timestamp: None,
                     };
                     let normalized = segment.normalized();
                     assert_eq!(normalized.content, $expected);
@@ -495,6 +1156,7 @@ mod tests {
         let segment = ContextSegment {
             source: Source::User,
             content: "test".to_string(),
+            timestamp: None,
         };
         let added = ctx.add(segment);
         assert_eq!(added.content, "test");
@@ -502,11 +1164,148 @@ mod tests {
         ctx.add(ContextSegment {
             source: Source::Model,
             content: "response".to_string(),
+            timestamp: None,
         });
         assert_eq!(ctx.items.len(), 3);
         assert_eq!(unwrap_message(&ctx.items[2]).content, "response");
     }
 
+    #[test]
+    fn test_cache_watermark_advances_and_resets_on_edit() {
+        let mut ctx = Context::new();
+        ctx.add_user_message("hi".to_string());
+        assert_eq!(ctx.cache_watermark(), 0);
+
+        ctx.advance_cache_watermark();
+        ctx.add_user_message("next".to_string());
+        assert_eq!(ctx.cache_watermark(), 2);
+
+        // Trimming below the watermark clamps it
+        ctx.items.truncate(1);
+        assert_eq!(ctx.cache_watermark(), 1);
+
+        // Re-applying the same prompt keeps the prefix; changing it resets
+        ctx.advance_cache_watermark();
+        let prompt = unwrap_message(&ctx.items[0]).content.clone();
+        ctx.set_system_prompt(prompt);
+        assert_eq!(ctx.cache_watermark(), 1);
+        ctx.set_system_prompt("Be terse.".to_string());
+        assert_eq!(ctx.cache_watermark(), 0);
+    }
+
+    #[test]
+    fn test_cancel_partial_model_message() {
+        // An empty partial is dropped
+        let mut ctx = Context::new();
+        ctx.add_user_message("hi".to_string());
+        ctx.add(ContextSegment {
+            source: Source::Model,
+            content: " ".to_string(),
+            timestamp: None,
+        });
+        ctx.cancel_partial_model_message();
+        assert_eq!(ctx.items.len(), 2);
+        assert_eq!(unwrap_message(&ctx.items[1]).source, Source::User);
+
+        // A partial with text is kept and marked
+        ctx.append_to_last_model_message("Half an ans", Some("msg_1"));
+        ctx.cancel_partial_model_message();
+        assert_eq!(ctx.items.len(), 3);
+        assert_eq!(
+            unwrap_message(&ctx.items[2]).content,
+            "Half an ans\n\n[cancelled]"
+        );
+
+        // Nothing to settle when the model wasn't mid-message
+        let mut ctx = Context::new();
+        ctx.add_user_message("hi".to_string());
+        ctx.cancel_partial_model_message();
+        assert_eq!(unwrap_message(&ctx.items[1]).content, "hi");
+    }
+
+    fn tool_call(call_id: &str) -> ContextItem {
+        ContextItem::ToolCall(ToolCall {
+            id: format!("fc_{call_id}"),
+            call_id: call_id.to_string(),
+            name: "add".to_string(),
+            arguments: "{}".to_string(),
+        })
+    }
+
+    fn tool_result(call_id: &str, output: &str) -> ContextItem {
+        ContextItem::ToolResult(ToolResult {
+            call_id: call_id.to_string(),
+            output: output.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_tool_results_follow_call_order() {
+        let mut ctx = Context::new();
+        ctx.add_user_message("1+1, 2+2 and 3+3?".to_string());
+        ctx.items.push(tool_call("call_a"));
+        ctx.items.push(tool_call("call_b"));
+        ctx.items.push(tool_call("call_c"));
+        ctx.advance_cache_watermark();
+
+        // Finished out of order: c, a, b
+        for (call_id, output) in [("call_c", "6"), ("call_a", "2"), ("call_b", "4")] {
+            ctx.add_tool_result(ToolResult {
+                call_id: call_id.to_string(),
+                output: output.to_string(),
+            });
+        }
+
+        assert_eq!(
+            ctx.items[5..],
+            [
+                tool_result("call_a", "2"),
+                tool_result("call_b", "4"),
+                tool_result("call_c", "6"),
+            ]
+        );
+        // Results slotted in before an existing one are not part of the cached prefix
+        assert_eq!(ctx.cache_watermark(), 5);
+    }
+
+    #[test]
+    fn test_repair_tool_pairs_completes_orphaned_call() {
+        let mut ctx = Context::new();
+        ctx.add_user_message("1+1 and 2+2?".to_string());
+        ctx.items.push(tool_call("call_a"));
+        ctx.items.push(tool_call("call_b"));
+        ctx.items.push(tool_result("call_b", "4"));
+
+        ctx.repair_tool_pairs();
+
+        // The lost result is filled in right after its call
+        assert_eq!(ctx.items.len(), 6);
+        assert_eq!(ctx.items[3], tool_result("call_a", MISSING_RESULT_OUTPUT));
+        assert_eq!(ctx.items[5], tool_result("call_b", "4"));
+    }
+
+    #[test]
+    fn test_repair_tool_pairs_drops_orphaned_result() {
+        let mut ctx = Context::new();
+        ctx.add_user_message("hi".to_string());
+        ctx.items.push(tool_result("call_gone", "2"));
+        ctx.items.push(tool_call("call_a"));
+        ctx.items.push(tool_result("call_a", "3"));
+        let expected = vec![
+            ctx.items[0].clone(),
+            ctx.items[1].clone(),
+            tool_call("call_a"),
+            tool_result("call_a", "3"),
+        ];
+
+        ctx.repair_tool_pairs();
+        assert_eq!(ctx.items, expected);
+
+        // Intact pairs are left alone
+        ctx.repair_tool_pairs();
+        assert_eq!(ctx.items, expected);
+    }
+
     #[test]
     fn test_effort_cycle() {
         assert_eq!(Effort::None.next(), Effort::Auto);
@@ -516,6 +1315,218 @@ mod tests {
         assert_eq!(Effort::High.next(), Effort::None);
     }
 
+    #[test]
+    fn test_add_tool_result_capped_keeps_full_output_aside() {
+        let mut ctx = Context::new();
+        let full = format!("{}é{}", "a".repeat(9), "b".repeat(100));
+        ctx.add_tool_result_capped(
+            ToolResult {
+                call_id: "call_1".to_string(),
+                output: full.clone(),
+            },
+            10, // Lands inside 'é', so the cut backs up to 9
+        );
+        ctx.add_tool_result_capped(
+            ToolResult {
+                call_id: "call_2".to_string(),
+                output: "short".to_string(),
+            },
+            10,
+        );
+
+        let ContextItem::ToolResult(capped) = &ctx.items[1] else {
+            panic!("expected a tool result");
+        };
+        assert_eq!(
+            capped.output,
+            "aaaaaaaaa\n[output truncated: first 9 of 111 bytes shown]"
+        );
+        assert_eq!(ctx.full_tool_result("call_1").unwrap().output, full);
+
+        // Outputs within the cap are stored as-is, with nothing set aside
+        assert!(matches!(&ctx.items[2], ContextItem::ToolResult(tr) if tr.output == "short"));
+        assert!(ctx.full_tool_result("call_2").is_none());
+    }
+
+    /// `items` with the message timestamps cleared, to compare with literals.
+    fn untimed(items: &[ContextItem]) -> Vec<ContextItem> {
+        let mut items = items.to_vec();
+        for item in &mut items {
+            if let ContextItem::Message(seg) = item {
+                seg.timestamp = None;
+            }
+        }
+        items
+    }
+
+    fn model_reply(text: &str) -> ContextItem {
+        ContextItem::Message(ContextSegment {
+            source: Source::Model,
+            content: text.to_string(),
+            timestamp: None,
+        })
+    }
+
+    #[test]
+    fn test_begin_variant_sets_answer_aside() {
+        let mut ctx = Context::new();
+        assert!(!ctx.begin_variant()); // No user message yet
+        ctx.add_user_message("question".to_string());
+        assert!(!ctx.begin_variant()); // Nothing answered yet
+        ctx.append_to_last_model_message("first", None);
+        ctx.advance_cache_watermark();
+
+        assert!(ctx.begin_variant());
+        assert_eq!(ctx.items.len(), 2); // Directive + question
+        assert_eq!(ctx.cache_watermark(), 2);
+        let variants = ctx.variants.as_ref().unwrap();
+        assert_eq!(variants.anchor, 1);
+        let answers: Vec<_> = variants.variants.iter().map(|v| untimed(v)).collect();
+        assert_eq!(answers, vec![vec![model_reply("first")], vec![]]);
+        assert_eq!(variants.active, 1);
+
+        // A new user message keeps the shown answer and drops the rest
+        ctx.append_to_last_model_message("second", None);
+        ctx.add_user_message("follow-up".to_string());
+        assert!(ctx.variants.is_none());
+        assert_eq!(untimed(&ctx.items[2..3]), [model_reply("second")]);
+    }
+
+    #[test]
+    fn test_replace_with_summary_keeps_latest_turn() {
+        let mut ctx = Context::new();
+        ctx.add_user_message("first question".to_string());
+        ctx.add_tool_call(ToolCall {
+            id: "fc_1".to_string(),
+            call_id: "call_1".to_string(),
+            name: "read_file".to_string(),
+            arguments: "{}".to_string(),
+        });
+        ctx.add_tool_result_capped(
+            ToolResult {
+                call_id: "call_1".to_string(),
+                output: "x".repeat(100),
+            },
+            10,
+        );
+        ctx.append_to_last_model_message("first answer", None);
+        ctx.add_user_message("second question".to_string());
+        ctx.append_to_last_model_message("second answer", None);
+        ctx.advance_cache_watermark();
+        // The new message, not answered yet
+        ctx.add_user_message("third question".to_string());
+
+        // The first turn goes; the last completed turn and the new message stay
+        let range = ctx.summarizable_range().unwrap();
+        assert_eq!(range, 1..5);
+        assert!(ctx.replace_with_summary(range, "Read a file together.\n"));
+
+        assert_eq!(ctx.items.len(), 5);
+        assert_eq!(
+            untimed(&ctx.items[1..2]),
+            [ContextItem::Message(ContextSegment {
+                source: Source::User,
+                content: format!("{SUMMARY_HEADER}\n\nRead a file together."),
+                timestamp: None,
+            })]
+        );
+        assert_eq!(
+            untimed(&ctx.items[2..4]),
+            [
+                ContextItem::Message(ContextSegment {
+                    source: Source::User,
+                    content: "second question".to_string(),
+                    timestamp: None,
+                }),
+                model_reply("second answer"),
+            ]
+        );
+        assert!(ctx.full_tool_result("call_1").is_none());
+        assert_eq!(ctx.cache_watermark(), 1);
+
+        // Nothing counts as sent again until the next turn completes
+        assert_eq!(ctx.summarizable_range(), None);
+        // A range from before the context changed no longer fits
+        assert!(!ctx.replace_with_summary(1..9, "stale"));
+    }
+
+    #[test]
+    fn test_cycle_variant_swaps_answers() {
+        let mut ctx = Context::new();
+        ctx.add_user_message("question".to_string());
+        assert_eq!(ctx.cycle_variant(true), None);
+        ctx.append_to_last_model_message("first", None);
+        ctx.begin_variant();
+        ctx.append_to_last_model_message("second", None);
+        ctx.begin_variant();
+        ctx.append_to_last_model_message("third", None);
+
+        assert_eq!(ctx.cycle_variant(true), Some((0, 3)));
+        assert_eq!(untimed(&ctx.items[2..]), [model_reply("first")]);
+        assert_eq!(ctx.cycle_variant(false), Some((2, 3)));
+        assert_eq!(untimed(&ctx.items[2..]), [model_reply("third")]);
+        assert_eq!(ctx.cycle_variant(false), Some((1, 3)));
+        assert_eq!(untimed(&ctx.items[2..]), [model_reply("second")]);
+
+        // Regenerating from an older answer keeps it and adds a fourth
+        assert!(ctx.begin_variant());
+        ctx.append_to_last_model_message("fourth", None);
+        assert_eq!(ctx.cycle_variant(false), Some((2, 4)));
+        assert_eq!(untimed(&ctx.items[2..]), [model_reply("third")]);
+        assert_eq!(ctx.cycle_variant(false), Some((1, 4)));
+        assert_eq!(untimed(&ctx.items[2..]), [model_reply("second")]);
+    }
+
+    #[test]
+    fn test_variant_texts_pair_shown_answer_with_previous() {
+        let mut ctx = Context::new();
+        ctx.add_user_message("question".to_string());
+        ctx.append_to_last_model_message("first", None);
+        assert_eq!(ctx.variant_texts(), None);
+
+        ctx.begin_variant();
+        ctx.append_to_last_model_message("second", None);
+        assert_eq!(
+            ctx.variant_texts(),
+            Some(("first".to_string(), "second".to_string()))
+        );
+
+        // Wraps: the first answer is compared with the last
+        ctx.cycle_variant(true);
+        assert_eq!(
+            ctx.variant_texts(),
+            Some(("second".to_string(), "first".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_effort_parses_each_level() {
+        assert_eq!("high".parse(), Ok(Effort::High));
+        assert_eq!("medium".parse(), Ok(Effort::Medium));
+        assert_eq!("low".parse(), Ok(Effort::Low));
+        assert_eq!("auto".parse(), Ok(Effort::Auto));
+        assert_eq!("none".parse(), Ok(Effort::None));
+        assert_eq!("Off".parse(), Ok(Effort::None));
+        // Every level round-trips through its config name
+        for effort in [
+            Effort::High,
+            Effort::Medium,
+            Effort::Low,
+            Effort::Auto,
+            Effort::None,
+        ] {
+            let name = serde_json::to_value(effort).unwrap();
+            assert_eq!(name.as_str().unwrap().parse(), Ok(effort));
+        }
+    }
+
+    #[test]
+    fn test_effort_rejects_unknown_level() {
+        let err = "extreme".parse::<Effort>().unwrap_err();
+        assert!(err.contains("'extreme'"));
+        assert!(err.contains("expected off, auto, low, medium or high"));
+    }
+
     #[test]
     fn test_context_append_to_last_thinking_message() {
         let mut ctx = Context::new();
@@ -610,6 +1621,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_estimate_tokens_skips_thinking_and_status() {
+        let mut ctx = Context::with_system_prompt("abcd".repeat(10)); // 40 chars
+        ctx.add_user_message("abcd".repeat(5)); // 20 chars
+        ctx.append_to_last_thinking_message(&"x".repeat(400), None);
+        ctx.add(ContextSegment {
+            source: Source::Status,
+            content: "y".repeat(400),
+            timestamp: None,
+        });
+        ctx.add_tool_result(ToolResult {
+            call_id: "call_1".to_string(),
+            output: "42".to_string(), // 2 chars, rounds up
+        });
+        assert_eq!(ctx.estimate_tokens(), 16);
+    }
+
     // =====================================================================
     // UsageStats tests
     // =====================================================================
@@ -634,6 +1662,25 @@ mod tests {
         assert_eq!(base.total_tokens, Some(300));
     }
 
+    #[test]
+    fn test_total_falls_back_to_input_plus_output() {
+        let reported = UsageStats {
+            input_tokens: Some(100),
+            output_tokens: Some(20),
+            total_tokens: Some(125),
+            ..Default::default()
+        };
+        assert_eq!(reported.total(), Some(125));
+
+        let derived = UsageStats {
+            input_tokens: Some(100),
+            output_tokens: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(derived.total(), Some(120));
+        assert_eq!(UsageStats::default().total(), None);
+    }
+
     #[test]
     fn test_accumulate_preserves_first_ttft() {
         let mut base = UsageStats {
@@ -656,6 +1703,26 @@ mod tests {
         assert_eq!(empty.ttft_ms, Some(200));
     }
 
+    #[test]
+    fn test_live_rate_from_chunk_samples() {
+        let mut rate = LiveRate::default();
+        assert_eq!(rate.tokens_per_sec(), None);
+
+        // 120 chars = 30 tokens over 1.5s
+        for (chars, ms) in [(40, 500), (40, 1000), (40, 1500)] {
+            rate.record(chars, Duration::from_millis(ms));
+        }
+        assert!((rate.tokens_per_sec().unwrap() - 20.0).abs() < 0.01);
+
+        // A slow gap lowers the estimate at the next chunk
+        rate.record(40, Duration::from_millis(4000));
+        assert!((rate.tokens_per_sec().unwrap() - 10.0).abs() < 0.01);
+
+        // The next round's request restarts the clock
+        rate.record(8, Duration::from_millis(200));
+        assert!((rate.tokens_per_sec().unwrap() - 10.0).abs() < 0.01);
+    }
+
     #[test]
     fn test_accumulate_recalculates_tok_per_sec() {
         let mut base = UsageStats {
@@ -673,6 +1740,29 @@ mod tests {
         assert!((base.tokens_per_sec.unwrap() - 25.0).abs() < 0.1);
     }
 
+    #[test]
+    fn test_finish_reason_hint_for_common_reasons() {
+        for truncated in ["length", "max_tokens", "max_output_tokens", "incomplete"] {
+            let hint = finish_reason_hint(truncated).unwrap();
+            assert!(hint.contains("max_output_tokens"), "{truncated}: {hint}");
+        }
+        assert!(
+            finish_reason_hint("content_filter")
+                .unwrap()
+                .contains("content filter")
+        );
+        for finished in ["completed", "stop", "end_turn", "tool_calls", ""] {
+            assert_eq!(finish_reason_hint(finished), None, "{finished}");
+        }
+
+        let stats = UsageStats {
+            finish_reason: Some("length".to_string()),
+            ..Default::default()
+        };
+        assert!(stats.finish_hint().is_some());
+        assert_eq!(UsageStats::default().finish_hint(), None);
+    }
+
     #[test]
     fn test_display_summary_all_fields() {
         let stats = UsageStats {
@@ -684,7 +1774,7 @@ mod tests {
             generation_duration_ms: Some(1200),
             ..Default::default()
         };
-        let summary = stats.display_summary();
+        let summary = stats.display_summary(None);
         assert!(summary.contains("150 in"));
         assert!(summary.contains("42 out"));
         assert!(summary.contains("80 cached"));
@@ -696,7 +1786,7 @@ mod tests {
     #[test]
     fn test_display_summary_empty() {
         let stats = UsageStats::default();
-        assert_eq!(stats.display_summary(), "Response complete.");
+        assert_eq!(stats.display_summary(None), "Response complete.");
     }
 
     #[test]
@@ -706,9 +1796,110 @@ mod tests {
             generation_duration_ms: Some(1500),
             ..Default::default()
         };
-        let summary = stats.display_summary();
+        let summary = stats.display_summary(None);
         assert!(summary.contains("42 out"));
         assert!(summary.contains("1.5s"));
         assert!(!summary.contains("in")); // no input tokens
     }
+
+    fn test_pricing() -> Pricing {
+        Pricing {
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+            cache_read_per_million: Some(0.3),
+        }
+    }
+
+    #[test]
+    fn test_cost_known_rates() {
+        let stats = UsageStats {
+            input_tokens: Some(1_000),
+            output_tokens: Some(500),
+            ..Default::default()
+        };
+        // 1000 * $3/M + 500 * $15/M = $0.003 + $0.0075
+        let cost = stats.cost(&test_pricing()).unwrap();
+        assert!((cost - 0.0105).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cost_discounts_cached_tokens() {
+        let stats = UsageStats {
+            input_tokens: Some(1_000),
+            output_tokens: Some(500),
+            cache_read_input_tokens: Some(800),
+            ..Default::default()
+        };
+        // 200 uncached * $3/M + 800 cached * $0.3/M + 500 * $15/M
+        let cost = stats.cost(&test_pricing()).unwrap();
+        assert!((cost - (0.0006 + 0.00024 + 0.0075)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cost_cached_at_input_rate_without_cache_price() {
+        let pricing = Pricing {
+            cache_read_per_million: None,
+            ..test_pricing()
+        };
+        let stats = UsageStats {
+            input_tokens: Some(1_000),
+            cache_read_input_tokens: Some(800),
+            ..Default::default()
+        };
+        let cost = stats.cost(&pricing).unwrap();
+        assert!((cost - 0.003).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cost_none_without_token_counts() {
+        assert!(UsageStats::default().cost(&test_pricing()).is_none());
+    }
+
+    #[test]
+    fn test_display_summary_includes_cost_when_priced() {
+        let stats = UsageStats {
+            input_tokens: Some(1_000),
+            output_tokens: Some(500),
+            ..Default::default()
+        };
+        assert!(
+            stats
+                .display_summary(Some(&test_pricing()))
+                .ends_with(" | $0.0105")
+        );
+        assert!(!stats.display_summary(None).contains('$'));
+    }
+
+    #[test]
+    fn test_image_mime_for_path() {
+        use std::path::Path;
+        assert_eq!(image_mime_for_path(Path::new("a.PNG")), Some("image/png"));
+        assert_eq!(image_mime_for_path(Path::new("a.jpeg")), Some("image/jpeg"));
+        assert_eq!(image_mime_for_path(Path::new("a.webp")), Some("image/webp"));
+        assert_eq!(image_mime_for_path(Path::new("notes.txt")), None);
+        assert_eq!(image_mime_for_path(Path::new("README")), None);
+    }
+
+    #[test]
+    fn test_image_attachment_data_url_and_label() {
+        let image = ImageAttachment {
+            data_base64: "AAAA".to_string(),
+            mime: "image/gif".to_string(),
+            caption: Some("spin.gif".to_string()),
+        };
+        assert_eq!(image.data_url(), "data:image/gif;base64,AAAA");
+        assert_eq!(image.label(), "spin.gif · image/gif · 1 KB");
+    }
+
+    #[test]
+    fn test_image_attachment_from_data_url() {
+        let image = ImageAttachment::from_data_url("data:image/png;base64,iVBO\nRw==\n").unwrap();
+        assert_eq!(image.mime, "image/png");
+        assert_eq!(image.data_base64, "iVBORw==");
+        assert_eq!(image.caption, None);
+
+        assert!(ImageAttachment::from_data_url("data:text/plain;base64,aGk=").is_none());
+        assert!(ImageAttachment::from_data_url("data:image/png;base64,not base64!").is_none());
+        assert!(ImageAttachment::from_data_url("just some text").is_none());
+    }
 }
@@ -7,6 +7,12 @@ mod tui;
 use clap::Parser;
 use simplelog::{ConfigBuilder, LevelFilter, WriteLogger};
 use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+
+use crate::core::config::ResolvedConfig;
+use crate::core::state::SessionState;
+use crate::inference::Effort;
 
 #[derive(Parser)]
 #[command(name = "navi", about = "Model-agnostic AI assistant")]
@@ -14,6 +20,27 @@ struct Args {
     /// LLM provider to use (overrides config file and env vars)
     #[arg(short, long)]
     provider: Option<String>,
+
+    /// Config profile to apply (overrides NAVI_PROFILE)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Reasoning effort for this run: off, auto, low, medium or high
+    /// (overrides config file and per-model settings)
+    #[arg(long, value_name = "LEVEL")]
+    effort: Option<Effort>,
+
+    /// Print the request JSON a saved session would send, then exit
+    #[arg(long, value_name = "SESSION_ID")]
+    dump_request: Option<String>,
+
+    /// Write every saved session to one JSON archive, then exit
+    #[arg(long, value_name = "PATH")]
+    export_sessions: Option<PathBuf>,
+
+    /// Restore the sessions in an archive under new ids, then exit
+    #[arg(long, value_name = "PATH")]
+    import_sessions: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -33,7 +60,35 @@ async fn main() -> std::io::Result<()> {
         log::warn!("Config error: {}, using defaults", e);
         core::config::NaviConfig::default()
     });
-    let resolved = core::config::resolve(&config, args.provider.as_deref());
+    let config_warnings = core::config::validate(&config);
+    for warning in &config_warnings {
+        log::warn!("Config warning: {}", warning);
+    }
+    let mut resolved =
+        core::config::resolve(&config, args.provider.as_deref(), args.profile.as_deref());
+
+    if let Some(effort) = args.effort {
+        resolved.effort = effort;
+    }
+    if let Some(dir) = resolved.sessions_dir.clone() {
+        core::session::set_sessions_dir(dir);
+    }
+
+    if let Some(id) = args.dump_request.as_deref() {
+        return dump_request(id, &resolved);
+    }
+
+    if let Some(path) = args.export_sessions.as_deref() {
+        core::session::export_archive(path)?;
+        println!("Exported sessions to {}", path.display());
+        return Ok(());
+    }
+
+    if let Some(path) = args.import_sessions.as_deref() {
+        let count = core::session::import_archive(path)?;
+        println!("Imported {count} sessions from {}", path.display());
+        return Ok(());
+    }
 
     log::info!(
         "Navi starting up: provider={}, model={}",
@@ -41,5 +96,24 @@ async fn main() -> std::io::Result<()> {
         resolved.model_name,
     );
 
-    tui::run(resolved)
+    tui::run(resolved, args.effort, &config_warnings)
+}
+
+/// Rebuild saved session `id` the way loading it in the TUI does, with the
+/// configured system prompt in front, and print its request body.
+fn dump_request(id: &str, config: &ResolvedConfig) -> io::Result<()> {
+    let data = core::session::read_session(id)?;
+    let mut session = SessionState::new(&config.system_prompt);
+    session.context.items.extend(data.items);
+    session.context.repair_tool_pairs();
+
+    let model = if data.meta.model_name.is_empty() {
+        &config.model_name
+    } else {
+        &data.meta.model_name
+    };
+    let json = core::session::build_request_json(&session.context.items, model, config.effort)
+        .map_err(io::Error::other)?;
+    println!("{json}");
+    Ok(())
 }
@@ -22,7 +22,10 @@ impl CompletionProvider for NoopProvider {
     }
 }
 
-/// Creates a test App with a NoopProvider.
+/// Creates a test App with a NoopProvider and an OpenRouter key, so sends
+/// pass the credentials pre-flight.
 pub fn test_app() -> crate::core::state::App {
-    crate::core::state::App::new(Arc::new(NoopProvider), "test-model".to_string())
+    let mut app = crate::core::state::App::new(Arc::new(NoopProvider), "test-model".to_string());
+    app.config.openrouter_api_key = Some("sk-or-test".to_string());
+    app
 }
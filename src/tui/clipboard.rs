@@ -0,0 +1,87 @@
+//! # Clipboard
+//!
+//! Copies text through the terminal with the OSC 52 escape sequence, so it
+//! works over SSH and needs no platform clipboard library. Terminals without
+//! OSC 52 support ignore the sequence.
+//!
+//! Terminals only ever paste text, so images are read from the local
+//! clipboard by running the platform's command line tool instead.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use base64::Engine;
+
+/// Put `text` on the system clipboard via the terminal.
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52(text).as_bytes())?;
+    stdout.flush()
+}
+
+/// Commands that print the clipboard's PNG image to stdout, tried in order.
+const IMAGE_READERS: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline", "--type", "image/png"]),
+    ("xclip", &["-sel", "clip", "-t", "image/png", "-o"]),
+    ("pngpaste", &["-"]),
+];
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// The PNG image on the system clipboard, read with `wl-paste` (Wayland),
+/// `xclip` (X11) or `pngpaste` (macOS). Ok(None) when the clipboard holds no
+/// image; an error when none of the tools is installed.
+pub fn read_image() -> io::Result<Option<Vec<u8>>> {
+    let mut found_reader = false;
+    for (program, args) in IMAGE_READERS {
+        let output = match Command::new(program)
+            .args(*args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        found_reader = true;
+        if output.status.success() && is_png(&output.stdout) {
+            return Ok(Some(output.stdout));
+        }
+    }
+    if found_reader {
+        Ok(None)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no clipboard tool found (install wl-paste, xclip or pngpaste)",
+        ))
+    }
+}
+
+fn is_png(bytes: &[u8]) -> bool {
+    bytes.starts_with(PNG_SIGNATURE)
+}
+
+/// The OSC 52 "set clipboard" sequence carrying `text`.
+fn osc52(text: &str) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    format!("\x1b]52;c;{encoded}\x07")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_encodes_text() {
+        assert_eq!(osc52("hi\nthere"), "\x1b]52;c;aGkKdGhlcmU=\x07");
+    }
+
+    #[test]
+    fn test_is_png_checks_signature() {
+        assert!(is_png(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert!(!is_png(b"hello"));
+        assert!(!is_png(b""));
+    }
+}
@@ -0,0 +1,214 @@
+//! # Slash Commands
+//!
+//! Input starting with `/` is a command rather than a chat message, unless
+//! it starts with `//`: that sends the message with a single leading `/`.
+//! `parse()` turns the raw buffer into a `Command`; the handlers module
+//! dispatches it to the matching `Action`s.
+
+use std::fmt;
+
+use crate::inference::Effort;
+
+/// A parsed slash command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// `/clear` — start a fresh conversation
+    Clear,
+    /// `/model <name>` — switch the active model
+    Model(String),
+    /// `/save` — persist the current session now
+    Save,
+    /// `/export` — write the conversation to a markdown file
+    Export,
+    /// `/effort <level>` — set the reasoning effort
+    Effort(Effort),
+    /// `/once <instruction>` — extra instruction for the next message only
+    Once(String),
+    /// `/continue` — ask the model to pick up where a cut-off response stopped
+    Continue,
+    /// `/regenerate` — answer the last message again, keeping the old answer
+    Regenerate,
+}
+
+/// Why a command could not be parsed. Displayed in the status bar.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    Unknown(String),
+    MissingArgument { usage: &'static str },
+    InvalidArgument { usage: &'static str, arg: String },
+    UnexpectedArgument(&'static str),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Unknown(name) => write!(f, "Unknown command: /{}", name),
+            CommandError::MissingArgument { usage } => write!(f, "Usage: {}", usage),
+            CommandError::InvalidArgument { usage, arg } => {
+                write!(f, "Invalid argument '{}'. Usage: {}", arg, usage)
+            }
+            CommandError::UnexpectedArgument(command) => {
+                write!(f, "{} takes no arguments", command)
+            }
+        }
+    }
+}
+
+const MODEL_USAGE: &str = "/model <name>";
+const EFFORT_USAGE: &str = "/effort <off|auto|low|medium|high>";
+const ONCE_USAGE: &str = "/once <instruction>";
+
+/// Message sent by `/continue`.
+pub const CONTINUE_PROMPT: &str = "Continue exactly where you left off.";
+
+/// True if submitted text should be treated as a command.
+pub fn is_command(input: &str) -> bool {
+    let trimmed = input.trim_start();
+    trimmed.starts_with('/') && !trimmed.starts_with("//")
+}
+
+/// The chat message for submitted text that isn't a command: a leading `//`
+/// is sent as `/`, so text like a path can start with a slash.
+pub fn message_text(input: String) -> String {
+    let trimmed = input.trim_start();
+    match trimmed.strip_prefix("//") {
+        Some(rest) => format!("/{rest}"),
+        None => input,
+    }
+}
+
+/// Parse a `/`-prefixed command line. Command names are case-insensitive.
+pub fn parse(input: &str) -> Result<Command, CommandError> {
+    let trimmed = input.trim();
+    let body = trimmed.strip_prefix('/').unwrap_or(trimmed);
+    let (name, arg) = match body.split_once(char::is_whitespace) {
+        Some((name, rest)) => (name, rest.trim()),
+        None => (body, ""),
+    };
+
+    match name.to_lowercase().as_str() {
+        "clear" => no_argument(Command::Clear, "/clear", arg),
+        "save" => no_argument(Command::Save, "/save", arg),
+        "export" => no_argument(Command::Export, "/export", arg),
+        "continue" => no_argument(Command::Continue, "/continue", arg),
+        "regenerate" => no_argument(Command::Regenerate, "/regenerate", arg),
+        "model" if arg.is_empty() => Err(CommandError::MissingArgument { usage: MODEL_USAGE }),
+        "model" => Ok(Command::Model(arg.to_string())),
+        "effort" if arg.is_empty() => Err(CommandError::MissingArgument {
+            usage: EFFORT_USAGE,
+        }),
+        "effort" => parse_effort(arg).map(Command::Effort),
+        "once" if arg.is_empty() => Err(CommandError::MissingArgument { usage: ONCE_USAGE }),
+        "once" => Ok(Command::Once(arg.to_string())),
+        _ => Err(CommandError::Unknown(name.to_string())),
+    }
+}
+
+fn no_argument(command: Command, name: &'static str, arg: &str) -> Result<Command, CommandError> {
+    if arg.is_empty() {
+        Ok(command)
+    } else {
+        Err(CommandError::UnexpectedArgument(name))
+    }
+}
+
+fn parse_effort(arg: &str) -> Result<Effort, CommandError> {
+    arg.parse().map_err(|_| CommandError::InvalidArgument {
+        usage: EFFORT_USAGE,
+        arg: arg.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_command() {
+        assert!(is_command("/clear"));
+        assert!(is_command("  /model x"));
+        assert!(!is_command("hello /clear"));
+        assert!(!is_command(""));
+        assert!(!is_command("//usr/bin is missing"));
+    }
+
+    #[test]
+    fn test_message_text_unescapes_double_slash() {
+        assert_eq!(
+            message_text("//usr/bin is missing".to_string()),
+            "/usr/bin is missing"
+        );
+        assert_eq!(message_text("  //clear".to_string()), "/clear");
+        assert_eq!(message_text("a // b".to_string()), "a // b");
+    }
+
+    #[test]
+    fn test_parse_simple_commands() {
+        assert_eq!(parse("/clear"), Ok(Command::Clear));
+        assert_eq!(parse("/save"), Ok(Command::Save));
+        assert_eq!(parse("/export"), Ok(Command::Export));
+        assert_eq!(parse("/continue"), Ok(Command::Continue));
+        assert_eq!(parse("/regenerate"), Ok(Command::Regenerate));
+        assert_eq!(parse("  /CLEAR  "), Ok(Command::Clear));
+    }
+
+    #[test]
+    fn test_parse_model_argument() {
+        assert_eq!(
+            parse("/model anthropic/claude-sonnet-4"),
+            Ok(Command::Model("anthropic/claude-sonnet-4".to_string()))
+        );
+        assert_eq!(
+            parse("/model   qwen2.5-coder-32b  "),
+            Ok(Command::Model("qwen2.5-coder-32b".to_string()))
+        );
+        assert_eq!(
+            parse("/model"),
+            Err(CommandError::MissingArgument { usage: MODEL_USAGE })
+        );
+    }
+
+    #[test]
+    fn test_parse_effort_argument() {
+        assert_eq!(parse("/effort high"), Ok(Command::Effort(Effort::High)));
+        assert_eq!(parse("/effort Off"), Ok(Command::Effort(Effort::None)));
+        assert_eq!(
+            parse("/effort extreme"),
+            Err(CommandError::InvalidArgument {
+                usage: EFFORT_USAGE,
+                arg: "extreme".to_string(),
+            })
+        );
+        assert!(matches!(
+            parse("/effort"),
+            Err(CommandError::MissingArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_once_argument() {
+        assert_eq!(
+            parse("/once answer in French"),
+            Ok(Command::Once("answer in French".to_string()))
+        );
+        assert_eq!(
+            parse("/once"),
+            Err(CommandError::MissingArgument { usage: ONCE_USAGE })
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_command() {
+        let err = parse("/frobnicate now").unwrap_err();
+        assert_eq!(err, CommandError::Unknown("frobnicate".to_string()));
+        assert_eq!(err.to_string(), "Unknown command: /frobnicate");
+    }
+
+    #[test]
+    fn test_parse_rejects_extra_arguments() {
+        assert_eq!(
+            parse("/clear everything"),
+            Err(CommandError::UnexpectedArgument("/clear"))
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! # Diff Overlay Component
+//!
+//! Centered overlay comparing a regenerated answer with the one before it,
+//! word by word: removed words red and struck through, added words green.
+//! Opened with `Alt+V` once `/regenerate` has kept two answers; arrows and
+//! the wheel scroll, any other key closes it.
+
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap};
+use similar::{ChangeTag, TextDiff};
+
+/// Word-level diff of `old` against `new` as styled lines.
+pub fn diff_lines(old: &str, new: &str) -> Vec<Line<'static>> {
+    let diff = TextDiff::from_words(old, new);
+    let mut lines = vec![Line::default()];
+    for change in diff.iter_all_changes() {
+        let style = match change.tag() {
+            ChangeTag::Equal => Style::default(),
+            ChangeTag::Delete => Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::CROSSED_OUT),
+            ChangeTag::Insert => Style::default().fg(Color::Green),
+        };
+        // Whitespace tokens may hold newlines; each one starts a new line
+        for (idx, part) in change.value().split('\n').enumerate() {
+            if idx > 0 {
+                lines.push(Line::default());
+            }
+            if !part.is_empty()
+                && let Some(line) = lines.last_mut()
+            {
+                line.push_span(Span::styled(part.to_string(), style));
+            }
+        }
+    }
+    lines
+}
+
+/// The diff being shown and how far it is scrolled.
+pub struct DiffOverlayState {
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+}
+
+impl DiffOverlayState {
+    pub fn new(old: &str, new: &str) -> Self {
+        Self {
+            lines: diff_lines(old, new),
+            scroll: 0,
+        }
+    }
+
+    /// Scroll by `delta` lines; `render` clamps it to the content.
+    pub fn scroll_by(&mut self, delta: i32) {
+        self.scroll = (self.scroll as i32 + delta).clamp(0, u16::MAX as i32) as u16;
+    }
+}
+
+/// Transient render wrapper for the diff overlay.
+pub struct DiffOverlay<'a> {
+    state: &'a mut DiffOverlayState,
+}
+
+impl<'a> DiffOverlay<'a> {
+    pub fn new(state: &'a mut DiffOverlayState) -> Self {
+        Self { state }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let overlay = centered_percent(80, 80, area);
+        frame.render_widget(Clear, overlay);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(" Previous answer → shown answer ")
+            .title_alignment(Alignment::Left)
+            .title_bottom(Line::from(" ↑↓ scroll · any other key closes ").centered())
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(overlay);
+
+        let paragraph = Paragraph::new(self.state.lines.clone()).wrap(Wrap { trim: false });
+        let max_scroll = paragraph
+            .line_count(inner.width)
+            .saturating_sub(inner.height as usize);
+        self.state.scroll = self.state.scroll.min(max_scroll as u16);
+
+        frame.render_widget(
+            paragraph.block(block).scroll((self.state.scroll, 0)),
+            overlay,
+        );
+    }
+}
+
+/// A rect `width_pct` by `height_pct` of `outer`, centered in it.
+fn centered_percent(width_pct: u16, height_pct: u16, outer: Rect) -> Rect {
+    let width = outer.width * width_pct / 100;
+    let height = outer.height * height_pct / 100;
+    Rect {
+        x: outer.x + (outer.width - width) / 2,
+        y: outer.y + (outer.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_word_becomes_removed_and_added_spans() {
+        let lines = diff_lines("The cat sat on the mat.", "The dog sat on the mat.");
+        assert_eq!(lines.len(), 1);
+
+        let text: String = lines[0].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "The catdog sat on the mat.");
+
+        let changed: Vec<(&str, Option<Color>)> = lines[0]
+            .spans
+            .iter()
+            .filter(|s| s.style != Style::default())
+            .map(|s| (s.content.as_ref(), s.style.fg))
+            .collect();
+        assert_eq!(
+            changed,
+            [("cat", Some(Color::Red)), ("dog", Some(Color::Green))]
+        );
+        assert!(
+            lines[0].spans[2]
+                .style
+                .add_modifier
+                .contains(Modifier::CROSSED_OUT)
+        );
+    }
+
+    #[test]
+    fn test_newlines_split_lines() {
+        let lines = diff_lines("one\ntwo", "one\nthree");
+        assert_eq!(lines.len(), 2);
+        let second: String = lines[1].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(second, "twothree");
+    }
+}
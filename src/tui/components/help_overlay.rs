@@ -0,0 +1,155 @@
+//! # Help Overlay Component
+//!
+//! Centered overlay listing every keybinding, grouped by category.
+//! Opened with `?`, dismissed by any key.
+//!
+//! Stateless: the binding list comes from `event::KEY_BINDINGS`, so the help
+//! text lives next to the key mappings it documents.
+
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph};
+
+use crate::tui::event::KeyBinding;
+
+/// Transient render wrapper for the help overlay.
+pub struct HelpOverlay<'a> {
+    bindings: &'a [KeyBinding],
+}
+
+impl<'a> HelpOverlay<'a> {
+    pub fn new(bindings: &'a [KeyBinding]) -> Self {
+        Self { bindings }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let lines = self.build_lines();
+
+        let keys_width = self.keys_width();
+        let desc_width = self
+            .bindings
+            .iter()
+            .map(|b| b.description.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        // Content: indent(2) + keys + gap(2) + description; chrome: borders(2) + padding(2)
+        let width = (2 + keys_width + 2 + desc_width + 4) as u16;
+        let height = lines.len() as u16 + 2;
+        let overlay = centered_fixed(width, height, area);
+
+        frame.render_widget(Clear, overlay);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(" Keybindings ")
+            .title_alignment(Alignment::Left)
+            .title_bottom(Line::from(" Press any key to close ").centered())
+            .padding(Padding::horizontal(1));
+
+        frame.render_widget(Paragraph::new(lines).block(block), overlay);
+    }
+
+    /// Width of the widest key column entry, used to align descriptions.
+    fn keys_width(&self) -> usize {
+        self.bindings
+            .iter()
+            .map(|b| b.keys.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// One header line per category followed by its bindings, with a blank
+    /// line between categories.
+    fn build_lines(&self) -> Vec<Line<'static>> {
+        let keys_width = self.keys_width();
+
+        let mut lines = Vec::new();
+        let mut current_category: Option<&str> = None;
+        for binding in self.bindings {
+            if current_category != Some(binding.category) {
+                if current_category.is_some() {
+                    lines.push(Line::default());
+                }
+                lines.push(Line::from(Span::styled(
+                    binding.category,
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )));
+                current_category = Some(binding.category);
+            }
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(
+                    format!("{:<width$}", binding.keys, width = keys_width),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw("  "),
+                Span::styled(binding.description, Style::default().fg(Color::Gray)),
+            ]));
+        }
+        lines
+    }
+}
+
+/// Center a fixed-size rect inside `outer`, clamping to its bounds.
+fn centered_fixed(width: u16, height: u16, outer: Rect) -> Rect {
+    let width = width.min(outer.width);
+    let height = height.min(outer.height);
+    Rect {
+        x: outer.x + (outer.width - width) / 2,
+        y: outer.y + (outer.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::event::KEY_BINDINGS;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn buffer_text(terminal: &Terminal<TestBackend>) -> String {
+        let buffer = terminal.backend().buffer();
+        let mut text = String::new();
+        for y in 0..buffer.area.height {
+            for x in 0..buffer.area.width {
+                text.push_str(buffer[(x, y)].symbol());
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    #[test]
+    fn test_render_shows_known_bindings() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 60)).unwrap();
+        terminal
+            .draw(|frame| HelpOverlay::new(KEY_BINDINGS).render(frame, frame.area()))
+            .unwrap();
+
+        let text = buffer_text(&terminal);
+        assert!(text.contains("Keybindings"));
+        assert!(text.contains("Cursor mode"));
+        assert!(text.contains("Ctrl+R"));
+        assert!(text.contains("Cycle reasoning effort"));
+        assert!(text.contains("Kill to line start / end"));
+        assert!(text.contains("PgUp / PgDn"));
+    }
+
+    #[test]
+    fn test_centered_fixed_clamps_to_area() {
+        let outer = Rect::new(0, 0, 20, 10);
+        let rect = centered_fixed(50, 30, outer);
+        assert_eq!(rect, outer);
+
+        let rect = centered_fixed(10, 4, outer);
+        assert_eq!(rect, Rect::new(5, 3, 10, 4));
+    }
+}
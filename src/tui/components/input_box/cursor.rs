@@ -1,11 +1,11 @@
 //! Cursor position tracking and navigation for the InputBox.
 //!
-//! `CursorState` owns the cursor byte offset, scroll offset, and cached width.
+//! `CursorState` owns the cursor byte offset, scroll offsets, and cached width.
 //! All navigation methods accept `buffer: &str` explicitly — the text data is
 //! owned by `InputBox`, keeping the dependency visible.
 
 use super::text_wrap::{
-    BORDER_OFFSET, MAX_VISIBLE_LINES, inner_width, wrap_line_count, wrapped_line_byte_starts,
+    BORDER_OFFSET, inner_width, visible_columns, wrap_line_count, wrapped_line_byte_starts,
 };
 use ratatui::layout::Rect;
 use unicode_width::UnicodeWidthStr;
@@ -16,6 +16,10 @@ pub(super) struct CursorState {
     pub pos: usize,
     /// Line offset for internal scrolling (0 when content fits in viewport)
     pub scroll_offset: u16,
+    /// Column offset for horizontal scrolling (0 unless the cursor's display
+    /// line runs past the visible width, e.g. a long run of spaces that
+    /// textwrap won't break)
+    pub h_offset: u16,
     /// Cached content width from last render (used for cursor movement)
     pub last_content_width: u16,
 }
@@ -43,6 +47,7 @@ impl CursorState {
         Self {
             pos: 0,
             scroll_offset: 0,
+            h_offset: 0,
             last_content_width: Self::DEFAULT_WIDTH,
         }
     }
@@ -51,6 +56,7 @@ impl CursorState {
     pub fn reset(&mut self) {
         self.pos = 0;
         self.scroll_offset = 0;
+        self.h_offset = 0;
     }
 
     /// Move cursor vertically (up or down) while trying to maintain column position.
@@ -96,12 +102,22 @@ impl CursorState {
         line_index_for_pos(&starts, self.pos) as u16
     }
 
-    /// Update scroll offset to keep cursor visible within the viewport.
-    pub fn update_scroll_offset(&mut self, buffer: &str, content_width: u16) {
+    /// 1-based (line, column) of the cursor, counting wrapped display lines
+    /// and characters (not bytes) within the line.
+    pub fn line_column(&self, buffer: &str, content_width: u16) -> (usize, usize) {
+        let starts = wrapped_line_byte_starts(buffer, inner_width(content_width));
+        let line_idx = line_index_for_pos(&starts, self.pos);
+        let column = buffer[starts[line_idx]..self.pos].chars().count();
+        (line_idx + 1, column + 1)
+    }
+
+    /// Update scroll offset to keep cursor visible within a viewport of
+    /// `max_lines` lines.
+    pub fn update_scroll_offset(&mut self, buffer: &str, content_width: u16, max_lines: u16) {
         let width = inner_width(content_width);
         let total_lines = wrap_line_count(buffer, width);
 
-        if total_lines <= MAX_VISIBLE_LINES {
+        if total_lines <= max_lines {
             self.scroll_offset = 0;
             return;
         }
@@ -110,8 +126,29 @@ impl CursorState {
 
         if cursor_line < self.scroll_offset {
             self.scroll_offset = cursor_line;
-        } else if cursor_line >= self.scroll_offset + MAX_VISIBLE_LINES {
-            self.scroll_offset = cursor_line.saturating_sub(MAX_VISIBLE_LINES - 1);
+        } else if cursor_line >= self.scroll_offset + max_lines {
+            self.scroll_offset = cursor_line.saturating_sub(max_lines - 1);
+        }
+    }
+
+    /// Display column of the cursor within its wrapped line (before horizontal scrolling).
+    fn display_column(&self, buffer: &str, content_width: u16) -> u16 {
+        let starts = wrapped_line_byte_starts(buffer, inner_width(content_width));
+        let line_start = starts[line_index_for_pos(&starts, self.pos)];
+        UnicodeWidthStr::width(&buffer[line_start..self.pos]) as u16
+    }
+
+    /// Update horizontal offset to keep the cursor column within the visible width.
+    pub fn update_h_offset(&mut self, buffer: &str, content_width: u16) {
+        let visible = visible_columns(content_width);
+        let column = self.display_column(buffer, content_width);
+
+        if visible == 0 || column < visible {
+            self.h_offset = 0;
+        } else if column < self.h_offset {
+            self.h_offset = column;
+        } else if column >= self.h_offset + visible {
+            self.h_offset = column - (visible - 1);
         }
     }
 
@@ -125,9 +162,10 @@ impl CursorState {
 
         let starts = wrapped_line_byte_starts(buffer, width);
         let line_idx = line_index_for_pos(&starts, self.pos);
-        let line_start = starts[line_idx];
 
-        let cursor_col = UnicodeWidthStr::width(&buffer[line_start..self.pos]) as u16;
+        let cursor_col = self
+            .display_column(buffer, area.width)
+            .saturating_sub(self.h_offset);
         let visible_line = (line_idx as u16).saturating_sub(self.scroll_offset);
 
         (
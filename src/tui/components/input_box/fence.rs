@@ -0,0 +1,76 @@
+//! Code-fence wrapping for pasted code (Alt+F).
+//!
+//! The buffer is wrapped in triple-backtick fences, with a language tag when
+//! a few telltale lines make a guess safe. A wrong guess only costs syntax
+//! colors, so the guess stays cheap: no tag beats a confident wrong one.
+
+const FENCE: &str = "```";
+
+/// `text` inside a fenced code block, or None when there is nothing to wrap
+/// or it is already fenced.
+pub(super) fn fenced(text: &str) -> Option<String> {
+    let body = text.trim_end_matches('\n');
+    if body.trim().is_empty() || body.trim_start().starts_with(FENCE) {
+        return None;
+    }
+    let lang = guess_language(body).unwrap_or_default();
+    Some(format!("{FENCE}{lang}\n{body}\n{FENCE}"))
+}
+
+/// A language tag for `code`, from markers that rarely show up elsewhere.
+fn guess_language(code: &str) -> Option<&'static str> {
+    let first = code.lines().next().unwrap_or_default().trim();
+    if let Some(shebang) = first.strip_prefix("#!") {
+        return Some(if shebang.contains("python") {
+            "python"
+        } else {
+            "sh"
+        });
+    }
+    let trimmed = code.trim();
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return Some("json");
+    }
+
+    let has = |markers: &[&str]| {
+        code.lines()
+            .map(str::trim_start)
+            .any(|line| markers.iter().any(|m| line.starts_with(m)))
+    };
+    if has(&["fn ", "pub fn ", "impl ", "use std::", "let mut "]) {
+        Some("rust")
+    } else if has(&["def ", "from ", "elif "]) && code.contains(':') {
+        Some("python")
+    } else if has(&["package main", "func "]) {
+        Some("go")
+    } else if has(&["#include"]) {
+        Some("c")
+    } else if has(&["const ", "function ", "export ", "import {"]) {
+        Some("javascript")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guesses_from_telltale_lines() {
+        assert_eq!(guess_language("fn main() {\n}"), Some("rust"));
+        assert_eq!(guess_language("def f(x):\n    return x"), Some("python"));
+        assert_eq!(guess_language("#!/bin/bash\necho hi"), Some("sh"));
+        assert_eq!(guess_language(r#"{"a": [1, 2]}"#), Some("json"));
+        assert_eq!(guess_language("const x = 1;"), Some("javascript"));
+        assert_eq!(guess_language("just some prose"), None);
+    }
+
+    #[test]
+    fn test_empty_or_fenced_text_is_left_alone() {
+        assert_eq!(fenced("  \n"), None);
+        assert_eq!(fenced("```\nx\n```"), None);
+    }
+}
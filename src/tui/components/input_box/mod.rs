@@ -6,8 +6,10 @@
 //!
 //! - Capture text input
 //! - Handle editing (backspace, delete, cursor movement, paste)
-//! - Handle submission (Enter)
+//! - Handle submission (Enter), routing `/`-prefixed input as a command
 //! - Handle effort cycling (Ctrl+R)
+//! - Browse input history (Up/Down), abandoning it with Ctrl+G
+//! - Wrap the buffer in a code fence (Alt+F)
 //! - Display current input buffer and effort state
 //!
 //! ## State Management
@@ -16,13 +18,16 @@
 //! Cursor position and scroll state are encapsulated in `CursorState`.
 
 mod cursor;
+mod fence;
 mod text_wrap;
 
 use crate::inference::Effort;
+use crate::tui::command;
 use crate::tui::component::{Component, EventHandler};
 use crate::tui::event::TuiEvent;
 use ratatui::Frame;
 use ratatui::layout::Rect;
+use ratatui::text::Line;
 use ratatui::widgets::{Block, Paragraph};
 
 use cursor::CursorState;
@@ -31,11 +36,16 @@ use text_wrap::{
     prev_char_boundary, prev_word_boundary, wrap_line_count, wrap_options,
 };
 
+/// Lines added or removed per Alt+= / Alt+- press.
+const RESIZE_STEP: u16 = 2;
+
 /// High-level events emitted by the InputBox
 #[derive(Debug, Clone, PartialEq)]
 pub enum InputEvent {
     /// User submitted the text (Enter pressed)
     Submit(String),
+    /// User submitted a `/`-prefixed command line instead of a message
+    Command(String),
     /// User requested to cycle effort level (Ctrl+R)
     CycleEffort,
     /// Text content changed (optional, if parent needs to know)
@@ -114,6 +124,13 @@ impl InputHistory {
         }
     }
 
+    /// Stop navigating and hand back the saved draft, or None if not
+    /// navigating.
+    fn abort(&mut self) -> Option<String> {
+        self.index.take()?;
+        Some(std::mem::take(&mut self.draft))
+    }
+
     /// Reset navigation state (called on any edit to the buffer).
     fn reset_navigation(&mut self) {
         self.index = None;
@@ -161,6 +178,9 @@ impl KillBuffer {
 /// # Props
 ///
 /// - `effort`: Current reasoning effort level (from App state)
+/// - `show_counter`: Whether to show the line/column and character counter
+/// - `image_attached`: Whether a pasted image will be sent with the message
+/// - `max_lines_override`: Visible-lines cap set with Alt+= / Alt+-
 ///
 /// # State
 ///
@@ -173,6 +193,16 @@ pub struct InputBox {
     pub effort: Effort,
     /// Whether the input is visually dimmed (Prop — true in Cursor mode)
     pub dimmed: bool,
+    /// Border and text color (Prop — from the theme's user color)
+    pub accent: ratatui::style::Color,
+    /// Show "L2:C14 · 240 chars" in the top border (Prop — from config)
+    pub show_counter: bool,
+    /// Show an "[image attached]" chip in the bottom border (Prop — true
+    /// while a pasted image waits for the next Submit)
+    pub image_attached: bool,
+    /// Visible lines before the input scrolls internally (Prop — None uses
+    /// `MAX_VISIBLE_LINES`)
+    pub max_lines_override: Option<u16>,
     /// Cursor and scroll tracking
     cursor: CursorState,
     /// Emacs-style kill buffer for Ctrl+U/K/W → Ctrl+Y
@@ -188,18 +218,57 @@ impl InputBox {
             buffer: String::new(),
             effort,
             dimmed: false,
+            accent: ratatui::style::Color::Green,
+            show_counter: true,
+            image_attached: false,
+            max_lines_override: None,
             cursor: CursorState::new(),
             kill_buffer: KillBuffer::new(),
             history: InputHistory::new(),
         }
     }
 
+    /// Insert `text` before the current buffer and put the cursor right
+    /// after it, e.g. a quote above a half-written reply.
+    pub fn prepend(&mut self, text: &str) {
+        self.history.reset_navigation();
+        self.buffer.insert_str(0, text);
+        self.cursor.pos = text.len();
+    }
+
+    /// Visible lines before the input scrolls internally.
+    fn max_visible_lines(&self) -> u16 {
+        self.max_lines_override.unwrap_or(MAX_VISIBLE_LINES)
+    }
+
+    /// The visible-lines cap after growing (or shrinking) `current` by one
+    /// step on a terminal `terminal_height` rows tall. The input may take up
+    /// to two thirds of the screen and never drops below the default; None
+    /// means back at the default.
+    pub fn resized_max_lines(
+        current: Option<u16>,
+        grow: bool,
+        terminal_height: u16,
+    ) -> Option<u16> {
+        let limit = (terminal_height.saturating_mul(2) / 3)
+            .saturating_sub(VERTICAL_OVERHEAD)
+            .max(MAX_VISIBLE_LINES);
+        let current = current.unwrap_or(MAX_VISIBLE_LINES);
+        let lines = if grow {
+            current.saturating_add(RESIZE_STEP)
+        } else {
+            current.saturating_sub(RESIZE_STEP)
+        };
+        let lines = lines.clamp(MAX_VISIBLE_LINES, limit);
+        (lines != MAX_VISIBLE_LINES).then_some(lines)
+    }
+
     /// Calculate required height for current buffer content, clamped to viewport limits.
-    /// Returns value in range [1 + VERTICAL_OVERHEAD, MAX_VISIBLE_LINES + VERTICAL_OVERHEAD].
+    /// Returns value in range [1 + VERTICAL_OVERHEAD, max visible lines + VERTICAL_OVERHEAD].
     pub fn calculate_height(&self, content_width: u16) -> u16 {
         let width = inner_width(content_width);
         let content_lines = wrap_line_count(&self.buffer, width);
-        let visible_lines = content_lines.min(MAX_VISIBLE_LINES);
+        let visible_lines = content_lines.min(self.max_visible_lines());
         visible_lines + VERTICAL_OVERHEAD
     }
 
@@ -214,7 +283,7 @@ impl InputBox {
         let lines = textwrap::wrap(&self.buffer, wrap_options(width));
 
         let start = self.cursor.scroll_offset as usize;
-        let end = (start + MAX_VISIBLE_LINES as usize).min(lines.len());
+        let end = (start + self.max_visible_lines() as usize).min(lines.len());
 
         // Handle trailing newline that textwrap doesn't represent as an empty line
         let mut result: String = lines[start..end].join("\n");
@@ -228,6 +297,20 @@ impl InputBox {
         result
     }
 
+    /// Cursor line/column and total character count, e.g. "L2:C14 · 240 chars".
+    /// Cut to the position alone, or left out, when it doesn't fit beside a
+    /// title `title_width` cells wide in a border `width` cells wide.
+    fn counter_text(&self, width: u16, title_width: usize) -> Option<String> {
+        let (line, column) = self.cursor.line_column(&self.buffer, width);
+        let chars = self.buffer.chars().count();
+        let position = format!("L{line}:C{column}");
+        // Two corners, a gap between the titles and the counter's padding
+        let room = (width as usize).saturating_sub(title_width + 5);
+        [format!("{position} · {chars} chars"), position]
+            .into_iter()
+            .find(|text| text.chars().count() <= room)
+    }
+
     /// Render scrollbar when content exceeds visible area
     fn render_scrollbar(&self, frame: &mut Frame, area: Rect) {
         use ratatui::widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState};
@@ -235,15 +318,19 @@ impl InputBox {
         let width = inner_width(area.width);
         let total_lines = wrap_line_count(&self.buffer, width);
 
-        if total_lines <= MAX_VISIBLE_LINES {
+        let max_lines = self.max_visible_lines();
+        if total_lines <= max_lines {
             return;
         }
 
-        // ScrollbarState content_length is max scrollable position, not total items
-        let max_scroll = total_lines.saturating_sub(MAX_VISIBLE_LINES);
+        // Scroll positions run 0..=max_scroll, each showing `max_lines` rows,
+        // so the thumb covers max_lines / total_lines of the track and sits
+        // at the bottom once the last line is in view
+        let max_scroll = total_lines.saturating_sub(max_lines);
 
         let mut scrollbar_state = ScrollbarState::default()
-            .content_length(max_scroll as usize)
+            .content_length(max_scroll as usize + 1)
+            .viewport_content_length(max_lines as usize)
             .position(self.cursor.scroll_offset as usize);
 
         let scrollbar_area = Rect {
@@ -266,23 +353,39 @@ impl Component for InputBox {
         use ratatui::style::{Modifier, Style};
 
         self.cursor.last_content_width = area.width;
-        self.cursor.update_scroll_offset(&self.buffer, area.width);
+        let max_lines = self.max_visible_lines();
+        self.cursor
+            .update_scroll_offset(&self.buffer, area.width, max_lines);
+        self.cursor.update_h_offset(&self.buffer, area.width);
 
         let title = format!("Input (Reasoning: {})", self.effort.label());
+        let title_width = title.chars().count();
         let visible_text = self.get_visible_text(area.width);
 
-        let mut style = Style::default().fg(ratatui::style::Color::Green);
+        let mut style = Style::default().fg(self.accent);
         if self.dimmed {
             style = style.add_modifier(Modifier::DIM);
         }
 
-        let block = Block::bordered()
+        let mut block = Block::bordered()
             .border_type(ratatui::widgets::BorderType::Rounded)
             .border_style(style)
             .title(title)
             .title_style(style);
+        if self.show_counter
+            && let Some(counter) = self.counter_text(area.width, title_width)
+        {
+            block = block.title(Line::from(format!(" {counter} ")).right_aligned());
+        }
+        if self.image_attached {
+            block = block.title_bottom(" [image attached] ");
+        }
 
-        let input = Paragraph::new(visible_text).block(block).style(style);
+        // Vertical scrolling is applied by slicing lines in get_visible_text
+        let input = Paragraph::new(visible_text)
+            .block(block)
+            .style(style)
+            .scroll((0, self.cursor.h_offset));
 
         frame.render_widget(input, area);
         self.render_scrollbar(frame, area);
@@ -306,6 +409,12 @@ impl EventHandler for InputBox {
                 self.cursor.pos += c.len_utf8();
                 Some(InputEvent::ContentChanged)
             }
+            TuiEvent::InsertNewline => {
+                self.history.reset_navigation();
+                self.buffer.insert(self.cursor.pos, '\n');
+                self.cursor.pos += 1;
+                Some(InputEvent::ContentChanged)
+            }
             TuiEvent::Paste(text) => {
                 self.history.reset_navigation();
                 // Strip \r from Windows-style line endings before inserting
@@ -376,7 +485,11 @@ impl EventHandler for InputBox {
                     let text = std::mem::take(&mut self.buffer);
                     self.cursor.reset();
                     self.history.push(text.clone());
-                    Some(InputEvent::Submit(text))
+                    if command::is_command(&text) {
+                        Some(InputEvent::Command(text))
+                    } else {
+                        Some(InputEvent::Submit(command::message_text(text)))
+                    }
                 } else {
                     None
                 }
@@ -488,6 +601,20 @@ impl EventHandler for InputBox {
                     None
                 }
             }
+            TuiEvent::AbortEdit => {
+                // Leave history without submitting, back to what was typed
+                let draft = self.history.abort()?;
+                self.buffer = draft;
+                self.cursor.pos = self.buffer.len();
+                Some(InputEvent::ContentChanged)
+            }
+            TuiEvent::WrapInCodeFence => {
+                let wrapped = fence::fenced(&self.buffer)?;
+                self.history.reset_navigation();
+                self.buffer = wrapped;
+                self.cursor.pos = self.buffer.len();
+                Some(InputEvent::ContentChanged)
+            }
             TuiEvent::CycleEffort => Some(InputEvent::CycleEffort),
             _ => None,
         }
@@ -541,6 +668,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_insert_newline_then_submit_multiline() {
+        let mut input = InputBox::new(Effort::Low);
+        input.buffer = "first".to_string();
+        input.cursor.pos = 5;
+        let single_line_height = input.calculate_height(40);
+
+        let res = input.handle_event(&TuiEvent::InsertNewline);
+        assert_eq!(res, Some(InputEvent::ContentChanged));
+        assert_eq!(input.buffer, "first\n");
+        assert_eq!(input.cursor.pos, 6);
+        assert!(input.calculate_height(40) > single_line_height);
+
+        for c in "second".chars() {
+            input.handle_event(&TuiEvent::InputChar(c));
+        }
+        let res = input.handle_event(&TuiEvent::Submit);
+        assert_eq!(res, Some(InputEvent::Submit("first\nsecond".to_string())));
+        assert!(input.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_submit_slash_prefix_emits_command() {
+        let mut input = InputBox::new(Effort::Low);
+        input.buffer = "/effort high".to_string();
+
+        let res = input.handle_event(&TuiEvent::Submit);
+        assert_eq!(res, Some(InputEvent::Command("/effort high".to_string())));
+        assert!(input.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_submit_double_slash_sends_message() {
+        let mut input = InputBox::new(Effort::Low);
+        input.buffer = "//usr/bin is missing".to_string();
+
+        let res = input.handle_event(&TuiEvent::Submit);
+        assert_eq!(
+            res,
+            Some(InputEvent::Submit("/usr/bin is missing".to_string()))
+        );
+    }
+
     #[test]
     fn test_cycle_effort_event() {
         let mut input = InputBox::new(Effort::Low);
@@ -691,6 +861,32 @@ mod tests {
         assert_eq!(input.cursor.pos, 11);
     }
 
+    #[test]
+    fn test_wrap_in_code_fence_surrounds_buffer() {
+        let mut input = InputBox::new(Effort::Low);
+        input.buffer = "fn main() {\n    println!(\"hi\");\n}\n".to_string();
+        input.cursor.pos = 3;
+
+        let result = input.handle_event(&TuiEvent::WrapInCodeFence);
+        assert_eq!(result, Some(InputEvent::ContentChanged));
+        assert_eq!(
+            input.buffer,
+            "```rust\nfn main() {\n    println!(\"hi\");\n}\n```"
+        );
+        assert_eq!(input.cursor.pos, input.buffer.len());
+
+        // Already fenced: a second press changes nothing
+        assert_eq!(input.handle_event(&TuiEvent::WrapInCodeFence), None);
+    }
+
+    #[test]
+    fn test_wrap_in_code_fence_without_language_guess() {
+        let mut input = InputBox::new(Effort::Low);
+        input.buffer = "line one\nline two".to_string();
+        input.handle_event(&TuiEvent::WrapInCodeFence);
+        assert_eq!(input.buffer, "```\nline one\nline two\n```");
+    }
+
     #[test]
     fn test_yank_empty_kill_buffer() {
         let mut input = InputBox::new(Effort::Low);
@@ -838,6 +1034,41 @@ mod tests {
         assert!(!input.history.is_navigating());
     }
 
+    #[test]
+    fn test_abort_edit_restores_draft() {
+        let mut input = InputBox::new(Effort::Low);
+        input.buffer = "first message".to_string();
+        input.handle_event(&TuiEvent::Submit);
+        input.buffer = "second message".to_string();
+        input.handle_event(&TuiEvent::Submit);
+        input.handle_event(&TuiEvent::Paste("half a thought".to_string()));
+
+        input.handle_event(&TuiEvent::CursorUp);
+        input.handle_event(&TuiEvent::CursorUp);
+        assert_eq!(input.buffer, "first message");
+
+        let result = input.handle_event(&TuiEvent::AbortEdit);
+        assert_eq!(result, Some(InputEvent::ContentChanged));
+        assert_eq!(input.buffer, "half a thought");
+        assert_eq!(input.cursor.pos, input.buffer.len());
+        assert!(!input.history.is_navigating());
+        // Up starts from the newest entry again
+        input.handle_event(&TuiEvent::CursorUp);
+        assert_eq!(input.buffer, "second message");
+    }
+
+    #[test]
+    fn test_abort_edit_inert_when_not_navigating() {
+        let mut input = InputBox::new(Effort::Low);
+        input.buffer = "sent".to_string();
+        input.handle_event(&TuiEvent::Submit);
+        input.handle_event(&TuiEvent::Paste("typing".to_string()));
+
+        assert_eq!(input.handle_event(&TuiEvent::AbortEdit), None);
+        assert_eq!(input.buffer, "typing");
+        assert_eq!(input.cursor.pos, 6);
+    }
+
     // -- Paste improvements ---------------------------------------------------
 
     #[test]
@@ -888,4 +1119,218 @@ mod tests {
 
         assert!(text.contains("Reasoning: High"));
     }
+
+    #[test]
+    fn test_render_shows_line_column_counter() {
+        let backend = TestBackend::new(60, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let mut input = InputBox::new(Effort::High);
+        input.handle_event(&TuiEvent::Paste("first line\nsé".to_string()));
+
+        terminal
+            .draw(|f| {
+                input.render(f, f.area());
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text = buffer
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>();
+
+        // Cursor after "sé" on line 2; 13 chars although "é" is two bytes
+        assert!(text.contains("L2:C3 · 13 chars"));
+    }
+
+    #[test]
+    fn test_counter_shortens_to_keep_the_title() {
+        let render = |width| {
+            let mut terminal = Terminal::new(TestBackend::new(width, 3)).unwrap();
+            let mut input = InputBox::new(Effort::High);
+            terminal.draw(|f| input.render(f, f.area())).unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|c| c.symbol())
+                .collect::<String>()
+        };
+
+        let wide = render(60);
+        assert!(wide.contains("Input (Reasoning: High)"));
+        assert!(wide.contains("L1:C1 · 0 chars"), "got: {wide}");
+
+        let narrow = render(40);
+        assert!(narrow.contains("Input (Reasoning: High)"), "got: {narrow}");
+        assert!(narrow.contains("L1:C1") && !narrow.contains("chars"));
+
+        let tiny = render(28);
+        assert!(tiny.contains("Input (Reasoning: High)"), "got: {tiny}");
+        assert!(!tiny.contains("L1:C1"));
+    }
+
+    #[test]
+    fn test_scrollbar_thumb_shrinks_with_longer_content() {
+        let thumb_cells = |lines: usize| {
+            let mut input = InputBox::new(Effort::High);
+            let text: Vec<String> = (1..=lines).map(|n| format!("line {n}")).collect();
+            input.handle_event(&TuiEvent::Paste(text.join("\n")));
+            let height = input.calculate_height(40);
+            let mut terminal = Terminal::new(TestBackend::new(40, height)).unwrap();
+            terminal.draw(|f| input.render(f, f.area())).unwrap();
+
+            // Rightmost column between the borders, scrolled to the end
+            let buffer = terminal.backend().buffer();
+            let column: Vec<&str> = (1..height - 1).map(|y| buffer[(39, y)].symbol()).collect();
+            column.iter().filter(|&&cell| cell == "█").count()
+        };
+
+        let short = thumb_cells(MAX_VISIBLE_LINES as usize + 2);
+        let long = thumb_cells(60);
+        assert!(long >= 1);
+        assert!(long < short, "thumb {long} for 60 lines vs {short}");
+    }
+
+    #[test]
+    fn test_render_shows_image_chip() {
+        let backend = TestBackend::new(60, 3);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let mut input = InputBox::new(Effort::High);
+        input.image_attached = true;
+
+        terminal
+            .draw(|f| {
+                input.render(f, f.area());
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text = buffer
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>();
+
+        assert!(text.contains("[image attached]"));
+    }
+
+    #[test]
+    fn test_render_hides_counter_when_disabled() {
+        let backend = TestBackend::new(60, 3);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let mut input = InputBox::new(Effort::High);
+        input.show_counter = false;
+
+        terminal
+            .draw(|f| {
+                input.render(f, f.area());
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text = buffer
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>();
+
+        assert!(!text.contains("chars"));
+    }
+
+    /// Render once and return the cursor's screen position.
+    fn render_cursor(input: &mut InputBox, width: u16, height: u16) -> (u16, u16) {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| input.render(f, f.area())).unwrap();
+        input
+            .cursor
+            .screen_pos(&input.buffer, Rect::new(0, 0, width, height))
+    }
+
+    #[test]
+    fn test_cursor_stays_in_view_past_width() {
+        // textwrap never breaks a run of spaces, so this stays one display line
+        let mut input = InputBox::new(Effort::Low);
+        input.handle_event(&TuiEvent::InputChar('a'));
+        for _ in 0..60 {
+            input.handle_event(&TuiEvent::InputChar(' '));
+            let (x, y) = render_cursor(&mut input, 20, 3);
+            assert!(x < 19, "cursor x {} left the box", x);
+            assert_eq!(y, 1);
+        }
+        assert!(input.cursor.h_offset > 0);
+
+        // Moving back to the start scrolls back
+        input.handle_event(&TuiEvent::CursorHome);
+        assert_eq!(render_cursor(&mut input, 20, 3), (1, 1));
+        assert_eq!(input.cursor.h_offset, 0);
+    }
+
+    #[test]
+    fn test_multiline_paste_scrolls_to_cursor() {
+        let mut input = InputBox::new(Effort::Low);
+        let text: Vec<String> = (1..=30).map(|n| format!("line {n}")).collect();
+        input.handle_event(&TuiEvent::Paste(text.join("\n")));
+
+        // Height stays capped; the view follows the cursor to the last line
+        assert_eq!(
+            input.calculate_height(40),
+            MAX_VISIBLE_LINES + VERTICAL_OVERHEAD
+        );
+        let height = MAX_VISIBLE_LINES + VERTICAL_OVERHEAD;
+        let (x, y) = render_cursor(&mut input, 40, height);
+        assert_eq!(input.cursor.scroll_offset, 30 - MAX_VISIBLE_LINES);
+        assert_eq!((x, y), (8, MAX_VISIBLE_LINES));
+        assert!(input.get_visible_text(40).ends_with("line 30"));
+    }
+
+    #[test]
+    fn test_grow_raises_height_cap_and_shrink_restores_it() {
+        let mut input = InputBox::new(Effort::Low);
+        let text: Vec<String> = (1..=30).map(|n| format!("line {n}")).collect();
+        input.handle_event(&TuiEvent::Paste(text.join("\n")));
+        let default_height = MAX_VISIBLE_LINES + VERTICAL_OVERHEAD;
+        assert_eq!(input.calculate_height(40), default_height);
+
+        input.max_lines_override = InputBox::resized_max_lines(input.max_lines_override, true, 40);
+        assert_eq!(
+            input.max_lines_override,
+            Some(MAX_VISIBLE_LINES + RESIZE_STEP)
+        );
+        assert_eq!(input.calculate_height(40), default_height + RESIZE_STEP);
+
+        input.max_lines_override = InputBox::resized_max_lines(input.max_lines_override, false, 40);
+        assert_eq!(input.max_lines_override, None);
+        assert_eq!(input.calculate_height(40), default_height);
+    }
+
+    #[test]
+    fn test_resize_clamped_to_terminal_height() {
+        let mut lines = None;
+        for _ in 0..20 {
+            lines = InputBox::resized_max_lines(lines, true, 30);
+        }
+        // Two thirds of 30 rows, less the borders
+        assert_eq!(lines, Some(18));
+
+        // A terminal too short to grow keeps the default
+        assert_eq!(InputBox::resized_max_lines(None, true, 6), None);
+        assert_eq!(InputBox::resized_max_lines(None, false, 40), None);
+    }
+
+    #[test]
+    fn test_wrapped_text_needs_no_horizontal_scroll() {
+        let mut input = InputBox::new(Effort::Low);
+        for _ in 0..60 {
+            input.handle_event(&TuiEvent::InputChar('x'));
+            let (x, _) = render_cursor(&mut input, 20, 8);
+            assert!(x < 19, "cursor x {} left the box", x);
+        }
+        assert_eq!(input.cursor.h_offset, 0);
+    }
 }
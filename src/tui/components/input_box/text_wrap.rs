@@ -6,7 +6,8 @@
 pub(super) const HORIZONTAL_OVERHEAD: u16 = 4;
 /// Top + bottom borders consumed vertically
 pub(super) const VERTICAL_OVERHEAD: u16 = 2;
-/// Maximum visible content lines before internal scrolling kicks in
+/// Maximum visible content lines before internal scrolling kicks in, unless
+/// the user has grown the input box
 pub(super) const MAX_VISIBLE_LINES: u16 = 5;
 /// Offset from area edge to content (border width)
 pub(super) const BORDER_OFFSET: u16 = 1;
@@ -24,6 +25,12 @@ pub(super) fn inner_width(content_width: u16) -> u16 {
     content_width.saturating_sub(HORIZONTAL_OVERHEAD)
 }
 
+/// Columns the bordered block leaves for text (the padding columns included).
+/// The cursor must stay left of this to remain visible.
+pub(super) fn visible_columns(content_width: u16) -> u16 {
+    content_width.saturating_sub(2 * BORDER_OFFSET)
+}
+
 /// Count wrapped lines for the given text, accounting for trailing newlines
 /// that textwrap may not represent as empty lines.
 pub(super) fn wrap_line_count(text: &str, width: u16) -> u16 {
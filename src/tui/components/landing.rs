@@ -1,6 +1,8 @@
 //! # Landing Page Component
 //!
-//! Displays an animated ASCII art sequence when the conversation is empty.
+//! Displays an animated ASCII art sequence when the conversation is empty,
+//! with a greeting and a tip beneath it. Both come from `[general]
+//! landing_message` and `landing_tips` when set.
 //!
 
 use crate::tui::component::Component;
@@ -11,17 +13,49 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style};
 use ratatui::widgets::Paragraph;
 
-pub struct LandingPage {
+/// Greeting shown when `landing_message` is unset.
+const DEFAULT_MESSAGE: &str = "Hey! Listen!";
+
+/// Tips picked from when `landing_tips` is unset.
+const DEFAULT_TIPS: &[&str] = &[
+    "Press ? to see every keybinding",
+    "Ctrl+O browses and reopens saved sessions",
+    "Ctrl+P switches models mid-conversation",
+    "Ctrl+T opens another conversation in a new tab",
+    "Alt+Up enters cursor mode to select, copy or fork messages",
+    "Drop an image file path into the input to attach it",
+    "/export writes the conversation to a markdown file",
+];
+
+pub struct LandingPage<'a> {
     frame_index: usize,
+    message: &'a str,
+    tip: Option<&'a str>,
 }
 
-impl LandingPage {
-    pub fn new(frame_index: usize) -> Self {
-        Self { frame_index }
+impl<'a> LandingPage<'a> {
+    /// `message` and `tips` come from the config; None uses the built-in
+    /// ones and an empty `tips` list shows no tip. `seed` picks the tip.
+    pub fn new(
+        frame_index: usize,
+        message: Option<&'a str>,
+        tips: Option<&'a [String]>,
+        seed: usize,
+    ) -> Self {
+        let tip = match tips {
+            Some([]) => None,
+            Some(tips) => Some(tips[seed % tips.len()].as_str()),
+            None => Some(DEFAULT_TIPS[seed % DEFAULT_TIPS.len()]),
+        };
+        Self {
+            frame_index,
+            message: message.unwrap_or(DEFAULT_MESSAGE),
+            tip,
+        }
     }
 }
 
-impl Component for LandingPage {
+impl Component for LandingPage<'_> {
     fn render(&mut self, frame: &mut Frame, area: Rect) {
         use ratatui::layout::{Constraint, Flex, Layout};
         use ratatui::style::Modifier;
@@ -32,7 +66,7 @@ impl Component for LandingPage {
         // Spacer is handled by layout splitting now
 
         text_lines.push(Line::from(Span::styled(
-            "Hey! Listen!",
+            self.message,
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
@@ -49,6 +83,16 @@ impl Component for LandingPage {
             Style::default().fg(Color::DarkGray),
         )));
 
+        if let Some(tip) = self.tip {
+            text_lines.push(Line::default());
+            text_lines.push(Line::from(Span::styled(
+                format!("Tip: {tip}"),
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            )));
+        }
+
         // --- Calculate Layout ---
         // We want the Canvas to take up natural height of the fairy
         // And the text to be below it.
@@ -76,3 +120,50 @@ impl Component for LandingPage {
         frame.render_widget(paragraph, vertical_layout[2]);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn render(page: &mut LandingPage) -> String {
+        let backend = TestBackend::new(80, 40);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| page.render(f, f.area())).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>()
+    }
+
+    #[test]
+    fn test_configured_message_and_tip_rendered() {
+        let tips = vec!["Drink some water".to_string()];
+        let text = render(&mut LandingPage::new(
+            0,
+            Some("Welcome back, friend"),
+            Some(&tips),
+            7,
+        ));
+        assert!(text.contains("Welcome back, friend"));
+        assert!(text.contains("Tip: Drink some water"));
+        assert!(!text.contains(DEFAULT_MESSAGE));
+    }
+
+    #[test]
+    fn test_defaults_when_unset() {
+        let text = render(&mut LandingPage::new(0, None, None, 3));
+        assert!(text.contains(DEFAULT_MESSAGE));
+        assert!(text.contains(&format!("Tip: {}", DEFAULT_TIPS[3])));
+    }
+
+    #[test]
+    fn test_empty_tip_list_hides_tip() {
+        let text = render(&mut LandingPage::new(0, None, Some(&[]), 0));
+        assert!(!text.contains("Tip:"));
+    }
+}
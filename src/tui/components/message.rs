@@ -1,11 +1,13 @@
 use ratatui::Frame;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::Text;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Text};
 use ratatui::widgets::{Block, Padding, Paragraph, Widget, Wrap};
 
-use crate::inference::{ContextSegment, Source, UsageStats};
+use crate::inference::{ContextSegment, Pricing, Source, UsageStats};
 use crate::tui::component::Component;
+use crate::tui::markdown::RenderOptions;
+use crate::tui::theme::Theme;
 
 /// Horizontal padding (per side) between the border and text content.
 const CONTENT_PAD_H: u16 = 1;
@@ -13,12 +15,63 @@ const CONTENT_PAD_H: u16 = 1;
 const HORIZONTAL_OVERHEAD: u16 = 2 + CONTENT_PAD_H * 2;
 /// Total vertical space consumed by borders (1 top + 1 bottom).
 const VERTICAL_OVERHEAD: u16 = 2;
+/// Compact messages have no border, only the horizontal padding.
+const COMPACT_HORIZONTAL_OVERHEAD: u16 = CONTENT_PAD_H * 2;
+/// Compact messages spend one row on the role line above the text.
+const COMPACT_VERTICAL_OVERHEAD: u16 = 1;
 
 /// Pulse intensity threshold above which the border transitions from normal to BOLD.
 const PULSE_BOLD_THRESHOLD: f32 = 0.6;
 /// Pulse intensity threshold above which the border transitions from DIM to normal.
 const PULSE_NORMAL_THRESHOLD: f32 = 0.2;
 
+/// Shown after the role of a pinned message.
+const PIN_MARKER: &str = "★";
+
+/// How much chrome surrounds each message (app-wide toggle).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Density {
+    /// A rounded border with the role in its top edge.
+    #[default]
+    Comfortable,
+    /// A single role line above the text and no border, for small terminals.
+    Compact,
+}
+
+impl Density {
+    pub fn toggled(self) -> Self {
+        match self {
+            Density::Comfortable => Density::Compact,
+            Density::Compact => Density::Comfortable,
+        }
+    }
+
+    fn horizontal_overhead(self) -> u16 {
+        match self {
+            Density::Comfortable => HORIZONTAL_OVERHEAD,
+            Density::Compact => COMPACT_HORIZONTAL_OVERHEAD,
+        }
+    }
+
+    fn vertical_overhead(self) -> u16 {
+        match self {
+            Density::Comfortable => VERTICAL_OVERHEAD,
+            Density::Compact => COMPACT_VERTICAL_OVERHEAD,
+        }
+    }
+}
+
+/// Display settings shared by every message in a list.
+#[derive(Clone, Copy)]
+pub struct MessageOptions<'a> {
+    /// Color palette for source styling and markdown
+    pub theme: &'a Theme,
+    /// Wrap long lines; when false, lines are clipped and scrolled sideways
+    pub wrap: bool,
+    /// Columns scrolled to the right (ignored while wrapping)
+    pub scroll_x: u16,
+}
+
 /// A stateless component that renders a single chat message with source-based styling.
 ///
 /// # Design
@@ -29,14 +82,27 @@ const PULSE_NORMAL_THRESHOLD: f32 = 0.2;
 ///
 /// # Styling
 ///
-/// Each message source gets distinct visual treatment:
-/// - **User** (cyan): Messages from the human
-/// - **Model** (green): Responses from the AI
+/// Each message source gets distinct visual treatment from the [`Theme`]
+/// (defaults in parentheses):
+/// - **User** (green): Messages from the human
+/// - **Model** (blue): Responses from the AI
 /// - **Directive** (yellow): System instructions
 /// - **Thinking** (dark gray, italic): Model reasoning traces
 ///
 /// Selected messages are rendered at normal brightness; unselected messages are dimmed.
 ///
+/// # Density
+///
+/// [`Density::Comfortable`] draws each message in a rounded border titled with
+/// its role. [`Density::Compact`] drops the border for a role line above the
+/// text, saving a row and both border columns per message.
+///
+/// # Wrapping
+///
+/// With `wrap` on, long lines wrap at the message width. With it off, each
+/// source line stays on one row and `scroll_x` shifts the text sideways, which
+/// suits preformatted output such as logs.
+///
 /// # Height Calculation
 ///
 /// The [`calculate_height`](Self::calculate_height) method predicts rendered height
@@ -53,6 +119,21 @@ pub struct Message<'a> {
     pub pulse_intensity: f32,
     /// Optional usage stats to display on the bottom border
     pub stats: Option<&'a UsageStats>,
+    /// Active model's token rates, for the cost estimate in the stats
+    pub pricing: Option<&'a Pricing>,
+    /// Color palette for source styling and markdown
+    pub theme: &'a Theme,
+    /// Wrap long lines; when false, lines are clipped and scrolled sideways
+    pub wrap: bool,
+    /// Columns scrolled to the right (ignored while wrapping)
+    pub scroll_x: u16,
+    /// Inclusive range of rendered text lines shown as selected for copying.
+    /// The end may run past the last line.
+    pub highlight: Option<(usize, usize)>,
+    /// Bordered or compact layout
+    pub density: Density,
+    /// Pinned by the user; marked next to the role
+    pub pinned: bool,
 }
 
 impl<'a> Message<'a> {
@@ -64,66 +145,179 @@ impl<'a> Message<'a> {
         is_selected: bool,
         pulse_intensity: f32,
         stats: Option<&'a UsageStats>,
+        pricing: Option<&'a Pricing>,
+        options: MessageOptions<'a>,
     ) -> Self {
+        let MessageOptions {
+            theme,
+            wrap,
+            scroll_x,
+        } = options;
         Self {
             segment,
             is_selected,
             pulse_intensity,
             stats,
+            pricing,
+            theme,
+            wrap,
+            scroll_x,
+            highlight: None,
+            density: Density::default(),
+            pinned: false,
         }
     }
 
+    /// Highlight rendered text lines `first..=last` as a copy selection.
+    pub fn with_highlight(mut self, lines: Option<(usize, usize)>) -> Self {
+        self.highlight = lines;
+        self
+    }
+
+    pub fn with_density(mut self, density: Density) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub fn with_pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// The message's rendered text at `width` (the full message width,
+    /// borders included), one `Line` per row before paragraph wrapping.
+    /// Selection line numbers index into this.
+    pub fn rendered_text<'t>(
+        segment: &'t ContextSegment,
+        width: u16,
+        theme: &Theme,
+        wrap: bool,
+        density: Density,
+    ) -> Text<'t> {
+        let content_width = width.saturating_sub(density.horizontal_overhead());
+        build_text(
+            segment.content.trim(),
+            &segment.source,
+            theme,
+            content_width,
+            wrap,
+        )
+    }
+
     /// Calculate the height required for this message given a width.
     ///
     /// Uses `Paragraph::line_count` to predict height from the same styled
     /// content we'd actually render — no separate wrapping library to keep in sync.
-    pub fn calculate_height(segment: &ContextSegment, width: u16) -> u16 {
-        let content_width = width.saturating_sub(HORIZONTAL_OVERHEAD);
+    /// Without `wrap`, this is simply the number of rendered lines.
+    pub fn calculate_height(
+        segment: &ContextSegment,
+        width: u16,
+        theme: &Theme,
+        wrap: bool,
+        density: Density,
+    ) -> u16 {
+        let content_width = width.saturating_sub(density.horizontal_overhead());
         if content_width == 0 {
             return 1;
         }
 
         let content = segment.content.trim();
         if content.is_empty() {
-            return VERTICAL_OVERHEAD;
+            return density.vertical_overhead();
         }
 
-        let paragraph = build_paragraph(content, &segment.source);
+        let paragraph = build_paragraph(content, &segment.source, theme, content_width, wrap, None);
         let lines = paragraph.line_count(content_width) as u16;
-        lines.max(1) + VERTICAL_OVERHEAD
+        lines.max(1) + density.vertical_overhead()
     }
 }
 
-/// Build the paragraph for a message — markdown for User/Model, plain for others.
-fn build_paragraph<'a>(content: &'a str, source: &Source) -> Paragraph<'a> {
+/// Build the text of a message — markdown for User/Model, plain for others.
+/// `width` is the inner content width.
+fn build_text<'a>(
+    content: &'a str,
+    source: &Source,
+    theme: &Theme,
+    width: u16,
+    wrap: bool,
+) -> Text<'a> {
     match source {
         Source::User | Source::Model => {
             let base_fg = match source {
-                Source::User => Color::Green,
-                Source::Model => Color::Blue,
+                Source::User => theme.user,
+                Source::Model => theme.model,
                 _ => unreachable!(),
             };
-            let text = crate::tui::markdown::render(content, base_fg);
-            // trim: false to preserve indentation in code blocks
-            Paragraph::new(text).wrap(Wrap { trim: false })
+            let options = RenderOptions { width, wrap };
+            crate::tui::markdown::render(content, base_fg, theme, options)
         }
-        _ => {
-            let style = source_style(source);
-            Paragraph::new(Text::raw(content))
-                .style(style)
-                .wrap(Wrap { trim: true })
+        _ => Text::raw(content),
+    }
+}
+
+/// Build the paragraph for a message, with `highlight` lines reversed.
+/// `width` is the inner content width. Without `wrap`, each line keeps to a single row.
+fn build_paragraph<'a>(
+    content: &'a str,
+    source: &Source,
+    theme: &Theme,
+    width: u16,
+    wrap: bool,
+    highlight: Option<(usize, usize)>,
+) -> Paragraph<'a> {
+    let mut text = build_text(content, source, theme, width, wrap);
+    if let Some((first, last)) = highlight {
+        for line in text
+            .lines
+            .iter_mut()
+            .take(last.saturating_add(1))
+            .skip(first)
+        {
+            line.style = line.style.add_modifier(Modifier::REVERSED);
         }
     }
+    let (paragraph, trim) = match source {
+        // trim: false to preserve indentation in code blocks
+        Source::User | Source::Model => (Paragraph::new(text), false),
+        _ => (
+            Paragraph::new(text).style(source_style(source, theme)),
+            true,
+        ),
+    };
+    if wrap {
+        paragraph.wrap(Wrap { trim })
+    } else {
+        paragraph
+    }
+}
+
+/// Plain text of lines `first..=last` of `text`, one per row. `last` is
+/// clamped to the final line; trailing padding is dropped.
+pub fn line_range_text(text: &Text, first: usize, last: usize) -> String {
+    text.lines
+        .iter()
+        .take(last.saturating_add(1))
+        .skip(first)
+        .map(|line| {
+            let row: String = line
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Get the base style for a message source.
-fn source_style(source: &Source) -> Style {
+fn source_style(source: &Source, theme: &Theme) -> Style {
     match source {
-        Source::Directive => Style::default().fg(Color::Yellow),
-        Source::User => Style::default().fg(Color::Green),
-        Source::Model => Style::default().fg(Color::Blue),
+        Source::Directive => Style::default().fg(theme.system),
+        Source::User => Style::default().fg(theme.user),
+        Source::Model => Style::default().fg(theme.model),
         Source::Thinking | Source::Status => Style::default()
-            .fg(Color::DarkGray)
+            .fg(theme.thinking)
             .add_modifier(Modifier::ITALIC),
     }
 }
@@ -138,8 +332,13 @@ impl<'a> Widget for Message<'a> {
             Source::Thinking => "thought",
             Source::Status => "navi",
         };
+        let role = if self.pinned {
+            format!("{role} {PIN_MARKER}")
+        } else {
+            role.to_string()
+        };
 
-        let style = source_style(&self.segment.source);
+        let style = source_style(&self.segment.source, self.theme);
 
         // Selected = source color at normal brightness (lightened from default dim)
         let mut border_style = if self.is_selected {
@@ -159,32 +358,70 @@ impl<'a> Widget for Message<'a> {
         }
 
         let content = self.segment.content.trim();
-
-        // Render the block into `area`, then the paragraph into the inner rect.
-        let mut block = Block::bordered()
-            .title(role)
-            .border_type(ratatui::widgets::BorderType::Rounded)
-            .border_style(border_style)
-            .title_style(border_style)
-            .padding(Padding::horizontal(CONTENT_PAD_H));
-
-        // Add stats on the bottom-right border, matching the border color
-        if let Some(stats) = self.stats {
-            let summary = stats.display_summary();
-            if summary != "Response complete." {
-                block = block.title_bottom(
-                    ratatui::text::Line::from(format!(" {summary} "))
+        let summary = self
+            .stats
+            .map(|stats| stats.display_summary(self.pricing))
+            .filter(|summary| summary != "Response complete.");
+
+        // Render the chrome into `area`, then the paragraph into the inner rect.
+        let inner_area = match self.density {
+            Density::Comfortable => {
+                let mut block = Block::bordered()
+                    .title(role.as_str())
+                    .border_type(ratatui::widgets::BorderType::Rounded)
+                    .border_style(border_style)
+                    .title_style(border_style)
+                    .padding(Padding::horizontal(CONTENT_PAD_H));
+
+                // Add stats on the bottom-right border, matching the border color
+                if let Some(summary) = summary {
+                    block = block.title_bottom(
+                        Line::from(format!(" {summary} "))
+                            .right_aligned()
+                            .style(border_style),
+                    );
+                }
+
+                let inner_area = block.inner(area);
+                block.render(area, buf);
+                inner_area
+            }
+            Density::Compact => {
+                // The top padding row holds the role, with stats on its right
+                let block = Block::new().padding(Padding::new(
+                    CONTENT_PAD_H,
+                    CONTENT_PAD_H,
+                    COMPACT_VERTICAL_OVERHEAD,
+                    0,
+                ));
+                let role_row = Rect {
+                    height: area.height.min(COMPACT_VERTICAL_OVERHEAD),
+                    ..area
+                };
+                if let Some(summary) = summary {
+                    Line::from(format!("{summary} "))
                         .right_aligned()
-                        .style(border_style),
-                );
+                        .style(border_style)
+                        .render(role_row, buf);
+                }
+                Line::from(format!("▌ {role} "))
+                    .style(border_style)
+                    .render(role_row, buf);
+                block.inner(area)
             }
-        }
-
-        // Get the inner area of the block where the paragraph will be rendered
-        let inner_area = block.inner(area);
-        block.render(area, buf);
+        };
 
-        let paragraph = build_paragraph(content, &self.segment.source);
+        let mut paragraph = build_paragraph(
+            content,
+            &self.segment.source,
+            self.theme,
+            inner_area.width,
+            self.wrap,
+            self.highlight,
+        );
+        if !self.wrap {
+            paragraph = paragraph.scroll((0, self.scroll_x));
+        }
         paragraph.render(inner_area, buf);
     }
 }
@@ -204,12 +441,23 @@ impl<'a> Component for Message<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ratatui::style::Color;
 
     /// Helper to create a segment with given source and content
     fn make_segment(source: Source, content: &str) -> ContextSegment {
         ContextSegment {
             source,
             content: content.to_string(),
+            timestamp: None,
+        }
+    }
+
+    /// Helper to create display options with the given settings
+    fn options(theme: &Theme, wrap: bool, scroll_x: u16) -> MessageOptions<'_> {
+        MessageOptions {
+            theme,
+            wrap,
+            scroll_x,
         }
     }
 
@@ -220,32 +468,50 @@ mod tests {
     #[test]
     fn calculate_height_empty_content_returns_border_height() {
         let segment = make_segment(Source::User, "");
-        assert_eq!(Message::calculate_height(&segment, 80), VERTICAL_OVERHEAD);
+        assert_eq!(
+            Message::calculate_height(&segment, 80, &Theme::default(), true, Density::Comfortable),
+            VERTICAL_OVERHEAD
+        );
     }
 
     #[test]
     fn calculate_height_whitespace_only_treated_as_empty() {
         let segment = make_segment(Source::User, "   \n\t  ");
-        assert_eq!(Message::calculate_height(&segment, 80), VERTICAL_OVERHEAD);
+        assert_eq!(
+            Message::calculate_height(&segment, 80, &Theme::default(), true, Density::Comfortable),
+            VERTICAL_OVERHEAD
+        );
     }
 
     #[test]
     fn calculate_height_zero_width_returns_minimum() {
         let segment = make_segment(Source::User, "Hello world");
-        assert_eq!(Message::calculate_height(&segment, 0), 1);
+        assert_eq!(
+            Message::calculate_height(&segment, 0, &Theme::default(), true, Density::Comfortable),
+            1
+        );
     }
 
     #[test]
     fn calculate_height_width_equals_overhead_returns_minimum() {
         let segment = make_segment(Source::User, "Hello world");
-        assert_eq!(Message::calculate_height(&segment, HORIZONTAL_OVERHEAD), 1);
+        assert_eq!(
+            Message::calculate_height(
+                &segment,
+                HORIZONTAL_OVERHEAD,
+                &Theme::default(),
+                true,
+                Density::Comfortable
+            ),
+            1
+        );
     }
 
     #[test]
     fn calculate_height_single_line_fits() {
         let segment = make_segment(Source::User, "Hello");
         assert_eq!(
-            Message::calculate_height(&segment, 80),
+            Message::calculate_height(&segment, 80, &Theme::default(), true, Density::Comfortable),
             1 + VERTICAL_OVERHEAD
         );
     }
@@ -255,7 +521,7 @@ mod tests {
         let segment = make_segment(Source::Thinking, "just thinking...");
         // Plain text, no markdown parsing — should be 1 line + overhead
         assert_eq!(
-            Message::calculate_height(&segment, 80),
+            Message::calculate_height(&segment, 80, &Theme::default(), true, Density::Comfortable),
             1 + VERTICAL_OVERHEAD
         );
     }
@@ -263,7 +529,8 @@ mod tests {
     #[test]
     fn calculate_height_markdown_heading() {
         let segment = make_segment(Source::Model, "# Big Title\n\nSome body text");
-        let height = Message::calculate_height(&segment, 80);
+        let height =
+            Message::calculate_height(&segment, 80, &Theme::default(), true, Density::Comfortable);
         // Heading + blank line + body = at least 3 content lines + overhead
         assert!(
             height >= 3 + VERTICAL_OVERHEAD,
@@ -276,7 +543,8 @@ mod tests {
     #[test]
     fn calculate_height_code_block_preserves_lines() {
         let segment = make_segment(Source::Model, "```\nline1\nline2\nline3\n```");
-        let height = Message::calculate_height(&segment, 80);
+        let height =
+            Message::calculate_height(&segment, 80, &Theme::default(), true, Density::Comfortable);
         // 3 code lines at minimum + overhead (fences may add more)
         assert!(
             height >= 3 + VERTICAL_OVERHEAD,
@@ -286,29 +554,198 @@ mod tests {
         );
     }
 
+    #[test]
+    fn calculate_height_wrap_modes_for_same_content() {
+        let long_line = "word ".repeat(30);
+        // Blank-line paragraphs so markdown and plain text break the same way
+        let content = format!("{long_line}\n\nsecond\n\nthird");
+        let width = 40;
+
+        for source in [Source::Thinking, Source::Model] {
+            let segment = make_segment(source, &content);
+            let wrapped = Message::calculate_height(
+                &segment,
+                width,
+                &Theme::default(),
+                true,
+                Density::Comfortable,
+            );
+            let unwrapped = Message::calculate_height(
+                &segment,
+                width,
+                &Theme::default(),
+                false,
+                Density::Comfortable,
+            );
+
+            // The long first line spans several rows only when wrapping
+            assert!(wrapped > 5 + VERTICAL_OVERHEAD, "wrapped: {wrapped}");
+            // Unwrapped: one row per newline-separated line
+            assert_eq!(unwrapped, 5 + VERTICAL_OVERHEAD, "{:?}", segment.source);
+        }
+    }
+
+    #[test]
+    fn unwrapped_render_scrolls_sideways() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let segment = make_segment(Source::Thinking, "0123456789abcdef");
+        let theme = Theme::default();
+        let mut terminal = Terminal::new(TestBackend::new(12, 3)).unwrap();
+        terminal
+            .draw(|f| {
+                let message =
+                    Message::new(&segment, false, 0.0, None, None, options(&theme, false, 4));
+                f.render_widget(message, f.area());
+            })
+            .unwrap();
+        let row: String = (0..12)
+            .map(|x| terminal.backend().buffer()[(x, 1)].symbol().to_string())
+            .collect();
+        // Border + padding, then text shifted four columns
+        assert_eq!(row, "│ 456789ab │");
+    }
+
+    #[test]
+    fn compact_height_smaller_than_comfortable() {
+        let theme = Theme::default();
+        for content in ["Hello", "one\ntwo\nthree", ""] {
+            let segment = make_segment(Source::Model, content);
+            let comfortable =
+                Message::calculate_height(&segment, 40, &theme, true, Density::Comfortable);
+            let compact = Message::calculate_height(&segment, 40, &theme, true, Density::Compact);
+            assert_eq!(compact, comfortable - 1, "content: {content:?}");
+        }
+    }
+
+    #[test]
+    fn compact_render_has_role_line_and_no_border() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let segment = make_segment(Source::User, "Hello there");
+        let theme = Theme::default();
+        let height = Message::calculate_height(&segment, 20, &theme, true, Density::Compact);
+        let mut terminal = Terminal::new(TestBackend::new(20, height)).unwrap();
+        terminal
+            .draw(|f| {
+                let message =
+                    Message::new(&segment, false, 0.0, None, None, options(&theme, true, 0))
+                        .with_density(Density::Compact);
+                f.render_widget(message, f.area());
+            })
+            .unwrap();
+        let row = |y: u16| -> String {
+            (0..20)
+                .map(|x| terminal.backend().buffer()[(x, y)].symbol().to_string())
+                .collect()
+        };
+        assert_eq!(height, 2);
+        assert_eq!(row(0).trim_end(), "▌ user");
+        assert_eq!(row(1).trim_end(), " Hello there");
+    }
+
+    #[test]
+    fn pinned_message_marks_its_title() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let segment = make_segment(Source::User, "Hello");
+        let theme = Theme::default();
+        let mut terminal = Terminal::new(TestBackend::new(20, 3)).unwrap();
+        terminal
+            .draw(|f| {
+                let message =
+                    Message::new(&segment, false, 0.0, None, None, options(&theme, true, 0))
+                        .with_pinned(true);
+                f.render_widget(message, f.area());
+            })
+            .unwrap();
+        let top: String = (0..20)
+            .map(|x| terminal.backend().buffer()[(x, 0)].symbol().to_string())
+            .collect();
+        assert!(top.starts_with("╭user ★"), "got: {top}");
+    }
+
     // ==========================================================================
     // Style tests
     // ==========================================================================
 
     #[test]
     fn style_user_is_green() {
-        assert_eq!(source_style(&Source::User).fg, Some(Color::Green));
+        assert_eq!(
+            source_style(&Source::User, &Theme::default()).fg,
+            Some(Color::Green)
+        );
     }
 
     #[test]
     fn style_model_is_blue() {
-        assert_eq!(source_style(&Source::Model).fg, Some(Color::Blue));
+        assert_eq!(
+            source_style(&Source::Model, &Theme::default()).fg,
+            Some(Color::Blue)
+        );
+    }
+
+    // ==========================================================================
+    // line_range_text tests
+    // ==========================================================================
+
+    #[test]
+    fn line_range_text_copies_code_block_slice() {
+        let segment = make_segment(
+            Source::Model,
+            "Set them up:\n\n```rust\nlet a = 1;\nlet b = 2;\n```\n\nDone.",
+        );
+        let text =
+            Message::rendered_text(&segment, 80, &Theme::default(), true, Density::Comfortable);
+        let start = text
+            .lines
+            .iter()
+            .position(|line| line.to_string().contains("let a"))
+            .unwrap();
+
+        let copied = line_range_text(&text, start, start + 1);
+        let rows: Vec<&str> = copied.lines().collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].ends_with("let a = 1;"), "got: {copied:?}");
+        assert!(rows[1].ends_with("let b = 2;"), "got: {copied:?}");
+        assert!(!copied.contains("Set them up"));
+    }
+
+    #[test]
+    fn line_range_text_clamps_past_last_line() {
+        let segment = make_segment(Source::Thinking, "one\ntwo\nthree");
+        let text =
+            Message::rendered_text(&segment, 80, &Theme::default(), true, Density::Comfortable);
+        assert_eq!(line_range_text(&text, 1, usize::MAX), "two\nthree");
+        assert_eq!(line_range_text(&text, 5, usize::MAX), "");
     }
 
     #[test]
     fn style_directive_is_yellow() {
-        assert_eq!(source_style(&Source::Directive).fg, Some(Color::Yellow));
+        assert_eq!(
+            source_style(&Source::Directive, &Theme::default()).fg,
+            Some(Color::Yellow)
+        );
     }
 
     #[test]
     fn style_thinking_is_dark_gray_italic() {
-        let style = source_style(&Source::Thinking);
+        let style = source_style(&Source::Thinking, &Theme::default());
         assert_eq!(style.fg, Some(Color::DarkGray));
         assert!(style.add_modifier.contains(Modifier::ITALIC));
     }
+
+    #[test]
+    fn style_uses_provided_theme() {
+        let theme = Theme {
+            user: Color::Magenta,
+            thinking: Color::Red,
+            ..Theme::default()
+        };
+        assert_eq!(source_style(&Source::User, &theme).fg, Some(Color::Magenta));
+        assert_eq!(source_style(&Source::Thinking, &theme).fg, Some(Color::Red));
+    }
 }
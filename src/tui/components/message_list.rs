@@ -18,18 +18,122 @@
 //! (including layout cache and scroll state) during the render pass, aligning
 //! with Ratatui's `StatefulWidget` pattern.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 
+use chrono::{DateTime, Datelike, Local, NaiveDate};
 use ratatui::Frame;
-use ratatui::layout::{Position, Rect, Size};
+use ratatui::layout::{Alignment, Position, Rect, Size};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
 use tui_scrollview::{ScrollView, ScrollViewState, ScrollbarVisibility};
 
-use crate::inference::{Context, ContextItem, Source, UsageStats};
+use crate::core::session::ScrollPosition;
+use crate::inference::{Context, ContextItem, ContextSegment, ImageAttachment, Source, TurnUsage};
 use crate::tui::component::{Component, EventHandler};
-use crate::tui::components::logo::Logo;
-use crate::tui::components::message::Message;
+use crate::tui::components::message::{Density, Message, MessageOptions};
+use crate::tui::components::spinner::Spinner;
 use crate::tui::components::tool_message::ToolGroup;
 use crate::tui::event::TuiEvent;
+use crate::tui::graphics::{GraphicsProtocol, IMAGE_ROWS, ImagePlacement};
+use crate::tui::theme::Theme;
+
+/// Columns moved per horizontal scroll step while word wrap is off.
+const H_SCROLL_STEP: u16 = 4;
+
+/// Widths whose measured heights are kept after a resize, so dragging the
+/// terminal back to a recent width doesn't re-measure the conversation.
+const WIDTH_MEMO_CAPACITY: usize = 4;
+
+/// A line of one message's rendered text: the context item index and the
+/// line within `Message::rendered_text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextPosition {
+    pub item: usize,
+    pub line: usize,
+}
+
+/// Lines selected for copying in cursor mode, from where the selection
+/// started (`anchor`) to where it has been extended (`cursor`). The range
+/// may span several messages, and either end may come first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSelection {
+    pub anchor: TextPosition,
+    pub cursor: TextPosition,
+}
+
+impl TextSelection {
+    /// A one-line selection at `position`.
+    pub fn at(position: TextPosition) -> Self {
+        Self {
+            anchor: position,
+            cursor: position,
+        }
+    }
+
+    /// The selection's ends in conversation order.
+    pub fn ordered(&self) -> (TextPosition, TextPosition) {
+        (self.anchor.min(self.cursor), self.anchor.max(self.cursor))
+    }
+
+    /// Inclusive line range of item `idx` inside the selection. Items in the
+    /// middle of the range are selected whole (`usize::MAX` as the end).
+    pub fn lines_of(&self, idx: usize) -> Option<(usize, usize)> {
+        let (start, end) = self.ordered();
+        if idx < start.item || idx > end.item {
+            return None;
+        }
+        let first = if idx == start.item { start.line } else { 0 };
+        let last = if idx == end.item {
+            end.line
+        } else {
+            usize::MAX
+        };
+        Some((first, last))
+    }
+}
+
+/// Identifies a context item regardless of its index. Tool items are keyed
+/// by call id. Other items are keyed by how many non-tool items come
+/// before them: the context only appends those, while tool results may be
+/// slotted in among the trailing results, so the count doesn't drift.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ItemKey {
+    Call(String),
+    Result(String),
+    Ordinal(usize),
+}
+
+impl ItemKey {
+    fn of(items: &[ContextItem], idx: usize) -> Option<Self> {
+        Some(match items.get(idx)? {
+            ContextItem::ToolCall(tc) => ItemKey::Call(tc.call_id.clone()),
+            ContextItem::ToolResult(tr) => ItemKey::Result(tr.call_id.clone()),
+            _ => ItemKey::Ordinal(items[..idx].iter().filter(|i| !is_tool_item(i)).count()),
+        })
+    }
+
+    fn find(&self, items: &[ContextItem]) -> Option<usize> {
+        match self {
+            ItemKey::Call(id) => items
+                .iter()
+                .position(|i| matches!(i, ContextItem::ToolCall(tc) if &tc.call_id == id)),
+            ItemKey::Result(id) => items
+                .iter()
+                .position(|i| matches!(i, ContextItem::ToolResult(tr) if &tr.call_id == id)),
+            ItemKey::Ordinal(n) => items
+                .iter()
+                .enumerate()
+                .filter(|(_, i)| !is_tool_item(i))
+                .nth(*n)
+                .map(|(idx, _)| idx),
+        }
+    }
+}
+
+fn is_tool_item(item: &ContextItem) -> bool {
+    matches!(item, ContextItem::ToolCall(_) | ContextItem::ToolResult(_))
+}
 
 /// Layout and scroll state for the message list.
 /// Must be persisted in the parent TuiState.
@@ -44,10 +148,24 @@ pub struct MessageListState {
     pub max_scroll_reached: u16,
     /// Currently selected message index (hover or keyboard navigation)
     pub selected_index: Option<usize>,
+    /// `selected_index` as of the last render and the item it pointed at,
+    /// so the selection follows that item if the context shifts under it
+    selection_anchor: Option<(usize, ItemKey)>,
+    /// Line selection for copying, while one is being made in cursor mode
+    pub text_selection: Option<TextSelection>,
     /// Tool call indices that are currently expanded (toggled by click or Space)
     pub expanded_indices: HashSet<usize>,
+    /// Pinned message indices, jumped between with n / N in cursor mode
+    pub pinned_indices: BTreeSet<usize>,
     /// Last known viewport height (for scroll clamping between frames)
     pub viewport_height: u16,
+    /// Total content height the user last saw while pinned to the bottom.
+    /// Content growth beyond this while scrolled up shows the "new lines" badge.
+    pub last_seen_height: u16,
+    /// Columns the message text is scrolled right while word wrap is off
+    pub scroll_x: u16,
+    /// Pictures fully in view as of the last render, drawn after the frame
+    pub image_placements: Vec<ImagePlacement>,
 }
 
 impl Default for MessageListState {
@@ -64,8 +182,86 @@ impl MessageListState {
             stick_to_bottom: true, // Start attached to bottom
             max_scroll_reached: 0,
             selected_index: None,
+            selection_anchor: None,
+            text_selection: None,
             expanded_indices: HashSet::new(),
+            pinned_indices: BTreeSet::new(),
             viewport_height: 0,
+            last_seen_height: 0,
+            scroll_x: 0,
+            image_placements: Vec::new(),
+        }
+    }
+
+    /// Fresh state at a saved scroll position. The offset is clamped to the
+    /// content on the first render, once the layout is known.
+    pub fn restored(position: ScrollPosition) -> Self {
+        let mut state = Self::new();
+        state.stick_to_bottom = position.stick_to_bottom;
+        if !position.stick_to_bottom {
+            state.scroll_state.set_offset(Position {
+                x: 0,
+                y: position.offset,
+            });
+        }
+        state
+    }
+
+    /// The same state with `pins` pinned, e.g. from a loaded session.
+    pub fn with_pins(mut self, pins: &[usize]) -> Self {
+        self.set_pins(pins);
+        self
+    }
+
+    /// Replace the pinned items, e.g. after the session remapped them.
+    pub fn set_pins(&mut self, pins: &[usize]) {
+        self.pinned_indices = pins.iter().copied().collect();
+    }
+
+    /// Pin item `idx`, or unpin it if it is pinned. Returns whether it is
+    /// pinned now.
+    pub fn toggle_pin(&mut self, idx: usize) -> bool {
+        let pinned = !self.pinned_indices.remove(&idx);
+        if pinned {
+            self.pinned_indices.insert(idx);
+        }
+        pinned
+    }
+
+    /// Select the first pin after the selection (before it, going back),
+    /// wrapping around at either end, and scroll it into view. Returns
+    /// false when nothing is pinned.
+    pub fn jump_to_pin(&mut self, forward: bool) -> bool {
+        let pins = &self.pinned_indices;
+        let target = match (self.selected_index, forward) {
+            (Some(idx), true) => pins.range(idx + 1..).next().or(pins.first()),
+            (Some(idx), false) => pins.range(..idx).next_back().or(pins.last()),
+            (None, true) => pins.first(),
+            (None, false) => pins.last(),
+        };
+        let Some(&idx) = target else {
+            return false;
+        };
+        self.selected_index = Some(idx);
+        self.scroll_to_selected();
+        true
+    }
+
+    /// Current scroll position, for saving with the session.
+    pub fn scroll_position(&self) -> ScrollPosition {
+        ScrollPosition {
+            offset: self.scroll_state.offset().y,
+            stick_to_bottom: self.stick_to_bottom,
+        }
+    }
+
+    /// Number of content lines added since the user scrolled away from the bottom.
+    /// Always 0 while pinned — the user is already looking at the newest content.
+    pub fn unseen_lines(&self, total_height: u16) -> u16 {
+        if self.stick_to_bottom {
+            0
+        } else {
+            total_height.saturating_sub(self.last_seen_height)
         }
     }
 
@@ -83,6 +279,21 @@ impl MessageListState {
         }
     }
 
+    /// Keep `selected_index` on the item it was pointing at while the
+    /// context changes underneath, e.g. as a response streams in. Only the
+    /// index is corrected; the viewport isn't scrolled, since the user
+    /// didn't move the selection. Clears the selection if its item is gone.
+    pub fn track_selection(&mut self, items: &[ContextItem]) {
+        if let (Some(idx), Some((anchored, key))) = (self.selected_index, &self.selection_anchor)
+            && idx == *anchored
+        {
+            self.selected_index = key.find(items);
+        }
+        self.selection_anchor = self
+            .selected_index
+            .and_then(|idx| Some((idx, ItemKey::of(items, idx)?)));
+    }
+
     /// Scroll the viewport so the selected message is fully visible.
     /// If the message is taller than the viewport, align its top edge.
     pub fn scroll_to_selected(&mut self) {
@@ -132,17 +343,20 @@ impl MessageListState {
     }
 }
 
-/// Build a lookup from call_id → (index, &ToolResult) for all ToolResult items,
-/// plus the set of consumed ToolResult indices (those whose call_id matches a ToolCall).
+/// Build a lookup from call_id → &ToolResult for all ToolResult items, preferring
+/// the untruncated result where the model was sent a cut one, plus the set of
+/// consumed ToolResult indices (those whose call_id matches a ToolCall).
 fn build_result_map(
-    items: &[ContextItem],
+    context: &Context,
 ) -> (HashMap<&str, &crate::inference::ToolResult>, HashSet<usize>) {
+    let items = &context.items;
     let mut result_map: HashMap<&str, &crate::inference::ToolResult> = HashMap::new();
     let mut result_indices: HashMap<&str, usize> = HashMap::new();
 
     for (i, item) in items.iter().enumerate() {
         if let ContextItem::ToolResult(tr) = item {
-            result_map.insert(&tr.call_id, tr);
+            let shown = context.full_tool_result(&tr.call_id).unwrap_or(tr);
+            result_map.insert(&tr.call_id, shown);
             result_indices.insert(&tr.call_id, i);
         }
     }
@@ -167,73 +381,151 @@ pub struct MessageList<'a> {
     pub state: &'a mut MessageListState,
     pub context: &'a Context,
     pub is_loading: bool,
+    /// Only reasoning has streamed this round; shows the thinking label
+    /// instead of the pulse
+    pub thinking: bool,
     pub pulse_value: f32,
     pub spinner_frame: usize,
-    pub message_stats: &'a HashMap<usize, UsageStats>,
+    pub spinner: Spinner,
+    pub message_stats: &'a HashMap<usize, TurnUsage>,
+    pub theme: &'a Theme,
+    /// Word wrap for message text (app-wide toggle)
+    pub wrap: bool,
+    /// Bordered or compact messages (app-wide toggle)
+    pub density: Density,
+    /// Give thinking segments no space (view filter, or the reasoning panel
+    /// shows them)
+    pub hide_thinking: bool,
+    /// Give the system directive no space (view filter)
+    pub hide_system: bool,
+    /// Draws images inline when set; otherwise they stay placeholders
+    pub graphics: Option<GraphicsProtocol>,
 }
 
 impl<'a> MessageList<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         state: &'a mut MessageListState,
         context: &'a Context,
         is_loading: bool,
+        thinking: bool,
         pulse_value: f32,
         spinner_frame: usize,
-        message_stats: &'a HashMap<usize, UsageStats>,
+        spinner: Spinner,
+        message_stats: &'a HashMap<usize, TurnUsage>,
+        theme: &'a Theme,
+        wrap: bool,
+        density: Density,
     ) -> Self {
         Self {
             state,
             context,
             is_loading,
+            thinking,
             pulse_value,
             spinner_frame,
+            spinner,
             message_stats,
+            theme,
+            wrap,
+            density,
+            hide_thinking: false,
+            hide_system: false,
+            graphics: None,
         }
     }
+
+    /// Leave thinking segments out. They stay in the context and keep their
+    /// indices; they just take no rows.
+    pub fn hide_thinking(mut self, hide: bool) -> Self {
+        self.hide_thinking = hide;
+        self
+    }
+
+    /// Leave the system directive out, the same way as `hide_thinking`.
+    pub fn hide_system(mut self, hide: bool) -> Self {
+        self.hide_system = hide;
+        self
+    }
+
+    /// Draw images with the terminal's graphics protocol, if it has one.
+    pub fn with_graphics(mut self, graphics: Option<GraphicsProtocol>) -> Self {
+        self.graphics = graphics;
+        self
+    }
+}
+
+/// The context's thinking segments on their own, for the reasoning panel.
+pub fn reasoning_context(context: &Context) -> Context {
+    let mut reasoning = Context::default();
+    reasoning.items = context
+        .items
+        .iter()
+        .filter(|item| matches!(item, ContextItem::Message(seg) if seg.source == Source::Thinking))
+        .cloned()
+        .collect();
+    reasoning
 }
 
 impl<'a> Component for MessageList<'a> {
     fn render(&mut self, frame: &mut Frame, area: Rect) {
         let content_width = area.width.saturating_sub(1); // -1 for scrollbar safe area
         let num_items = self.context.items.len();
+        self.state.track_selection(&self.context.items);
+        self.state.image_placements.clear();
+        let graphics = self.graphics;
+        let (hide_thinking, hide_system) = (self.hide_thinking, self.hide_system);
 
         // Build call_id → &ToolResult lookup and consumed index set
-        let (result_map, consumed) = build_result_map(&self.context.items);
+        let (result_map, consumed) = build_result_map(self.context);
+
+        let is_hidden = |seg: &ContextSegment| {
+            (hide_thinking && seg.source == Source::Thinking)
+                || (hide_system && seg.source == Source::Directive)
+        };
+        let breaks = day_breaks(&self.context.items, |seg| !is_hidden(seg));
 
         // 1. Update Layout Cache (Internal Mutation)
         let expanded_indices = &self.state.expanded_indices;
         let layout = &mut self.state.layout;
-        let reusable = layout.reusable_count(
-            num_items,
+        layout.set_wrap(self.wrap);
+        layout.set_density(self.density);
+        layout.set_hidden(hide_thinking, hide_system);
+        layout.sync(
+            &self.context.items,
             content_width,
             self.is_loading,
-            &self.context.items,
             expanded_indices,
-        );
-
-        layout.heights.truncate(reusable.min(layout.heights.len()));
-
-        for (i, item) in self
-            .context
-            .items
-            .iter()
-            .enumerate()
-            .skip(layout.heights.len())
-        {
-            let is_expanded = expanded_indices.contains(&i);
-            let height = match item {
-                ContextItem::Message(seg) => Message::calculate_height(seg, content_width),
+            |i, item| match item {
+                ContextItem::Message(seg) if is_hidden(seg) => 0,
+                ContextItem::Message(seg) => {
+                    Message::calculate_height(
+                        seg,
+                        content_width,
+                        self.theme,
+                        self.wrap,
+                        self.density,
+                    ) + u16::from(breaks[i].is_some())
+                }
                 ContextItem::ToolCall(tc) => {
                     let paired_result = result_map.get(tc.call_id.as_str()).copied();
+                    let is_expanded = expanded_indices.contains(&i);
                     ToolGroup::calculate_height(tc, paired_result, is_expanded, content_width)
                 }
                 ContextItem::ToolResult(_) if consumed.contains(&i) => 0,
                 ContextItem::ToolResult(_) => 0, // Defensive: orphaned results hidden too
-            };
-            layout.heights.push(height);
-        }
-        layout.rebuild_prefix_heights();
-        layout.update_metadata(num_items, content_width, expanded_indices);
+                ContextItem::Image(image) => {
+                    let inline = picture_rows(graphics, image);
+                    Message::calculate_height(
+                        &image_segment(image, inline > 0),
+                        content_width,
+                        self.theme,
+                        self.wrap,
+                        self.density,
+                    ) + inline
+                }
+            },
+        );
 
         let total_height: u16 = self.state.layout.heights.iter().sum();
 
@@ -261,6 +553,10 @@ impl<'a> Component for MessageList<'a> {
                 .scroll_state
                 .set_offset(Position { x: 0, y: bottom_y });
         } else {
+            // Restored away from the bottom: what is already there counts as seen
+            if self.state.last_seen_height == 0 {
+                self.state.last_seen_height = total_height;
+            }
             self.state.clamp_scroll();
         }
 
@@ -273,6 +569,11 @@ impl<'a> Component for MessageList<'a> {
             .vertical_scrollbar_visibility(ScrollbarVisibility::Always)
             .horizontal_scrollbar_visibility(ScrollbarVisibility::Never);
 
+        let options = MessageOptions {
+            theme: self.theme,
+            wrap: self.wrap,
+            scroll_x: self.state.scroll_x,
+        };
         let mut y_offset: u16 = if visible_range.start > 0 {
             self.state.layout.prefix_heights[visible_range.start - 1]
         } else {
@@ -283,7 +584,7 @@ impl<'a> Component for MessageList<'a> {
             let item = &self.context.items[i];
             let height = self.state.layout.heights[i];
 
-            // Skip consumed ToolResults (height=0, no visual space)
+            // Skip consumed ToolResults and hidden messages (height=0, no visual space)
             if height == 0 {
                 continue;
             }
@@ -295,17 +596,39 @@ impl<'a> Component for MessageList<'a> {
 
             match item {
                 ContextItem::Message(seg) => {
+                    let mut segment_rect = segment_rect;
+                    if let Some(day) = breaks[i] {
+                        let separator_rect = Rect {
+                            height: 1,
+                            ..segment_rect
+                        };
+                        scroll_view.render_widget(day_separator(day, self.theme), separator_rect);
+                        segment_rect.y += 1;
+                        segment_rect.height -= 1;
+                    }
                     let is_volatile = matches!(
                         seg.source,
                         Source::Model | Source::Thinking | Source::Status
                     );
-                    let pulse_intensity = if is_last && self.is_loading && is_volatile {
-                        self.pulse_value
-                    } else {
-                        0.0
-                    };
-                    let stats = self.message_stats.get(&i);
-                    let message = Message::new(seg, is_selected, pulse_intensity, stats);
+                    let pulse_intensity =
+                        if is_last && self.is_loading && is_volatile && !self.thinking {
+                            self.pulse_value
+                        } else {
+                            0.0
+                        };
+                    let usage = self.message_stats.get(&i);
+                    let highlight = self.state.text_selection.and_then(|sel| sel.lines_of(i));
+                    let message = Message::new(
+                        seg,
+                        is_selected,
+                        pulse_intensity,
+                        usage.map(|usage| &usage.stats),
+                        usage.and_then(|usage| usage.pricing.as_ref()),
+                        options,
+                    )
+                    .with_highlight(highlight)
+                    .with_density(self.density)
+                    .with_pinned(self.state.pinned_indices.contains(&i));
                     scroll_view.render_widget(message, segment_rect);
                 }
                 ContextItem::ToolCall(tc) => {
@@ -316,12 +639,38 @@ impl<'a> Component for MessageList<'a> {
                         is_selected,
                         is_expanded: self.state.expanded_indices.contains(&i),
                         spinner_frame: self.spinner_frame,
+                        theme: self.theme,
                     };
                     scroll_view.render_widget(group, segment_rect);
                 }
                 ContextItem::ToolResult(_) => {
                     // Should not reach here (height=0 items skipped above)
                 }
+                ContextItem::Image(image) => {
+                    // The picture's rows are at the bottom, under the label
+                    let inline = picture_rows(graphics, image);
+                    let label_height = height.saturating_sub(inline);
+                    let picture_top = y_offset + label_height;
+                    if inline > 0
+                        && picture_top >= scroll_offset
+                        && y_offset + height <= scroll_offset + area.height
+                    {
+                        self.state.image_placements.push(ImagePlacement::new(
+                            i,
+                            image,
+                            area.x + 2,
+                            area.y + picture_top - scroll_offset,
+                        ));
+                    }
+                    let seg = image_segment(image, inline > 0);
+                    let segment_rect = Rect {
+                        height: label_height,
+                        ..segment_rect
+                    };
+                    let message = Message::new(&seg, is_selected, 0.0, None, None, options)
+                        .with_density(self.density);
+                    scroll_view.render_widget(message, segment_rect);
+                }
             }
 
             y_offset += height;
@@ -335,7 +684,7 @@ impl<'a> Component for MessageList<'a> {
         // Render the ScrollView into the full viewport area
         frame.render_stateful_widget(scroll_view, area, &mut self.state.scroll_state);
 
-        // 4. Render animated logo centered in empty space below messages
+        // 4. Render the loading spinner centered in empty space below messages
         if show_spinner {
             // Calculate where messages end on screen (accounting for scroll)
             let content_screen_end = total_height.saturating_sub(scroll_offset);
@@ -344,18 +693,141 @@ impl<'a> Component for MessageList<'a> {
             let logo_h = (area.y + area.height)
                 .saturating_sub(logo_start)
                 .saturating_sub(bottom_pad);
-            if logo_h >= 3 {
+            if self.thinking && logo_h >= 4 {
+                let label_area = Rect::new(area.x, logo_start, area.width, 1);
+                render_thinking_label(frame, label_area, self.spinner_frame, self.theme);
+                let logo_area = Rect::new(area.x, logo_start + 1, area.width, logo_h - 1);
+                self.spinner
+                    .render(frame, logo_area, self.spinner_frame, self.theme);
+            } else if logo_h >= 3 {
                 let logo_area = Rect::new(area.x, logo_start, area.width, logo_h);
-                Logo::render(frame, logo_area, self.spinner_frame);
+                self.spinner
+                    .render(frame, logo_area, self.spinner_frame, self.theme);
             }
         }
 
+        // 5. "New lines" badge when content grew while scrolled up
+        let unseen = self.state.unseen_lines(total_height);
+        if unseen > 0 {
+            render_new_lines_badge(frame, area, unseen);
+        }
+
         // Update auxiliary state
         let current_offset = self.state.scroll_state.offset().y;
         self.state.max_scroll_reached = self.state.max_scroll_reached.max(current_offset);
+        if self.state.stick_to_bottom {
+            self.state.last_seen_height = total_height;
+        }
+    }
+}
+
+/// For each item, the day to announce above it: set on a visible message
+/// whose local date differs from the previous visible timestamped message.
+/// The first timestamped message gets no separator.
+fn day_breaks(
+    items: &[ContextItem],
+    visible: impl Fn(&ContextSegment) -> bool,
+) -> Vec<Option<NaiveDate>> {
+    let mut previous: Option<NaiveDate> = None;
+    items
+        .iter()
+        .map(|item| {
+            let ContextItem::Message(seg) = item else {
+                return None;
+            };
+            let day = seg
+                .timestamp
+                .filter(|_| visible(seg))
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .map(|utc| utc.with_timezone(&Local).date_naive())?;
+            let changed = previous.is_some_and(|prev| prev != day);
+            previous = Some(day);
+            changed.then_some(day)
+        })
+        .collect()
+}
+
+/// Centered `── March 3 ──` rule, with the year when it isn't this year.
+fn day_separator(day: NaiveDate, theme: &Theme) -> Paragraph<'static> {
+    let label = if day.year() == Local::now().year() {
+        day.format("%B %-d").to_string()
+    } else {
+        day.format("%B %-d, %Y").to_string()
+    };
+    Paragraph::new(Line::from(Span::styled(
+        format!("── {label} ──"),
+        Style::default().fg(theme.border),
+    )))
+    .alignment(Alignment::Center)
+}
+
+/// Label of an image attachment, rendered as a user message. Without a
+/// picture under it, it stands in for the image.
+fn image_segment(image: &ImageAttachment, inline: bool) -> ContextSegment {
+    let content = if inline {
+        image.label()
+    } else {
+        format!("[image] {}", image.label())
+    };
+    ContextSegment {
+        source: Source::User,
+        content,
+        timestamp: None,
+    }
+}
+
+/// Rows of picture drawn under an image's label; 0 leaves the placeholder.
+fn picture_rows(graphics: Option<GraphicsProtocol>, image: &ImageAttachment) -> u16 {
+    if graphics.is_some_and(|protocol| protocol.supports(&image.mime)) {
+        IMAGE_ROWS
+    } else {
+        0
     }
 }
 
+/// Floating badge anchored to the bottom-center of the viewport: `↓ N new lines`.
+/// "thinking" followed by zero to three dots, advancing a dot every four
+/// spinner frames. Padded to a fixed width so centering doesn't jitter.
+fn thinking_label(spinner_frame: usize) -> String {
+    let dots = (spinner_frame / 4) % 4;
+    format!("thinking{:<3}", ".".repeat(dots))
+}
+
+fn render_thinking_label(frame: &mut Frame, area: Rect, spinner_frame: usize, theme: &Theme) {
+    let label = Paragraph::new(Line::from(Span::styled(
+        thinking_label(spinner_frame),
+        Style::default()
+            .fg(theme.thinking)
+            .add_modifier(Modifier::ITALIC),
+    )))
+    .alignment(Alignment::Center);
+    frame.render_widget(label, area);
+}
+
+fn render_new_lines_badge(frame: &mut Frame, area: Rect, unseen: u16) {
+    let noun = if unseen == 1 { "line" } else { "lines" };
+    let label = format!(" ↓ {unseen} new {noun} ");
+    let width = (label.chars().count() as u16).min(area.width);
+    if width == 0 || area.height == 0 {
+        return;
+    }
+    let badge_area = Rect::new(
+        area.x + (area.width.saturating_sub(width)) / 2,
+        area.y + area.height - 1,
+        width,
+        1,
+    );
+    let badge = Paragraph::new(Line::from(Span::styled(
+        label,
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Blue)
+            .add_modifier(Modifier::BOLD),
+    )));
+    frame.render_widget(Clear, badge_area);
+    frame.render_widget(badge, badge_area);
+}
+
 /// EventHandler is implemented on `MessageListState` rather than `MessageList` because:
 /// 1. Event handling requires persistent state (scroll position, stick_to_bottom flag)
 /// 2. `MessageList` is recreated each frame with fresh props, so it can't hold state
@@ -385,6 +857,14 @@ impl EventHandler for MessageListState {
                 self.repin_if_at_bottom();
                 None
             }
+            TuiEvent::ScrollLeft => {
+                self.scroll_x = self.scroll_x.saturating_sub(H_SCROLL_STEP);
+                None
+            }
+            TuiEvent::ScrollRight => {
+                self.scroll_x = self.scroll_x.saturating_add(H_SCROLL_STEP);
+                None
+            }
             // Mouse moves handled by parent for now due to hit testing complexity
             _ => None,
         }
@@ -399,6 +879,25 @@ pub struct LayoutCache {
     content_width: u16,
     /// Tracks which tool calls are expanded so heights are invalidated on toggle.
     cached_expanded_indices: HashSet<usize>,
+    /// Word wrap mode the cached heights were measured with
+    wrap: bool,
+    /// Message density the cached heights were measured with
+    density: Density,
+    /// Whether thinking segments were measured as hidden
+    hide_thinking: bool,
+    /// Whether the system directive was measured as hidden
+    hide_system: bool,
+    /// Heights measured at recent other widths, most recent first
+    width_memo: VecDeque<WidthSnapshot>,
+}
+
+/// The cache's measurements at one width, set aside while another width
+/// is in use.
+#[derive(Debug, Clone)]
+struct WidthSnapshot {
+    content_width: u16,
+    heights: Vec<u16>,
+    expanded_indices: HashSet<usize>,
 }
 
 impl Default for LayoutCache {
@@ -415,6 +914,132 @@ impl LayoutCache {
             message_count: 0,
             content_width: 0,
             cached_expanded_indices: HashSet::new(),
+            wrap: true,
+            density: Density::default(),
+            hide_thinking: false,
+            hide_system: false,
+            width_memo: VecDeque::new(),
+        }
+    }
+
+    /// Width the cached heights were measured at, which is also the width
+    /// each message is rendered at.
+    pub fn content_width(&self) -> u16 {
+        self.content_width
+    }
+
+    /// Drop every cached height when the wrap mode changes, since each
+    /// message's height depends on it.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        if self.wrap != wrap {
+            self.wrap = wrap;
+            self.heights.clear();
+            self.width_memo.clear();
+        }
+    }
+
+    /// Drop every cached height when thinking segments or the system
+    /// directive are hidden or shown.
+    pub fn set_hidden(&mut self, hide_thinking: bool, hide_system: bool) {
+        if (self.hide_thinking, self.hide_system) != (hide_thinking, hide_system) {
+            self.hide_thinking = hide_thinking;
+            self.hide_system = hide_system;
+            self.heights.clear();
+            self.width_memo.clear();
+        }
+    }
+
+    /// Drop every cached height, at every width. For when items were
+    /// replaced in place, which the item count alone doesn't reveal.
+    pub fn clear(&mut self) {
+        self.heights.clear();
+        self.width_memo.clear();
+    }
+
+    /// Drop every cached height when the message density changes.
+    pub fn set_density(&mut self, density: Density) {
+        if self.density != density {
+            self.density = density;
+            self.heights.clear();
+            self.width_memo.clear();
+        }
+    }
+
+    /// Bring the cached heights up to date with `items` at `content_width`,
+    /// calling `measure` only for items whose height may have changed. On a
+    /// width change the heights measured at the old width are set aside and
+    /// any kept for the new one are brought back, then validated like any
+    /// other cached heights.
+    pub fn sync(
+        &mut self,
+        items: &[ContextItem],
+        content_width: u16,
+        is_loading: bool,
+        expanded_indices: &HashSet<usize>,
+        mut measure: impl FnMut(usize, &ContextItem) -> u16,
+    ) {
+        // A shrunk context was cleared or replaced; other widths' heights
+        // describe items that are gone
+        if items.len() < self.message_count {
+            self.width_memo.clear();
+        }
+        if content_width != self.content_width {
+            self.switch_width(content_width, items);
+        }
+
+        let reusable = self.reusable_count(
+            items.len(),
+            content_width,
+            is_loading,
+            items,
+            expanded_indices,
+        );
+        self.heights.truncate(reusable.min(self.heights.len()));
+        for (i, item) in items.iter().enumerate().skip(self.heights.len()) {
+            self.heights.push(measure(i, item));
+        }
+        self.rebuild_prefix_heights();
+        self.update_metadata(items.len(), content_width, expanded_indices);
+    }
+
+    /// Set the current heights aside under their width and restore those
+    /// kept for `content_width`, if any.
+    fn switch_width(&mut self, content_width: u16, items: &[ContextItem]) {
+        let mut heights = std::mem::take(&mut self.heights);
+        // The last message may still be growing, so it isn't kept
+        let last_is_volatile = heights
+            .len()
+            .checked_sub(1)
+            .and_then(|last| items.get(last))
+            .is_some_and(|item| {
+                matches!(item, ContextItem::Message(seg)
+                    if matches!(seg.source, Source::Model | Source::Thinking))
+            });
+        if last_is_volatile {
+            heights.pop();
+        }
+        if !heights.is_empty() {
+            self.width_memo.push_front(WidthSnapshot {
+                content_width: self.content_width,
+                heights,
+                expanded_indices: std::mem::take(&mut self.cached_expanded_indices),
+            });
+            self.width_memo.truncate(WIDTH_MEMO_CAPACITY);
+        }
+
+        let restored = self
+            .width_memo
+            .iter()
+            .position(|snapshot| snapshot.content_width == content_width)
+            .and_then(|i| self.width_memo.remove(i));
+        self.content_width = content_width;
+        match restored {
+            Some(snapshot) => {
+                self.message_count = snapshot.heights.len();
+                self.heights = snapshot.heights;
+                self.cached_expanded_indices = snapshot.expanded_indices;
+            }
+            None => self.message_count = 0,
         }
     }
 
@@ -447,6 +1072,26 @@ impl LayoutCache {
             return earliest;
         }
 
+        // A tool result arriving after its call was measured changes the
+        // call's height (its summary may now take two lines, or gain output)
+        let measured = self.message_count.min(items.len());
+        let call_position = |call_id: &str| {
+            items[..measured].iter().position(|item| match item {
+                ContextItem::ToolCall(call) => call.call_id == call_id,
+                _ => false,
+            })
+        };
+        if let Some(call_index) = items[measured..]
+            .iter()
+            .filter_map(|item| match item {
+                ContextItem::ToolResult(result) => call_position(&result.call_id),
+                _ => None,
+            })
+            .min()
+        {
+            return call_index;
+        }
+
         // If not loading, most heights are stable — but the last Model/Thinking
         // message may have grown during a streaming batch that completed between
         // frames (loading flipped false before we recalculated its height).
@@ -470,7 +1115,7 @@ impl LayoutCache {
             ContextItem::Message(seg) => {
                 matches!(seg.source, Source::User | Source::Directive)
             }
-            ContextItem::ToolCall(_) | ContextItem::ToolResult(_) => true,
+            ContextItem::ToolCall(_) | ContextItem::ToolResult(_) | ContextItem::Image(_) => true,
         });
 
         if last_is_stable {
@@ -502,6 +1147,17 @@ impl LayoutCache {
             .collect();
     }
 
+    /// Item at content row `content_y` (viewport row + scroll offset).
+    ///
+    /// Zero-height items (consumed ToolResults) are never hit. Rows past the
+    /// last item, such as the spinner padding below the conversation, give None.
+    pub fn index_at(&self, content_y: u16) -> Option<usize> {
+        let idx = self
+            .prefix_heights
+            .partition_point(|&end_y| end_y <= content_y);
+        (idx < self.prefix_heights.len()).then_some(idx)
+    }
+
     pub fn visible_range(
         &self,
         scroll_offset: u16,
@@ -530,6 +1186,345 @@ impl LayoutCache {
 mod tests {
     use super::*;
 
+    fn cache_with_heights(heights: Vec<u16>) -> LayoutCache {
+        let mut cache = LayoutCache::new();
+        cache.heights = heights;
+        cache.rebuild_prefix_heights();
+        cache
+    }
+
+    #[test]
+    fn test_thinking_label_cycles_dots() {
+        assert_eq!(thinking_label(0), "thinking   ");
+        assert_eq!(thinking_label(4), "thinking.  ");
+        assert_eq!(thinking_label(12), "thinking...");
+        assert_eq!(thinking_label(16), "thinking   ");
+    }
+
+    #[test]
+    fn test_day_breaks_mark_first_message_of_each_day() {
+        // Noon UTC, so the local date is the UTC date in any timezone
+        let noon = |day: i64| Some(1_700_000_000 - 1_700_000_000 % 86_400 + 43_200 + day * 86_400);
+        let message = |source, timestamp| {
+            ContextItem::Message(ContextSegment {
+                source,
+                content: String::from("hi"),
+                timestamp,
+            })
+        };
+        let items = vec![
+            message(Source::Directive, None),
+            message(Source::User, noon(0)),
+            message(Source::Model, noon(0)),
+            message(Source::User, noon(1)),
+            message(Source::Thinking, noon(2)),
+            message(Source::Model, noon(2)),
+        ];
+
+        let day = |offset| {
+            noon(offset)
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .map(|utc| utc.with_timezone(&Local).date_naive())
+        };
+        assert_eq!(
+            day_breaks(&items, |_| true),
+            vec![None, None, None, day(1), day(2), None]
+        );
+        // A hidden message hands its separator to the next visible one
+        assert_eq!(
+            day_breaks(&items, |seg| seg.source != Source::Thinking),
+            vec![None, None, None, day(1), None, day(2)]
+        );
+    }
+
+    #[test]
+    fn test_width_change_measures_each_item_once() {
+        // Ends on a user message, which is never re-measured while idle
+        let items: Vec<ContextItem> = (0..200)
+            .map(|i| {
+                ContextItem::Message(ContextSegment {
+                    source: if i % 2 == 0 {
+                        Source::Model
+                    } else {
+                        Source::User
+                    },
+                    content: format!("message {i}"),
+                    timestamp: None,
+                })
+            })
+            .collect();
+        let no_expanded = HashSet::new();
+        let every_item: Vec<usize> = (0..items.len()).collect();
+        let sync = |cache: &mut LayoutCache, width: u16| {
+            let mut measured = Vec::new();
+            cache.sync(&items, width, false, &no_expanded, |i, _| {
+                measured.push(i);
+                width / 10
+            });
+            measured
+        };
+        let mut cache = LayoutCache::new();
+
+        assert_eq!(sync(&mut cache, 80), every_item);
+        assert!(sync(&mut cache, 80).is_empty());
+
+        // Resizing re-measures every item exactly once at the new width
+        assert_eq!(sync(&mut cache, 60), every_item);
+        assert!(sync(&mut cache, 60).is_empty());
+        assert_eq!(cache.heights, vec![6; 200]);
+
+        // Widths seen recently are restored without measuring anything
+        assert!(sync(&mut cache, 80).is_empty());
+        assert_eq!(cache.heights, vec![8; 200]);
+        assert!(sync(&mut cache, 60).is_empty());
+        assert_eq!(cache.heights, vec![6; 200]);
+
+        // A changed wrap mode makes every kept width stale
+        cache.set_wrap(false);
+        assert_eq!(sync(&mut cache, 80), every_item);
+    }
+
+    #[test]
+    fn test_thinking_routes_to_reasoning_panel() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let segment = |source, content: &str| {
+            ContextItem::Message(ContextSegment {
+                source,
+                content: content.to_string(),
+                timestamp: None,
+            })
+        };
+        let mut context = Context::new();
+        context.items.extend([
+            segment(Source::User, "question"),
+            segment(Source::Thinking, "pondering"),
+            segment(Source::Model, "answer"),
+            segment(Source::Thinking, "more pondering"),
+        ]);
+        let reasoning = reasoning_context(&context);
+        assert_eq!(
+            reasoning.items,
+            [context.items[2].clone(), context.items[4].clone()]
+        );
+
+        let theme = Theme::default();
+        let no_stats = HashMap::new();
+        let heights = |state: &mut MessageListState, context: &Context, hide_thinking: bool| {
+            let mut terminal = Terminal::new(TestBackend::new(40, 30)).unwrap();
+            terminal
+                .draw(|f| {
+                    MessageList::new(
+                        state,
+                        context,
+                        false,
+                        false,
+                        0.0,
+                        0,
+                        Spinner::default(),
+                        &no_stats,
+                        &theme,
+                        true,
+                        Density::default(),
+                    )
+                    .hide_thinking(hide_thinking)
+                    .render(f, f.area())
+                })
+                .unwrap();
+            state.layout.heights.clone()
+        };
+
+        // The conversation keeps its indices but gives thinking no rows
+        let mut main = MessageListState::new();
+        let main_heights = heights(&mut main, &context, true);
+        assert_eq!(main_heights[2], 0);
+        assert_eq!(main_heights[4], 0);
+        assert!(main_heights[1] > 0 && main_heights[3] > 0);
+
+        // The panel's own list shows every thinking segment
+        let mut panel = MessageListState::new();
+        let panel_heights = heights(&mut panel, &reasoning, false);
+        assert!(panel_heights.iter().all(|&h| h > 0));
+
+        // Closing the panel brings thinking back into the conversation
+        assert!(heights(&mut main, &context, false)[2] > 0);
+    }
+
+    #[test]
+    fn test_view_filters_hide_thinking_and_system() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let segment = |source, content: &str| {
+            ContextItem::Message(ContextSegment {
+                source,
+                content: content.to_string(),
+                timestamp: None,
+            })
+        };
+        // Item 0 is the directive
+        let mut context = Context::new();
+        context.items.extend([
+            segment(Source::User, "question"),
+            segment(Source::Thinking, "pondering"),
+            segment(Source::Model, "answer"),
+        ]);
+
+        let theme = Theme::default();
+        let no_stats = HashMap::new();
+        let mut state = MessageListState::new();
+        let mut render = |hide_thinking: bool, hide_system: bool| {
+            let mut terminal = Terminal::new(TestBackend::new(60, 40)).unwrap();
+            terminal
+                .draw(|f| {
+                    MessageList::new(
+                        &mut state,
+                        &context,
+                        false,
+                        false,
+                        0.0,
+                        0,
+                        Spinner::default(),
+                        &no_stats,
+                        &theme,
+                        true,
+                        Density::default(),
+                    )
+                    .hide_thinking(hide_thinking)
+                    .hide_system(hide_system)
+                    .render(f, f.area())
+                })
+                .unwrap();
+            let screen: String = terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|c| c.symbol())
+                .collect();
+            (
+                state.layout.heights.clone(),
+                state.layout.visible_range(0, 40),
+                screen,
+            )
+        };
+
+        let (heights, visible, screen) = render(true, true);
+        assert_eq!(heights.len(), 4, "hidden items keep their indices");
+        assert_eq!((heights[0], heights[2]), (0, 0));
+        assert!(heights[1] > 0 && heights[3] > 0);
+        // The range starts past the hidden directive
+        assert_eq!(visible, 1..4);
+        assert!(!screen.contains("helpful assistant") && !screen.contains("pondering"));
+        assert!(screen.contains("question") && screen.contains("answer"));
+
+        // Each filter works on its own, and turning it off brings the rows back
+        let (heights, visible, _) = render(false, true);
+        assert_eq!(heights[0], 0);
+        assert!(heights[2] > 0);
+        assert_eq!(visible.start, 1);
+        let (heights, visible, screen) = render(false, false);
+        assert!(heights.iter().all(|&h| h > 0));
+        assert_eq!(visible.start, 0);
+        assert!(screen.contains("pondering"));
+    }
+
+    #[test]
+    fn test_images_inline_or_placeholder_by_protocol() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let mut context = Context::new();
+        let png = ImageAttachment::from_bytes(b"png", "image/png", Some("cat.png".into()));
+        let jpeg = ImageAttachment::from_bytes(b"jpg", "image/jpeg", Some("dog.jpg".into()));
+        context.add_image(png);
+        context.add_image(jpeg);
+
+        let theme = Theme::default();
+        let no_stats = HashMap::new();
+        let render = |graphics: Option<GraphicsProtocol>| {
+            let mut state = MessageListState::new();
+            let mut terminal = Terminal::new(TestBackend::new(60, 40)).unwrap();
+            terminal
+                .draw(|f| {
+                    MessageList::new(
+                        &mut state,
+                        &context,
+                        false,
+                        false,
+                        0.0,
+                        0,
+                        Spinner::default(),
+                        &no_stats,
+                        &theme,
+                        true,
+                        Density::default(),
+                    )
+                    .with_graphics(graphics)
+                    .render(f, f.area())
+                })
+                .unwrap();
+            let text: String = terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|c| c.symbol())
+                .collect();
+            (state.layout.heights.clone(), state.image_placements, text)
+        };
+
+        // No protocol: both images are placeholders with no picture rows
+        let (plain, placements, text) = render(None);
+        assert!(placements.is_empty());
+        assert!(text.contains("[image] cat.png"), "got: {text}");
+        assert!(text.contains("[image] dog.jpg"), "got: {text}");
+
+        // Kitty draws the PNG under its label; the JPEG stays a placeholder
+        let (inline, placements, text) = render(Some(GraphicsProtocol::Kitty));
+        assert_eq!(inline[1], plain[1] + IMAGE_ROWS);
+        assert_eq!(inline[2], plain[2]);
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].item, 1);
+        assert_eq!(placements[0].y, inline[0] + inline[1] - IMAGE_ROWS);
+        assert!(!text.contains("[image] cat.png"), "got: {text}");
+        assert!(text.contains("[image] dog.jpg"), "got: {text}");
+    }
+
+    #[test]
+    fn test_index_at_maps_rows_to_items() {
+        // Directive (hidden), user (3 rows), tool call (2), consumed result (0), model (4)
+        let cache = cache_with_heights(vec![0, 3, 2, 0, 4]);
+
+        assert_eq!(cache.index_at(0), Some(1));
+        assert_eq!(cache.index_at(2), Some(1));
+        assert_eq!(cache.index_at(3), Some(2));
+        assert_eq!(cache.index_at(4), Some(2));
+        // Zero-height result is skipped straight to the model message
+        assert_eq!(cache.index_at(5), Some(4));
+        assert_eq!(cache.index_at(8), Some(4));
+    }
+
+    #[test]
+    fn test_index_at_with_scroll_offset() {
+        let cache = cache_with_heights(vec![0, 3, 2, 0, 4]);
+        let scroll_offset = 4;
+
+        // Viewport row 0 is content row 4: the tool call's second line
+        assert_eq!(cache.index_at(scroll_offset), Some(2));
+        assert_eq!(cache.index_at(scroll_offset + 1), Some(4));
+    }
+
+    #[test]
+    fn test_index_at_below_content_is_none() {
+        let cache = cache_with_heights(vec![0, 3, 2, 0, 4]);
+        // Rows 9+ are the spinner padding below the last message
+        assert_eq!(cache.index_at(9), None);
+        assert_eq!(cache.index_at(50), None);
+        assert_eq!(LayoutCache::new().index_at(0), None);
+    }
+
     #[test]
     fn test_layout_cache_reusable() {
         let mut cache = LayoutCache::new();
@@ -547,11 +1542,25 @@ mod tests {
         // Case 3: Width changed -> 0 reusable
         assert_eq!(cache.reusable_count(5, 40, false, &[], &no_expanded), 0);
 
+        // Case 3b: Wrap mode changed -> heights dropped, 0 reusable
+        cache.set_wrap(true);
+        assert_eq!(cache.heights.len(), 5);
+        cache.set_wrap(false);
+        assert!(cache.heights.is_empty());
+        assert_eq!(cache.reusable_count(5, 80, false, &[], &no_expanded), 0);
+        cache.heights = vec![1; 5];
+
+        // Case 3c: Density changed -> heights dropped
+        cache.set_density(Density::Compact);
+        assert!(cache.heights.is_empty());
+        cache.heights = vec![1; 5];
+
         // Case 4: Loading (last message is volatile) -> n-1 reusable
         let volatile_items = vec![crate::inference::ContextItem::Message(
             crate::inference::ContextSegment {
                 source: Source::Model,
                 content: String::new(),
+                timestamp: None,
             },
         )];
         cache.update_metadata(1, 80, &no_expanded);
@@ -565,6 +1574,7 @@ mod tests {
             crate::inference::ContextSegment {
                 source: Source::User,
                 content: String::new(),
+                timestamp: None,
             },
         )];
         cache.update_metadata(1, 80, &no_expanded);
@@ -587,10 +1597,12 @@ mod tests {
             crate::inference::ContextItem::Message(crate::inference::ContextSegment {
                 source: Source::User,
                 content: "hello".into(),
+                timestamp: None,
             }),
             crate::inference::ContextItem::Message(crate::inference::ContextSegment {
                 source: Source::Model,
                 content: "full response".into(),
+                timestamp: None,
             }),
         ];
 
@@ -602,10 +1614,12 @@ mod tests {
             crate::inference::ContextItem::Message(crate::inference::ContextSegment {
                 source: Source::Model,
                 content: "response".into(),
+                timestamp: None,
             }),
             crate::inference::ContextItem::Message(crate::inference::ContextSegment {
                 source: Source::User,
                 content: "follow-up".into(),
+                timestamp: None,
             }),
         ];
         assert_eq!(
@@ -630,10 +1644,12 @@ mod tests {
         let user_seg = ContextSegment {
             source: Source::User,
             content: "hi".into(),
+            timestamp: None,
         };
         let partial_model = ContextSegment {
             source: Source::Model,
             content: "short".into(),
+            timestamp: None,
         };
 
         // --- Frame 1: mid-stream, cache the partial model message ---
@@ -648,7 +1664,13 @@ mod tests {
 
         for item in &items_streaming {
             let h = match item {
-                ContextItem::Message(seg) => Message::calculate_height(seg, width),
+                ContextItem::Message(seg) => Message::calculate_height(
+                    seg,
+                    width,
+                    &Theme::default(),
+                    true,
+                    Density::default(),
+                ),
                 _ => unreachable!(),
             };
             cache.heights.push(h);
@@ -663,6 +1685,7 @@ mod tests {
             source: Source::Model,
             content: "this response is long enough to wrap across multiple lines at width 30"
                 .into(),
+            timestamp: None,
         };
         let items_done: Vec<ContextItem> = vec![
             ContextItem::Message(user_seg.clone()),
@@ -680,7 +1703,13 @@ mod tests {
         // Recalculate from reusable onward (index 1)
         for item in items_done.iter().skip(cache.heights.len()) {
             let h = match item {
-                ContextItem::Message(seg) => Message::calculate_height(seg, width),
+                ContextItem::Message(seg) => Message::calculate_height(
+                    seg,
+                    width,
+                    &Theme::default(),
+                    true,
+                    Density::default(),
+                ),
                 _ => unreachable!(),
             };
             cache.heights.push(h);
@@ -697,10 +1726,120 @@ mod tests {
         );
 
         // User message height must be unchanged (was reusable)
-        let expected_user_height = Message::calculate_height(&user_seg, width);
+        let expected_user_height = Message::calculate_height(
+            &user_seg,
+            width,
+            &Theme::default(),
+            true,
+            Density::default(),
+        );
         assert_eq!(cache.heights[0], expected_user_height);
     }
 
+    #[test]
+    fn test_horizontal_scroll_steps_and_stops_at_zero() {
+        let mut state = MessageListState::new();
+        state.handle_event(&TuiEvent::ScrollRight);
+        state.handle_event(&TuiEvent::ScrollRight);
+        assert_eq!(state.scroll_x, 2 * H_SCROLL_STEP);
+        state.handle_event(&TuiEvent::ScrollLeft);
+        state.handle_event(&TuiEvent::ScrollLeft);
+        state.handle_event(&TuiEvent::ScrollLeft);
+        assert_eq!(state.scroll_x, 0);
+    }
+
+    #[test]
+    fn test_restored_scroll_position_round_trips() {
+        let saved = ScrollPosition {
+            offset: 17,
+            stick_to_bottom: false,
+        };
+        let state = MessageListState::restored(saved);
+        assert_eq!(state.scroll_position(), saved);
+        assert!(!state.stick_to_bottom);
+
+        // Saved at the bottom: auto-scroll stays engaged
+        let state = MessageListState::restored(ScrollPosition::default());
+        assert!(state.stick_to_bottom);
+        assert_eq!(state.scroll_position(), ScrollPosition::default());
+    }
+
+    #[test]
+    fn test_restored_offset_clamped_when_content_shrank() {
+        let mut state = MessageListState::restored(ScrollPosition {
+            offset: 90,
+            stick_to_bottom: false,
+        });
+        // 30 rows of content in a 10-row viewport: the furthest offset is 20
+        state.layout = cache_with_heights(vec![10, 10, 10]);
+        state.viewport_height = 10;
+        state.clamp_scroll();
+        assert_eq!(state.scroll_position().offset, 20);
+        assert!(!state.stick_to_bottom);
+    }
+
+    #[test]
+    fn test_toggle_pin_twice_unpins() {
+        let mut state = MessageListState::new();
+        assert!(state.toggle_pin(3));
+        assert!(state.pinned_indices.contains(&3));
+        assert!(!state.toggle_pin(3));
+        assert!(state.pinned_indices.is_empty());
+    }
+
+    #[test]
+    fn test_jump_to_pin_wraps_around() {
+        let mut state = MessageListState::new().with_pins(&[2, 5, 9]);
+        state.layout = cache_with_heights(vec![3; 10]);
+        state.viewport_height = 6;
+
+        // Nothing selected: forward starts at the first pin, back at the last
+        assert!(state.jump_to_pin(true));
+        assert_eq!(state.selected_index, Some(2));
+        state.selected_index = None;
+        assert!(state.jump_to_pin(false));
+        assert_eq!(state.selected_index, Some(9));
+
+        // Past the last pin wraps to the first, and back again
+        assert!(state.jump_to_pin(true));
+        assert_eq!(state.selected_index, Some(2));
+        assert!(state.jump_to_pin(false));
+        assert_eq!(state.selected_index, Some(9));
+
+        // From an unpinned message, the nearest pin in that direction
+        state.selected_index = Some(6);
+        assert!(state.jump_to_pin(false));
+        assert_eq!(state.selected_index, Some(5));
+
+        // The jump scrolls the pin into view
+        assert_eq!(state.scroll_state.offset().y, 15);
+    }
+
+    #[test]
+    fn test_jump_without_pins_keeps_selection() {
+        let mut state = MessageListState::new();
+        state.selected_index = Some(1);
+        assert!(!state.jump_to_pin(true));
+        assert_eq!(state.selected_index, Some(1));
+    }
+
+    #[test]
+    fn test_unseen_lines_counts_growth_while_scrolled_up() {
+        let mut state = MessageListState::new();
+        state.last_seen_height = 40;
+
+        // Pinned to bottom: nothing is unseen regardless of growth
+        assert_eq!(state.unseen_lines(55), 0);
+
+        // Scrolled up: growth past the last-seen height is reported
+        state.stick_to_bottom = false;
+        assert_eq!(state.unseen_lines(40), 0);
+        assert_eq!(state.unseen_lines(55), 15);
+
+        // Content shrank (e.g. layout recalculated narrower) — never underflows
+        assert_eq!(state.unseen_lines(30), 0);
+    }
+
     #[test]
     fn test_expansion_toggle_invalidates_cache() {
         let mut cache = LayoutCache::new();
@@ -712,6 +1851,7 @@ mod tests {
             crate::inference::ContextItem::Message(crate::inference::ContextSegment {
                 source: Source::User,
                 content: "hello".into(),
+                timestamp: None,
             }),
             crate::inference::ContextItem::ToolCall(crate::inference::ToolCall {
                 id: "fc_1".into(),
@@ -722,6 +1862,7 @@ mod tests {
             crate::inference::ContextItem::Message(crate::inference::ContextSegment {
                 source: Source::Model,
                 content: "done".into(),
+                timestamp: None,
             }),
         ];
 
@@ -733,4 +1874,115 @@ mod tests {
             1 // Only index 0 reusable; 1 and 2 need recalc
         );
     }
+
+    #[test]
+    fn test_selection_follows_item_while_streaming() {
+        use crate::inference::{ToolCall, ToolResult};
+
+        let call = |id: &str| ToolCall {
+            id: format!("fc_{id}"),
+            call_id: id.into(),
+            name: "add".into(),
+            arguments: "{}".into(),
+        };
+        let result = |id: &str| ToolResult {
+            call_id: id.into(),
+            output: "{}".into(),
+        };
+        let mut context = Context::new();
+        context.add_user_message("question".into());
+        context.add_tool_call(call("call_1"));
+        context.add_tool_call(call("call_2"));
+        let mut state = MessageListState::new();
+        state.selected_index = Some(2);
+        state.track_selection(&context.items);
+
+        // Results land out of order, then the reply streams in
+        context.add_tool_result(result("call_2"));
+        context.add_tool_result(result("call_1"));
+        context.append_to_last_model_message("answer", None);
+        state.track_selection(&context.items);
+        assert_eq!(state.selected_index, Some(2));
+        assert_eq!(context.items[2], ContextItem::ToolCall(call("call_1")));
+
+        // An item inserted before the selection moves it along
+        state.selected_index = Some(1);
+        state.track_selection(&context.items);
+        context
+            .items
+            .insert(1, ContextItem::ToolResult(result("call_0")));
+        state.track_selection(&context.items);
+        assert_eq!(state.selected_index, Some(2));
+        assert!(
+            matches!(&context.items[2], ContextItem::Message(seg) if seg.content == "question")
+        );
+
+        // Removing the selected item, as cancelling an empty reply does,
+        // clears the selection
+        state.selected_index = Some(context.items.len() - 1);
+        state.track_selection(&context.items);
+        context.items.pop();
+        state.track_selection(&context.items);
+        assert_eq!(state.selected_index, None);
+    }
+
+    #[test]
+    fn test_result_map_shows_untruncated_output() {
+        use crate::inference::{ToolCall, ToolResult};
+
+        let mut context = Context::new();
+        context.add_tool_call(ToolCall {
+            id: "fc_1".into(),
+            call_id: "call_1".into(),
+            name: "read_file".into(),
+            arguments: "{}".into(),
+        });
+        let full = "x".repeat(100);
+        context.add_tool_result_capped(
+            ToolResult {
+                call_id: "call_1".into(),
+                output: full.clone(),
+            },
+            10,
+        );
+
+        let (result_map, consumed) = build_result_map(&context);
+        assert_eq!(result_map["call_1"].output, full);
+        assert!(consumed.contains(&2));
+        // The model still gets the capped copy
+        assert!(
+            matches!(&context.items[2], ContextItem::ToolResult(tr) if tr.output.len() < full.len())
+        );
+    }
+
+    #[test]
+    fn test_tool_result_invalidates_its_call() {
+        use crate::inference::{ContextItem, ContextSegment, ToolCall, ToolResult};
+
+        let mut cache = LayoutCache::new();
+        let no_expanded = HashSet::new();
+        cache.heights = vec![3, 3];
+        cache.update_metadata(2, 80, &no_expanded);
+
+        let mut items = vec![
+            ContextItem::Message(ContextSegment {
+                source: Source::User,
+                content: "hello".into(),
+                timestamp: None,
+            }),
+            ContextItem::ToolCall(ToolCall {
+                id: "fc_1".into(),
+                call_id: "call_1".into(),
+                name: "add".into(),
+                arguments: "{}".into(),
+            }),
+        ];
+        assert_eq!(cache.reusable_count(2, 80, true, &items, &no_expanded), 2);
+
+        items.push(ContextItem::ToolResult(ToolResult {
+            call_id: "call_1".into(),
+            output: "{}".into(),
+        }));
+        assert_eq!(cache.reusable_count(3, 80, true, &items, &no_expanded), 1);
+    }
 }
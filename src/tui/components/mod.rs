@@ -9,11 +9,14 @@
 //! ### Stateless Components (Props-Based Rendering)
 //!
 //! Simple display components that receive all data as parameters:
-//! - `TitleBar`: Top status bar showing model name and status
+//! - `TitleBar`: Top status bar showing model name, effort and status
+//! - `TabBar`: Strip of open conversation tabs
 //! - `Message`: Individual conversation message rendering
 //! - `LandingPage`: Welcome screen when no messages exist
 //! - `Logo`: ASCII art logo rendering
+//! - `Spinner`: Configurable loading indicator styles
 //! - `ToolMessage`: Tool call/result display
+//! - `StatusHints`: Footer of key hints for the current mode
 //!
 //! ### Stateful Components (Event-Driven)
 //!
@@ -22,6 +25,8 @@
 //! - `MessageList`: Scrollable conversation view with layout caching
 //! - `SessionManager`: Session list overlay with create/rename/delete
 //! - `ModelPicker`: Fuzzy-search model selection overlay
+//! - `HelpOverlay`: Keybinding reference overlay (stateless, driven by `KEY_BINDINGS`)
+//! - `DiffOverlay`: Word diff of a regenerated answer against the previous one
 //!
 //! ## Design Philosophy
 //!
@@ -64,20 +69,28 @@
 //! components/
 //! ├── mod.rs            (this file)
 //! ├── title_bar.rs      (Top status bar)
+//! ├── tab_bar.rs        (Conversation tabs strip)
 //! ├── message.rs        (Single message renderer)
 //! ├── message_list.rs   (Scrollable message container)
 //! ├── input_box/        (Text input with effort indicator)
 //! ├── tool_message.rs   (Tool call/result display)
 //! ├── landing.rs        (Welcome/landing page)
 //! ├── logo.rs           (ASCII art logo)
+//! ├── spinner.rs        (Loading indicator styles)
+//! ├── status_hints.rs   (Key hints footer)
 //! ├── session_manager.rs(Session list overlay)
-//! └── model_picker.rs   (Model selection overlay)
+//! ├── model_picker.rs   (Model selection overlay)
+//! └── help_overlay.rs   (Keybinding reference overlay)
 //! ```
 
 // Re-export components
+mod tab_bar;
 mod title_bar;
+pub use tab_bar::TabBar;
 pub use title_bar::TitleBar;
 
+pub mod diff_overlay;
+pub mod help_overlay;
 pub mod input_box;
 pub mod message;
 pub use input_box::{InputBox, InputEvent};
@@ -87,7 +100,12 @@ pub mod landing;
 pub mod logo;
 pub mod model_picker;
 pub mod session_manager;
+pub mod spinner;
+pub mod status_hints;
 pub mod tool_message;
+pub use diff_overlay::{DiffOverlay, DiffOverlayState};
+pub use help_overlay::HelpOverlay;
 pub use landing::LandingPage;
 pub use model_picker::{ModelPicker, ModelPickerState};
 pub use session_manager::{SessionManager, SessionManagerState};
+pub use status_hints::{HintMode, StatusHints};
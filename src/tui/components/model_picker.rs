@@ -1,6 +1,7 @@
 //! # Model Picker Component
 //!
-//! Full-screen overlay for switching models at runtime. Opened with Ctrl+P.
+//! Full-screen overlay for switching models at runtime. Opened with Ctrl+P
+//! (or Ctrl+M on terminals that speak the Kitty keyboard protocol).
 //! Supports cross-provider switching (OpenRouter ↔ LM Studio).
 //!
 //! Models come from two sources:
@@ -88,7 +89,12 @@ impl ModelPickerState {
         // Dedup: exclude any fetched model whose name+provider matches a pinned model
         self.fetched_models = models
             .into_iter()
-            .filter(|m| !self.pinned_models.contains(m))
+            .filter(|m| {
+                !self
+                    .pinned_models
+                    .iter()
+                    .any(|p| p.same_model(&m.name, &m.provider))
+            })
             .collect();
         self.fetch_status = FetchStatus::Loaded;
         self.rebuild_filter();
@@ -392,11 +398,13 @@ mod tests {
                 name: "anthropic/claude-sonnet-4".to_string(),
                 provider: "openrouter".to_string(),
                 description: Some("Fast reasoning".to_string()),
+                ..Default::default()
             },
             ModelEntry {
                 name: "qwen2.5-coder-32b".to_string(),
                 provider: "lmstudio".to_string(),
                 description: None,
+                ..Default::default()
             },
         ]
     }
@@ -407,11 +415,13 @@ mod tests {
                 name: "google/gemini-2.5-flash".to_string(),
                 provider: "openrouter".to_string(),
                 description: Some("Gemini Flash".to_string()),
+                ..Default::default()
             },
             ModelEntry {
                 name: "meta-llama/llama-3.1-70b".to_string(),
                 provider: "openrouter".to_string(),
                 description: Some("Llama 3.1 70B".to_string()),
+                ..Default::default()
             },
         ]
     }
@@ -433,6 +443,7 @@ mod tests {
             name: "anthropic/claude-sonnet-4".to_string(),
             provider: "openrouter".to_string(),
             description: Some("Fast reasoning".to_string()),
+            ..Default::default()
         });
 
         picker.set_fetched_models(fetched);
@@ -611,4 +622,24 @@ mod tests {
         // Out of bounds
         assert!(picker.get_model(4).is_none());
     }
+
+    #[test]
+    fn test_render_lists_pinned_models() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let mut state = ModelPickerState::new(sample_pinned());
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal
+            .draw(|frame| {
+                ModelPicker::new(&mut state, "qwen2.5-coder-32b").render(frame, frame.area())
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("anthropic/claude-sonnet-4"));
+        assert!(text.contains("qwen2.5-coder-32b"));
+        assert!(text.contains("lmstudio"));
+    }
 }
@@ -1,20 +1,44 @@
 //! # Session Manager Component
 //!
 //! Full-screen overlay for browsing, loading, and deleting saved sessions.
-//! Opened with Ctrl+O, dismissed with Esc.
+//! Opened with Ctrl+O, dismissed with Esc. Sessions can be tagged (`t`) and
+//! the list filtered to one tag at a time (`f` cycles through them).
+//!
+//! `/` searches titles fuzzily: the list narrows to matching sessions, best
+//! match first, with the matched characters highlighted, and Enter opens
+//! the highlighted one.
+//!
+//! A preview pane beside the list shows the opening messages of the
+//! highlighted session. Each session is read from disk the first time it is
+//! highlighted and cached for as long as the overlay stays open.
 //!
 //! Follows the persistent state + transient wrapper pattern:
 //! - `SessionManagerState` lives in `TuiState`
 //! - `SessionManager` is created each frame with borrowed state
 
+use std::collections::HashMap;
+
+use log::warn;
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph};
-
-use crate::core::session::SessionMeta;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{
+    Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph, Wrap,
+};
+
+use crate::core::fuzzy::fuzzy_match;
+use crate::core::session::{self, SessionMeta};
+use crate::inference::{ContextItem, ContextSegment, Source};
 use crate::tui::event::TuiEvent;
+use crate::tui::markdown::{self, RenderOptions};
+use crate::tui::theme::Theme;
+
+/// Messages shown in the preview pane.
+const PREVIEW_MESSAGES: usize = 4;
+
+/// Overlays narrower than this show the list only.
+const MIN_PREVIEW_WIDTH: u16 = 70;
 
 /// Inline rename editing state.
 pub struct RenameState {
@@ -24,11 +48,24 @@ pub struct RenameState {
 
 /// Persistent state for the session manager overlay.
 pub struct SessionManagerState {
+    /// Sessions shown in the list (all of them, or those matching
+    /// `tag_filter` and `search`, best search match first).
     pub sessions: Vec<SessionMeta>,
+    /// Matched title character indices, parallel to `sessions`.
+    matched_chars: Vec<Vec<usize>>,
+    /// Every session in the index, regardless of the filter.
+    all_sessions: Vec<SessionMeta>,
     pub selected: usize,
     pub confirm_delete: bool,
     pub list_state: ListState,
     pub rename: Option<RenameState>,
+    /// Tag being typed for the selected session (`t`); Enter toggles it.
+    pub tag_input: Option<String>,
+    pub tag_filter: Option<String>,
+    /// Fuzzy title search being typed (`/`); None when not searching.
+    pub search: Option<String>,
+    /// Preview messages per session id, filled as sessions are highlighted.
+    previews: HashMap<String, Vec<ContextSegment>>,
 }
 
 impl SessionManagerState {
@@ -38,14 +75,113 @@ impl SessionManagerState {
             list_state.select(Some(0));
         }
         Self {
+            all_sessions: sessions.clone(),
+            matched_chars: vec![Vec::new(); sessions.len()],
             sessions,
             selected: 0,
             confirm_delete: false,
             list_state,
             rename: None,
+            tag_input: None,
+            tag_filter: None,
+            search: None,
+            previews: HashMap::new(),
+        }
+    }
+
+    /// Preview messages for the highlighted session, read from disk on
+    /// first use. A session that fails to load previews as empty.
+    fn selected_preview(&mut self) -> Option<&[ContextSegment]> {
+        let id = self.sessions.get(self.selected)?.id.clone();
+        let preview = self
+            .previews
+            .entry(id)
+            .or_insert_with_key(|id| load_preview(id));
+        Some(preview)
+    }
+
+    /// All distinct tags across sessions, sorted case-insensitively.
+    fn known_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.all_sessions.iter().flat_map(|m| &m.tags) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags
+    }
+
+    /// Advance the filter: no filter → first tag → ... → last tag → no filter.
+    fn cycle_tag_filter(&mut self) {
+        let tags = self.known_tags();
+        let next = match &self.tag_filter {
+            None => tags.first().cloned(),
+            Some(current) => tags
+                .iter()
+                .position(|t| t.eq_ignore_ascii_case(current))
+                .and_then(|i| tags.get(i + 1).cloned()),
+        };
+        self.tag_filter = next;
+        self.apply_filter();
+    }
+
+    /// Rebuild the visible list from `all_sessions` and clamp the selection.
+    /// While searching, sessions are ranked by how well their titles match;
+    /// equal scores keep their most-recent-first order.
+    fn apply_filter(&mut self) {
+        let query = self.search.as_deref().unwrap_or_default();
+        let mut ranked: Vec<(i32, SessionMeta, Vec<usize>)> = self
+            .all_sessions
+            .iter()
+            .filter(|m| self.tag_filter.as_ref().is_none_or(|tag| m.has_tag(tag)))
+            .filter_map(|m| {
+                let found = fuzzy_match(query, &m.title)?;
+                Some((found.score, m.clone(), found.positions))
+            })
+            .collect();
+        ranked.sort_by_key(|(score, ..)| std::cmp::Reverse(*score));
+        (self.sessions, self.matched_chars) = ranked
+            .into_iter()
+            .map(|(_, meta, chars)| (meta, chars))
+            .unzip();
+        if self.sessions.is_empty() {
+            self.selected = 0;
+            self.list_state.select(None);
+        } else {
+            self.selected = self.selected.min(self.sessions.len() - 1);
+            self.list_state.select(Some(self.selected));
         }
     }
 
+    /// Apply `edit` to session `id` in both the visible and full lists.
+    fn edit_session(&mut self, id: &str, edit: impl Fn(&mut SessionMeta)) {
+        for meta in self
+            .sessions
+            .iter_mut()
+            .chain(self.all_sessions.iter_mut())
+            .filter(|m| m.id == id)
+        {
+            edit(meta);
+        }
+    }
+
+    /// Toggle `tag` on the selected session, returning the event to persist it.
+    fn toggle_tag(&mut self, tag: String) -> Option<SessionEvent> {
+        let session = self.sessions.get(self.selected)?;
+        let id = session.id.clone();
+        let event = if session.has_tag(&tag) {
+            self.edit_session(&id, |m| m.tags.retain(|t| !t.eq_ignore_ascii_case(&tag)));
+            SessionEvent::RemoveTag { id, tag }
+        } else {
+            self.edit_session(&id, |m| m.tags.push(tag.clone()));
+            SessionEvent::AddTag { id, tag }
+        };
+        // Removing the filtered tag hides the session
+        self.apply_filter();
+        Some(event)
+    }
+
     /// Handle a key event, returning a SessionEvent if the overlay should act.
     pub fn handle_event(&mut self, event: &TuiEvent) -> Option<SessionEvent> {
         // Rename mode intercepts all input
@@ -58,7 +194,7 @@ impl SessionManagerState {
                     let new_title = rs.buffer.trim().to_string();
                     if !new_title.is_empty() {
                         let id = self.sessions[self.selected].id.clone();
-                        self.sessions[self.selected].title = new_title.clone();
+                        self.edit_session(&id, |m| m.title = new_title.clone());
                         self.rename = None;
                         return Some(SessionEvent::Rename { id, new_title });
                     }
@@ -68,35 +204,87 @@ impl SessionManagerState {
                     rs.buffer.insert(rs.cursor, *ch);
                     rs.cursor += ch.len_utf8();
                 }
-                TuiEvent::Backspace => {
-                    if rs.cursor > 0 {
-                        // Find previous char boundary
-                        let prev = rs.buffer[..rs.cursor]
-                            .char_indices()
-                            .next_back()
-                            .map(|(i, _)| i)
-                            .unwrap_or(0);
-                        rs.buffer.drain(prev..rs.cursor);
-                        rs.cursor = prev;
-                    }
+                TuiEvent::Backspace if rs.cursor > 0 => {
+                    // Find previous char boundary
+                    let prev = rs.buffer[..rs.cursor]
+                        .char_indices()
+                        .next_back()
+                        .map(|(i, _)| i)
+                        .unwrap_or(0);
+                    rs.buffer.drain(prev..rs.cursor);
+                    rs.cursor = prev;
                 }
-                TuiEvent::CursorLeft => {
-                    if rs.cursor > 0 {
-                        rs.cursor = rs.buffer[..rs.cursor]
-                            .char_indices()
-                            .next_back()
-                            .map(|(i, _)| i)
-                            .unwrap_or(0);
-                    }
+                TuiEvent::CursorLeft if rs.cursor > 0 => {
+                    rs.cursor = rs.buffer[..rs.cursor]
+                        .char_indices()
+                        .next_back()
+                        .map(|(i, _)| i)
+                        .unwrap_or(0);
                 }
-                TuiEvent::CursorRight => {
-                    if rs.cursor < rs.buffer.len() {
-                        rs.cursor += rs.buffer[rs.cursor..]
-                            .chars()
-                            .next()
-                            .map_or(0, |c| c.len_utf8());
+                TuiEvent::CursorRight if rs.cursor < rs.buffer.len() => {
+                    rs.cursor += rs.buffer[rs.cursor..]
+                        .chars()
+                        .next()
+                        .map_or(0, |c| c.len_utf8());
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        // Search intercepts typing; arrows still move and Enter opens
+        if let Some(query) = self.search.as_mut() {
+            let edited = match event {
+                TuiEvent::InputChar(ch) => {
+                    query.push(*ch);
+                    true
+                }
+                TuiEvent::Backspace => query.pop().is_some(),
+                _ => false,
+            };
+            if edited {
+                // The best match is at the top
+                self.selected = 0;
+                self.apply_filter();
+                return None;
+            }
+            match event {
+                TuiEvent::Escape => {
+                    self.search = None;
+                    self.apply_filter();
+                }
+                TuiEvent::CursorUp => self.move_selection(false),
+                TuiEvent::CursorDown => self.move_selection(true),
+                TuiEvent::Submit => {
+                    return self
+                        .sessions
+                        .get(self.selected)
+                        .map(|session| SessionEvent::Load(session.id.clone()));
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        // Tag input intercepts all input too
+        if let Some(ref mut buffer) = self.tag_input {
+            match event {
+                TuiEvent::Escape => {
+                    self.tag_input = None;
+                }
+                TuiEvent::Submit => {
+                    let tag = buffer.trim().to_string();
+                    self.tag_input = None;
+                    if !tag.is_empty() {
+                        return self.toggle_tag(tag);
                     }
                 }
+                TuiEvent::InputChar(ch) if !ch.is_whitespace() => {
+                    buffer.push(*ch);
+                }
+                TuiEvent::Backspace => {
+                    buffer.pop();
+                }
                 _ => {}
             }
             return None;
@@ -112,17 +300,11 @@ impl SessionManagerState {
         match event {
             TuiEvent::Escape => Some(SessionEvent::Dismiss),
             TuiEvent::CursorUp => {
-                if !self.sessions.is_empty() {
-                    self.selected = self.selected.saturating_sub(1);
-                    self.list_state.select(Some(self.selected));
-                }
+                self.move_selection(false);
                 None
             }
             TuiEvent::CursorDown => {
-                if !self.sessions.is_empty() {
-                    self.selected = (self.selected + 1).min(self.sessions.len() - 1);
-                    self.list_state.select(Some(self.selected));
-                }
+                self.move_selection(true);
                 None
             }
             TuiEvent::Submit => self
@@ -141,6 +323,20 @@ impl SessionManagerState {
                 }
                 None
             }
+            TuiEvent::InputChar('t') => {
+                if !self.sessions.is_empty() {
+                    self.tag_input = Some(String::new());
+                }
+                None
+            }
+            TuiEvent::InputChar('f') => {
+                self.cycle_tag_filter();
+                None
+            }
+            TuiEvent::InputChar('/') => {
+                self.search = Some(String::new());
+                None
+            }
             TuiEvent::InputChar('d') => {
                 if self.sessions.is_empty() {
                     return None;
@@ -158,16 +354,23 @@ impl SessionManagerState {
         }
     }
 
-    /// Remove a session from the local list after deletion.
-    pub fn remove_session(&mut self, id: &str) {
-        self.sessions.retain(|s| s.id != id);
+    fn move_selection(&mut self, down: bool) {
         if self.sessions.is_empty() {
-            self.selected = 0;
-            self.list_state.select(None);
-        } else {
-            self.selected = self.selected.min(self.sessions.len() - 1);
-            self.list_state.select(Some(self.selected));
+            return;
         }
+        self.selected = if down {
+            (self.selected + 1).min(self.sessions.len() - 1)
+        } else {
+            self.selected.saturating_sub(1)
+        };
+        self.list_state.select(Some(self.selected));
+    }
+
+    /// Remove a session from the local list after deletion.
+    pub fn remove_session(&mut self, id: &str) {
+        self.all_sessions.retain(|s| s.id != id);
+        self.previews.remove(id);
+        self.apply_filter();
     }
 }
 
@@ -177,17 +380,73 @@ pub enum SessionEvent {
     CreateNew,
     Delete(String),
     Rename { id: String, new_title: String },
+    AddTag { id: String, tag: String },
+    RemoveTag { id: String, tag: String },
     Dismiss,
 }
 
+fn load_preview(id: &str) -> Vec<ContextSegment> {
+    match session::read_session(id) {
+        Ok(data) => preview_messages(&data.items, PREVIEW_MESSAGES),
+        Err(e) => {
+            warn!("Failed to read session {} for preview: {}", id, e);
+            Vec::new()
+        }
+    }
+}
+
+/// The first `max` user and model messages; directives, thinking, status
+/// lines, tool traffic, and images are skipped.
+fn preview_messages(items: &[ContextItem], max: usize) -> Vec<ContextSegment> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            ContextItem::Message(seg) if matches!(seg.source, Source::User | Source::Model) => {
+                Some(seg.clone())
+            }
+            _ => None,
+        })
+        .take(max)
+        .collect()
+}
+
 /// Transient render wrapper for the session manager overlay.
 pub struct SessionManager<'a> {
     state: &'a mut SessionManagerState,
+    theme: &'a Theme,
 }
 
 impl<'a> SessionManager<'a> {
-    pub fn new(state: &'a mut SessionManagerState) -> Self {
-        Self { state }
+    pub fn new(state: &'a mut SessionManagerState, theme: &'a Theme) -> Self {
+        Self { state, theme }
+    }
+
+    /// Render the preview messages: a speaker label, then the markdown body
+    /// laid out for `width` columns.
+    fn preview_text(&mut self, width: u16) -> Text<'static> {
+        let theme = self.theme;
+        let Some(messages) = self.state.selected_preview() else {
+            return Text::default();
+        };
+        if messages.is_empty() {
+            return Text::styled("No messages.", Style::default().fg(Color::DarkGray));
+        }
+        let mut text = Text::default();
+        for seg in messages {
+            let (label, color) = match seg.source {
+                Source::User => ("You", theme.user),
+                _ => ("Model", theme.model),
+            };
+            text.lines.push(Line::styled(
+                label,
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ));
+            let options = RenderOptions { width, wrap: true };
+            text.lines
+                .extend(markdown::render(&seg.content, color, theme, options).lines);
+            text.lines.push(Line::default());
+        }
+        text
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
@@ -199,23 +458,41 @@ impl<'a> SessionManager<'a> {
 
         // Help bar text
         let help_text = if self.state.rename.is_some() {
-            " Enter Save  Esc Cancel "
+            String::from(" Enter Save  Esc Cancel ")
+        } else if let Some(ref tag) = self.state.tag_input {
+            format!(" Tag: {}_  Enter Add/Remove  Esc Cancel ", tag)
+        } else if let Some(ref query) = self.state.search {
+            format!(" Search: {}_  ↑↓ Move  Enter Open  Esc Clear ", query)
         } else if self.state.confirm_delete {
-            " Press d again to confirm delete | Esc Cancel "
+            String::from(" Press d again to confirm delete | Esc Cancel ")
         } else {
-            " n New  r Rename  d Delete  Enter Open  Esc Back "
+            String::from(
+                " / Search  n New  r Rename  t Tag  f Filter  d Delete  Enter Open  Esc Back ",
+            )
+        };
+
+        let title = match self.state.tag_filter {
+            Some(ref tag) => format!(" Sessions · {} ", tag),
+            None => String::from(" Sessions "),
         };
 
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::DarkGray))
-            .title(" Sessions ")
+            .title(title)
             .title_alignment(Alignment::Left)
             .title_bottom(Line::from(help_text).centered())
             .padding(Padding::horizontal(1));
 
         if self.state.sessions.is_empty() {
-            let empty = Paragraph::new("No saved sessions.")
+            let message = if self.state.search.is_some() {
+                "No matching sessions."
+            } else if self.state.tag_filter.is_some() {
+                "No sessions with this tag."
+            } else {
+                "No saved sessions."
+            };
+            let empty = Paragraph::new(message)
                 .style(Style::default().fg(Color::DarkGray))
                 .alignment(Alignment::Center)
                 .block(block);
@@ -223,8 +500,32 @@ impl<'a> SessionManager<'a> {
             return;
         }
 
-        // Calculate inner dimensions for title column
-        let inner_width = overlay.width.saturating_sub(4) as usize; // borders + padding
+        let inner = block.inner(overlay);
+        frame.render_widget(block, overlay);
+
+        // List on the left, preview of the highlighted session on the right
+        let (list_area, preview_area) = if overlay.width >= MIN_PREVIEW_WIDTH {
+            let [list, preview] =
+                Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+                    .areas(inner);
+            (list, Some(preview))
+        } else {
+            (inner, None)
+        };
+        if let Some(area) = preview_area {
+            let preview_block = Block::default()
+                .borders(Borders::LEFT)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .padding(Padding::horizontal(1));
+            let width = preview_block.inner(area).width;
+            let preview = Paragraph::new(self.preview_text(width))
+                .wrap(Wrap { trim: false })
+                .block(preview_block);
+            frame.render_widget(preview, area);
+        }
+
+        // Calculate dimensions for title column
+        let inner_width = list_area.width as usize;
 
         // Track cursor position for rename mode
         let mut rename_cursor_pos: Option<(u16, u16)> = None;
@@ -239,8 +540,11 @@ impl<'a> SessionManager<'a> {
                 let date = format_timestamp(session.updated_at);
                 let count = format!("{} msgs", session.message_count);
 
-                // Layout: "  Jan 15  <title>   12 msgs  "
-                let fixed_width = date.len() + 2 + count.len() + 2; // date + gaps + count
+                let chips = tag_chips(&session.tags);
+                let chips_width: usize = chips.iter().map(|c| c.width()).sum();
+
+                // Layout: "  Jan 15  <title>   rust  work  12 msgs  "
+                let fixed_width = date.len() + 2 + chips_width + count.len() + 2; // date + gaps + tags + count
                 let title_width = inner_width.saturating_sub(fixed_width);
 
                 let is_renaming = i == self.state.selected && self.state.rename.is_some();
@@ -251,12 +555,12 @@ impl<'a> SessionManager<'a> {
                     let padded = format!("{:<width$}", display, width = title_width);
 
                     // Compute cursor screen position:
-                    // overlay.x + 1(border) + 1(padding) + date.len() + 2(gap) + cursor_in_title
+                    // list_area.x + date.len() + 2(gap) + cursor_in_title
                     let cursor_in_title = rs.cursor.min(title_width);
-                    let cursor_x = overlay.x + 2 + date.len() as u16 + 2 + cursor_in_title as u16;
-                    // overlay.y + 1(border) + row index (relative to list scroll)
+                    let cursor_x = list_area.x + date.len() as u16 + 2 + cursor_in_title as u16;
+                    // list_area.y + row index (relative to list scroll)
                     let visible_row = i.saturating_sub(self.state.list_state.offset());
-                    let cursor_y = overlay.y + 1 + visible_row as u16;
+                    let cursor_y = list_area.y + visible_row as u16;
                     rename_cursor_pos = Some((cursor_x, cursor_y));
 
                     let style = Style::default()
@@ -283,21 +587,33 @@ impl<'a> SessionManager<'a> {
                     (padded, style)
                 };
 
-                let line = Line::from(vec![
-                    Span::styled(date, style),
-                    Span::styled("  ", style),
-                    Span::styled(padded_title, style),
-                    Span::styled("  ", style),
-                    Span::styled(count, style),
-                ]);
+                let mut spans = vec![Span::styled(date, style), Span::styled("  ", style)];
+                match self.state.matched_chars.get(i) {
+                    Some(chars) if !is_renaming && !chars.is_empty() => {
+                        // Matches cut off by truncation would land on the "..."
+                        let shown = if session.title.len() > title_width {
+                            title_width.saturating_sub(3)
+                        } else {
+                            title_width
+                        };
+                        let chars: Vec<usize> =
+                            chars.iter().copied().filter(|&c| c < shown).collect();
+                        spans.extend(highlight_chars(padded_title, &chars, style));
+                    }
+                    _ => spans.push(Span::styled(padded_title, style)),
+                }
+                spans.extend(chips);
+                spans.push(Span::styled("  ", style));
+                spans.push(Span::styled(count, style));
+                let line = Line::from(spans);
 
                 ListItem::new(line)
             })
             .collect();
 
-        let list = List::new(items).block(block);
+        let list = List::new(items);
 
-        frame.render_stateful_widget(list, overlay, &mut self.state.list_state);
+        frame.render_stateful_widget(list, list_area, &mut self.state.list_state);
 
         // Show cursor when renaming
         if let Some((cx, cy)) = rename_cursor_pos {
@@ -306,6 +622,41 @@ impl<'a> SessionManager<'a> {
     }
 }
 
+/// Split `title` into spans, with the characters at `chars` (char indices)
+/// picked out from the rest.
+fn highlight_chars(title: String, chars: &[usize], style: Style) -> Vec<Span<'static>> {
+    let matched = style.fg(Color::Yellow).add_modifier(Modifier::UNDERLINED);
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in title.chars().enumerate() {
+        let is_match = chars.contains(&i);
+        if is_match != run_matched && !run.is_empty() {
+            let run_style = if run_matched { matched } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_matched = is_match;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_matched { matched } else { style }));
+    }
+    spans
+}
+
+/// Render tags as chips, each preceded by a one-column gap.
+fn tag_chips(tags: &[String]) -> Vec<Span<'static>> {
+    let chip_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+    tags.iter()
+        .flat_map(|tag| {
+            [
+                Span::raw(" "),
+                Span::styled(format!(" {} ", tag), chip_style),
+            ]
+        })
+        .collect()
+}
+
 /// Format a Unix timestamp as "Jan 15 14:30" style date+time.
 fn format_timestamp(ts: i64) -> String {
     use chrono::{DateTime, Local, Utc};
@@ -342,3 +693,153 @@ fn centered_rect(percent_x: u16, percent_y: u16, outer: Rect) -> Rect {
     .areas(center_v);
     center
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::ToolResult;
+
+    fn meta(id: &str, tags: &[&str]) -> SessionMeta {
+        SessionMeta {
+            id: id.to_string(),
+            title: format!("Session {}", id),
+            created_at: 0,
+            updated_at: 0,
+            message_count: 1,
+            model_name: "test-model".to_string(),
+            provider_name: "openrouter".to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            scroll: Default::default(),
+            pins: Vec::new(),
+        }
+    }
+
+    fn visible_ids(state: &SessionManagerState) -> Vec<&str> {
+        state.sessions.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_preview_takes_first_messages_and_skips_directives() {
+        fn msg(source: Source, content: &str) -> ContextItem {
+            ContextItem::Message(ContextSegment {
+                source,
+                content: content.to_string(),
+                timestamp: None,
+            })
+        }
+        let items = vec![
+            msg(Source::Directive, "You are a helpful assistant."),
+            msg(Source::User, "one"),
+            msg(Source::Thinking, "hmm"),
+            ContextItem::ToolResult(ToolResult {
+                call_id: "call_1".to_string(),
+                output: "{}".to_string(),
+            }),
+            msg(Source::Model, "two"),
+            msg(Source::Status, "Error: boom"),
+            msg(Source::User, "three"),
+            msg(Source::Model, "four"),
+        ];
+
+        let preview = preview_messages(&items, 3);
+        let contents: Vec<&str> = preview.iter().map(|s| s.content.as_str()).collect();
+        assert_eq!(contents, vec!["one", "two", "three"]);
+        assert!(preview_messages(&items[..1], 3).is_empty());
+    }
+
+    #[test]
+    fn test_filter_cycles_through_tags() {
+        let mut state = SessionManagerState::new(vec![
+            meta("a", &["work"]),
+            meta("b", &[]),
+            meta("c", &["Rust", "work"]),
+        ]);
+
+        state.handle_event(&TuiEvent::InputChar('f'));
+        assert_eq!(state.tag_filter.as_deref(), Some("Rust"));
+        assert_eq!(visible_ids(&state), vec!["c"]);
+
+        state.handle_event(&TuiEvent::InputChar('f'));
+        assert_eq!(state.tag_filter.as_deref(), Some("work"));
+        assert_eq!(visible_ids(&state), vec!["a", "c"]);
+
+        state.handle_event(&TuiEvent::InputChar('f'));
+        assert_eq!(state.tag_filter, None);
+        assert_eq!(visible_ids(&state), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_tag_filter_ignores_case() {
+        let mut state = SessionManagerState::new(vec![
+            meta("a", &["rust", "work"]),
+            meta("b", &[]),
+            meta("c", &["Rust"]),
+        ]);
+
+        state.handle_event(&TuiEvent::InputChar('f'));
+        assert_eq!(state.tag_filter.as_deref(), Some("rust"));
+        assert_eq!(visible_ids(&state), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_search_ranks_fuzzy_matches_and_opens_best() {
+        let titled = |id: &str, title: &str| SessionMeta {
+            title: title.to_string(),
+            ..meta(id, &[])
+        };
+        let mut state = SessionManagerState::new(vec![
+            titled("a", "Cool film ideas"),
+            titled("b", "Rust lifetimes"),
+            titled("c", "claude model comparison"),
+        ]);
+
+        state.handle_event(&TuiEvent::InputChar('/'));
+        for ch in "clm".chars() {
+            state.handle_event(&TuiEvent::InputChar(ch));
+        }
+        assert_eq!(visible_ids(&state), vec!["c", "a"]);
+        assert_eq!(state.matched_chars[0], vec![0, 1, 7]);
+
+        let event = state.handle_event(&TuiEvent::Submit);
+        assert!(matches!(event, Some(SessionEvent::Load(id)) if id == "c"));
+
+        // Esc clears the search and restores the full list
+        state.handle_event(&TuiEvent::Escape);
+        assert_eq!(state.search, None);
+        assert_eq!(visible_ids(&state), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_highlight_chars_splits_runs() {
+        let style = Style::default();
+        let spans = highlight_chars("claude".to_string(), &[0, 1, 4], style);
+        let texts: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, ["cl", "au", "d", "e"]);
+        assert!(spans[0].style.add_modifier.contains(Modifier::UNDERLINED));
+        assert!(!spans[1].style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn test_tag_input_toggles_tag_on_selected_session() {
+        fn type_tag(state: &mut SessionManagerState) -> Option<SessionEvent> {
+            state.handle_event(&TuiEvent::InputChar('t'));
+            for ch in "rust".chars() {
+                state.handle_event(&TuiEvent::InputChar(ch));
+            }
+            state.handle_event(&TuiEvent::Submit)
+        }
+
+        let mut state = SessionManagerState::new(vec![meta("a", &[])]);
+
+        let added = type_tag(&mut state);
+        assert!(
+            matches!(added, Some(SessionEvent::AddTag { ref id, ref tag }) if id == "a" && tag == "rust")
+        );
+        assert_eq!(state.sessions[0].tags, vec!["rust"]);
+
+        let removed = type_tag(&mut state);
+        assert!(matches!(removed, Some(SessionEvent::RemoveTag { ref tag, .. }) if tag == "rust"));
+        assert!(state.sessions[0].tags.is_empty());
+        assert!(state.tag_input.is_none());
+    }
+}
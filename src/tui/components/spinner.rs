@@ -0,0 +1,127 @@
+//! # Spinner
+//!
+//! Loading indicator styles, selected by `[general] spinner` in the config.
+//! `Logo` animates the braille fairy; the text styles cycle short glyph frames.
+//! Unknown names are logged and fall back to the default.
+
+use log::warn;
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Flex, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::Paragraph;
+
+use super::logo::Logo;
+use crate::tui::theme::Theme;
+
+const DOTS_FRAMES: &[&str] = &["·  ", "·· ", "···", " ··", "  ·"];
+const BRAILLE_FRAMES: &[&str] = &["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+const LINE_FRAMES: &[&str] = &["-", "\\", "|", "/"];
+/// Text form of the logo style, for places too small for the canvas.
+const LOGO_TEXT_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Spinner {
+    /// Animated braille fairy (the landing-page logo)
+    #[default]
+    Logo,
+    Dots,
+    Braille,
+    Line,
+}
+
+impl Spinner {
+    /// Parse a config value; None or an unknown name gives the default.
+    pub fn from_config(name: Option<&str>) -> Self {
+        match name.map(str::to_lowercase).as_deref() {
+            None | Some("logo") => Spinner::Logo,
+            Some("dots") => Spinner::Dots,
+            Some("braille") => Spinner::Braille,
+            Some("line") => Spinner::Line,
+            Some(other) => {
+                warn!("Unknown spinner '{}', using logo", other);
+                Spinner::Logo
+            }
+        }
+    }
+
+    fn frames(self) -> &'static [&'static str] {
+        match self {
+            Spinner::Logo => LOGO_TEXT_FRAMES,
+            Spinner::Dots => DOTS_FRAMES,
+            Spinner::Braille => BRAILLE_FRAMES,
+            Spinner::Line => LINE_FRAMES,
+        }
+    }
+
+    /// Text frame for animation tick `i` (wraps around).
+    pub fn frame(self, i: usize) -> &'static str {
+        let frames = self.frames();
+        frames[i % frames.len()]
+    }
+
+    /// Draw the loading indicator centered in `area`, text styles in the
+    /// theme's model color.
+    pub fn render(self, frame: &mut Frame, area: Rect, frame_index: usize, theme: &Theme) {
+        if self == Spinner::Logo {
+            Logo::render(frame, area, frame_index);
+            return;
+        }
+
+        let [row] = Layout::vertical([Constraint::Length(1)])
+            .flex(Flex::Center)
+            .areas(area);
+        let text = Paragraph::new(self.frame(frame_index))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(theme.model));
+        frame.render_widget(text, row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Spinner; 4] = [
+        Spinner::Logo,
+        Spinner::Dots,
+        Spinner::Braille,
+        Spinner::Line,
+    ];
+
+    #[test]
+    fn test_frames_are_non_empty_and_stable() {
+        for spinner in ALL {
+            let len = spinner.frames().len();
+            assert!(len > 1, "{:?} should animate", spinner);
+            for i in 0..len * 2 {
+                assert!(!spinner.frame(i).is_empty(), "{:?} frame {}", spinner, i);
+                // Sequence wraps after one full cycle
+                assert_eq!(spinner.frame(i), spinner.frame(i + len));
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_config() {
+        assert_eq!(Spinner::from_config(None), Spinner::Logo);
+        assert_eq!(Spinner::from_config(Some("dots")), Spinner::Dots);
+        assert_eq!(Spinner::from_config(Some("Braille")), Spinner::Braille);
+        assert_eq!(Spinner::from_config(Some("line")), Spinner::Line);
+        assert_eq!(Spinner::from_config(Some("nope")), Spinner::Logo);
+    }
+
+    #[test]
+    fn test_text_spinner_uses_theme_color() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let theme = Theme::light();
+        let mut terminal = Terminal::new(TestBackend::new(5, 1)).unwrap();
+        terminal
+            .draw(|f| Spinner::Line.render(f, f.area(), 0, &theme))
+            .unwrap();
+        let cell = &terminal.backend().buffer()[(2, 0)];
+        assert_eq!(cell.symbol(), "-");
+        assert_eq!(cell.fg, theme.model);
+    }
+}
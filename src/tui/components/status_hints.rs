@@ -0,0 +1,132 @@
+//! # StatusHints Component
+//!
+//! One-line footer under the input box listing the keys that matter right
+//! now. The hints follow whatever has the keyboard: the input box, cursor
+//! mode, or an open overlay. The full list stays in the help overlay (`?`).
+
+use crate::tui::component::Component;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+/// What currently receives key presses, which picks the hints shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintMode {
+    Input,
+    Cursor,
+    SessionManager,
+    ModelPicker,
+    Help,
+    Diff,
+}
+
+impl HintMode {
+    /// (keys, action) pairs for this mode, most useful first so a narrow
+    /// terminal cuts the least useful ones.
+    fn hints(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            HintMode::Input => &[
+                ("Enter", "send"),
+                ("Alt+↑", "select messages"),
+                ("Ctrl+P", "model"),
+                ("Ctrl+O", "sessions"),
+                ("Ctrl+R", "effort"),
+                ("?", "help"),
+            ],
+            HintMode::Cursor => &[
+                ("↑↓", "move"),
+                ("Space", "expand"),
+                ("y", "copy"),
+                ("r", "quote"),
+                ("f", "fork"),
+                ("m", "pin"),
+                ("Enter", "back to input"),
+            ],
+            HintMode::SessionManager => &[
+                ("↑↓", "move"),
+                ("Enter", "open"),
+                ("/", "search"),
+                ("n", "new"),
+                ("Esc", "close"),
+            ],
+            HintMode::ModelPicker => &[
+                ("type", "filter"),
+                ("↑↓", "move"),
+                ("Enter", "switch"),
+                ("Esc", "close"),
+            ],
+            HintMode::Help => &[("any key", "close")],
+            HintMode::Diff => &[("↑↓", "scroll"), ("any key", "close")],
+        }
+    }
+}
+
+pub struct StatusHints {
+    mode: HintMode,
+}
+
+impl StatusHints {
+    pub fn new(mode: HintMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl Component for StatusHints {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let key_style = Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::BOLD);
+        let action_style = Style::default().fg(Color::DarkGray);
+        let mut spans: Vec<Span> = vec![Span::raw(" ")];
+        for (idx, (keys, action)) in self.mode.hints().iter().enumerate() {
+            if idx > 0 {
+                spans.push(Span::styled("  ", action_style));
+            }
+            spans.push(Span::styled(*keys, key_style));
+            spans.push(Span::styled(format!(" {}", action), action_style));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn render(mode: HintMode) -> String {
+        let backend = TestBackend::new(100, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| StatusHints::new(mode).render(f, f.area()))
+            .unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>()
+    }
+
+    #[test]
+    fn test_hints_follow_mode() {
+        let input = render(HintMode::Input);
+        assert!(input.contains("Enter send"), "got: {input}");
+        assert!(input.contains("? help"), "got: {input}");
+        assert!(!input.contains("quote"));
+
+        let cursor = render(HintMode::Cursor);
+        assert!(cursor.contains("r quote"), "got: {cursor}");
+        assert!(!cursor.contains("Enter send"));
+
+        let sessions = render(HintMode::SessionManager);
+        assert!(sessions.contains("/ search"), "got: {sessions}");
+        assert!(sessions.contains("Esc close"), "got: {sessions}");
+
+        assert!(render(HintMode::Help).contains("any key close"));
+    }
+}
@@ -0,0 +1,104 @@
+//! # TabBar Component
+//!
+//! Single-line strip of open conversation tabs, shown under the title bar
+//! when more than one tab is open. The active tab is highlighted; tabs with
+//! a response in flight carry a dot so background streams stay visible.
+
+use crate::tui::component::Component;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+/// Longest tab label, in characters, before it is cut with an ellipsis.
+const MAX_LABEL_CHARS: usize = 24;
+
+pub struct TabBar<'a> {
+    /// (label, is_loading) per tab, in order
+    tabs: &'a [(String, bool)],
+    active: usize,
+}
+
+impl<'a> TabBar<'a> {
+    pub fn new(tabs: &'a [(String, bool)], active: usize) -> Self {
+        Self { tabs, active }
+    }
+}
+
+/// Cut `label` to `MAX_LABEL_CHARS`, ending in "…" when shortened.
+fn truncate_label(label: &str) -> String {
+    if label.chars().count() <= MAX_LABEL_CHARS {
+        return label.to_string();
+    }
+    let mut cut: String = label.chars().take(MAX_LABEL_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+impl Component for TabBar<'_> {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let mut spans: Vec<Span> = Vec::new();
+        for (idx, (label, is_loading)) in self.tabs.iter().enumerate() {
+            if idx > 0 {
+                spans.push(Span::styled("│", Style::default().fg(Color::DarkGray)));
+            }
+            let style = if idx == self.active {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Blue)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let marker = if *is_loading { " •" } else { "" };
+            spans.push(Span::styled(
+                format!(" {} {}{} ", idx + 1, truncate_label(label), marker),
+                style,
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn render(width: u16, bar: &mut TabBar) -> String {
+        let backend = TestBackend::new(width, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| bar.render(f, f.area())).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>()
+    }
+
+    #[test]
+    fn test_tabs_rendered_in_order_with_loading_marker() {
+        let tabs = vec![
+            ("Session #1".to_string(), true),
+            ("Tab 2".to_string(), false),
+        ];
+        let text = render(80, &mut TabBar::new(&tabs, 1));
+        assert!(
+            text.starts_with(" 1 Session #1 • │ 2 Tab 2 "),
+            "got: {text}"
+        );
+    }
+
+    #[test]
+    fn test_long_label_truncated() {
+        assert_eq!(truncate_label("short"), "short");
+        let long = "a".repeat(40);
+        let cut = truncate_label(&long);
+        assert_eq!(cut.chars().count(), MAX_LABEL_CHARS);
+        assert!(cut.ends_with('…'));
+    }
+}
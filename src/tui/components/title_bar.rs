@@ -1,8 +1,10 @@
 //! # TitleBar Component
 //!
-//! Single-line status bar: navi branding, loading spinner, model (provider),
-//! session title, and session token count.
+//! Single-line status bar: navi branding, loading spinner with the live
+//! tok/s estimate, model (provider), reasoning effort badge, session title,
+//! session token count, provider rate limit, and context window usage gauge.
 
+use crate::inference::{Effort, RateLimitInfo};
 use crate::tui::component::Component;
 use ratatui::Frame;
 use ratatui::layout::Rect;
@@ -13,6 +15,9 @@ use ratatui::widgets::Paragraph;
 /// Braille circle-worm spinner frames (standard CLI pattern).
 const SPINNER_FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
+/// Cells in the context usage gauge.
+const GAUGE_WIDTH: usize = 8;
+
 pub struct TitleBar<'a> {
     model_name: &'a str,
     provider_name: &'a str,
@@ -20,6 +25,13 @@ pub struct TitleBar<'a> {
     spinner_frame: usize,
     session_title: &'a str,
     session_total_tokens: u32,
+    /// (estimated tokens in context, model context window)
+    context_usage: Option<(u32, u32)>,
+    rate_limit: Option<&'a RateLimitInfo>,
+    /// None for models without reasoning support, where effort is ignored
+    effort: Option<Effort>,
+    /// Estimated tok/s of the answer streaming now
+    live_rate: Option<f32>,
 }
 
 impl<'a> TitleBar<'a> {
@@ -30,6 +42,7 @@ impl<'a> TitleBar<'a> {
         spinner_frame: usize,
         session_title: &'a str,
         session_total_tokens: u32,
+        context_usage: Option<(u32, u32)>,
     ) -> Self {
         Self {
             model_name,
@@ -38,8 +51,30 @@ impl<'a> TitleBar<'a> {
             spinner_frame,
             session_title,
             session_total_tokens,
+            context_usage,
+            rate_limit: None,
+            effort: None,
+            live_rate: None,
         }
     }
+
+    /// Latest quota and credits reported by the provider, if any.
+    pub fn with_rate_limit(mut self, rate_limit: Option<&'a RateLimitInfo>) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Reasoning effort shown as a badge beside the model.
+    pub fn with_effort(mut self, effort: Option<Effort>) -> Self {
+        self.effort = effort;
+        self
+    }
+
+    /// Streaming speed shown beside the spinner while loading.
+    pub fn with_live_rate(mut self, live_rate: Option<f32>) -> Self {
+        self.live_rate = live_rate;
+        self
+    }
 }
 
 /// Format a token count compactly: "1.2k" for >= 1000, raw number otherwise.
@@ -53,6 +88,78 @@ fn format_tokens(n: u32) -> String {
     }
 }
 
+/// Percentage of the context window in use, capped at 100.
+fn context_percent(used: u32, window: u32) -> u32 {
+    if window == 0 {
+        return 100;
+    }
+    ((used as u64 * 100 / window as u64) as u32).min(100)
+}
+
+/// Green while there's room, yellow past half, red once nearly full.
+fn gauge_color(percent: u32) -> Color {
+    match percent {
+        0..50 => Color::Green,
+        50..80 => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+/// "ctx ▰▰▰▱▱▱▱▱ 42%" — filled cells rounded to the nearest eighth.
+fn context_gauge(used: u32, window: u32) -> Vec<Span<'static>> {
+    let percent = context_percent(used, window);
+    let filled = (percent as usize * GAUGE_WIDTH + 50) / 100;
+    let color = gauge_color(percent);
+    vec![
+        Span::styled("ctx ", Style::default().fg(Color::DarkGray)),
+        Span::styled("▰".repeat(filled), Style::default().fg(color)),
+        Span::styled(
+            "▱".repeat(GAUGE_WIDTH - filled),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(format!(" {percent}%"), Style::default().fg(color)),
+    ]
+}
+
+/// " High " on a background that warms with the effort; dim when off.
+fn effort_badge(effort: Effort) -> Span<'static> {
+    let bg = match effort {
+        Effort::None => Color::DarkGray,
+        Effort::Auto => Color::Blue,
+        Effort::Low => Color::Green,
+        Effort::Medium => Color::Yellow,
+        Effort::High => Color::Magenta,
+    };
+    Span::styled(
+        format!(" {} ", effort.label()),
+        Style::default().fg(Color::Black).bg(bg),
+    )
+}
+
+/// "3/20 req · $4.25" — yellow when few requests remain, red at none.
+fn rate_limit_label(info: &RateLimitInfo) -> Option<Span<'static>> {
+    let mut parts = Vec::new();
+    match (info.remaining, info.limit) {
+        (Some(remaining), Some(limit)) => parts.push(format!("{remaining}/{limit} req")),
+        (Some(remaining), None) => parts.push(format!("{remaining} req left")),
+        _ => {}
+    }
+    if let Some(credits) = info.credits_remaining {
+        parts.push(format!("${credits:.2}"));
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    let color = if info.remaining == Some(0) {
+        Color::Red
+    } else if info.is_low() {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
+    Some(Span::styled(parts.join(" · "), Style::default().fg(color)))
+}
+
 impl Component for TitleBar<'_> {
     fn render(&mut self, frame: &mut Frame, area: Rect) {
         let sep = Span::styled(" │ ", Style::default().fg(Color::DarkGray));
@@ -73,6 +180,12 @@ impl Component for TitleBar<'_> {
                 format!(" {ch}"),
                 Style::default().fg(Color::Blue),
             ));
+            if let Some(tps) = self.live_rate {
+                left.push(Span::styled(
+                    format!(" {tps:.1} tok/s"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
         }
 
         left.push(sep.clone());
@@ -89,6 +202,11 @@ impl Component for TitleBar<'_> {
             ));
         }
 
+        if let Some(effort) = self.effort {
+            left.push(Span::raw(" "));
+            left.push(effort_badge(effort));
+        }
+
         // -- Right side: session title + session tokens --
         let mut right: Vec<Span> = Vec::new();
 
@@ -101,7 +219,7 @@ impl Component for TitleBar<'_> {
 
         if self.session_total_tokens > 0 {
             if !right.is_empty() {
-                right.push(sep);
+                right.push(sep.clone());
             }
             right.push(Span::styled(
                 format!("{} tokens", format_tokens(self.session_total_tokens)),
@@ -109,6 +227,20 @@ impl Component for TitleBar<'_> {
             ));
         }
 
+        if let Some(label) = self.rate_limit.and_then(rate_limit_label) {
+            if !right.is_empty() {
+                right.push(sep.clone());
+            }
+            right.push(label);
+        }
+
+        if let Some((used, window)) = self.context_usage {
+            if !right.is_empty() {
+                right.push(sep);
+            }
+            right.extend(context_gauge(used, window));
+        }
+
         // -- Compose: left + padding + right --
         let left_width: usize = left.iter().map(|s| s.width()).sum();
         let right_width: usize = right.iter().map(|s| s.width()).sum();
@@ -148,7 +280,7 @@ mod tests {
         title: &'a str,
         tokens: u32,
     ) -> TitleBar<'a> {
-        TitleBar::new(model, provider, loading, 0, title, tokens)
+        TitleBar::new(model, provider, loading, 0, title, tokens, None)
     }
 
     #[test]
@@ -174,6 +306,16 @@ mod tests {
         assert!(!SPINNER_FRAMES.iter().any(|&ch| text.contains(ch)));
     }
 
+    #[test]
+    fn test_live_rate_shown_only_while_loading() {
+        let mut streaming = bar("gpt-4", "", true, "", 0).with_live_rate(Some(23.46));
+        let text = render(80, &mut streaming);
+        assert!(text.contains(" 23.5 tok/s"), "got: {text}");
+
+        let mut done = bar("gpt-4", "", false, "", 0).with_live_rate(Some(23.46));
+        assert!(!render(80, &mut done).contains("tok/s"));
+    }
+
     #[test]
     fn test_provider_shown() {
         let mut b = bar("claude-sonnet", "openrouter", false, "", 0);
@@ -217,6 +359,81 @@ mod tests {
         assert!(!text.contains("tokens"));
     }
 
+    #[test]
+    fn test_context_gauge_shows_percentage() {
+        let mut b = TitleBar::new("gpt-4", "", false, 0, "", 0, Some((50_000, 200_000)));
+        let text = render(80, &mut b);
+        assert!(text.contains("ctx"));
+        assert!(text.contains("▰▰▱▱▱▱▱▱ 25%"));
+    }
+
+    #[test]
+    fn test_context_gauge_hidden_without_window() {
+        let mut b = bar("gpt-4", "", false, "", 0);
+        let text = render(80, &mut b);
+        assert!(!text.contains("ctx"));
+    }
+
+    #[test]
+    fn test_rate_limit_shown_and_colored() {
+        let info = RateLimitInfo {
+            limit: Some(20),
+            remaining: Some(2),
+            reset_ms: None,
+            credits_remaining: Some(4.25),
+        };
+        let mut b = bar("gpt-4", "", false, "", 0).with_rate_limit(Some(&info));
+        let text = render(80, &mut b);
+        assert!(text.contains("2/20 req · $4.25"));
+
+        let label = rate_limit_label(&info).unwrap();
+        assert_eq!(label.style.fg, Some(Color::Yellow));
+        let empty = RateLimitInfo {
+            remaining: Some(0),
+            ..info
+        };
+        assert_eq!(rate_limit_label(&empty).unwrap().style.fg, Some(Color::Red));
+        assert!(rate_limit_label(&RateLimitInfo::default()).is_none());
+    }
+
+    #[test]
+    fn test_effort_badge_follows_effort() {
+        let mut low = bar("gpt-4", "openrouter", false, "", 0).with_effort(Some(Effort::Low));
+        let text = render(80, &mut low);
+        assert!(text.contains("gpt-4 (openrouter)  Low "), "got: {text}");
+
+        let mut high = bar("gpt-4", "openrouter", false, "", 0).with_effort(Some(Effort::High));
+        let text = render(80, &mut high);
+        assert!(text.contains(" High "), "got: {text}");
+        assert!(!text.contains("Low"));
+        assert_ne!(
+            effort_badge(Effort::Low).style.bg,
+            effort_badge(Effort::High).style.bg
+        );
+    }
+
+    #[test]
+    fn test_effort_badge_hidden_without_reasoning() {
+        let mut b = bar("gpt-4", "", false, "", 0).with_effort(None);
+        let text = render(80, &mut b);
+        assert!(!text.contains("Auto"));
+    }
+
+    #[test]
+    fn test_context_percent_caps_at_full() {
+        assert_eq!(context_percent(0, 1000), 0);
+        assert_eq!(context_percent(999, 1000), 99);
+        assert_eq!(context_percent(5000, 1000), 100);
+        assert_eq!(context_percent(10, 0), 100);
+    }
+
+    #[test]
+    fn test_gauge_color_thresholds() {
+        assert_eq!(gauge_color(10), Color::Green);
+        assert_eq!(gauge_color(50), Color::Yellow);
+        assert_eq!(gauge_color(80), Color::Red);
+    }
+
     #[test]
     fn test_format_tokens_small() {
         assert_eq!(format_tokens(42), "42");
@@ -7,6 +7,12 @@
 //!   `│ a: 42, b: 8 → result: 50 │`
 //!   `╰───────────────────────────╯`
 //!
+//! **Collapsed, two lines** (summary too wide for one line but each half fits):
+//!   `╭─ ◈ read_file ────────────────╮`
+//!   `│ path: "src/tui/mod.rs"     │`
+//!   `│  → lines: 412, bytes: 15833│`
+//!   `╰───────────────────────────╯`
+//!
 //! **Pending** (tool executing, alternates ◇/⟐):
 //!   `╭─ ◇ add ─────────────────────╮`
 //!   `│ a: 42, b: 8 …              │`
@@ -32,6 +38,7 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Padding, Paragraph, Widget};
 
 use crate::inference::{ToolCall, ToolResult};
+use crate::tui::theme::Theme;
 
 /// Horizontal padding (per side) inside the bordered view.
 const CONTENT_PAD_H: u16 = 1;
@@ -43,24 +50,31 @@ const VERTICAL_OVERHEAD: u16 = 2;
 const MAX_SECTION_LINES: usize = 8;
 /// Max chars before truncating a nested value in the collapsed summary.
 const MAX_VALUE_CHARS: usize = 20;
+/// Separator between args and result in the collapsed summary.
+const RESULT_SEP: &str = " → ";
 
 // ─── Styles ──────────────────────────────────────────────────────────
-// Yellow = tool identity/input (action happening), White = output (the answer).
+// Theme tool color = tool identity/input (action happening), White = output
+// (the answer), theme border color = secondary chrome.
 
-const fn tool_style() -> Style {
-    Style::new().fg(Color::Yellow)
-}
-const fn result_style() -> Style {
-    Style::new().fg(Color::White)
-}
-const fn pending_style() -> Style {
-    Style::new().fg(Color::DarkGray)
-}
-const fn overflow_style() -> Style {
-    Style::new().fg(Color::DarkGray).add_modifier(Modifier::DIM)
-}
-const fn sep_style() -> Style {
-    Style::new().fg(Color::DarkGray)
+impl<'a> ToolGroup<'a> {
+    fn tool_style(&self) -> Style {
+        Style::new().fg(self.theme.tool)
+    }
+    fn result_style(&self) -> Style {
+        Style::new().fg(Color::White)
+    }
+    fn pending_style(&self) -> Style {
+        Style::new().fg(self.theme.border)
+    }
+    fn overflow_style(&self) -> Style {
+        Style::new()
+            .fg(self.theme.border)
+            .add_modifier(Modifier::DIM)
+    }
+    fn sep_style(&self) -> Style {
+        Style::new().fg(self.theme.border)
+    }
 }
 
 // ─── ToolGroup ───────────────────────────────────────────────────────
@@ -72,12 +86,14 @@ pub struct ToolGroup<'a> {
     pub is_selected: bool,
     pub is_expanded: bool,
     pub spinner_frame: usize,
+    pub theme: &'a Theme,
 }
 
 impl<'a> ToolGroup<'a> {
     /// Calculate height needed to render this group at the given width.
     ///
-    /// Collapsed: borders + 1 summary line.
+    /// Collapsed: borders + 1 summary line, or 2 when args and result get
+    /// a line each.
     /// Expanded: borders + pretty-printed args + result (capped per section).
     pub fn calculate_height(
         call: &ToolCall,
@@ -91,7 +107,12 @@ impl<'a> ToolGroup<'a> {
         }
 
         if !is_expanded {
-            return 1 + VERTICAL_OVERHEAD;
+            let lines = if splits_collapsed(call, result, content_width) {
+                2
+            } else {
+                1
+            };
+            return lines + VERTICAL_OVERHEAD;
         }
 
         // Expanded: label line + pretty-printed content per section
@@ -139,15 +160,17 @@ impl<'a> Widget for ToolGroup<'a> {
 }
 
 impl<'a> ToolGroup<'a> {
-    /// Bordered block with a single summary line using colored spans.
+    /// Bordered block with a summary line using colored spans.
     /// Args in dim yellow, ` → ` separator gray, result in dim white.
+    /// A summary too wide for one line puts the result on a second line
+    /// when that fits both in full.
     /// Border brightens when selected to indicate the item is focused.
     fn render_collapsed(self, area: Rect, buf: &mut Buffer) {
         let title = format!("{} {}", self.icon(), self.call.name);
         let border_style = if self.is_selected {
-            tool_style() // Bright border when selected (press Space to expand)
+            self.tool_style() // Bright border when selected (press Space to expand)
         } else {
-            tool_style().add_modifier(Modifier::DIM)
+            self.tool_style().add_modifier(Modifier::DIM)
         };
 
         let block = Block::bordered()
@@ -167,9 +190,29 @@ impl<'a> ToolGroup<'a> {
         let budget = inner.width as usize;
         let args_str = summarize_json(&self.call.arguments, budget);
 
+        if let Some(tr) = self.result
+            && splits_collapsed(self.call, self.result, budget)
+        {
+            let lines = vec![
+                Line::from(Span::styled(
+                    args_str,
+                    self.tool_style().add_modifier(Modifier::DIM),
+                )),
+                Line::from(vec![
+                    Span::styled(RESULT_SEP, self.sep_style()),
+                    Span::styled(
+                        summarize_json(&tr.output, budget),
+                        self.result_style().add_modifier(Modifier::DIM),
+                    ),
+                ]),
+            ];
+            Paragraph::new(lines).render(inner, buf);
+            return;
+        }
+
         let spans = match &self.result {
             Some(tr) => {
-                let sep = " → ";
+                let sep = RESULT_SEP;
                 let args_len = args_str.chars().count();
                 let sep_len = sep.chars().count();
                 let result_budget = budget.saturating_sub(args_len + sep_len);
@@ -178,15 +221,15 @@ impl<'a> ToolGroup<'a> {
                 let total = args_len + sep_len + result_str.chars().count();
                 if total <= budget {
                     vec![
-                        Span::styled(args_str, tool_style().add_modifier(Modifier::DIM)),
-                        Span::styled(sep, sep_style()),
-                        Span::styled(result_str, result_style().add_modifier(Modifier::DIM)),
+                        Span::styled(args_str, self.tool_style().add_modifier(Modifier::DIM)),
+                        Span::styled(sep, self.sep_style()),
+                        Span::styled(result_str, self.result_style().add_modifier(Modifier::DIM)),
                     ]
                 } else {
                     // Not enough room for result — just show args truncated
                     vec![Span::styled(
                         truncate_to(&args_str, budget),
-                        tool_style().add_modifier(Modifier::DIM),
+                        self.tool_style().add_modifier(Modifier::DIM),
                     )]
                 }
             }
@@ -195,8 +238,8 @@ impl<'a> ToolGroup<'a> {
                 let args_budget = budget.saturating_sub(suffix.chars().count());
                 let args_str = summarize_json(&self.call.arguments, args_budget);
                 vec![
-                    Span::styled(args_str, tool_style().add_modifier(Modifier::DIM)),
-                    Span::styled(suffix, pending_style()),
+                    Span::styled(args_str, self.tool_style().add_modifier(Modifier::DIM)),
+                    Span::styled(suffix, self.pending_style()),
                 ]
             }
         };
@@ -207,7 +250,7 @@ impl<'a> ToolGroup<'a> {
     /// Bordered block with labeled sections and pretty-printed JSON.
     fn render_expanded(self, area: Rect, buf: &mut Buffer) {
         let title = format!("{} {}", self.icon(), self.call.name);
-        let border_style = tool_style();
+        let border_style = self.tool_style();
 
         let block = Block::bordered()
             .title(title)
@@ -227,13 +270,13 @@ impl<'a> ToolGroup<'a> {
         let mut lines = Vec::new();
 
         // ▸ input label
-        lines.push(Line::from(Span::styled("▸ input", tool_style())));
+        lines.push(Line::from(Span::styled("▸ input", self.tool_style())));
         // Indented args content
         for text in &args_content {
             let style = if text.starts_with("… +") {
-                overflow_style()
+                self.overflow_style()
             } else {
-                tool_style()
+                self.tool_style()
             };
             lines.push(Line::from(Span::styled(format!("  {text}"), style)));
         }
@@ -243,19 +286,19 @@ impl<'a> ToolGroup<'a> {
             Some(tr) => {
                 let result_content = format_json_pretty(&tr.output, MAX_SECTION_LINES);
                 // ◂ output label
-                lines.push(Line::from(Span::styled("◂ output", result_style())));
+                lines.push(Line::from(Span::styled("◂ output", self.result_style())));
                 // Indented result content
                 for text in &result_content {
                     let style = if text.starts_with("… +") {
-                        overflow_style()
+                        self.overflow_style()
                     } else {
-                        result_style()
+                        self.result_style()
                     };
                     lines.push(Line::from(Span::styled(format!("  {text}"), style)));
                 }
             }
             None => {
-                lines.push(Line::from(Span::styled("◂ …", pending_style())));
+                lines.push(Line::from(Span::styled("◂ …", self.pending_style())));
             }
         }
 
@@ -263,6 +306,19 @@ impl<'a> ToolGroup<'a> {
     }
 }
 
+/// Whether a collapsed group shows args and result on separate lines: the
+/// full one-line summary is wider than `budget`, but the args and the
+/// ` → result` part each fit on a line of their own.
+fn splits_collapsed(call: &ToolCall, result: Option<&ToolResult>, budget: usize) -> bool {
+    let Some(tr) = result else {
+        return false;
+    };
+    let args_len = summarize_json(&call.arguments, usize::MAX).chars().count();
+    let result_len =
+        RESULT_SEP.chars().count() + summarize_json(&tr.output, usize::MAX).chars().count();
+    args_len + result_len > budget && args_len <= budget && result_len <= budget
+}
+
 // ─── JSON Formatting Helpers ─────────────────────────────────────────
 
 /// Summarize a JSON string as `k: v, k: v, …` for collapsed display.
@@ -414,6 +470,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn collapsed_height_grows_when_summary_splits() {
+        // "path: \"src/main.rs\"" is 19 chars, " → lines: 120" is 13
+        let call = make_call("read_file", r#"{"path": "src/main.rs"}"#);
+        let result = make_result(r#"{"lines": 120}"#);
+        let width_for = |content: u16| content + HORIZONTAL_OVERHEAD;
+
+        let one_line = ToolGroup::calculate_height(&call, Some(&result), false, width_for(32));
+        assert_eq!(one_line, 1 + VERTICAL_OVERHEAD);
+        let two_lines = ToolGroup::calculate_height(&call, Some(&result), false, width_for(31));
+        assert_eq!(two_lines, 2 + VERTICAL_OVERHEAD);
+    }
+
+    #[test]
+    fn collapsed_split_needs_each_half_to_fit() {
+        let call = make_call("read_file", r#"{"path": "src/main.rs"}"#);
+        let result = make_result(r#"{"lines": 120}"#);
+        assert!(!splits_collapsed(&call, Some(&result), 32));
+        assert!(splits_collapsed(&call, Some(&result), 31));
+        assert!(splits_collapsed(&call, Some(&result), 19));
+        // Args no longer fit on a line of their own: back to one truncated line
+        assert!(!splits_collapsed(&call, Some(&result), 18));
+        // Nothing to split while the tool is pending
+        assert!(!splits_collapsed(&call, None, 19));
+    }
+
+    #[test]
+    fn collapsed_split_renders_result_on_second_line() {
+        let call = make_call("read_file", r#"{"path": "src/main.rs"}"#);
+        let result = make_result(r#"{"lines": 120}"#);
+        let theme = Theme::default();
+        let width = 25 + HORIZONTAL_OVERHEAD;
+        let height = ToolGroup::calculate_height(&call, Some(&result), false, width);
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        ToolGroup {
+            call: &call,
+            result: Some(&result),
+            is_selected: false,
+            is_expanded: false,
+            spinner_frame: 0,
+            theme: &theme,
+        }
+        .render(area, &mut buf);
+
+        let row = |y: u16| -> String { (0..width).map(|x| buf[(x, y)].symbol()).collect() };
+        assert!(row(1).contains(r#"path: "src/main.rs""#), "got: {}", row(1));
+        assert!(row(2).contains("→ lines: 120"), "got: {}", row(2));
+    }
+
     #[test]
     fn expanded_height_includes_labels_and_content() {
         let call = make_call("add", r#"{"a": 1, "b": 2}"#);
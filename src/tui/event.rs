@@ -7,11 +7,14 @@ pub enum TuiEvent {
     // Raw input events (mode system decides semantics)
     Escape,    // Esc key — mode-dependent (switch to Cursor or no-op)
     ForceQuit, // Ctrl+C — always quits regardless of mode
+    Suspend,   // Ctrl+Z — hand the terminal back to the shell until `fg`
     Submit,
+    InsertNewline, // Shift/Ctrl/Alt+Enter, Ctrl+J — literal newline instead of submit
 
     // TUI-local events (handled directly in TUI)
     InputChar(char),
     Paste(String), // Bracketed paste - preserves newlines
+    PasteImage,    // Ctrl+V — attach the clipboard's image to the next message
     Backspace,
     Delete, // Delete character after cursor
 
@@ -31,21 +34,284 @@ pub enum TuiEvent {
     KillToLineStart,    // Ctrl+U — kill text from cursor to line start
     KillToLineEnd,      // Ctrl+K — kill text from cursor to line end
     Yank,               // Ctrl+Y — yank (paste) from kill buffer
+    AbortEdit,          // Ctrl+G — leave input history, restoring the draft
+    WrapInCodeFence,    // Alt+F — fence the input as a code block
 
     // Scrolling (message list only)
     ScrollUp,   // Mouse wheel only (arrow keys now move cursor)
     ScrollDown, // Mouse wheel only (arrow keys now move cursor)
     ScrollPageUp,
     ScrollPageDown,
+    ScrollLeft,           // Horizontal or Shift+wheel — only visible with word wrap off
+    ScrollRight,          // Horizontal or Shift+wheel — only visible with word wrap off
+    ToggleWrap,           // Alt+Z — wrap long lines or scroll them sideways
+    ToggleDensity,        // Alt+C — bordered or compact messages
+    GrowInput,            // Alt+= — let the input box show more lines
+    ShrinkInput,          // Alt+- — back toward the default input height
+    ToggleReasoningPanel, // Alt+T — show thinking beside the conversation
+    ToggleShowThinking,   // Alt+H — hide or show thinking in the conversation
+    ToggleShowSystem,     // Alt+S — hide or show the system directive
+
+    // Mode switching
+    EnterCursorMode, // Alt+Up — select messages instead of typing
+    ExitCursorMode,  // Alt+Down — back to the input box
+    QuoteSelected,   // Alt+Q / r in cursor mode — quote the selected message into the input
+    TogglePin,       // Alt+B / m in cursor mode — pin or unpin the selected message
+    JumpNextPin,     // Alt+J / n in cursor mode — select the next pinned message
+    JumpPrevPin,     // Alt+K / N in cursor mode — select the previous pinned message
+
+    // Conversation tabs
+    NewTab,   // Ctrl+T — open a fresh conversation in a new tab
+    CloseTab, // Ctrl+D — close the active tab
+    NextTab,  // Ctrl/Alt+PgDn
+    PrevTab,  // Ctrl/Alt+PgUp
 
     MouseMove(u16, u16),
     MouseClick(u16, u16), // Left click — col, row
     CycleEffort,          // Ctrl+R to cycle reasoning effort
     OpenSessionManager,   // Ctrl+O to open session browser
-    OpenModelPicker,      // Ctrl+P to switch models at runtime
+    OpenModelPicker,      // Ctrl+P / Ctrl+M to switch models at runtime
+    ReloadConfig,         // F5 re-reads ~/.navi/config.toml
+    ClearConversation,    // Ctrl+L starts over, keeping the directive
+    NextVariant,          // Alt+N shows the next regenerated answer
+    PrevVariant,          // Alt+P shows the previous regenerated answer
+    ShowVariantDiff,      // Alt+V compares the shown answer with the previous one
     Resize,               // Terminal resized — triggers redraw
 }
 
+/// A user-facing keybinding description, shown in the help overlay.
+///
+/// Kept next to `poll_event_timeout` so that adding or changing a key arm
+/// and its help entry happen in the same place.
+pub struct KeyBinding {
+    pub category: &'static str,
+    pub keys: &'static str,
+    pub description: &'static str,
+}
+
+/// Every documented keybinding, grouped by category in display order.
+pub const KEY_BINDINGS: &[KeyBinding] = &[
+    // General
+    KeyBinding {
+        category: "General",
+        keys: "Enter",
+        description: "Send message",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Shift/Alt/Ctrl+Enter, Ctrl+J",
+        description: "Insert newline",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Esc",
+        description: "Cancel generation / cursor mode",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Ctrl+R",
+        description: "Cycle reasoning effort",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Ctrl+O",
+        description: "Open session manager",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Ctrl+P, Ctrl+M",
+        description: "Switch model",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Ctrl+L",
+        description: "Clear conversation",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Alt+P / Alt+N",
+        description: "Previous / next regenerated answer",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Alt+V",
+        description: "Compare answer with the previous one",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Ctrl+T / Ctrl+D",
+        description: "New tab / close tab",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Ctrl/Alt+PgUp / PgDn",
+        description: "Previous / next tab",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "F5",
+        description: "Reload config file",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "?",
+        description: "Show this help",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Ctrl+C",
+        description: "Quit",
+    },
+    KeyBinding {
+        category: "General",
+        keys: "Ctrl+Z",
+        description: "Suspend to the shell (fg resumes)",
+    },
+    // Editing
+    KeyBinding {
+        category: "Editing",
+        keys: "Ctrl+A / Ctrl+E",
+        description: "Line start / end",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Alt+Left / Alt+Right",
+        description: "Previous / next word",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Ctrl+W, Alt+Backspace",
+        description: "Delete word backward",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Alt+D",
+        description: "Delete word forward",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Ctrl+U / Ctrl+K",
+        description: "Kill to line start / end",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Ctrl+Y",
+        description: "Yank killed text",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Ctrl+V",
+        description: "Attach clipboard image",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Up / Down",
+        description: "Move line / browse history",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Ctrl+G",
+        description: "Leave history, restore draft",
+    },
+    KeyBinding {
+        category: "Editing",
+        keys: "Alt+F",
+        description: "Wrap input in a code fence",
+    },
+    // Scrolling
+    KeyBinding {
+        category: "Scrolling",
+        keys: "PgUp / PgDn",
+        description: "Scroll messages by page",
+    },
+    KeyBinding {
+        category: "Scrolling",
+        keys: "Mouse wheel",
+        description: "Scroll messages",
+    },
+    KeyBinding {
+        category: "Scrolling",
+        keys: "Alt+Z",
+        description: "Toggle word wrap",
+    },
+    KeyBinding {
+        category: "Scrolling",
+        keys: "Alt+C",
+        description: "Toggle compact messages",
+    },
+    KeyBinding {
+        category: "Scrolling",
+        keys: "Alt+= / Alt+-",
+        description: "Grow / shrink input box",
+    },
+    KeyBinding {
+        category: "Scrolling",
+        keys: "Alt+T",
+        description: "Toggle reasoning panel",
+    },
+    KeyBinding {
+        category: "Scrolling",
+        keys: "Alt+H / Alt+S",
+        description: "Hide thinking / system directive",
+    },
+    KeyBinding {
+        category: "Scrolling",
+        keys: "Shift+wheel",
+        description: "Scroll sideways (word wrap off)",
+    },
+    // Cursor mode
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "Alt+Up",
+        description: "Enter cursor mode",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "Up / Down, k / j",
+        description: "Select previous / next message",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "Space, click",
+        description: "Expand / collapse tool call",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "f",
+        description: "Fork conversation at message",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "v, then k / j",
+        description: "Select lines to copy",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "y",
+        description: "Copy selection / message",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "r, Alt+Q",
+        description: "Quote message into input",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "m, Alt+B",
+        description: "Pin / unpin message",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "n / N, Alt+J / Alt+K",
+        description: "Next / previous pinned message",
+    },
+    KeyBinding {
+        category: "Cursor mode",
+        keys: "Enter, Alt+Down, type",
+        description: "Back to input",
+    },
+];
+
 /// Poll for an event without blocking (returns immediately)
 pub fn poll_event_immediate() -> Option<TuiEvent> {
     poll_event_timeout(std::time::Duration::ZERO)
@@ -71,6 +337,8 @@ pub fn poll_event_timeout(timeout: std::time::Duration) -> Option<TuiEvent> {
                 match (key_event.modifiers, key_event.code) {
                     // Force quit (always works regardless of mode)
                     (KeyModifiers::CONTROL, KeyCode::Char('c')) => Some(TuiEvent::ForceQuit),
+                    // Raw mode turns Ctrl+Z into a key; the run loop suspends
+                    (KeyModifiers::CONTROL, KeyCode::Char('z')) => Some(TuiEvent::Suspend),
                     // Ctrl+R cycles reasoning effort
                     (KeyModifiers::CONTROL, KeyCode::Char('r')) => Some(TuiEvent::CycleEffort),
                     // Ctrl+O opens session manager
@@ -78,10 +346,21 @@ pub fn poll_event_timeout(timeout: std::time::Duration) -> Option<TuiEvent> {
                         Some(TuiEvent::OpenSessionManager)
                     }
                     // Ctrl+P opens model picker
-                    // (Ctrl+M is ASCII 13 = Enter, indistinguishable without Kitty protocol)
                     (KeyModifiers::CONTROL, KeyCode::Char('p')) => Some(TuiEvent::OpenModelPicker),
+                    // Ctrl+M also opens it where the Kitty protocol is active; legacy
+                    // terminals send ASCII 13 and it arrives as plain Enter instead
+                    (KeyModifiers::CONTROL, KeyCode::Char('m')) => Some(TuiEvent::OpenModelPicker),
+                    // Ctrl+L clears the conversation
+                    (KeyModifiers::CONTROL, KeyCode::Char('l')) => {
+                        Some(TuiEvent::ClearConversation)
+                    }
+                    // Ctrl+T opens a new tab, Ctrl+D closes the active one
+                    (KeyModifiers::CONTROL, KeyCode::Char('t')) => Some(TuiEvent::NewTab),
+                    (KeyModifiers::CONTROL, KeyCode::Char('d')) => Some(TuiEvent::CloseTab),
+                    // F5 reloads the config file
+                    (_, KeyCode::F(5)) => Some(TuiEvent::ReloadConfig),
                     // Ctrl+J inserts newline (ASCII LF; Ctrl+Enter sends this in most terminals)
-                    (KeyModifiers::CONTROL, KeyCode::Char('j')) => Some(TuiEvent::InputChar('\n')),
+                    (KeyModifiers::CONTROL, KeyCode::Char('j')) => Some(TuiEvent::InsertNewline),
 
                     // Emacs-style bindings (must precede wildcard Char arm)
                     (KeyModifiers::CONTROL, KeyCode::Char('a')) => Some(TuiEvent::CursorHome),
@@ -92,7 +371,24 @@ pub fn poll_event_timeout(timeout: std::time::Duration) -> Option<TuiEvent> {
                     (KeyModifiers::CONTROL, KeyCode::Char('u')) => Some(TuiEvent::KillToLineStart),
                     (KeyModifiers::CONTROL, KeyCode::Char('k')) => Some(TuiEvent::KillToLineEnd),
                     (KeyModifiers::CONTROL, KeyCode::Char('y')) => Some(TuiEvent::Yank),
+                    (KeyModifiers::CONTROL, KeyCode::Char('g')) => Some(TuiEvent::AbortEdit),
+                    (KeyModifiers::CONTROL, KeyCode::Char('v')) => Some(TuiEvent::PasteImage),
                     (KeyModifiers::ALT, KeyCode::Char('d')) => Some(TuiEvent::DeleteWordForward),
+                    (KeyModifiers::ALT, KeyCode::Char('f')) => Some(TuiEvent::WrapInCodeFence),
+                    (KeyModifiers::ALT, KeyCode::Char('z')) => Some(TuiEvent::ToggleWrap),
+                    (KeyModifiers::ALT, KeyCode::Char('c')) => Some(TuiEvent::ToggleDensity),
+                    (KeyModifiers::ALT, KeyCode::Char('=' | '+')) => Some(TuiEvent::GrowInput),
+                    (KeyModifiers::ALT, KeyCode::Char('-')) => Some(TuiEvent::ShrinkInput),
+                    (KeyModifiers::ALT, KeyCode::Char('t')) => Some(TuiEvent::ToggleReasoningPanel),
+                    (KeyModifiers::ALT, KeyCode::Char('h')) => Some(TuiEvent::ToggleShowThinking),
+                    (KeyModifiers::ALT, KeyCode::Char('s')) => Some(TuiEvent::ToggleShowSystem),
+                    (KeyModifiers::ALT, KeyCode::Char('q')) => Some(TuiEvent::QuoteSelected),
+                    (KeyModifiers::ALT, KeyCode::Char('b')) => Some(TuiEvent::TogglePin),
+                    (KeyModifiers::ALT, KeyCode::Char('j')) => Some(TuiEvent::JumpNextPin),
+                    (KeyModifiers::ALT, KeyCode::Char('k')) => Some(TuiEvent::JumpPrevPin),
+                    (KeyModifiers::ALT, KeyCode::Char('n')) => Some(TuiEvent::NextVariant),
+                    (KeyModifiers::ALT, KeyCode::Char('p')) => Some(TuiEvent::PrevVariant),
+                    (KeyModifiers::ALT, KeyCode::Char('v')) => Some(TuiEvent::ShowVariantDiff),
                     (m, KeyCode::Backspace) if m.contains(KeyModifiers::ALT) => {
                         Some(TuiEvent::DeleteWordBackward)
                     }
@@ -101,12 +397,13 @@ pub fn poll_event_timeout(timeout: std::time::Duration) -> Option<TuiEvent> {
                     (_, KeyCode::Char(c)) => Some(TuiEvent::InputChar(c)),
                     (_, KeyCode::Backspace) => Some(TuiEvent::Backspace),
                     (_, KeyCode::Delete) => Some(TuiEvent::Delete),
-                    // Ctrl+Enter / Shift+Enter insert newline (must precede wildcard Enter)
-                    (m, KeyCode::Enter) if m.contains(KeyModifiers::CONTROL) => {
-                        Some(TuiEvent::InputChar('\n'))
-                    }
-                    (m, KeyCode::Enter) if m.contains(KeyModifiers::SHIFT) => {
-                        Some(TuiEvent::InputChar('\n'))
+                    // Ctrl/Shift/Alt+Enter insert newline (must precede wildcard Enter)
+                    (m, KeyCode::Enter)
+                        if m.intersects(
+                            KeyModifiers::CONTROL | KeyModifiers::SHIFT | KeyModifiers::ALT,
+                        ) =>
+                    {
+                        Some(TuiEvent::InsertNewline)
                     }
                     (_, KeyCode::Enter) => Some(TuiEvent::Submit),
                     (_, KeyCode::Esc) => Some(TuiEvent::Escape),
@@ -117,6 +414,13 @@ pub fn poll_event_timeout(timeout: std::time::Duration) -> Option<TuiEvent> {
                     (m, KeyCode::Right) if m.contains(KeyModifiers::ALT) => {
                         Some(TuiEvent::CursorWordRight)
                     }
+                    // Alt+Up/Down switch between cursor and input mode
+                    (m, KeyCode::Up) if m.contains(KeyModifiers::ALT) => {
+                        Some(TuiEvent::EnterCursorMode)
+                    }
+                    (m, KeyCode::Down) if m.contains(KeyModifiers::ALT) => {
+                        Some(TuiEvent::ExitCursorMode)
+                    }
                     // Arrow keys move cursor
                     (_, KeyCode::Left) => Some(TuiEvent::CursorLeft),
                     (_, KeyCode::Right) => Some(TuiEvent::CursorRight),
@@ -124,6 +428,17 @@ pub fn poll_event_timeout(timeout: std::time::Duration) -> Option<TuiEvent> {
                     (_, KeyCode::Down) => Some(TuiEvent::CursorDown),
                     (_, KeyCode::Home) => Some(TuiEvent::CursorHome),
                     (_, KeyCode::End) => Some(TuiEvent::CursorEnd),
+                    // Ctrl/Alt+Page Up/Down switch tabs (Alt for terminals that claim Ctrl)
+                    (m, KeyCode::PageUp)
+                        if m.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                    {
+                        Some(TuiEvent::PrevTab)
+                    }
+                    (m, KeyCode::PageDown)
+                        if m.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                    {
+                        Some(TuiEvent::NextTab)
+                    }
                     // Page Up/Down for scrolling messages
                     (_, KeyCode::PageUp) => Some(TuiEvent::ScrollPageUp),
                     (_, KeyCode::PageDown) => Some(TuiEvent::ScrollPageDown),
@@ -137,8 +452,18 @@ pub fn poll_event_timeout(timeout: std::time::Duration) -> Option<TuiEvent> {
                 MouseEventKind::Down(MouseButton::Left) => {
                     Some(TuiEvent::MouseClick(mouse_event.column, mouse_event.row))
                 }
+                MouseEventKind::ScrollUp if mouse_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                    Some(TuiEvent::ScrollLeft)
+                }
+                MouseEventKind::ScrollDown
+                    if mouse_event.modifiers.contains(KeyModifiers::SHIFT) =>
+                {
+                    Some(TuiEvent::ScrollRight)
+                }
                 MouseEventKind::ScrollUp => Some(TuiEvent::ScrollUp),
                 MouseEventKind::ScrollDown => Some(TuiEvent::ScrollDown),
+                MouseEventKind::ScrollLeft => Some(TuiEvent::ScrollLeft),
+                MouseEventKind::ScrollRight => Some(TuiEvent::ScrollRight),
                 _ => None,
             },
             Event::Paste(data) => Some(TuiEvent::Paste(data)),
@@ -0,0 +1,281 @@
+//! # Inline Images
+//!
+//! Kitty and iTerm2 can draw pictures in the terminal through escape
+//! sequences. When one of them is detected at startup, attached images are
+//! shown in the conversation under their label rather than as an `[image]`
+//! line. The message list reserves `IMAGE_ROWS` rows for each picture and
+//! records where they land on screen. After a frame is drawn, `InlineImages`
+//! writes the pictures there, but only when the placements changed.
+//!
+//! Detection reads the environment. Querying the terminal would also find
+//! other terminals, but its reply would arrive mixed in with key presses.
+//! Inside tmux or screen the sequences need passthrough, so images stay
+//! placeholders there.
+
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
+
+use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+use crossterm::queue;
+
+use crate::inference::{ContextItem, ImageAttachment};
+
+/// Rows a picture takes under its label.
+pub const IMAGE_ROWS: u16 = 10;
+
+/// Base64 bytes per Kitty escape sequence; longer data is sent in chunks.
+const KITTY_CHUNK: usize = 4096;
+
+/// Image escape sequences the terminal understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// Kitty graphics protocol (kitty, Ghostty)
+    Kitty,
+    /// iTerm2 inline images (iTerm2, WezTerm)
+    Iterm2,
+}
+
+impl GraphicsProtocol {
+    /// The protocol of the terminal Navi runs in, if it has one.
+    pub fn detect() -> Option<Self> {
+        Self::detect_with(&|name| std::env::var(name).ok())
+    }
+
+    fn detect_with(env: &dyn Fn(&str) -> Option<String>) -> Option<Self> {
+        let term = env("TERM").unwrap_or_default();
+        if env("TMUX").is_some() || term.starts_with("screen") || term.starts_with("tmux") {
+            return None;
+        }
+        let program = env("TERM_PROGRAM").unwrap_or_default();
+        if env("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || program == "ghostty"
+        {
+            Some(GraphicsProtocol::Kitty)
+        } else if program == "iTerm.app"
+            || program == "WezTerm"
+            || env("LC_TERMINAL").as_deref() == Some("iTerm2")
+        {
+            Some(GraphicsProtocol::Iterm2)
+        } else {
+            None
+        }
+    }
+
+    /// Whether pictures of type `mime` can be drawn. Kitty is sent the file
+    /// as is, which it only accepts for PNG.
+    pub fn supports(self, mime: &str) -> bool {
+        match self {
+            GraphicsProtocol::Kitty => mime == "image/png",
+            GraphicsProtocol::Iterm2 => mime.starts_with("image/"),
+        }
+    }
+}
+
+/// Where a picture goes: the top-left cell of its rows, and which image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImagePlacement {
+    /// Index of the `ContextItem::Image` in the context
+    pub item: usize,
+    /// Tells images apart across context changes; also the Kitty image id
+    pub id: u32,
+    pub x: u16,
+    pub y: u16,
+}
+
+impl ImagePlacement {
+    pub fn new(item: usize, image: &ImageAttachment, x: u16, y: u16) -> Self {
+        Self {
+            item,
+            id: image_id(image),
+            x,
+            y,
+        }
+    }
+}
+
+/// A nonzero id derived from the image data.
+fn image_id(image: &ImageAttachment) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    image.data_base64.hash(&mut hasher);
+    (hasher.finish() as u32).max(1)
+}
+
+/// The pictures on screen and, for Kitty, the images already sent.
+pub struct InlineImages {
+    protocol: GraphicsProtocol,
+    shown: Vec<ImagePlacement>,
+    transmitted: HashSet<u32>,
+}
+
+impl InlineImages {
+    pub fn new(protocol: GraphicsProtocol) -> Self {
+        Self {
+            protocol,
+            shown: Vec::new(),
+            transmitted: HashSet::new(),
+        }
+    }
+
+    pub fn protocol(&self) -> GraphicsProtocol {
+        self.protocol
+    }
+
+    /// Whether `placements` differ from the pictures on screen.
+    pub fn changed(&self, placements: &[ImagePlacement]) -> bool {
+        self.shown != placements
+    }
+
+    /// Replace the pictures on screen with `placements`, looking the images
+    /// up in `items`. The cursor is left where it was.
+    ///
+    /// Kitty pictures float above the text, so the old ones are deleted
+    /// first. iTerm2 pictures are part of the cells: the caller redraws the
+    /// whole frame before this so none of the old ones are left.
+    pub fn show(
+        &mut self,
+        out: &mut impl Write,
+        placements: &[ImagePlacement],
+        items: &[ContextItem],
+    ) -> io::Result<()> {
+        queue!(out, SavePosition)?;
+        if self.protocol == GraphicsProtocol::Kitty {
+            write!(out, "\x1b_Ga=d,d=a,q=2\x1b\\")?;
+        }
+        for placement in placements {
+            let Some(ContextItem::Image(image)) = items.get(placement.item) else {
+                continue;
+            };
+            if self.protocol == GraphicsProtocol::Kitty && self.transmitted.insert(placement.id) {
+                kitty_transmit(out, placement.id, &image.data_base64)?;
+            }
+            queue!(out, MoveTo(placement.x, placement.y))?;
+            match self.protocol {
+                GraphicsProtocol::Kitty => kitty_place(out, placement.id)?,
+                GraphicsProtocol::Iterm2 => iterm2_image(out, &image.data_base64)?,
+            }
+        }
+        queue!(out, RestorePosition)?;
+        out.flush()?;
+        self.shown = placements.to_vec();
+        Ok(())
+    }
+
+    /// Forget what was drawn, after the terminal was reset (e.g. on resume).
+    pub fn reset(&mut self) {
+        self.shown.clear();
+        self.transmitted.clear();
+    }
+}
+
+/// Send PNG data under `id` without showing it, in chunks. `q=2` keeps the
+/// terminal from answering into the input stream.
+fn kitty_transmit(out: &mut impl Write, id: u32, data: &str) -> io::Result<()> {
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(idx + 1 < chunks.len());
+        if idx == 0 {
+            write!(out, "\x1b_Ga=t,f=100,i={id},q=2,m={more};")?;
+        } else {
+            write!(out, "\x1b_Gm={more};")?;
+        }
+        out.write_all(chunk)?;
+        write!(out, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+/// Show image `id` at the cursor, `IMAGE_ROWS` tall with its aspect ratio
+/// kept, without moving the cursor.
+fn kitty_place(out: &mut impl Write, id: u32) -> io::Result<()> {
+    write!(out, "\x1b_Ga=p,i={id},r={IMAGE_ROWS},C=1,q=2\x1b\\")
+}
+
+/// Draw an image at the cursor, `IMAGE_ROWS` tall with its aspect ratio kept.
+fn iterm2_image(out: &mut impl Write, data: &str) -> io::Result<()> {
+    write!(
+        out,
+        "\x1b]1337;File=inline=1;height={IMAGE_ROWS};preserveAspectRatio=1:{data}\x07"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> Option<GraphicsProtocol> {
+        GraphicsProtocol::detect_with(&|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_protocol_detected_from_environment() {
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty")]),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "ghostty")]),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "iTerm.app")]),
+            Some(GraphicsProtocol::Iterm2)
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("LC_TERMINAL", "iTerm2")]),
+            Some(GraphicsProtocol::Iterm2)
+        );
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), None);
+        // tmux swallows the sequences
+        assert_eq!(
+            detect(&[("TERM", "tmux-256color"), ("KITTY_WINDOW_ID", "1")]),
+            None
+        );
+        assert_eq!(
+            detect(&[("TMUX", "/tmp/tmux"), ("TERM_PROGRAM", "WezTerm")]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_kitty_only_supports_png() {
+        assert!(GraphicsProtocol::Kitty.supports("image/png"));
+        assert!(!GraphicsProtocol::Kitty.supports("image/jpeg"));
+        assert!(GraphicsProtocol::Iterm2.supports("image/jpeg"));
+        assert!(!GraphicsProtocol::Iterm2.supports("text/plain"));
+    }
+
+    #[test]
+    fn test_kitty_sends_each_image_once() {
+        let image = ImageAttachment::from_bytes(&[0u8; 4000], "image/png", None);
+        let items = [ContextItem::Image(image.clone())];
+        let placement = ImagePlacement::new(0, &image, 2, 5);
+        let mut images = InlineImages::new(GraphicsProtocol::Kitty);
+        assert!(images.changed(&[placement]));
+
+        let mut out = Vec::new();
+        images.show(&mut out, &[placement], &items).unwrap();
+        let first = String::from_utf8(out).unwrap();
+        // 4000 bytes are 5336 base64 characters: two chunks
+        assert_eq!(first.matches("\x1b_Ga=t,f=100").count(), 1);
+        assert_eq!(first.matches("\x1b_Gm=0;").count(), 1);
+        assert!(first.contains("\x1b[6;3H"), "got: {first:?}");
+        assert!(first.contains(&format!("a=p,i={},r={IMAGE_ROWS}", placement.id)));
+        assert!(!images.changed(&[placement]));
+
+        // Moved: deleted and placed again, but not resent
+        let moved = ImagePlacement { y: 1, ..placement };
+        let mut out = Vec::new();
+        images.show(&mut out, &[moved], &items).unwrap();
+        let second = String::from_utf8(out).unwrap();
+        assert!(second.starts_with("\x1b7\x1b_Ga=d"), "got: {second:?}");
+        assert!(!second.contains("a=t"));
+        assert!(second.contains("a=p"));
+    }
+}
@@ -1,20 +1,34 @@
 //! Event dispatch and effect processing, extracted from the main event loop.
 
 use log::{debug, info, warn};
+use std::path::PathBuf;
 use std::sync::mpsc;
 
-use ratatui::layout::Rect;
+use ratatui::layout::{Position, Rect};
 
 use crate::core::action::{Action, Effect, update};
+use crate::core::config;
 use crate::core::session;
 use crate::core::state::{ActiveModel, App};
-use crate::inference::ContextItem;
+use crate::core::usage;
+use crate::inference::types::image_mime_for_path;
+use crate::inference::{ContextItem, ImageAttachment, Source};
+use crate::tui::command::{self, Command};
 use crate::tui::component::EventHandler;
+use crate::tui::components::message::{Density, Message, line_range_text};
+use crate::tui::components::message_list::{LayoutCache, TextPosition, TextSelection};
 use crate::tui::components::model_picker::ModelPickerEvent;
 use crate::tui::components::session_manager::SessionEvent;
-use crate::tui::components::{InputEvent, MessageListState, ModelPickerState, SessionManagerState};
+use crate::tui::components::spinner::Spinner;
+use crate::tui::components::{
+    DiffOverlayState, InputBox, InputEvent, MessageListState, ModelPickerState, SessionManagerState,
+};
 use crate::tui::event::TuiEvent;
-use crate::tui::{InputMode, TuiState, tasks, ui};
+use crate::tui::theme::Theme;
+use crate::tui::{InputMode, TuiState, clipboard, tabs, tasks, ui};
+
+/// Quoted messages are cut to this many lines.
+const QUOTE_MAX_LINES: usize = 6;
 
 /// Dispatch a single TuiEvent. Returns true if the app should quit.
 pub fn handle_event(
@@ -38,6 +52,55 @@ pub fn handle_event(
         return false;
     }
 
+    if matches!(event, TuiEvent::ReloadConfig) {
+        let mut warnings = Vec::new();
+        let action = match config::load_config() {
+            Ok(file_config) => {
+                warnings = config::validate(&file_config);
+                Action::ConfigReloaded(Box::new(config::resolve(
+                    &file_config,
+                    None,
+                    app.config.profile.as_deref(),
+                )))
+            }
+            Err(e) => {
+                warn!("Config reload failed: {}", e);
+                Action::ConfigReloadFailed(e.to_string())
+            }
+        };
+        let effect = update(app, action);
+        if effect == Effect::SwitchProvider {
+            switch_provider(app);
+        }
+        if let Some(status) = config::warnings_status(&warnings) {
+            app.session.status_message = status;
+        }
+        tui.theme = Theme::from_config(&app.config.theme);
+        tui.spinner = Spinner::from_config(app.config.spinner.as_deref());
+        return effect == Effect::Quit;
+    }
+
+    if matches!(event, TuiEvent::ClearConversation) {
+        return clear_conversation(app, tui);
+    }
+
+    if matches!(event, TuiEvent::NextVariant | TuiEvent::PrevVariant) {
+        return cycle_variant(app, tui, matches!(event, TuiEvent::NextVariant));
+    }
+
+    if matches!(event, TuiEvent::ShowVariantDiff) {
+        match app.session.context.variant_texts() {
+            Some((previous, shown)) => {
+                tui.variant_diff = Some(DiffOverlayState::new(&previous, &shown));
+            }
+            None => {
+                app.session.status_message =
+                    String::from("No earlier answer to compare; /regenerate keeps one.");
+            }
+        }
+        return false;
+    }
+
     if matches!(event, TuiEvent::OpenModelPicker) {
         let mut picker = ModelPickerState::new(app.available_models.clone());
         if let Some(ref models) = tui.fetched_models {
@@ -47,6 +110,27 @@ pub fn handle_event(
         return false;
     }
 
+    // Help overlay swallows the next key press to dismiss itself
+    if tui.show_help {
+        if !matches!(event, TuiEvent::MouseMove(..)) {
+            tui.show_help = false;
+        }
+        return false;
+    }
+
+    // Diff overlay scrolls with the arrows and wheel; any other key closes it
+    if let Some(diff) = tui.variant_diff.as_mut() {
+        match event {
+            TuiEvent::CursorUp | TuiEvent::ScrollUp => diff.scroll_by(-1),
+            TuiEvent::CursorDown | TuiEvent::ScrollDown => diff.scroll_by(1),
+            TuiEvent::ScrollPageUp => diff.scroll_by(-10),
+            TuiEvent::ScrollPageDown => diff.scroll_by(10),
+            TuiEvent::MouseMove(..) => {}
+            _ => tui.variant_diff = None,
+        }
+        return false;
+    }
+
     if tui.model_picker.is_some() {
         return handle_model_picker_event(&event, app, tui);
     }
@@ -55,24 +139,88 @@ pub fn handle_event(
         return handle_session_event(&event, app, tui);
     }
 
-    if let TuiEvent::MouseMove(_col, row) = event {
-        handle_mouse_move(row, app, tui, frame_area);
+    if handle_tab_event(&event, app, tui) {
+        return false;
+    }
+
+    // `?` opens help unless the user is typing it into a message
+    if matches!(event, TuiEvent::InputChar('?'))
+        && (tui.input_mode == InputMode::Cursor || tui.input_box.buffer.is_empty())
+    {
+        tui.show_help = true;
+        return false;
+    }
+
+    if matches!(event, TuiEvent::JumpNextPin | TuiEvent::JumpPrevPin) {
+        jump_to_pin(app, tui, matches!(event, TuiEvent::JumpNextPin));
+        return false;
+    }
+
+    if let TuiEvent::MouseMove(col, row) = event {
+        handle_mouse_move(Position::new(col, row), app, tui, frame_area);
         return false;
     }
 
-    if let TuiEvent::MouseClick(_col, row) = event {
-        handle_mouse_click(row, app, tui, frame_area);
+    if let TuiEvent::MouseClick(col, row) = event {
+        handle_mouse_click(Position::new(col, row), app, tui, frame_area);
+        return false;
+    }
+
+    if matches!(event, TuiEvent::ToggleReasoningPanel) {
+        tui.show_reasoning_panel = !tui.show_reasoning_panel;
+        // Line numbers change as thinking leaves or rejoins the conversation
+        tui.message_list.text_selection = None;
+        app.session.status_message = if tui.show_reasoning_panel {
+            String::from("Reasoning panel on")
+        } else {
+            String::from("Reasoning panel off")
+        };
         return false;
     }
 
+    if matches!(
+        event,
+        TuiEvent::ToggleShowThinking | TuiEvent::ToggleShowSystem
+    ) {
+        toggle_view_filter(app, tui, matches!(event, TuiEvent::ToggleShowThinking));
+        return false;
+    }
+
+    if matches!(event, TuiEvent::ToggleWrap) {
+        toggle_wrap(app, tui);
+        return false;
+    }
+
+    if matches!(event, TuiEvent::ToggleDensity) {
+        toggle_density(app, tui);
+        return false;
+    }
+
+    if matches!(event, TuiEvent::GrowInput | TuiEvent::ShrinkInput) {
+        let grow = matches!(event, TuiEvent::GrowInput);
+        let lines = InputBox::resized_max_lines(app.input_max_lines, grow, frame_area.height);
+        return update(app, Action::SetInputMaxLines(lines)) == Effect::Quit;
+    }
+
     if matches!(
         event,
         TuiEvent::ScrollUp
             | TuiEvent::ScrollDown
             | TuiEvent::ScrollPageUp
             | TuiEvent::ScrollPageDown
+            | TuiEvent::ScrollLeft
+            | TuiEvent::ScrollRight
     ) {
-        tui.message_list.handle_event(&event);
+        // The wheel scrolls the reasoning panel while the pointer is over it
+        let over_panel = tui
+            .mouse_position
+            .zip(ui::reasoning_panel_area(frame_area, tui))
+            .is_some_and(|(pointer, panel)| panel.contains(pointer));
+        if over_panel {
+            tui.reasoning_list.handle_event(&event);
+        } else {
+            tui.message_list.handle_event(&event);
+        }
         return false;
     }
 
@@ -82,7 +230,8 @@ pub fn handle_event(
     }
 }
 
-/// Drain the background action channel. Returns (should_quit, had_actions).
+/// Drain the background action channel, then every tab's channel.
+/// `tx` is the active tab's sender. Returns (should_quit, had_actions).
 pub fn process_background_actions(
     rx: &mpsc::Receiver<Action>,
     app: &mut App,
@@ -92,41 +241,164 @@ pub fn process_background_actions(
     let mut had_actions = false;
     while let Ok(action) = rx.try_recv() {
         had_actions = true;
+        if apply_background_action(action, app, tui, tx) {
+            return (true, had_actions);
+        }
+    }
+    let (quit, had_tab_actions) = process_tab_actions(app, tui);
+    (quit, had_actions || had_tab_actions)
+}
 
-        // Intercept ModelsFetched — TUI-only state, not core business logic
-        if let Action::ModelsFetched(models) = action {
-            debug!("Received {} fetched models", models.len());
-            tui.fetched_models = Some(models.clone());
-            if let Some(ref mut mp) = tui.model_picker {
-                mp.set_fetched_models(models);
-            }
+/// Apply the actions queued by each tab's tasks to that tab. Background tabs
+/// are swapped in for the duration, so their streams never touch the active
+/// conversation. Returns (should_quit, had_actions).
+fn process_tab_actions(app: &mut App, tui: &mut TuiState) -> (bool, bool) {
+    let mut had_actions = false;
+    for idx in 0..tui.tabs.len() {
+        let actions = tui.tabs.drain(idx);
+        if actions.is_empty() {
             continue;
         }
-
-        debug!("Event loop received: {:?}", action);
-        let effect = update(app, action);
-        match effect {
-            Effect::Quit => return (true, had_actions),
-            Effect::SpawnRequest => {
-                tui.active_abort_handles = tasks::spawn_request(app, tx.clone());
-            }
-            Effect::ExecuteTool(tool_call) => {
-                tasks::spawn_tool_execution(tool_call, app.registry.clone(), tx.clone());
-            }
-            Effect::SaveSession => {
-                session::save_current_session(app);
-            }
-            Effect::SwitchProvider => {
-                warn!("Unexpected SwitchProvider from background action");
+        had_actions = true;
+        let tx = tui.tabs.sender(idx);
+        let background = idx != tui.tabs.active();
+        if background {
+            tabs::swap_in(app, tui, idx);
+        }
+        let mut quit = false;
+        for action in actions {
+            if apply_background_action(action, app, tui, &tx) {
+                quit = true;
+                break;
             }
-            _ => {}
+        }
+        if background {
+            tabs::swap_in(app, tui, idx);
+        }
+        if quit {
+            return (true, had_actions);
         }
     }
     (false, had_actions)
 }
 
+/// Run one background action through the reducer and carry out its effect.
+/// Returns true if the app should quit.
+fn apply_background_action(
+    action: Action,
+    app: &mut App,
+    tui: &mut TuiState,
+    tx: &mpsc::Sender<Action>,
+) -> bool {
+    // Intercept ModelsFetched — TUI-only state, not core business logic
+    if let Action::ModelsFetched(models) = action {
+        debug!("Received {} fetched models", models.len());
+        tui.fetched_models = Some(models.clone());
+        if let Some(ref mut mp) = tui.model_picker {
+            mp.set_fetched_models(models);
+        }
+        return false;
+    }
+
+    debug!("Event loop received: {:?}", action);
+    // A SaveSession in response to these means the turn just finished
+    let ends_round = matches!(
+        action,
+        Action::ResponseDone(_) | Action::ToolResultReady { .. }
+    );
+    // Summarized items are gone, so cached heights and selections are stale
+    let summarized = matches!(action, Action::ContextSummarized { .. });
+    if summarized {
+        tui.message_list.layout.clear();
+        tui.reasoning_list.layout.clear();
+        tui.message_list.text_selection = None;
+    }
+    let effect = update(app, action);
+    if summarized {
+        tui.message_list.set_pins(&app.session.pins);
+    }
+    match effect {
+        Effect::Quit => return true,
+        Effect::SpawnRequest => {
+            tui.active_abort_handles = tasks::spawn_request(app, tx.clone());
+        }
+        Effect::ExecuteTool(tool_call) => {
+            tasks::spawn_tool_execution(tool_call, app.dispatcher.clone(), tx.clone());
+        }
+        Effect::SaveSession => {
+            session::save_current_session(app);
+            if ends_round {
+                let pricing = app.pricing();
+                if let Err(e) = usage::append_csv_row(
+                    &app.session.usage_stats,
+                    &app.model.name,
+                    pricing.as_ref(),
+                ) {
+                    warn!("Failed to append usage row: {}", e);
+                }
+            }
+        }
+        Effect::SwitchProvider => {
+            warn!("Unexpected SwitchProvider from background action");
+        }
+        _ => {}
+    }
+    false
+}
+
 // --- Private helpers ---
 
+/// Open, close, or switch tabs. Returns false if `event` isn't a tab event.
+fn handle_tab_event(event: &TuiEvent, app: &mut App, tui: &mut TuiState) -> bool {
+    match event {
+        TuiEvent::NewTab => tabs::open(app, tui),
+        TuiEvent::CloseTab => tabs::close_active(app, tui),
+        TuiEvent::NextTab => tabs::cycle(app, tui, true),
+        TuiEvent::PrevTab => tabs::cycle(app, tui, false),
+        _ => return false,
+    }
+    true
+}
+
+/// Switch message text between wrapping and sideways scrolling.
+fn toggle_wrap(app: &mut App, tui: &mut TuiState) {
+    tui.wrap_messages = !tui.wrap_messages;
+    tui.message_list.scroll_x = 0;
+    // Line numbers change with the wrap mode
+    tui.message_list.text_selection = None;
+    app.session.status_message = if tui.wrap_messages {
+        String::from("Word wrap on")
+    } else {
+        String::from("Word wrap off (Shift+wheel scrolls sideways)")
+    };
+}
+
+/// Show or hide thinking (`thinking`) or the system directive. Hidden
+/// messages stay in the context and are still sent; they just take no rows.
+fn toggle_view_filter(app: &mut App, tui: &mut TuiState, thinking: bool) {
+    let (shown, name) = if thinking {
+        (&mut tui.show_thinking, "Thinking")
+    } else {
+        (&mut tui.show_system, "System directive")
+    };
+    *shown = !*shown;
+    let state = if *shown { "shown" } else { "hidden" };
+    app.session.status_message = format!("{name} {state}");
+    // Line numbers change as messages leave or rejoin the conversation
+    tui.message_list.text_selection = None;
+}
+
+/// Switch messages between bordered and compact layouts.
+fn toggle_density(app: &mut App, tui: &mut TuiState) {
+    tui.message_density = tui.message_density.toggled();
+    // Line numbers change with the content width
+    tui.message_list.text_selection = None;
+    app.session.status_message = match tui.message_density {
+        Density::Comfortable => String::from("Comfortable messages"),
+        Density::Compact => String::from("Compact messages"),
+    };
+}
+
 /// Cancel in-progress generation: abort tasks and dispatch CancelGeneration.
 /// Returns true if the app should quit.
 fn try_cancel_generation(app: &mut App, tui: &mut TuiState) -> bool {
@@ -147,31 +419,68 @@ fn handle_input_mode(
         return try_cancel_generation(app, tui);
     }
     // Esc → switch to Cursor mode
-    if matches!(event, TuiEvent::Escape) {
-        tui.input_mode = InputMode::Cursor;
-        // Select the last non-ToolResult item
-        let items = &app.session.context.items;
-        let mut idx = items.len();
-        while idx > 0 {
-            idx -= 1;
-            if !matches!(items[idx], ContextItem::ToolResult(_)) {
-                break;
-            }
+    if matches!(event, TuiEvent::Escape | TuiEvent::EnterCursorMode) {
+        enter_cursor_mode(app, tui);
+        return false;
+    }
+    // Up on an empty input with no history to recall also enters Cursor mode
+    if matches!(event, TuiEvent::CursorUp) && tui.input_box.buffer.is_empty() {
+        if tui.input_box.handle_event(event).is_none() {
+            enter_cursor_mode(app, tui);
         }
-        tui.message_list.selected_index = if !items.is_empty() { Some(idx) } else { None };
         return false;
     }
 
+    if matches!(event, TuiEvent::PasteImage) {
+        tasks::spawn_clipboard_image_read(tx.clone());
+        return false;
+    }
+
+    // Backspace on an empty input drops a staged image
+    if matches!(event, TuiEvent::Backspace)
+        && tui.input_box.buffer.is_empty()
+        && app.session.pending_image.is_some()
+    {
+        return update(app, Action::UnstageImage) == Effect::Quit;
+    }
+
+    // Pasted image data is staged for the next message
+    if let TuiEvent::Paste(data) = event
+        && let Some(image) = ImageAttachment::from_data_url(data)
+    {
+        return update(app, Action::StageImage(image)) == Effect::Quit;
+    }
+
+    // A pasted path to an image file stages the image instead of inserting text
+    if let TuiEvent::Paste(data) = event
+        && let Some(path) = pasted_image_path(data)
+    {
+        match ImageAttachment::from_file(&path) {
+            Ok(image) => return update(app, Action::StageImage(image)) == Effect::Quit,
+            Err(e) => warn!("Image attach failed, pasting as text: {}", e),
+        }
+    }
+
+    // A send without credentials is refused; do it before the input box
+    // clears the text, so the message and any staged image stay for retry
+    if matches!(event, TuiEvent::Submit)
+        && !tui.input_box.buffer.trim().is_empty()
+        && !command::is_command(&tui.input_box.buffer)
+        && app.missing_credentials().is_some()
+    {
+        let text = command::message_text(tui.input_box.buffer.clone());
+        return update(app, Action::Submit(text)) == Effect::Quit;
+    }
+
     if let Some(input_event) = tui.input_box.handle_event(event) {
         match input_event {
             InputEvent::Submit(text) => {
                 if !app.session.is_loading {
                     let effect = update(app, Action::Submit(text));
-                    if effect == Effect::SpawnRequest {
-                        tui.active_abort_handles = tasks::spawn_request(app, tx.clone());
-                    }
+                    start_turn(effect, app, tui, tx);
                 }
             }
+            InputEvent::Command(text) => return run_command(&text, app, tui, tx),
             InputEvent::CycleEffort => {
                 return update(app, Action::CycleEffort) == Effect::Quit;
             }
@@ -181,6 +490,131 @@ fn handle_input_mode(
     false
 }
 
+/// Parse and dispatch a slash command. Parse errors surface in the status bar.
+/// Returns true if the app should quit.
+fn run_command(input: &str, app: &mut App, tui: &mut TuiState, tx: &mpsc::Sender<Action>) -> bool {
+    let command = match command::parse(input) {
+        Ok(command) => command,
+        Err(e) => {
+            app.session.status_message = e.to_string();
+            return false;
+        }
+    };
+    if app.session.is_loading && !matches!(command, Command::Effort(_) | Command::Once(_)) {
+        app.session.status_message = String::from("Wait for the response to finish.");
+        return false;
+    }
+
+    match command {
+        Command::Clear => clear_conversation(app, tui),
+        Command::Model(name) => {
+            let model = ActiveModel::new(name.clone(), provider_for_model(&name, app, tui));
+            let effect = update(app, Action::SwitchModel(model));
+            if effect == Effect::SwitchProvider {
+                switch_provider(app);
+            }
+            info!(
+                "Model switched: {} ({})",
+                app.model.name, app.model.provider
+            );
+            effect == Effect::Quit
+        }
+        Command::Save => {
+            session::save_current_session(app);
+            app.session.status_message = String::from("Session saved.");
+            false
+        }
+        Command::Export => {
+            app.session.status_message = match session::export_current_session(app) {
+                Ok(path) => format!("Exported to {}", path.display()),
+                Err(e) => {
+                    warn!("Export failed: {}", e);
+                    format!("Export failed: {}", e)
+                }
+            };
+            false
+        }
+        Command::Effort(effort) => update(app, Action::SetEffort(effort)) == Effect::Quit,
+        Command::Once(directive) => {
+            update(app, Action::SetTurnDirective(directive)) == Effect::Quit
+        }
+        Command::Continue => {
+            let effect = update(app, Action::Submit(String::from(command::CONTINUE_PROMPT)));
+            let quit = effect == Effect::Quit;
+            start_turn(effect, app, tui, tx);
+            quit
+        }
+        Command::Regenerate => {
+            let effect = update(app, Action::Regenerate);
+            tui.message_list.set_pins(&app.session.pins);
+            let quit = effect == Effect::Quit;
+            start_turn(effect, app, tui, tx);
+            quit
+        }
+    }
+}
+
+/// Clear the conversation (keeping the directive) and drop all cached layout,
+/// scroll, and expansion state. Returns true if the app should quit.
+fn clear_conversation(app: &mut App, tui: &mut TuiState) -> bool {
+    if app.session.is_loading {
+        app.session.status_message = String::from("Wait for the response to finish.");
+        return false;
+    }
+    let quit = update(app, Action::ClearConversation) == Effect::Quit;
+    tui.reset_message_list(MessageListState::new());
+    quit
+}
+
+/// Start the request a submitted or regenerated turn calls for: the turn
+/// itself, or first a summary of older turns when the context is nearly full.
+fn start_turn(effect: Effect, app: &App, tui: &mut TuiState, tx: &mpsc::Sender<Action>) {
+    match effect {
+        Effect::SpawnRequest => {
+            tui.active_abort_handles = tasks::spawn_request(app, tx.clone());
+        }
+        Effect::SummarizeContext(range) => {
+            tui.active_abort_handles = tasks::summarize_old_context(app, range, tx.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Show another regenerated answer. It replaces the current one in place, so
+/// cached heights can't be matched up by item count. Returns true if the app
+/// should quit.
+fn cycle_variant(app: &mut App, tui: &mut TuiState, forward: bool) -> bool {
+    let quit = update(app, Action::CycleVariant(forward)) == Effect::Quit;
+    tui.message_list.set_pins(&app.session.pins);
+    tui.message_list.layout.clear();
+    tui.reasoning_list.layout.clear();
+    tui.message_list.text_selection = None;
+    quit
+}
+
+/// Provider for a model named in `/model`: its configured or fetched entry,
+/// otherwise the current provider.
+fn provider_for_model(name: &str, app: &App, tui: &TuiState) -> String {
+    app.available_models
+        .iter()
+        .chain(tui.fetched_models.iter().flatten())
+        .find(|m| m.name == name)
+        .map(|m| m.provider.clone())
+        .unwrap_or_else(|| app.model.provider.clone())
+}
+
+/// If pasted text is a single path to an existing image file, return it.
+/// Accepts the quoted and `file://` forms terminals produce on drag-and-drop.
+fn pasted_image_path(data: &str) -> Option<PathBuf> {
+    let trimmed = data.trim();
+    if trimmed.contains('\n') {
+        return None;
+    }
+    let unquoted = trimmed.trim_matches(|c| c == '\'' || c == '"');
+    let path = PathBuf::from(unquoted.strip_prefix("file://").unwrap_or(unquoted));
+    (image_mime_for_path(&path).is_some() && path.is_file()).then_some(path)
+}
+
 fn handle_cursor_mode(
     event: &TuiEvent,
     app: &mut App,
@@ -188,6 +622,23 @@ fn handle_cursor_mode(
     tx: &mpsc::Sender<Action>,
 ) -> bool {
     let _ = tx; // unused here but kept for symmetry with handle_input_mode
+    if tui.message_list.text_selection.is_some() {
+        match event {
+            TuiEvent::CursorUp | TuiEvent::InputChar('k') => {
+                extend_text_selection(app, tui, false);
+                return false;
+            }
+            TuiEvent::CursorDown | TuiEvent::InputChar('j') => {
+                extend_text_selection(app, tui, true);
+                return false;
+            }
+            TuiEvent::Escape | TuiEvent::InputChar('v') => {
+                tui.message_list.text_selection = None;
+                return false;
+            }
+            _ => {}
+        }
+    }
     match event {
         TuiEvent::Escape if app.session.is_loading => try_cancel_generation(app, tui),
         TuiEvent::Escape => false,
@@ -203,23 +654,43 @@ fn handle_cursor_mode(
             }
             false
         }
-        TuiEvent::InputChar(_) | TuiEvent::Paste(_) => {
-            tui.input_mode = InputMode::Input;
-            tui.message_list.selected_index = None;
-            tui.input_box.handle_event(event);
+        TuiEvent::InputChar('f') => fork_at_selection(app, tui),
+        TuiEvent::InputChar('v') => {
+            start_text_selection(app, tui);
             false
         }
-        TuiEvent::Submit => {
-            tui.input_mode = InputMode::Input;
-            tui.message_list.selected_index = None;
+        TuiEvent::InputChar('y') => {
+            copy_selection(app, tui);
             false
         }
-        TuiEvent::CursorUp => {
-            navigate_messages_up(app, tui);
+        TuiEvent::QuoteSelected | TuiEvent::InputChar('r') => {
+            quote_selected(app, tui);
             false
         }
-        TuiEvent::CursorDown => {
-            navigate_messages_down(app, tui);
+        TuiEvent::TogglePin | TuiEvent::InputChar('m') => toggle_pin(app, tui),
+        TuiEvent::InputChar('n') => {
+            jump_to_pin(app, tui, true);
+            false
+        }
+        TuiEvent::InputChar('N') => {
+            jump_to_pin(app, tui, false);
+            false
+        }
+        TuiEvent::CursorUp | TuiEvent::InputChar('k') => {
+            navigate_messages(app, tui, false);
+            false
+        }
+        TuiEvent::CursorDown | TuiEvent::InputChar('j') => {
+            navigate_messages(app, tui, true);
+            false
+        }
+        TuiEvent::InputChar(_) | TuiEvent::InsertNewline | TuiEvent::Paste(_) => {
+            exit_cursor_mode(tui);
+            tui.input_box.handle_event(event);
+            false
+        }
+        TuiEvent::Submit | TuiEvent::ExitCursorMode => {
+            exit_cursor_mode(tui);
             false
         }
         TuiEvent::CycleEffort => update(app, Action::CycleEffort) == Effect::Quit,
@@ -227,59 +698,262 @@ fn handle_cursor_mode(
     }
 }
 
-fn navigate_messages_up(app: &App, tui: &mut TuiState) {
+/// Switch to Cursor mode with the last selectable item selected.
+fn enter_cursor_mode(app: &App, tui: &mut TuiState) {
+    tui.input_mode = InputMode::Cursor;
     let items = &app.session.context.items;
-    if !items.is_empty() {
-        let mut idx = tui
-            .message_list
-            .selected_index
-            .map(|i| i.saturating_sub(1))
-            .unwrap_or(items.len() - 1);
-        while idx > 0 && matches!(items[idx], ContextItem::ToolResult(_)) {
-            idx -= 1;
+    tui.message_list.selected_index = (0..items.len())
+        .rev()
+        .find(|&idx| is_selectable(items, &tui.message_list.layout, idx));
+}
+
+fn exit_cursor_mode(tui: &mut TuiState) {
+    tui.input_mode = InputMode::Input;
+    tui.message_list.selected_index = None;
+    tui.message_list.text_selection = None;
+}
+
+/// Start a line selection on the first line of the selected message.
+fn start_text_selection(app: &mut App, tui: &mut TuiState) {
+    let Some(idx) = tui.message_list.selected_index else {
+        return;
+    };
+    if rendered_line_count(app, tui, idx).unwrap_or(0) == 0 {
+        app.session.status_message = String::from("Only message text can be selected.");
+        return;
+    }
+    let start = TextPosition { item: idx, line: 0 };
+    tui.message_list.text_selection = Some(TextSelection::at(start));
+    app.session.status_message = String::from("Selecting: j/k extend, y copies, Esc cancels");
+}
+
+/// Rendered text lines in `items[idx]`, or None if it isn't a message.
+/// A hidden message has none.
+fn rendered_line_count(app: &App, tui: &TuiState, idx: usize) -> Option<usize> {
+    let ContextItem::Message(seg) = app.session.context.items.get(idx)? else {
+        return None;
+    };
+    if tui.message_list.layout.heights.get(idx) == Some(&0) {
+        return Some(0);
+    }
+    let width = tui.message_list.layout.content_width();
+    let text = Message::rendered_text(
+        seg,
+        width,
+        &tui.theme,
+        tui.wrap_messages,
+        tui.message_density,
+    );
+    Some(text.lines.len())
+}
+
+/// Move the selection's cursor one line, crossing into the next (or
+/// previous) message at either end of the current one.
+fn extend_text_selection(app: &App, tui: &mut TuiState, forward: bool) {
+    let Some(mut selection) = tui.message_list.text_selection else {
+        return;
+    };
+    let TextPosition { item, line } = selection.cursor;
+    let line_count = |idx| rendered_line_count(app, tui, idx).filter(|&count| count > 0);
+    let next = if forward {
+        if line + 1 < line_count(item).unwrap_or(0) {
+            Some(TextPosition {
+                item,
+                line: line + 1,
+            })
+        } else {
+            (item + 1..app.session.context.items.len())
+                .find(|&idx| line_count(idx).is_some())
+                .map(|idx| TextPosition { item: idx, line: 0 })
         }
-        tui.message_list.selected_index = Some(idx);
+    } else if line > 0 {
+        Some(TextPosition {
+            item,
+            line: line - 1,
+        })
+    } else {
+        (0..item).rev().find_map(|idx| {
+            let count = line_count(idx)?;
+            Some(TextPosition {
+                item: idx,
+                line: count - 1,
+            })
+        })
+    };
+    let Some(cursor) = next else {
+        return;
+    };
+    selection.cursor = cursor;
+    tui.message_list.text_selection = Some(selection);
+    if cursor.item != item {
+        tui.message_list.selected_index = Some(cursor.item);
         tui.message_list.scroll_to_selected();
     }
 }
 
-fn navigate_messages_down(app: &App, tui: &mut TuiState) {
+/// Copy the selected lines to the clipboard, or the whole selected message
+/// when no lines are selected. Ends the line selection.
+fn copy_selection(app: &mut App, tui: &mut TuiState) {
     let items = &app.session.context.items;
-    if let Some(mut idx) = tui.message_list.selected_index
-        && idx + 1 < items.len()
-    {
-        idx += 1;
-        while idx < items.len() && matches!(items[idx], ContextItem::ToolResult(_)) {
-            idx += 1;
+    let text = match tui.message_list.text_selection.take() {
+        Some(selection) => {
+            let (start, end) = selection.ordered();
+            let width = tui.message_list.layout.content_width();
+            (start.item..=end.item)
+                .filter_map(|idx| {
+                    let ContextItem::Message(seg) = items.get(idx)? else {
+                        return None;
+                    };
+                    let (first, last) = selection.lines_of(idx)?;
+                    let text = Message::rendered_text(
+                        seg,
+                        width,
+                        &tui.theme,
+                        tui.wrap_messages,
+                        tui.message_density,
+                    );
+                    Some(line_range_text(&text, first, last))
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
         }
-        if idx < items.len() {
-            tui.message_list.selected_index = Some(idx);
-            tui.message_list.scroll_to_selected();
+        None => {
+            let selected = tui.message_list.selected_index;
+            match selected.and_then(|idx| items.get(idx)) {
+                Some(ContextItem::Message(seg)) => seg.content.trim().to_string(),
+                _ => return,
+            }
+        }
+    };
+    let lines = text.lines().count();
+    app.session.status_message = match clipboard::copy(&text) {
+        Ok(()) if lines == 1 => String::from("Copied 1 line"),
+        Ok(()) => format!("Copied {lines} lines"),
+        Err(e) => {
+            warn!("Clipboard copy failed: {}", e);
+            format!("Copy failed: {}", e)
+        }
+    };
+}
+
+/// Quote the selected user or model message at the top of the input as a
+/// Markdown blockquote, and return to the input to type below it.
+fn quote_selected(app: &mut App, tui: &mut TuiState) {
+    let items = &app.session.context.items;
+    let selected = tui.message_list.selected_index;
+    let Some(ContextItem::Message(seg)) = selected.and_then(|idx| items.get(idx)) else {
+        return;
+    };
+    if !matches!(seg.source, Source::User | Source::Model) || seg.content.trim().is_empty() {
+        app.session.status_message = String::from("Only your messages and replies can be quoted.");
+        return;
+    }
+    let quote = blockquote(&seg.content);
+    exit_cursor_mode(tui);
+    tui.input_box.prepend(&quote);
+}
+
+/// Pin or unpin the selected message; the pins are saved with the session.
+fn toggle_pin(app: &mut App, tui: &mut TuiState) -> bool {
+    let Some(idx) = tui.message_list.selected_index else {
+        return false;
+    };
+    if !matches!(
+        app.session.context.items.get(idx),
+        Some(ContextItem::Message(_))
+    ) {
+        app.session.status_message = String::from("Only messages can be pinned.");
+        return false;
+    }
+    let pinned = tui.message_list.toggle_pin(idx);
+    let pins = tui.message_list.pinned_indices.iter().copied().collect();
+    let quit = update(app, Action::SetPins(pins)) == Effect::Quit;
+    app.session.status_message = String::from(if pinned { "Pinned" } else { "Unpinned" });
+    quit
+}
+
+/// Select the next (or previous) pinned message, in cursor mode.
+fn jump_to_pin(app: &mut App, tui: &mut TuiState, forward: bool) {
+    if tui.message_list.jump_to_pin(forward) {
+        tui.input_mode = InputMode::Cursor;
+    } else {
+        app.session.status_message = String::from("No pinned messages; m pins one in cursor mode.");
+    }
+}
+
+/// `text` as a "> " blockquote of at most `QUOTE_MAX_LINES` lines, followed
+/// by a blank line.
+fn blockquote(text: &str) -> String {
+    let lines: Vec<&str> = text.trim().lines().collect();
+    let mut quote = String::new();
+    for line in lines.iter().take(QUOTE_MAX_LINES) {
+        quote.push('>');
+        if !line.is_empty() {
+            quote.push(' ');
+            quote.push_str(line);
         }
+        quote.push('\n');
+    }
+    if lines.len() > QUOTE_MAX_LINES {
+        quote.push_str("> …\n");
     }
+    quote.push('\n');
+    quote
 }
 
-fn handle_mouse_move(row: u16, _app: &App, tui: &mut TuiState, frame_area: Rect) {
+/// Whether cursor navigation can land on `items[idx]`. ToolResults are shown
+/// inside their ToolCall, and zero-height items take no rows on screen.
+/// Items not yet measured count as selectable.
+fn is_selectable(items: &[ContextItem], layout: &LayoutCache, idx: usize) -> bool {
+    !matches!(items[idx], ContextItem::ToolResult(_)) && layout.heights.get(idx) != Some(&0)
+}
+
+/// Move the selection to the next (or previous) selectable item, wrapping
+/// around at either end of the conversation.
+fn navigate_messages(app: &App, tui: &mut TuiState, forward: bool) {
+    let items = &app.session.context.items;
+    let len = items.len();
+    let current = tui.message_list.selected_index.filter(|&idx| idx < len);
+    let next = (1..=len)
+        .map(|step| match (current, forward) {
+            (Some(idx), true) => (idx + step) % len,
+            (Some(idx), false) => (idx + len - step) % len,
+            (None, true) => step - 1,
+            (None, false) => len - step,
+        })
+        .find(|&idx| is_selectable(items, &tui.message_list.layout, idx));
+    if let Some(idx) = next {
+        tui.message_list.selected_index = Some(idx);
+        tui.message_list.scroll_to_selected();
+    }
+}
+
+fn handle_mouse_move(position: Position, _app: &App, tui: &mut TuiState, frame_area: Rect) {
+    tui.mouse_position = Some(position);
     let scroll_offset = tui.message_list.scroll_state.offset().y;
     let input_height = tui.input_box.calculate_height(frame_area.width);
     tui.message_list.selected_index = ui::hit_test_message(
-        row,
+        position,
         frame_area,
         scroll_offset,
-        &tui.message_list.layout.prefix_heights,
+        &tui.message_list.layout,
+        ui::tab_bar_height(tui),
         input_height,
+        tui.show_reasoning_panel,
     );
 }
 
-fn handle_mouse_click(row: u16, app: &App, tui: &mut TuiState, frame_area: Rect) {
+fn handle_mouse_click(position: Position, app: &App, tui: &mut TuiState, frame_area: Rect) {
     let scroll_offset = tui.message_list.scroll_state.offset().y;
     let input_height = tui.input_box.calculate_height(frame_area.width);
     let hit = ui::hit_test_message(
-        row,
+        position,
         frame_area,
         scroll_offset,
-        &tui.message_list.layout.prefix_heights,
+        &tui.message_list.layout,
+        ui::tab_bar_height(tui),
         input_height,
+        tui.show_reasoning_panel,
     );
     if let Some(idx) = hit {
         tui.message_list.selected_index = Some(idx);
@@ -308,7 +982,10 @@ fn handle_session_event(event: &TuiEvent, app: &mut App, tui: &mut TuiState) ->
                             tui.session_manager = None;
                             return true;
                         }
-                        tui.message_list = MessageListState::new();
+                        tui.reset_message_list(
+                            MessageListState::restored(app.session.scroll)
+                                .with_pins(&app.session.pins),
+                        );
                     }
                     Err(e) => {
                         warn!("Failed to load session {}: {}", id, e);
@@ -324,7 +1001,7 @@ fn handle_session_event(event: &TuiEvent, app: &mut App, tui: &mut TuiState) ->
                     tui.session_manager = None;
                     return true;
                 }
-                tui.message_list = MessageListState::new();
+                tui.reset_message_list(MessageListState::new());
                 tui.session_manager = None;
             }
             SessionEvent::Rename { id, new_title } => {
@@ -335,6 +1012,18 @@ fn handle_session_event(event: &TuiEvent, app: &mut App, tui: &mut TuiState) ->
                     return true;
                 }
             }
+            SessionEvent::AddTag { id, tag } => {
+                if let Err(e) = session::add_tag(&id, &tag) {
+                    warn!("Failed to tag session {}: {}", id, e);
+                    app.session.status_message = format!("Tag failed: {}", e);
+                }
+            }
+            SessionEvent::RemoveTag { id, tag } => {
+                if let Err(e) = session::remove_tag(&id, &tag) {
+                    warn!("Failed to untag session {}: {}", id, e);
+                    app.session.status_message = format!("Tag failed: {}", e);
+                }
+            }
             SessionEvent::Delete(id) => {
                 let is_active = app.session.current_session_id.as_deref() == Some(&id);
                 if let Err(e) = session::delete_session(&id) {
@@ -343,7 +1032,7 @@ fn handle_session_event(event: &TuiEvent, app: &mut App, tui: &mut TuiState) ->
                 sm.remove_session(&id);
                 let effect = update(app, Action::SessionDeleted(id));
                 if is_active {
-                    tui.message_list = MessageListState::new();
+                    tui.reset_message_list(MessageListState::new());
                 }
                 if effect == Effect::Quit {
                     return true;
@@ -357,11 +1046,48 @@ fn handle_session_event(event: &TuiEvent, app: &mut App, tui: &mut TuiState) ->
     false
 }
 
+/// Fork the conversation at the selected message and switch to the fork.
+/// Returns true if the app should quit.
+fn fork_at_selection(app: &mut App, tui: &mut TuiState) -> bool {
+    let Some(index) = tui.message_list.selected_index else {
+        return false;
+    };
+    if app.session.is_loading {
+        app.session.status_message = String::from("Wait for the response to finish to fork.");
+        return false;
+    }
+    match session::fork_current_session(app, index) {
+        Ok(fork) => {
+            let title = fork.meta.title.clone();
+            let effect = update(app, Action::LoadSession(fork));
+            if effect == Effect::SwitchProvider {
+                switch_provider(app);
+            }
+            app.session.status_message = format!("Forked: {}", title);
+            tui.reset_message_list(MessageListState::new().with_pins(&app.session.pins));
+            tui.input_mode = InputMode::Input;
+            effect == Effect::Quit
+        }
+        Err(e) => {
+            warn!("Fork failed: {}", e);
+            app.session.status_message = format!("Fork failed: {}", e);
+            false
+        }
+    }
+}
+
 fn switch_provider(app: &mut App) {
     let mut build_config = app.config.clone();
     build_config.provider = app.model.provider.clone();
     build_config.model_name = app.model.name.clone();
-    app.provider = crate::inference::build_provider(&build_config);
+    match crate::inference::build_provider(&build_config) {
+        Ok(provider) => app.provider = provider,
+        // Keep the current provider; sends are refused until credentials are set
+        Err(hint) => {
+            warn!("Provider '{}' unavailable: {}", app.model.provider, hint);
+            app.session.status_message = hint.to_string();
+        }
+    }
 }
 
 fn handle_model_picker_event(event: &TuiEvent, app: &mut App, tui: &mut TuiState) -> bool {
@@ -444,19 +1170,315 @@ mod tests {
             name: "fetched-model".to_string(),
             provider: "openrouter".to_string(),
             description: None,
+            ..Default::default()
         }]);
         let (tx, _rx) = mpsc::channel();
 
-        let quit = handle_event(
-            TuiEvent::OpenModelPicker,
+        let quit = handle_event(
+            TuiEvent::OpenModelPicker,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+
+        assert!(!quit);
+        assert!(tui.model_picker.is_some());
+    }
+
+    #[test]
+    fn test_question_mark_opens_help_when_input_empty() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::InputChar('?'),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+
+        assert!(tui.show_help);
+        assert!(tui.input_box.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_question_mark_typed_when_input_has_text() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        tui.input_box.buffer = "why".to_string();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::InputChar('?'),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+
+        assert!(!tui.show_help);
+    }
+
+    #[test]
+    fn test_help_dismissed_by_any_key() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        tui.show_help = true;
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::MouseMove(0, 0),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert!(tui.show_help, "mouse movement should not dismiss help");
+
+        handle_event(
+            TuiEvent::InputChar('x'),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert!(!tui.show_help);
+        assert!(tui.input_box.buffer.is_empty(), "dismiss key is swallowed");
+    }
+
+    #[test]
+    fn test_toggle_wrap_resets_sideways_scroll() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+        assert!(tui.wrap_messages);
+
+        handle_event(
+            TuiEvent::ToggleWrap,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert!(!tui.wrap_messages);
+
+        handle_event(
+            TuiEvent::ScrollRight,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert!(tui.message_list.scroll_x > 0);
+
+        handle_event(
+            TuiEvent::ToggleWrap,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert!(tui.wrap_messages);
+        assert_eq!(tui.message_list.scroll_x, 0);
+        assert_eq!(app.session.status_message, "Word wrap on");
+    }
+
+    #[test]
+    fn test_toggle_density_switches_layout() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+        assert_eq!(tui.message_density, Density::Comfortable);
+
+        handle_event(
+            TuiEvent::ToggleDensity,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.message_density, Density::Compact);
+        assert_eq!(app.session.status_message, "Compact messages");
+
+        handle_event(
+            TuiEvent::ToggleDensity,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.message_density, Density::Comfortable);
+    }
+
+    #[test]
+    fn test_model_picker_select_switches_provider_and_keeps_conversation() {
+        let mut app = test_app();
+        app.session.context.add_user_message("hello".to_string());
+        let items_before = app.session.context.items.len();
+        let mut tui = test_tui_state();
+        tui.model_picker = Some(ModelPickerState::new(vec![ModelEntry {
+            name: "qwen2.5-coder-32b".to_string(),
+            provider: "lmstudio".to_string(),
+            description: None,
+            ..Default::default()
+        }]));
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(TuiEvent::Submit, &mut app, &mut tui, &tx, test_frame_area());
+
+        assert!(tui.model_picker.is_none());
+        assert_eq!(app.model.name, "qwen2.5-coder-32b");
+        assert_eq!(app.model.provider, "lmstudio");
+        assert_eq!(app.session.context.items.len(), items_before);
+    }
+
+    #[test]
+    fn test_submit_without_credentials_keeps_the_input() {
+        let mut app = test_app();
+        app.model.provider = "openrouter".to_string();
+        app.config.openrouter_api_key = None;
+        app.session.pending_image = Some(ImageAttachment::from_bytes(b"png", "image/png", None));
+        let mut tui = test_tui_state();
+        tui.input_box.buffer = "Hello, model!".to_string();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(TuiEvent::Submit, &mut app, &mut tui, &tx, test_frame_area());
+
+        assert_eq!(tui.input_box.buffer, "Hello, model!");
+        assert!(app.session.pending_image.is_some());
+        assert!(!app.session.is_loading);
+        assert!(matches!(
+            app.session.context.items.last(),
+            Some(ContextItem::Message(seg)) if seg.source == Source::Status
+        ));
+    }
+
+    #[test]
+    fn test_slash_command_dispatches_instead_of_submitting() {
+        let mut app = test_app();
+        let items_before = app.session.context.items.len();
+        let mut tui = test_tui_state();
+        tui.input_box.buffer = "/effort high".to_string();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(TuiEvent::Submit, &mut app, &mut tui, &tx, test_frame_area());
+
+        assert_eq!(app.effort, Effort::High);
+        assert_eq!(app.session.context.items.len(), items_before);
+        assert!(!app.session.is_loading);
+    }
+
+    #[test]
+    fn test_unknown_slash_command_sets_status_error() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        tui.input_box.buffer = "/bogus".to_string();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(TuiEvent::Submit, &mut app, &mut tui, &tx, test_frame_area());
+
+        assert_eq!(app.session.status_message, "Unknown command: /bogus");
+        assert!(!app.session.is_loading);
+    }
+
+    #[test]
+    fn test_model_command_uses_configured_provider() {
+        let mut app = test_app();
+        app.available_models = vec![ModelEntry {
+            name: "qwen2.5-coder-32b".to_string(),
+            provider: "lmstudio".to_string(),
+            ..Default::default()
+        }];
+        let mut tui = test_tui_state();
+        tui.input_box.buffer = "/model qwen2.5-coder-32b".to_string();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(TuiEvent::Submit, &mut app, &mut tui, &tx, test_frame_area());
+
+        assert_eq!(app.model.name, "qwen2.5-coder-32b");
+        assert_eq!(app.model.provider, "lmstudio");
+    }
+
+    #[test]
+    fn test_click_selects_and_toggles_tool_call() {
+        let mut app = test_app();
+        app.session.context.add_user_message("1+1?".to_string());
+        app.session
+            .context
+            .add_tool_call(crate::inference::ToolCall {
+                id: "fc_1".to_string(),
+                call_id: "call_1".to_string(),
+                name: "add".to_string(),
+                arguments: "{}".to_string(),
+            });
+        let mut tui = test_tui_state();
+        // Directive hidden, user message rows 0-2, tool call rows 3-4
+        tui.message_list.layout.heights = vec![0, 3, 2];
+        tui.message_list.layout.rebuild_prefix_heights();
+        let (tx, _rx) = mpsc::channel();
+        // Main area starts below the one-line title bar
+        let tool_row = 1 + 3;
+
+        handle_event(
+            TuiEvent::MouseClick(10, tool_row),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.message_list.selected_index, Some(2));
+        assert!(tui.message_list.expanded_indices.contains(&2));
+
+        handle_event(
+            TuiEvent::MouseClick(10, tool_row),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert!(!tui.message_list.expanded_indices.contains(&2));
+
+        // Clicking the empty area below the conversation hits nothing
+        handle_event(
+            TuiEvent::MouseClick(10, 12),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.message_list.selected_index, Some(2));
+        assert!(tui.message_list.expanded_indices.is_empty());
+    }
+
+    #[test]
+    fn test_clear_conversation_invalidates_layout() {
+        let mut app = test_app();
+        app.session.context.add_user_message("hello".to_string());
+        app.session.current_session_id = Some("sess-1".to_string());
+        let mut tui = test_tui_state();
+        tui.message_list.layout.heights = vec![3, 5];
+        tui.message_list.layout.prefix_heights = vec![0, 3];
+        tui.message_list.expanded_indices.insert(1);
+        tui.message_list.selected_index = Some(1);
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::ClearConversation,
             &mut app,
             &mut tui,
             &tx,
             test_frame_area(),
         );
 
-        assert!(!quit);
-        assert!(tui.model_picker.is_some());
+        assert_eq!(app.session.context.items.len(), 1);
+        assert!(app.session.current_session_id.is_none());
+        assert!(tui.message_list.layout.heights.is_empty());
+        assert!(tui.message_list.layout.prefix_heights.is_empty());
+        assert!(tui.message_list.expanded_indices.is_empty());
+        assert!(tui.message_list.selected_index.is_none());
     }
 
     #[test]
@@ -509,6 +1531,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::User,
                 content: "hello".to_string(),
+                timestamp: None,
             }));
         let (tx, _rx) = mpsc::channel();
 
@@ -536,6 +1559,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::User,
                 content: "hello".to_string(),
+                timestamp: None,
             }));
         // items[2] = tool result (should be skipped)
         app.session
@@ -552,6 +1576,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::Model,
                 content: "response".to_string(),
+                timestamp: None,
             }));
         let (tx, _rx) = mpsc::channel();
 
@@ -586,6 +1611,29 @@ mod tests {
         assert_eq!(tui.message_list.selected_index, None);
     }
 
+    #[test]
+    fn test_fork_in_cursor_mode_refused_while_loading() {
+        let mut app = test_app();
+        app.session.is_loading = true;
+        let mut tui = test_tui_state();
+        tui.input_mode = InputMode::Cursor;
+        tui.message_list.selected_index = Some(0);
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::InputChar('f'),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+
+        // 'f' forks rather than switching to input, and does nothing mid-response
+        assert_eq!(tui.input_mode, InputMode::Cursor);
+        assert_eq!(tui.message_list.selected_index, Some(0));
+        assert!(app.session.current_session_id.is_none());
+    }
+
     #[test]
     fn test_enter_in_cursor_mode_switches_to_input() {
         let mut app = test_app();
@@ -651,6 +1699,7 @@ mod tests {
                 .push(ContextItem::Message(ContextSegment {
                     source: Source::User,
                     content: format!("msg {i}"),
+                    timestamp: None,
                 }));
         }
         tui.message_list.selected_index = Some(3); // last item
@@ -667,6 +1716,115 @@ mod tests {
         assert_eq!(tui.message_list.selected_index, Some(2));
     }
 
+    #[test]
+    fn test_text_selection_extends_across_messages() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        tui.input_mode = InputMode::Cursor;
+        for content in ["one\ntwo\nthree", "four"] {
+            app.session
+                .context
+                .items
+                .push(ContextItem::Message(ContextSegment {
+                    source: Source::Thinking,
+                    content: content.to_string(),
+                    timestamp: None,
+                }));
+        }
+        tui.message_list
+            .layout
+            .update_metadata(3, 80, &std::collections::HashSet::new());
+        tui.message_list.selected_index = Some(1);
+        let (tx, _rx) = mpsc::channel();
+
+        for event in [
+            TuiEvent::InputChar('v'),
+            TuiEvent::InputChar('j'),
+            TuiEvent::CursorDown,
+            TuiEvent::InputChar('j'),
+        ] {
+            handle_event(event, &mut app, &mut tui, &tx, test_frame_area());
+        }
+
+        let selection = tui.message_list.text_selection.unwrap();
+        assert_eq!(selection.anchor, TextPosition { item: 1, line: 0 });
+        assert_eq!(selection.cursor, TextPosition { item: 2, line: 0 });
+        assert_eq!(selection.lines_of(1), Some((0, usize::MAX)));
+        assert_eq!(selection.lines_of(2), Some((0, 0)));
+        assert_eq!(tui.message_list.selected_index, Some(2));
+
+        // Esc drops the selection but stays in cursor mode
+        handle_event(TuiEvent::Escape, &mut app, &mut tui, &tx, test_frame_area());
+        assert!(tui.message_list.text_selection.is_none());
+        assert_eq!(tui.input_mode, InputMode::Cursor);
+    }
+
+    #[tokio::test]
+    async fn test_pasted_image_attached_on_submit() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+        let area = test_frame_area();
+        let paste = TuiEvent::Paste("data:image/png;base64,iVBORw==".to_string());
+
+        handle_event(paste, &mut app, &mut tui, &tx, area);
+        assert!(tui.input_box.buffer.is_empty());
+        assert!(app.session.pending_image.is_some());
+        assert_eq!(app.session.context.items.len(), 1);
+
+        for event in [
+            TuiEvent::InputChar('h'),
+            TuiEvent::InputChar('i'),
+            TuiEvent::Submit,
+        ] {
+            handle_event(event, &mut app, &mut tui, &tx, area);
+        }
+
+        assert!(app.session.pending_image.is_none());
+        let items = &app.session.context.items;
+        assert!(matches!(&items[1], ContextItem::Image(image) if image.mime == "image/png"));
+        assert!(matches!(&items[2], ContextItem::Message(seg) if seg.content == "hi"));
+    }
+
+    #[test]
+    fn test_quote_selected_prepends_blockquote() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        tui.input_mode = InputMode::Cursor;
+        let long = (1..=8).map(|n| format!("line {n}")).collect::<Vec<_>>();
+        for (source, content) in [
+            (Source::User, "Why?\n\nJust curious".to_string()),
+            (Source::Model, long.join("\n")),
+        ] {
+            app.session
+                .context
+                .items
+                .push(ContextItem::Message(ContextSegment {
+                    source,
+                    content,
+                    timestamp: None,
+                }));
+        }
+        tui.input_box.buffer = String::from("draft");
+        tui.message_list.selected_index = Some(1);
+        let (tx, _rx) = mpsc::channel();
+        let area = test_frame_area();
+
+        handle_event(TuiEvent::QuoteSelected, &mut app, &mut tui, &tx, area);
+        assert_eq!(tui.input_mode, InputMode::Input);
+        assert_eq!(tui.input_box.buffer, "> Why?\n>\n> Just curious\n\ndraft");
+
+        // The cursor sits after the quote, so typing lands before the draft
+        handle_event(TuiEvent::InputChar('!'), &mut app, &mut tui, &tx, area);
+        assert!(tui.input_box.buffer.ends_with("curious\n\n!draft"));
+
+        // Long messages are cut short
+        assert_eq!(
+            blockquote(&long.join("\n")),
+            "> line 1\n> line 2\n> line 3\n> line 4\n> line 5\n> line 6\n> …\n\n"
+        );
+    }
+
     #[test]
     fn test_navigate_up_skips_tool_results() {
         let mut app = test_app();
@@ -679,6 +1837,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::User,
                 content: "hello".to_string(),
+                timestamp: None,
             }));
         app.session
             .context
@@ -693,6 +1852,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::Model,
                 content: "response".to_string(),
+                timestamp: None,
             }));
         tui.message_list.selected_index = Some(3);
         let (tx, _rx) = mpsc::channel();
@@ -721,6 +1881,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::User,
                 content: "hello".to_string(),
+                timestamp: None,
             }));
         app.session
             .context
@@ -735,6 +1896,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::Model,
                 content: "response".to_string(),
+                timestamp: None,
             }));
         tui.message_list.selected_index = Some(1);
         let (tx, _rx) = mpsc::channel();
@@ -762,6 +1924,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::User,
                 content: "hello".to_string(),
+                timestamp: None,
             }));
         tui.message_list.selected_index = None;
         let (tx, _rx) = mpsc::channel();
@@ -779,7 +1942,7 @@ mod tests {
     }
 
     #[test]
-    fn test_navigate_down_at_end_stays_put() {
+    fn test_navigate_down_at_end_wraps_to_top() {
         let mut app = test_app();
         let mut tui = test_tui_state();
         tui.input_mode = InputMode::Cursor;
@@ -789,6 +1952,7 @@ mod tests {
             .push(ContextItem::Message(ContextSegment {
                 source: Source::User,
                 content: "hello".to_string(),
+                timestamp: None,
             }));
         tui.message_list.selected_index = Some(1); // last item
         let (tx, _rx) = mpsc::channel();
@@ -800,8 +1964,109 @@ mod tests {
             &tx,
             test_frame_area(),
         );
+        assert_eq!(tui.message_list.selected_index, Some(0));
+
+        handle_event(
+            TuiEvent::CursorUp,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.message_list.selected_index, Some(1));
+    }
+
+    #[test]
+    fn test_navigate_skips_zero_height_items() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        tui.input_mode = InputMode::Cursor;
+        // [0]=system, [1]=user, [2]=empty model (height 0), [3]=model
+        for (source, content) in [
+            (Source::User, "hello"),
+            (Source::Model, ""),
+            (Source::Model, "response"),
+        ] {
+            app.session
+                .context
+                .items
+                .push(ContextItem::Message(ContextSegment {
+                    source,
+                    content: content.to_string(),
+                    timestamp: None,
+                }));
+        }
+        tui.message_list.layout.heights = vec![0, 2, 0, 3];
+        tui.message_list.layout.rebuild_prefix_heights();
+        tui.message_list.selected_index = Some(3);
+        let (tx, _rx) = mpsc::channel();
 
+        // Up skips [2]; from [1] it wraps past the zero-height directive to [3]
+        for expected in [1, 3] {
+            handle_event(
+                TuiEvent::InputChar('k'),
+                &mut app,
+                &mut tui,
+                &tx,
+                test_frame_area(),
+            );
+            assert_eq!(tui.message_list.selected_index, Some(expected));
+        }
+        // Down wraps back to [1] without stopping on [0]
+        handle_event(
+            TuiEvent::InputChar('j'),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
         assert_eq!(tui.message_list.selected_index, Some(1));
+        assert_eq!(tui.input_mode, InputMode::Cursor);
+    }
+
+    #[test]
+    fn test_enter_and_exit_cursor_mode_events() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::EnterCursorMode,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.input_mode, InputMode::Cursor);
+        assert_eq!(tui.message_list.selected_index, Some(0));
+
+        handle_event(
+            TuiEvent::ExitCursorMode,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.input_mode, InputMode::Input);
+        assert_eq!(tui.message_list.selected_index, None);
+    }
+
+    #[test]
+    fn test_up_on_empty_input_enters_cursor_mode() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::CursorUp,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+
+        assert_eq!(tui.input_mode, InputMode::Cursor);
+        assert_eq!(tui.message_list.selected_index, Some(0));
     }
 
     // --- Phase 5: Background action processing ---
@@ -827,6 +2092,7 @@ mod tests {
             name: "test-model".to_string(),
             provider: "openrouter".to_string(),
             description: None,
+            ..Default::default()
         }];
         tx.send(Action::ModelsFetched(models.clone())).unwrap();
 
@@ -871,4 +2137,176 @@ mod tests {
         // The chunk should have been processed by the reducer
         assert_eq!(app.session.context.items.len(), 2); // system + model response
     }
+
+    // --- Phase 6: Conversation tabs ---
+
+    fn last_text(app: &App) -> &str {
+        match app.session.context.items.last() {
+            Some(ContextItem::Message(seg)) => &seg.content,
+            other => panic!("expected a message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tab_switch_preserves_each_context() {
+        let mut app = test_app();
+        app.session.context.add_user_message("first".to_string());
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(TuiEvent::NewTab, &mut app, &mut tui, &tx, test_frame_area());
+        assert_eq!((tui.tabs.len(), tui.tabs.active()), (2, 1));
+        assert_eq!(app.session.context.items.len(), 1); // just system
+        app.session.context.add_user_message("second".to_string());
+
+        handle_event(
+            TuiEvent::PrevTab,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.tabs.active(), 0);
+        assert_eq!(last_text(&app), "first");
+
+        // Wraps around from the first tab to the last
+        handle_event(
+            TuiEvent::PrevTab,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.tabs.active(), 1);
+        assert_eq!(last_text(&app), "second");
+    }
+
+    #[test]
+    fn test_background_tab_stream_stays_in_its_tab() {
+        let mut app = test_app();
+        app.session.is_loading = true;
+        let mut tui = test_tui_state();
+        let (tx, rx) = mpsc::channel();
+        let tab0_tx = tui.tabs.sender(0);
+
+        handle_event(TuiEvent::NewTab, &mut app, &mut tui, &tx, test_frame_area());
+        tab0_tx
+            .send(Action::ResponseChunk {
+                text: "hello".to_string(),
+                item_id: None,
+            })
+            .unwrap();
+        let active_tx = tui.tabs.active_sender();
+        let (quit, had_actions) = process_background_actions(&rx, &mut app, &mut tui, &active_tx);
+
+        assert!(!quit);
+        assert!(had_actions);
+        // The active tab is untouched
+        assert_eq!(app.session.context.items.len(), 1);
+        assert!(!app.session.is_loading);
+        assert_eq!(tui.tabs.labels(&app)[0], ("Tab 1".to_string(), true));
+
+        handle_event(
+            TuiEvent::NextTab,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!(tui.tabs.active(), 0);
+        assert_eq!(last_text(&app), "hello");
+        assert!(app.session.is_loading);
+    }
+
+    #[test]
+    fn test_close_tab_keeps_other_tabs_stream() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, rx) = mpsc::channel();
+
+        handle_event(TuiEvent::NewTab, &mut app, &mut tui, &tx, test_frame_area());
+        app.session
+            .context
+            .add_user_message("still streaming".to_string());
+        app.session.is_loading = true;
+        let streaming_tx = tui.tabs.active_sender();
+
+        // Close the idle first tab; the streaming tab moves to index 0
+        handle_event(
+            TuiEvent::PrevTab,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        handle_event(
+            TuiEvent::CloseTab,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+        assert_eq!((tui.tabs.len(), tui.tabs.active()), (1, 0));
+        assert!(app.session.is_loading);
+
+        streaming_tx
+            .send(Action::ResponseChunk {
+                text: "more".to_string(),
+                item_id: None,
+            })
+            .unwrap();
+        let active_tx = tui.tabs.active_sender();
+        process_background_actions(&rx, &mut app, &mut tui, &active_tx);
+        assert_eq!(last_text(&app), "more");
+        assert!(app.session.is_loading);
+    }
+
+    #[test]
+    fn test_close_last_tab_refused() {
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::CloseTab,
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+
+        assert_eq!(tui.tabs.len(), 1);
+        assert_eq!(app.session.status_message, "Only one tab open.");
+    }
+
+    #[test]
+    fn test_paste_image_path_stages_image() {
+        let path = std::env::temp_dir().join("navi_test_paste_image.png");
+        std::fs::write(&path, [0x89, b'P', b'N', b'G']).unwrap();
+
+        let mut app = test_app();
+        let mut tui = test_tui_state();
+        let (tx, _rx) = mpsc::channel();
+
+        handle_event(
+            TuiEvent::Paste(format!("'file://{}'\n", path.display())),
+            &mut app,
+            &mut tui,
+            &tx,
+            test_frame_area(),
+        );
+
+        // Held back like any other pasted image until the message is sent
+        assert!(app.session.pending_image.is_some());
+        assert_eq!(app.session.context.items.len(), 1);
+        assert!(tui.input_box.buffer.is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_paste_plain_text_is_not_an_image() {
+        assert_eq!(pasted_image_path("hello world"), None);
+        assert_eq!(pasted_image_path("/no/such/file.png"), None);
+        assert_eq!(pasted_image_path("a.png\nb.png"), None);
+    }
 }
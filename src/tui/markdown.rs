@@ -3,9 +3,15 @@
 //! Thin wrapper around `pulldown_cmark` that converts markdown events into
 //! styled `Line`/`Span` values. Headings, bold, italic, inline code, fenced
 //! code blocks (with syntect highlighting), lists, blockquotes, and links.
+//!
+//! Rendering is width-aware: rules shrink to fit, and lines carrying a
+//! prefix (the `│ ` of blockquotes and code blocks) are wrapped here rather
+//! than by the `Paragraph`, so every wrapped row keeps its prefix.
 
 use std::sync::LazyLock;
 
+use log::warn;
+
 use pulldown_cmark::{
     Alignment, CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd,
 };
@@ -15,24 +21,77 @@ use syntect::easy::HighlightLines;
 use syntect::highlighting::ThemeSet;
 use syntect::parsing::SyntaxSet;
 use syntect::util::LinesWithEndings;
+use unicode_width::UnicodeWidthChar;
+
+use crate::tui::theme::{DEFAULT_CODE_THEME, Theme};
 
 static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
 static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
 
-/// Parse markdown content into styled `Text` using Navi's color scheme.
+/// Longest a horizontal rule gets, even in a wide message.
+const RULE_MAX_WIDTH: usize = 40;
+
+/// Layout limits for [`render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Columns available to the rendered text.
+    pub width: u16,
+    /// Wrap prefixed lines (blockquotes, code) to `width`. Off when the
+    /// caller scrolls long lines sideways instead of wrapping them.
+    pub wrap: bool,
+}
+
+/// How link URLs are shown, from `[theme] link_style`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LinkStyle {
+    /// Link text followed by ` (url)`.
+    #[default]
+    Inline,
+    /// Only the styled link text; the URL is dropped.
+    Hidden,
+    /// Link text with a `[n]` marker, URLs listed at the end of the message.
+    Footnote,
+}
+
+impl LinkStyle {
+    /// Parse a config value; None or an unknown name gives the default.
+    pub fn from_config(name: Option<&str>) -> Self {
+        match name.map(str::to_lowercase).as_deref() {
+            None | Some("inline") => LinkStyle::Inline,
+            Some("hidden") => LinkStyle::Hidden,
+            Some("footnote") => LinkStyle::Footnote,
+            Some(other) => {
+                warn!("Unknown link_style '{}', using inline", other);
+                LinkStyle::Inline
+            }
+        }
+    }
+}
+
+/// Parse markdown content into styled `Text` using the given theme, laid
+/// out for `options.width` columns.
 ///
 /// Returns owned text (`'static`) so callers aren't constrained by input lifetime.
-pub fn render(content: &str, base_fg: Color) -> Text<'static> {
+pub fn render(
+    content: &str,
+    base_fg: Color,
+    theme: &Theme,
+    options: RenderOptions,
+) -> Text<'static> {
     let mut opts = Options::empty();
     opts.insert(Options::ENABLE_STRIKETHROUGH);
     opts.insert(Options::ENABLE_TASKLISTS);
     opts.insert(Options::ENABLE_TABLES);
 
     let events: Vec<Event<'_>> = Parser::new_ext(content, opts).collect();
-    let mut w = Writer::new(base_fg);
+    let mut w = Writer::new(base_fg, theme, options.width);
     for event in events {
         w.handle(event);
     }
+    w.push_footnotes();
+    if options.wrap {
+        w.wrap_prefixed_lines();
+    }
     w.text
 }
 
@@ -51,7 +110,15 @@ struct TableState {
 
 struct Writer {
     text: Text<'static>,
+    /// Number of leading prefix spans on each line of `text`.
+    prefix_lens: Vec<usize>,
+    /// Columns available to each line, prefixes included.
+    width: usize,
     base_fg: Color,
+    /// Color for markup chrome: rules, fences, blockquote bars, list markers.
+    chrome_fg: Color,
+    /// Syntect theme name for fenced code blocks.
+    code_theme: String,
     /// Inline style stack (bold, italic, heading text, etc.). Styles compose
     /// via `patch` so nested bold+italic works.
     styles: Vec<Style>,
@@ -63,8 +130,12 @@ struct Writer {
     highlighter: Option<HighlightLines<'static>>,
     /// True when inside a fenced code block without syntax highlighting.
     in_plain_code: bool,
+    /// How to show link URLs (inline, hidden, or as footnotes).
+    link_style: LinkStyle,
     /// Stored link URL, appended after the link text closes.
     link_url: Option<String>,
+    /// Footnote URLs in order of first appearance; `[n]` refers to index n-1.
+    footnotes: Vec<String>,
     /// Whether the next block element should be preceded by a blank line.
     needs_newline: bool,
     /// Active table being buffered — `None` when not inside a table.
@@ -72,16 +143,22 @@ struct Writer {
 }
 
 impl Writer {
-    fn new(base_fg: Color) -> Self {
+    fn new(base_fg: Color, theme: &Theme, width: u16) -> Self {
         Self {
             text: Text::default(),
+            prefix_lens: vec![],
+            width: width as usize,
             base_fg,
+            chrome_fg: theme.border,
+            code_theme: theme.code_theme.clone(),
             styles: vec![],
             line_prefixes: vec![],
             list_indices: vec![],
             highlighter: None,
             in_plain_code: false,
+            link_style: theme.link_style,
             link_url: None,
+            footnotes: vec![],
             needs_newline: false,
             table: None,
         }
@@ -114,6 +191,35 @@ impl Writer {
             out.spans.insert(0, pfx);
         }
         self.text.lines.push(out);
+        self.prefix_lens.push(self.line_prefixes.len());
+    }
+
+    /// Columns left for content after the current line prefixes.
+    fn available_width(&self) -> usize {
+        let prefix: usize = self.line_prefixes.iter().map(Span::width).sum();
+        self.width.saturating_sub(prefix)
+    }
+
+    /// Break every prefixed line that is wider than `width`, repeating its
+    /// prefix on each continuation row.
+    fn wrap_prefixed_lines(&mut self) {
+        let lines = std::mem::take(&mut self.text.lines);
+        for (mut line, prefix_len) in lines.into_iter().zip(&self.prefix_lens) {
+            if *prefix_len == 0 || line.width() <= self.width {
+                self.text.lines.push(line);
+                continue;
+            }
+            let body = line.spans.split_off(*prefix_len);
+            let prefix = line.spans;
+            let prefix_width: usize = prefix.iter().map(Span::width).sum();
+            let body_width = self.width.saturating_sub(prefix_width).max(1);
+            for row in wrap_spans(body, body_width) {
+                let mut spans = prefix.clone();
+                spans.extend(row);
+                self.text.lines.push(Line::from(spans));
+            }
+        }
+        self.prefix_lens.clear();
     }
 
     fn push_span(&mut self, span: Span<'static>) {
@@ -136,10 +242,26 @@ impl Writer {
         }
     }
 
+    /// Append the numbered URL list collected in footnote mode.
+    fn push_footnotes(&mut self) {
+        if self.footnotes.is_empty() {
+            return;
+        }
+        self.needs_newline = true;
+        self.blank_line_if_needed();
+        let marker_style = Style::default().fg(self.chrome_fg);
+        for (i, url) in std::mem::take(&mut self.footnotes).into_iter().enumerate() {
+            self.push_line(Line::from(vec![
+                Span::styled(format!("[{}] ", i + 1), marker_style),
+                Span::styled(url, link_text_style()),
+            ]));
+        }
+    }
+
     // ── Table rendering ──────────────────────────────────────────────────
 
     fn render_table(&mut self, table: TableState) {
-        let bs = Style::default().fg(Color::DarkGray);
+        let bs = Style::default().fg(self.chrome_fg);
         let num_cols = table.alignments.len();
 
         // Column widths: max content width per column (minimum 1).
@@ -237,9 +359,10 @@ impl Writer {
             Event::HardBreak => self.push_line(Line::default()),
             Event::Rule => {
                 self.blank_line_if_needed();
+                let rule_width = self.available_width().min(RULE_MAX_WIDTH);
                 self.push_line(Line::from(Span::styled(
-                    "─".repeat(40),
-                    Style::default().fg(Color::DarkGray),
+                    "─".repeat(rule_width),
+                    Style::default().fg(self.chrome_fg),
                 )));
                 self.needs_newline = true;
             }
@@ -273,7 +396,7 @@ impl Writer {
             Tag::BlockQuote(_) => {
                 self.blank_line_if_needed();
                 self.line_prefixes
-                    .push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                    .push(Span::styled("│ ", Style::default().fg(self.chrome_fg)));
                 self.push_style(
                     Style::default()
                         .fg(self.base_fg)
@@ -290,7 +413,7 @@ impl Writer {
                 };
 
                 // Top border: ╭── lang  or just ╭──
-                let bs = Style::default().fg(Color::DarkGray);
+                let bs = Style::default().fg(self.chrome_fg);
                 let top = if lang.is_empty() {
                     Line::from(Span::styled("╭──", bs))
                 } else {
@@ -309,7 +432,10 @@ impl Writer {
                 if !lang.is_empty()
                     && let Some(syn) = SYNTAX_SET.find_syntax_by_token(lang)
                 {
-                    let theme = &THEME_SET.themes["base16-ocean.dark"];
+                    let theme = THEME_SET
+                        .themes
+                        .get(&self.code_theme)
+                        .unwrap_or(&THEME_SET.themes[DEFAULT_CODE_THEME]);
                     self.highlighter = Some(HighlightLines::new(syn, theme));
                 }
                 if self.highlighter.is_none() {
@@ -335,7 +461,7 @@ impl Writer {
                             s
                         }
                     };
-                    self.push_span(Span::styled(marker, Style::default().fg(Color::DarkGray)));
+                    self.push_span(Span::styled(marker, Style::default().fg(self.chrome_fg)));
                 }
             }
 
@@ -347,11 +473,7 @@ impl Writer {
             }
             Tag::Link { dest_url, .. } => {
                 self.link_url = Some(dest_url.to_string());
-                self.push_style(
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::UNDERLINED),
-                );
+                self.push_style(link_text_style());
             }
             Tag::Table(alignments) => {
                 self.blank_line_if_needed();
@@ -389,7 +511,7 @@ impl Writer {
                 self.highlighter = None;
                 self.in_plain_code = false;
                 self.line_prefixes.pop(); // remove │ prefix before bottom border
-                let bs = Style::default().fg(Color::DarkGray);
+                let bs = Style::default().fg(self.chrome_fg);
                 self.push_line(Line::from(Span::styled("╰──", bs)));
                 self.needs_newline = true;
             }
@@ -427,20 +549,37 @@ impl Writer {
             TagEnd::Link => {
                 self.pop_style();
                 if let Some(url) = self.link_url.take() {
-                    self.push_span(Span::raw(" ("));
-                    self.push_span(Span::styled(
-                        url,
-                        Style::default()
-                            .fg(Color::Cyan)
-                            .add_modifier(Modifier::UNDERLINED),
-                    ));
-                    self.push_span(Span::raw(")"));
+                    self.push_link_url(url);
                 }
             }
             _ => {}
         }
     }
 
+    /// Show a closed link's URL according to `link_style`.
+    fn push_link_url(&mut self, url: String) {
+        match self.link_style {
+            LinkStyle::Inline => {
+                self.push_span(Span::raw(" ("));
+                self.push_span(Span::styled(url, link_text_style()));
+                self.push_span(Span::raw(")"));
+            }
+            LinkStyle::Hidden => {}
+            LinkStyle::Footnote => {
+                // Repeated URLs share one footnote number
+                let n = match self.footnotes.iter().position(|u| *u == url) {
+                    Some(i) => i + 1,
+                    None => {
+                        self.footnotes.push(url);
+                        self.footnotes.len()
+                    }
+                };
+                let style = Style::default().fg(self.chrome_fg);
+                self.push_span(Span::styled(format!("[{n}]"), style));
+            }
+        }
+    }
+
     // ── Content handlers ────────────────────────────────────────────────
 
     fn text(&mut self, cow: CowStr<'_>) {
@@ -504,6 +643,74 @@ impl Writer {
 
 // ── Helpers ─────────────────────────────────────────────────────────────────
 
+/// Greedy word wrap of styled spans into rows of at most `width` columns.
+/// Breaks at spaces, dropping the space at each break, and splits words
+/// longer than a row. Styles carry over, even when a word spans two spans.
+fn wrap_spans(spans: Vec<Span<'static>>, width: usize) -> Vec<Vec<Span<'static>>> {
+    let cells: Vec<(char, Style)> = spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .collect();
+
+    let mut rows: Vec<Vec<(char, Style)>> = vec![vec![]];
+    let mut row_width = 0;
+    let mut i = 0;
+    while i < cells.len() {
+        // Next token: a run of spaces or a run of non-spaces
+        let start = i;
+        let is_space = cells[i].0 == ' ';
+        while i < cells.len() && (cells[i].0 == ' ') == is_space {
+            i += 1;
+        }
+        let token = &cells[start..i];
+        let token_width: usize = token.iter().map(|(c, _)| c.width().unwrap_or(0)).sum();
+
+        if row_width + token_width <= width {
+            rows.last_mut()
+                .expect("never empty")
+                .extend_from_slice(token);
+            row_width += token_width;
+            continue;
+        }
+        if row_width > 0 {
+            rows.push(vec![]);
+            row_width = 0;
+        }
+        if is_space {
+            continue;
+        }
+        for &(c, style) in token {
+            let char_width = c.width().unwrap_or(0);
+            if row_width + char_width > width && row_width > 0 {
+                rows.push(vec![]);
+                row_width = 0;
+            }
+            rows.last_mut().expect("never empty").push((c, style));
+            row_width += char_width;
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| {
+            let mut spans: Vec<Span<'static>> = vec![];
+            for (c, style) in row {
+                match spans.last_mut() {
+                    Some(last) if last.style == style => last.content.to_mut().push(c),
+                    _ => spans.push(Span::styled(c.to_string(), style)),
+                }
+            }
+            spans
+        })
+        .collect()
+}
+
+/// Style for link text and URLs.
+fn link_text_style() -> Style {
+    Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::UNDERLINED)
+}
+
 fn heading_style(base_fg: Color, level: HeadingLevel) -> Style {
     match level {
         HeadingLevel::H1 => Style::default()
@@ -531,9 +738,14 @@ fn heading_depth(level: HeadingLevel) -> u8 {
 mod tests {
     use super::*;
 
+    const WIDE: RenderOptions = RenderOptions {
+        width: 80,
+        wrap: true,
+    };
+
     #[test]
     fn heading_text_inherits_heading_style() {
-        let text = render("## Hello", Color::Blue);
+        let text = render("## Hello", Color::Blue, &Theme::default(), WIDE);
         // Line 0 should contain "## " and "Hello", both with bold + blue
         let line = &text.lines[0];
         assert!(line.spans.len() >= 2, "expected >= 2 spans, got {:?}", line);
@@ -547,7 +759,7 @@ mod tests {
 
     #[test]
     fn bold_text_is_bold() {
-        let text = render("Some **bold** text", Color::Blue);
+        let text = render("Some **bold** text", Color::Blue, &Theme::default(), WIDE);
         let line = &text.lines[0];
         // Find the "bold" span
         let bold_span = line.spans.iter().find(|s| s.content == "bold").unwrap();
@@ -556,7 +768,7 @@ mod tests {
 
     #[test]
     fn inline_code_styled() {
-        let text = render("Use `foo()` here", Color::Blue);
+        let text = render("Use `foo()` here", Color::Blue, &Theme::default(), WIDE);
         let line = &text.lines[0];
         let code_span = line.spans.iter().find(|s| s.content == "foo()").unwrap();
         assert_eq!(code_span.style.fg, Some(Color::White));
@@ -565,7 +777,12 @@ mod tests {
 
     #[test]
     fn code_block_has_border_structure() {
-        let text = render("```\nline1\nline2\n```", Color::Blue);
+        let text = render(
+            "```\nline1\nline2\n```",
+            Color::Blue,
+            &Theme::default(),
+            WIDE,
+        );
         let all_content: Vec<String> = text
             .lines
             .iter()
@@ -606,7 +823,7 @@ mod tests {
 
     #[test]
     fn plain_text_uses_base_color() {
-        let text = render("hello", Color::Green);
+        let text = render("hello", Color::Green, &Theme::default(), WIDE);
         let line = &text.lines[0];
         let span = &line.spans[0];
         assert_eq!(span.style.fg, Some(Color::Green));
@@ -615,7 +832,7 @@ mod tests {
     #[test]
     fn table_renders_with_borders() {
         let md = "| Name | Age |\n|------|-----|\n| Alice | 30 |\n| Bob | 25 |";
-        let text = render(md, Color::Blue);
+        let text = render(md, Color::Blue, &Theme::default(), WIDE);
         let lines: Vec<String> = text
             .lines
             .iter()
@@ -674,7 +891,7 @@ mod tests {
     #[test]
     fn table_header_is_bold() {
         let md = "| H1 | H2 |\n|---|---|\n| a | b |";
-        let text = render(md, Color::Blue);
+        let text = render(md, Color::Blue, &Theme::default(), WIDE);
         // Line 1 is the header data row (line 0 is top border)
         let header_line = &text.lines[1];
         let h1_span = header_line
@@ -688,7 +905,7 @@ mod tests {
     #[test]
     fn table_with_inline_styles() {
         let md = "| A | B |\n|---|---|\n| **bold** | `code` |";
-        let text = render(md, Color::Blue);
+        let text = render(md, Color::Blue, &Theme::default(), WIDE);
         // Find the bold span in a data row
         let bold_span = text
             .lines
@@ -715,7 +932,7 @@ mod tests {
 
     #[test]
     fn tabs_expanded_to_spaces() {
-        let text = render("```\n\tindented\n```", Color::Blue);
+        let text = render("```\n\tindented\n```", Color::Blue, &Theme::default(), WIDE);
         let has_spaces = text
             .lines
             .iter()
@@ -727,4 +944,173 @@ mod tests {
             .any(|l| l.spans.iter().any(|s| s.content.contains('\t')));
         assert!(!has_tabs, "no raw tabs should remain");
     }
+
+    #[test]
+    fn test_chrome_uses_theme_border_color() {
+        let theme = Theme {
+            border: Color::Magenta,
+            ..Theme::default()
+        };
+        let text = render("> quoted", Color::Blue, &theme, WIDE);
+        let bar = text.lines[0]
+            .spans
+            .iter()
+            .find(|s| s.content.as_ref() == "│ ")
+            .unwrap();
+        assert_eq!(bar.style.fg, Some(Color::Magenta));
+    }
+
+    #[test]
+    fn test_unknown_code_theme_falls_back() {
+        let theme = Theme {
+            code_theme: "no-such-theme".to_string(),
+            ..Theme::default()
+        };
+        let text = render("```rust\nfn main() {}\n```", Color::Blue, &theme, WIDE);
+        assert!(text.lines.len() >= 3);
+    }
+
+    /// Render two links (one URL repeated) with the given link style.
+    fn render_links(link_style: LinkStyle) -> Vec<String> {
+        let theme = Theme {
+            link_style,
+            ..Theme::default()
+        };
+        let md =
+            "See [docs](https://a.dev) and [more](https://b.dev).\n\nAgain [docs](https://a.dev).";
+        render(md, Color::Blue, &theme, WIDE)
+            .lines
+            .iter()
+            .map(|l| {
+                l.spans
+                    .iter()
+                    .map(|s| s.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_inline_links_append_url() {
+        let lines = render_links(LinkStyle::Inline);
+        assert_eq!(
+            lines[0],
+            "See docs (https://a.dev) and more (https://b.dev)."
+        );
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn test_hidden_links_show_only_styled_text() {
+        let theme = Theme {
+            link_style: LinkStyle::Hidden,
+            ..Theme::default()
+        };
+        let text = render("See [docs](https://a.dev).", Color::Blue, &theme, WIDE);
+        let link = text.lines[0]
+            .spans
+            .iter()
+            .find(|s| s.content.as_ref() == "docs")
+            .unwrap();
+        assert_eq!(link.style.fg, Some(Color::Cyan));
+        assert!(link.style.add_modifier.contains(Modifier::UNDERLINED));
+
+        let lines = render_links(LinkStyle::Hidden);
+        assert_eq!(lines, ["See docs and more.", "", "Again docs."]);
+    }
+
+    #[test]
+    fn test_footnote_links_list_urls_at_end() {
+        let lines = render_links(LinkStyle::Footnote);
+        assert_eq!(
+            lines,
+            [
+                "See docs[1] and more[2].",
+                "",
+                "Again docs[1].",
+                "",
+                "[1] https://a.dev",
+                "[2] https://b.dev",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_style_from_config() {
+        assert_eq!(LinkStyle::from_config(None), LinkStyle::Inline);
+        assert_eq!(
+            LinkStyle::from_config(Some("Footnote")),
+            LinkStyle::Footnote
+        );
+        assert_eq!(LinkStyle::from_config(Some("hidden")), LinkStyle::Hidden);
+        assert_eq!(LinkStyle::from_config(Some("bogus")), LinkStyle::Inline);
+    }
+
+    /// Render at `width` columns and flatten each line to a string.
+    fn render_lines(md: &str, width: u16, wrap: bool) -> Vec<String> {
+        render(
+            md,
+            Color::Blue,
+            &Theme::default(),
+            RenderOptions { width, wrap },
+        )
+        .lines
+        .iter()
+        .map(|l| {
+            l.spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect::<String>()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_rule_shrinks_to_width() {
+        assert_eq!(render_lines("---", 80, true), ["─".repeat(RULE_MAX_WIDTH)]);
+        assert_eq!(render_lines("---", 12, true), ["─".repeat(12)]);
+        // Inside a quote the prefix takes its share of the width
+        assert_eq!(
+            render_lines("> ---", 12, true),
+            [format!("│ {}", "─".repeat(10))]
+        );
+    }
+
+    #[test]
+    fn test_quote_wraps_with_prefix_on_every_line() {
+        let lines = render_lines("> one two three four five", 12, true);
+        assert_eq!(lines, ["│ one two ", "│ three four", "│ five"]);
+        assert!(lines.iter().all(|l| l.chars().count() <= 12));
+
+        // A word longer than the line is split rather than overflowing
+        let lines = render_lines("> abcdefghijklmn", 8, true);
+        assert_eq!(lines, ["│ abcdef", "│ ghijkl", "│ mn"]);
+    }
+
+    #[test]
+    fn test_wrap_keeps_span_styles() {
+        let text = render(
+            "> plain **bold words** end",
+            Color::Blue,
+            &Theme::default(),
+            RenderOptions {
+                width: 10,
+                wrap: true,
+            },
+        );
+        let bold: String = text
+            .lines
+            .iter()
+            .flat_map(|l| &l.spans)
+            .filter(|s| s.style.add_modifier.contains(Modifier::BOLD))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(bold, "bold words");
+    }
+
+    #[test]
+    fn test_no_wrap_keeps_long_quote_on_one_line() {
+        let lines = render_lines("> one two three four five", 12, false);
+        assert_eq!(lines, ["│ one two three four five"]);
+    }
 }
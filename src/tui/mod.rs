@@ -19,32 +19,43 @@
 //! ratatui's `set_cursor_position` resets the terminal's blink timer on every
 //! `draw()` call, making blinking cursors appear erratic during continuous redraws.
 
+mod clipboard;
+mod command;
 mod component;
 mod components;
 mod event;
+mod graphics;
 mod handlers;
 pub mod markdown;
+mod shutdown;
 mod stream_buffer;
+mod tabs;
 mod tasks;
+mod terminal;
+mod theme;
 mod ui;
 
 use log::info;
 use std::io::stdout;
 use std::sync::mpsc;
 
-use crossterm::cursor::{Hide, SetCursorStyle, Show};
-use crossterm::event::{
-    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
-    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
-};
-use crossterm::execute;
+use ratatui::DefaultTerminal;
+use ratatui::layout::Position;
 
-use crate::core::config::{ModelEntry, ResolvedConfig};
+use crate::core::config::{self, ConfigWarning, ModelEntry, ResolvedConfig};
 use crate::core::session;
 use crate::core::state::App;
 use crate::inference::Effort;
-use crate::tui::components::{InputBox, MessageListState, ModelPickerState, SessionManagerState};
-use crate::tui::event::{poll_event_immediate, poll_event_timeout};
+use crate::tui::components::message::Density;
+use crate::tui::components::spinner::Spinner;
+use crate::tui::components::{
+    DiffOverlayState, InputBox, MessageListState, ModelPickerState, SessionManagerState,
+};
+use crate::tui::event::{TuiEvent, poll_event_immediate, poll_event_timeout};
+use crate::tui::graphics::{GraphicsProtocol, InlineImages};
+use crate::tui::shutdown::AppShutdown;
+use crate::tui::tabs::Tabs;
+use crate::tui::theme::Theme;
 
 /// Modal input mode: determines how keyboard events are interpreted.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -59,6 +70,8 @@ pub enum InputMode {
 pub struct TuiState {
     // Persistent component states
     pub message_list: MessageListState,
+    // The reasoning panel's own list of the active conversation's thinking
+    pub reasoning_list: MessageListState,
     pub input_box: InputBox,
     // Modal input mode
     pub input_mode: InputMode,
@@ -68,23 +81,70 @@ pub struct TuiState {
     pub session_manager: Option<SessionManagerState>,
     // Model picker overlay (None = hidden)
     pub model_picker: Option<ModelPickerState>,
+    // Color palette, built from the `[theme]` config table
+    pub theme: Theme,
+    // Loading indicator style, from `[general] spinner`
+    pub spinner: Spinner,
+    // Keybinding help overlay visibility
+    pub show_help: bool,
+    // Regenerated answer compared with the previous one (Alt+V; None = hidden)
+    pub variant_diff: Option<DiffOverlayState>,
+    // Thinking shown in a side panel instead of in the conversation (Alt+T)
+    pub show_reasoning_panel: bool,
+    // View filters: thinking (Alt+H) and the system directive (Alt+S) in the
+    // conversation (shared by all tabs)
+    pub show_thinking: bool,
+    pub show_system: bool,
+    // Last pointer position, so the wheel scrolls the list under it
+    pub mouse_position: Option<Position>,
+    // Word wrap for message text, toggled with Alt+Z (shared by all tabs)
+    pub wrap_messages: bool,
+    // Bordered or compact messages, toggled with Alt+C (shared by all tabs)
+    pub message_density: Density,
+    // Picks the landing page tip; drawn once per launch so it stays put between frames
+    pub landing_tip_seed: usize,
     // Pre-fetched models from provider APIs (populated at startup)
     pub fetched_models: Option<Vec<ModelEntry>>,
     // Abort handles for the current generation (used by Escape-to-cancel)
     pub active_abort_handles: Vec<tokio::task::AbortHandle>,
+    // Pictures drawn in the conversation; None when the terminal can't draw them
+    pub inline_images: Option<InlineImages>,
+    // Open conversations; the active one lives in `app.session` and the fields above
+    pub tabs: Tabs,
 }
 
 impl TuiState {
+    /// Replace the message list after the conversation itself was replaced,
+    /// starting the reasoning panel's list over with it.
+    pub fn reset_message_list(&mut self, list: MessageListState) {
+        self.message_list = list;
+        self.reasoning_list = MessageListState::new();
+    }
+
     pub fn new(initial_effort: Effort) -> Self {
         Self {
             message_list: MessageListState::new(),
+            reasoning_list: MessageListState::new(),
             input_box: InputBox::new(initial_effort),
             input_mode: InputMode::Input, // User expects to type immediately
             pulse_value: 0.0,
             session_manager: None,
             model_picker: None,
+            theme: Theme::default(),
+            spinner: Spinner::default(),
+            show_help: false,
+            variant_diff: None,
+            show_reasoning_panel: false,
+            show_thinking: true,
+            show_system: true,
+            mouse_position: None,
+            wrap_messages: true,
+            message_density: Density::default(),
+            landing_tip_seed: uuid::Uuid::new_v4().as_u128() as usize,
             fetched_models: None,
             active_abort_handles: Vec::new(),
+            inline_images: None,
+            tabs: Tabs::new(),
         }
     }
 }
@@ -93,20 +153,7 @@ struct TerminalModeGuard;
 
 impl TerminalModeGuard {
     fn new() -> std::io::Result<Self> {
-        // Enable Kitty keyboard protocol unconditionally (allows Shift+Enter detection)
-        // Detection via supports_keyboard_enhancement() fails in WSL, but the protocol
-        // is harmlessly ignored by terminals that don't support it
-        execute!(
-            stdout(),
-            EnableMouseCapture,
-            EnableBracketedPaste,
-            Show,                        // Show cursor for input editing
-            SetCursorStyle::SteadyBlock, // Non-blinking: avoids blink timer reset from continuous redraws
-            PushKeyboardEnhancementFlags(
-                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
-                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
-            )
-        )?;
+        terminal::enter_modes(&mut stdout())?;
         info!(
             "Terminal modes enabled (mouse, bracketed paste, steady block cursor, keyboard enhancement)"
         );
@@ -116,13 +163,7 @@ impl TerminalModeGuard {
 
 impl Drop for TerminalModeGuard {
     fn drop(&mut self) {
-        let _ = execute!(
-            stdout(),
-            PopKeyboardEnhancementFlags,
-            DisableMouseCapture,
-            DisableBracketedPaste,
-            Hide // Hide cursor on exit
-        );
+        let _ = terminal::leave_modes(&mut stdout());
 
         // Drain any buffered mouse/keyboard events before restoring the terminal.
         // If ratatui::restore() disables raw mode while mouse tracking escape
@@ -135,31 +176,86 @@ impl Drop for TerminalModeGuard {
     }
 }
 
-pub fn run(config: ResolvedConfig) -> std::io::Result<()> {
-    let provider = crate::inference::build_provider(&config);
+/// `effort` comes from the `--effort` flag and takes precedence over the
+/// starting model's own effort setting.
+pub fn run(
+    config: ResolvedConfig,
+    effort: Option<Effort>,
+    config_warnings: &[ConfigWarning],
+) -> std::io::Result<()> {
+    let (provider, unconfigured) = match crate::inference::build_provider(&config) {
+        Ok(provider) => (provider, None),
+        Err(hint) => (crate::inference::unconfigured_provider(&config), Some(hint)),
+    };
     let mut app = App::from_config(provider, config);
+    if let Some(effort) = effort {
+        app.effort = effort;
+        app.effort_override = Some(effort);
+    }
+    if let Some(status) = config::warnings_status(config_warnings) {
+        app.session.status_message = status;
+    } else if let Some(hint) = unconfigured {
+        app.session.status_message = hint.to_string();
+    }
     let mut tui = TuiState::new(app.effort);
+    tui.theme = Theme::from_config(&app.config.theme);
+    tui.spinner = Spinner::from_config(app.config.spinner.as_deref());
+    tui.inline_images = GraphicsProtocol::detect().map(InlineImages::new);
+    info!(
+        "Inline images: {:?}",
+        tui.inline_images.as_ref().map(InlineImages::protocol)
+    );
 
     // Open session manager on startup so user picks a session (or starts new)
     let index = session::load_index().unwrap_or_default();
     tui.session_manager = Some(SessionManagerState::new(index.sessions));
 
     let mut terminal = ratatui::init();
-    let _terminal_mode_guard = TerminalModeGuard::new();
+    let terminal_modes = TerminalModeGuard::new().ok();
+
+    let result = event_loop(&mut terminal, &mut app, &mut tui);
+
+    // Also after an error, so a failed draw doesn't lose the conversations
+    shutdown::shutdown(&mut AppShutdown {
+        app: &mut app,
+        tui: &mut tui,
+        terminal_modes,
+    });
+    result
+}
+
+/// Draws, handles input and applies background actions until the user
+/// quits.
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    tui: &mut TuiState,
+) -> std::io::Result<()> {
+    #[cfg(unix)]
+    let mut job_signals = terminal::JobSignals::new()?;
+    // Set while the SIGCONT that ends Navi's own suspend is still to arrive
+    #[cfg(unix)]
+    let mut self_suspended = false;
 
-    // Channel for actions from background tasks
+    // Channel for app-wide background actions; each tab has its own channel
+    // for the requests and tool calls it starts
     let (tx, rx) = mpsc::channel();
 
     // Fetch available models from providers in the background at startup
-    tasks::spawn_model_fetch(&app, tx.clone());
+    tasks::spawn_model_fetch(app, tx.clone());
 
     // Animation timer
     let start_time = std::time::Instant::now();
     let mut needs_redraw = true; // Force first frame
+    let mut last_autosave = std::time::Instant::now();
 
     loop {
         // Sync InputBox props with App/TUI state
         tui.input_box.effort = app.effort;
+        tui.input_box.accent = tui.theme.user;
+        tui.input_box.show_counter = app.config.show_input_counter;
+        tui.input_box.image_attached = app.session.pending_image.is_some();
+        tui.input_box.max_lines_override = app.input_max_lines;
         tui.input_box.dimmed = matches!(tui.input_mode, InputMode::Cursor);
 
         // Determine if animations are running (landing page or loading spinner)
@@ -174,8 +270,28 @@ pub fn run(config: ResolvedConfig) -> std::io::Result<()> {
             let elapsed = start_time.elapsed().as_secs_f32();
             tui.pulse_value = (elapsed * 5.0).sin() * 0.5 + 0.5;
             let spinner_frame = (elapsed * 12.0) as usize;
-            terminal.draw(|f| ui::draw_ui(f, &app, &mut tui, spinner_frame))?;
+            terminal.draw(|f| ui::draw_ui(f, app, tui, spinner_frame))?;
+            // Pictures go over the drawn frame, and only when they moved
+            let placements = &tui.message_list.image_placements;
+            let changed = tui
+                .inline_images
+                .as_ref()
+                .filter(|images| images.changed(placements))
+                .map(InlineImages::protocol);
+            if let Some(protocol) = changed {
+                // iTerm2 pictures live in the cells; redraw them all so no old one lingers
+                if protocol == GraphicsProtocol::Iterm2 {
+                    terminal.clear()?;
+                    terminal.draw(|f| ui::draw_ui(f, app, tui, spinner_frame))?;
+                }
+                if let Some(images) = tui.inline_images.as_mut() {
+                    let placements = &tui.message_list.image_placements;
+                    images.show(&mut stdout(), placements, &app.session.context.items)?;
+                }
+            }
             needs_redraw = false;
+            // Keep the position current so every save records it
+            app.session.scroll = tui.message_list.scroll_position();
         }
 
         // Dynamic poll timeout: short when animating (~12fps), long when idle
@@ -188,6 +304,7 @@ pub fn run(config: ResolvedConfig) -> std::io::Result<()> {
 
         // Process first event + drain ALL pending events before next draw
         let mut should_quit = false;
+        let mut should_suspend = false;
         if first_event.is_some() {
             needs_redraw = true;
         }
@@ -196,7 +313,13 @@ pub fn run(config: ResolvedConfig) -> std::io::Result<()> {
             .into_iter()
             .chain(std::iter::from_fn(poll_event_immediate))
         {
-            if handlers::handle_event(event, &mut app, &mut tui, &tx, frame_area) {
+            if matches!(event, TuiEvent::Suspend) {
+                should_suspend = true;
+                continue;
+            }
+            // Re-read per event: a tab switch changes where new tasks report
+            let tab_tx = tui.tabs.active_sender();
+            if handlers::handle_event(event, app, tui, &tab_tx, frame_area) {
                 should_quit = true;
             }
         }
@@ -205,22 +328,48 @@ pub fn run(config: ResolvedConfig) -> std::io::Result<()> {
             break;
         }
 
-        // Handle background task actions (streaming responses)
-        let (quit, had_actions) =
-            handlers::process_background_actions(&rx, &mut app, &mut tui, &tx);
+        // Job control: Ctrl+Z arrives as a key in raw mode, `kill -TSTP` and
+        // `fg` as signals
+        #[cfg(unix)]
+        {
+            match job_signals.poll() {
+                Some(terminal::JobSignal::Stop) => should_suspend = true,
+                // `suspend` already put the terminal back
+                Some(terminal::JobSignal::Continue) if self_suspended => self_suspended = false,
+                Some(terminal::JobSignal::Continue) if !should_suspend => {
+                    terminal::reapply(terminal)?;
+                    if let Some(images) = tui.inline_images.as_mut() {
+                        images.reset();
+                    }
+                    needs_redraw = true;
+                }
+                _ => {}
+            }
+            if should_suspend {
+                terminal::suspend(terminal)?;
+                self_suspended = true;
+                if let Some(images) = tui.inline_images.as_mut() {
+                    images.reset();
+                }
+                needs_redraw = true;
+            }
+        }
+
+        // Handle background task actions (streaming responses) for every tab
+        let tab_tx = tui.tabs.active_sender();
+        let (quit, had_actions) = handlers::process_background_actions(&rx, app, tui, &tab_tx);
         if had_actions {
             needs_redraw = true;
         }
         if quit {
             break;
         }
-    }
-
-    // Save on exit if there's content
-    session::save_current_session(&mut app);
 
-    // Terminal restoration happens in TerminalModeGuard::drop() — it disables
-    // mouse capture, drains buffered events, then calls ratatui::restore().
-    // This ordering prevents escape sequence garbage on exit.
+        // Periodic autosave; turns already save as they complete
+        if last_autosave.elapsed() >= session::AUTOSAVE_INTERVAL {
+            tabs::autosave_all(app, tui);
+            last_autosave = std::time::Instant::now();
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,93 @@
+//! # Shutdown
+//!
+//! Leaving Navi in an order that loses nothing:
+//!
+//! 1. Cancel every tab's in-flight request. What already streamed stays in
+//!    the conversation, marked as cancelled.
+//! 2. Save every tab's session, now that the partial answers are final.
+//! 3. Flush the debug log. Stream transcripts and usage rows are written
+//!    unbuffered as they happen, so only the log can hold anything back.
+//! 4. Restore the terminal, handing the shell back once everything is on
+//!    disk.
+//!
+//! The steps are a trait so the order can be checked without a terminal.
+
+use crate::core::state::App;
+use crate::tui::{TerminalModeGuard, TuiState, tabs};
+
+/// What leaving Navi involves; `shutdown` runs the steps in order.
+pub(super) trait ShutdownSteps {
+    fn cancel_streams(&mut self);
+    fn save_sessions(&mut self);
+    fn flush_logs(&mut self);
+    fn restore_terminal(&mut self);
+}
+
+/// Runs every step, in the order the module docs give.
+pub(super) fn shutdown(steps: &mut impl ShutdownSteps) {
+    steps.cancel_streams();
+    steps.save_sessions();
+    steps.flush_logs();
+    steps.restore_terminal();
+}
+
+/// The steps for the running app.
+pub(super) struct AppShutdown<'a> {
+    pub(super) app: &'a mut App,
+    pub(super) tui: &'a mut TuiState,
+    /// Dropping it leaves the terminal modes and restores the terminal
+    pub(super) terminal_modes: Option<TerminalModeGuard>,
+}
+
+impl ShutdownSteps for AppShutdown<'_> {
+    fn cancel_streams(&mut self) {
+        tabs::cancel_all(self.app, self.tui);
+    }
+
+    fn save_sessions(&mut self) {
+        tabs::save_all(self.app, self.tui);
+    }
+
+    fn flush_logs(&mut self) {
+        log::logger().flush();
+    }
+
+    fn restore_terminal(&mut self) {
+        drop(self.terminal_modes.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        steps: Vec<&'static str>,
+    }
+
+    impl ShutdownSteps for Recorder {
+        fn cancel_streams(&mut self) {
+            self.steps.push("cancel");
+        }
+
+        fn save_sessions(&mut self) {
+            self.steps.push("save");
+        }
+
+        fn flush_logs(&mut self) {
+            self.steps.push("flush");
+        }
+
+        fn restore_terminal(&mut self) {
+            self.steps.push("restore");
+        }
+    }
+
+    #[test]
+    fn test_cancel_before_save_before_restore() {
+        let mut recorder = Recorder::default();
+        shutdown(&mut recorder);
+        assert_eq!(recorder.steps, ["cancel", "save", "flush", "restore"]);
+    }
+}
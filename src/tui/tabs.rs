@@ -0,0 +1,233 @@
+//! # Conversation Tabs
+//!
+//! Several conversations can be open at once, one per tab. Each tab owns a
+//! full `SessionState`, its `MessageListState`, the abort handles of its
+//! in-flight request, and its own action channel, so a stream started in one
+//! tab keeps landing in that tab after the user switches away.
+//!
+//! The active tab's state is not stored here: it lives where the rest of the
+//! code expects it (`app.session`, `tui.message_list`,
+//! `tui.active_abort_handles`), and its slot in `Tabs` holds a placeholder.
+//! Switching tabs swaps those three fields with the target slot. Background
+//! actions are applied the same way: swap the tab in, run the reducer, swap
+//! it back out.
+//!
+//! Tabs live in `TuiState` rather than `App` because `MessageListState` is
+//! presentation state; core only ever sees the one active `SessionState`.
+
+use std::sync::mpsc;
+
+use tokio::task::AbortHandle;
+
+use crate::core::action::{Action, update};
+use crate::core::session;
+use crate::core::state::{App, SessionState};
+use crate::tui::components::MessageListState;
+use crate::tui::{InputMode, TuiState};
+
+/// One tab's conversation, parked while another tab is active.
+pub struct Conversation {
+    session: SessionState,
+    message_list: MessageListState,
+    reasoning_list: MessageListState,
+    abort_handles: Vec<AbortHandle>,
+    tx: mpsc::Sender<Action>,
+    rx: mpsc::Receiver<Action>,
+}
+
+impl Conversation {
+    fn new(session: SessionState) -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            session,
+            message_list: MessageListState::new(),
+            reasoning_list: MessageListState::new(),
+            abort_handles: Vec::new(),
+            tx,
+            rx,
+        }
+    }
+}
+
+/// The open tabs. Always holds at least one conversation.
+pub struct Tabs {
+    conversations: Vec<Conversation>,
+    active: usize,
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tabs {
+    pub fn new() -> Self {
+        Self {
+            conversations: vec![Conversation::new(SessionState::new(""))],
+            active: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.conversations.len()
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Channel for background tasks started from tab `idx`.
+    pub fn sender(&self, idx: usize) -> mpsc::Sender<Action> {
+        self.conversations[idx].tx.clone()
+    }
+
+    /// Channel for background tasks started from the active tab.
+    pub fn active_sender(&self) -> mpsc::Sender<Action> {
+        self.sender(self.active)
+    }
+
+    /// Take every action queued for tab `idx`.
+    pub fn drain(&self, idx: usize) -> Vec<Action> {
+        self.conversations[idx].rx.try_iter().collect()
+    }
+
+    /// Tab labels with their loading flags, in tab order. The active tab's
+    /// slot is a placeholder, so its values come from `app.session`.
+    pub fn labels(&self, app: &App) -> Vec<(String, bool)> {
+        self.conversations
+            .iter()
+            .enumerate()
+            .map(|(idx, conversation)| {
+                let session = if idx == self.active {
+                    &app.session
+                } else {
+                    &conversation.session
+                };
+                let title = if session.session_title.is_empty() {
+                    format!("Tab {}", idx + 1)
+                } else {
+                    session.session_title.clone()
+                };
+                (title, session.is_loading)
+            })
+            .collect()
+    }
+}
+
+/// Exchange the live conversation with the one parked in slot `idx`.
+/// Calling it twice with the same index restores the original state.
+pub fn swap_in(app: &mut App, tui: &mut TuiState, idx: usize) {
+    let conversation = &mut tui.tabs.conversations[idx];
+    std::mem::swap(&mut app.session, &mut conversation.session);
+    std::mem::swap(&mut tui.message_list, &mut conversation.message_list);
+    std::mem::swap(&mut tui.reasoning_list, &mut conversation.reasoning_list);
+    std::mem::swap(
+        &mut tui.active_abort_handles,
+        &mut conversation.abort_handles,
+    );
+}
+
+/// Make tab `idx` the active one. Cursor mode ends, since the selection
+/// belongs to the tab being left.
+pub fn switch_to(app: &mut App, tui: &mut TuiState, idx: usize) {
+    if idx == tui.tabs.active || idx >= tui.tabs.len() {
+        return;
+    }
+    let previous = tui.tabs.active;
+    swap_in(app, tui, previous);
+    swap_in(app, tui, idx);
+    tui.tabs.active = idx;
+    tui.input_mode = InputMode::Input;
+    tui.message_list.selected_index = None;
+}
+
+/// Move to the next tab (or the previous one), wrapping around.
+pub fn cycle(app: &mut App, tui: &mut TuiState, forward: bool) {
+    let len = tui.tabs.len();
+    let idx = if forward {
+        (tui.tabs.active + 1) % len
+    } else {
+        (tui.tabs.active + len - 1) % len
+    };
+    switch_to(app, tui, idx);
+}
+
+/// Open a fresh conversation in a new tab after the last one and switch to it.
+pub fn open(app: &mut App, tui: &mut TuiState) {
+    let session = SessionState::new(&app.system_prompt);
+    tui.tabs.conversations.push(Conversation::new(session));
+    let idx = tui.tabs.len() - 1;
+    switch_to(app, tui, idx);
+}
+
+/// Close the active tab, saving its session and aborting its request.
+/// The last remaining tab cannot be closed.
+pub fn close_active(app: &mut App, tui: &mut TuiState) {
+    if tui.tabs.len() == 1 {
+        app.session.status_message = String::from("Only one tab open.");
+        return;
+    }
+    session::save_current_session(app);
+    for handle in tui.active_abort_handles.drain(..) {
+        handle.abort();
+    }
+
+    let closing = tui.tabs.active;
+    tui.tabs.conversations.remove(closing);
+    let idx = closing.min(tui.tabs.len() - 1);
+    // The live state still belongs to the closed tab; pull the new active
+    // tab's state out of its slot and drop what the swap left behind. The
+    // slot keeps its channel: the new active tab may have a stream running.
+    swap_in(app, tui, idx);
+    tui.tabs.active = idx;
+    let slot = &mut tui.tabs.conversations[idx];
+    slot.session = SessionState::new("");
+    slot.message_list = MessageListState::new();
+    slot.reasoning_list = MessageListState::new();
+    tui.input_mode = InputMode::Input;
+    tui.message_list.selected_index = None;
+}
+
+/// Abort every tab's in-flight request, e.g. on exit. What already
+/// streamed is kept, marked as cancelled, so the sessions can be saved.
+pub fn cancel_all(app: &mut App, tui: &mut TuiState) {
+    for_each_tab(app, tui, cancel_active);
+}
+
+fn cancel_active(app: &mut App, tui: &mut TuiState) {
+    for handle in tui.active_abort_handles.drain(..) {
+        handle.abort();
+    }
+    if app.session.is_loading {
+        update(app, Action::CancelGeneration);
+    }
+}
+
+/// Autosave every tab whose conversation changed since its last save.
+pub fn autosave_all(app: &mut App, tui: &mut TuiState) {
+    for_each_tab(app, tui, |app, tui| {
+        app.session.scroll = tui.message_list.scroll_position();
+        session::autosave(app);
+    });
+}
+
+/// Save every tab's session, e.g. on exit, with its current scroll position.
+pub fn save_all(app: &mut App, tui: &mut TuiState) {
+    for_each_tab(app, tui, |app, tui| {
+        app.session.scroll = tui.message_list.scroll_position();
+        session::save_current_session(app);
+    });
+}
+
+/// Run `f` on every tab: each background tab is swapped into the live
+/// state for the call and swapped back out, then the active tab runs last.
+fn for_each_tab(app: &mut App, tui: &mut TuiState, mut f: impl FnMut(&mut App, &mut TuiState)) {
+    let active = tui.tabs.active;
+    for idx in (0..tui.tabs.len()).filter(|&idx| idx != active) {
+        swap_in(app, tui, idx);
+        f(app, tui);
+        swap_in(app, tui, idx);
+    }
+    f(app, tui);
+}
@@ -1,16 +1,21 @@
 //! Background task spawners for async operations (API requests, tool execution, model fetching).
 
 use log::{debug, info, warn};
-use std::sync::{Arc, mpsc};
+use std::ops::Range;
+use std::sync::mpsc;
 
 use crate::core::action::Action;
 use crate::core::state::App;
-use crate::inference::{CompletionRequest, StreamChunk, model_discovery};
+use crate::core::tools::dispatch::ToolDispatcher;
+use crate::inference::{CompletionRequest, Context, ImageAttachment, StreamChunk, model_discovery};
+use crate::tui::clipboard;
 use crate::tui::stream_buffer::{BufferableChunk, ChunkKind, SmoothedChunk, StreamBuffer};
 
+/// Run a tool call on the dispatcher's pool and report its result. Calls
+/// spawned together run concurrently, up to the pool size.
 pub fn spawn_tool_execution(
     tool_call: crate::inference::ToolCall,
-    registry: Arc<crate::core::tools::ToolRegistry>,
+    dispatcher: ToolDispatcher,
     tx: mpsc::Sender<Action>,
 ) {
     info!(
@@ -18,25 +23,11 @@ pub fn spawn_tool_execution(
         tool_call.name, tool_call.call_id
     );
     tokio::spawn(async move {
-        let output = match tokio::time::timeout(
-            std::time::Duration::from_secs(30),
-            registry.execute(&tool_call),
-        )
-        .await
-        {
-            Ok(result) => result,
-            Err(_) => {
-                warn!(
-                    "Tool '{}' timed out after 30s (call_id={})",
-                    tool_call.name, tool_call.call_id
-                );
-                serde_json::json!({"error": "Tool execution timed out after 30s"}).to_string()
-            }
-        };
+        let result = dispatcher.execute(&tool_call).await;
         if tx
             .send(Action::ToolResultReady {
-                call_id: tool_call.call_id.clone(),
-                output,
+                call_id: result.call_id,
+                output: result.output,
             })
             .is_err()
         {
@@ -58,9 +49,16 @@ pub fn spawn_request(app: &App, tx: mpsc::Sender<Action>) -> Vec<tokio::task::Ab
     let effort = app.effort;
     let tools = app.tool_definitions();
     let max_output_tokens = Some(app.max_output_tokens);
+    let temperature = app.temperature;
+    let reasoning = app.supports_reasoning;
+    let reasoning_mode = app.config.reasoning_mode;
+    let dedupe_stream = app.config.dedupe_stream;
+    let prompt_cache = app.config.prompt_cache;
+    let turn_directive = app.session.turn_directive.clone();
 
-    // Async channel for streaming chunks
-    let (chunk_tx, mut chunk_rx) = tokio::sync::mpsc::channel::<StreamChunk>(100);
+    // Async channel for streaming chunks; the provider waits when it is full
+    let (chunk_tx, mut chunk_rx) =
+        tokio::sync::mpsc::channel::<StreamChunk>(app.config.stream_channel_capacity);
 
     // Clone tx for the streaming task
     let tx_stream = tx.clone();
@@ -71,8 +69,14 @@ pub fn spawn_request(app: &App, tx: mpsc::Sender<Action>) -> Vec<tokio::task::Ab
             context: &context,
             model: &model,
             effort,
+            reasoning,
             tools: &tools,
             max_output_tokens,
+            temperature,
+            reasoning_mode,
+            dedupe_stream,
+            prompt_cache,
+            turn_directive: turn_directive.as_deref(),
         };
 
         if let Err(e) = provider.stream_completion(request, chunk_tx).await {
@@ -113,6 +117,15 @@ pub fn spawn_request(app: &App, tx: mpsc::Sender<Action>) -> Vec<tokio::task::Ab
                             let (text, item_id, kind) = match chunk {
                                 StreamChunk::Content { text, item_id } => {
                                     total_content_len += text.len();
+                                    // Timed on arrival; the smoothed drip would hide the real rate
+                                    let received = Action::ContentReceived {
+                                        chars: text.chars().count(),
+                                        elapsed: request_start.elapsed(),
+                                    };
+                                    if tx.send(received).is_err() {
+                                        warn!("Failed to forward ContentReceived: receiver dropped");
+                                        return;
+                                    }
                                     (text, item_id, ChunkKind::Content)
                                 }
                                 StreamChunk::Thinking { text, item_id } => {
@@ -126,6 +139,19 @@ pub fn spawn_request(app: &App, tx: mpsc::Sender<Action>) -> Vec<tokio::task::Ab
                             forwarded_count += 1;
                             buffer.push(BufferableChunk { kind, item_id, text });
                         }
+                        Some(StreamChunk::ThinkingStart { item_id }) => {
+                            debug!("Reasoning started (item_id={:?})", item_id);
+                        }
+                        Some(StreamChunk::ThinkingEnd { item_id }) => {
+                            // Finish showing the reasoning before the answer starts
+                            if flush_and_send(&mut buffer, &tx, true) {
+                                return;
+                            }
+                            if tx.send(Action::ThinkingDone { item_id }).is_err() {
+                                warn!("Failed to forward ThinkingEnd: receiver dropped");
+                                return;
+                            }
+                        }
                         Some(StreamChunk::ToolCall(tc)) => {
                             // Flush buffered text before passing through tool calls
                             if flush_and_send(&mut buffer, &tx, true) {
@@ -137,11 +163,31 @@ pub fn spawn_request(app: &App, tx: mpsc::Sender<Action>) -> Vec<tokio::task::Ab
                                 return;
                             }
                         }
+                        Some(StreamChunk::RateLimit(info)) => {
+                            if tx.send(Action::RateLimitUpdated(info)).is_err() {
+                                warn!("Failed to forward RateLimit: receiver dropped");
+                                return;
+                            }
+                        }
                         Some(StreamChunk::Completed(provider_stats)) => {
                             got_completed = true;
                             completed_stats = provider_stats;
                             stream_ended = true;
                         }
+                        Some(StreamChunk::Error(message)) => {
+                            // Deliver what already streamed, then end the turn with the error
+                            if flush_and_send(&mut buffer, &tx, true) {
+                                return;
+                            }
+                            info!(
+                                "Stream failed after {} actions: {}",
+                                forwarded_count, message
+                            );
+                            if tx.send(Action::StreamError(message)).is_err() {
+                                warn!("Failed to send StreamError: receiver dropped");
+                            }
+                            return;
+                        }
                         None => {
                             // Channel closed without Completed
                             stream_ended = true;
@@ -201,11 +247,108 @@ pub fn spawn_request(app: &App, tx: mpsc::Sender<Action>) -> Vec<tokio::task::Ab
     vec![stream_handle.abort_handle(), forward_handle.abort_handle()]
 }
 
+/// System prompt for the request that condenses older turns.
+const SUMMARY_PROMPT: &str = "You condense conversations so they fit in less context.";
+
+/// Final message of that request, after the turns to condense.
+const SUMMARY_INSTRUCTION: &str = "Summarize the conversation above so it can replace it. \
+    Keep the facts, decisions, names, file paths, numbers and open questions later \
+    messages may rely on. Reply with the summary only, as plain prose.";
+
+/// Ask the model to condense the items in `range` and report the summary as
+/// `ContextSummarized`, or `SummaryFailed` if nothing usable came back.
+pub fn summarize_old_context(
+    app: &App,
+    range: Range<usize>,
+    tx: mpsc::Sender<Action>,
+) -> Vec<tokio::task::AbortHandle> {
+    info!("Summarizing context items {:?}", range);
+
+    let provider = app.provider.clone();
+    let mut context = Context::with_system_prompt(SUMMARY_PROMPT.to_string());
+    context
+        .items
+        .extend(app.session.context.items[range.clone()].iter().cloned());
+    context.add_user_message(SUMMARY_INSTRUCTION.to_string());
+    let model = app.model.name.clone();
+    let effort = app.effort;
+    let reasoning = app.supports_reasoning;
+    // Offered so the condensed tool calls are valid input; any new call is ignored
+    let tools = app.tool_definitions();
+    let max_output_tokens = Some(app.max_output_tokens);
+    let reasoning_mode = app.config.reasoning_mode;
+    let channel_capacity = app.config.stream_channel_capacity;
+
+    let handle = tokio::spawn(async move {
+        let request = CompletionRequest {
+            context: &context,
+            model: &model,
+            effort,
+            reasoning,
+            tools: &tools,
+            max_output_tokens,
+            temperature: None,
+            reasoning_mode,
+            dedupe_stream: false,
+            prompt_cache: false,
+            turn_directive: None,
+        };
+        let (chunk_tx, mut chunk_rx) = tokio::sync::mpsc::channel::<StreamChunk>(channel_capacity);
+        let collect = async move {
+            let mut summary = String::new();
+            while let Some(chunk) = chunk_rx.recv().await {
+                match chunk {
+                    StreamChunk::Content { text, .. } => summary.push_str(&text),
+                    StreamChunk::Error(message) => return Err(message),
+                    _ => {}
+                }
+            }
+            Ok(summary)
+        };
+        let (sent, collected) =
+            tokio::join!(provider.stream_completion(request, chunk_tx), collect);
+
+        let action = match (sent, collected) {
+            (Err(e), _) => Action::SummaryFailed(e.to_string()),
+            (_, Err(message)) => Action::SummaryFailed(message),
+            (Ok(()), Ok(summary)) if summary.trim().is_empty() => {
+                Action::SummaryFailed(String::from("empty summary"))
+            }
+            (Ok(()), Ok(summary)) => Action::ContextSummarized { range, summary },
+        };
+        if tx.send(action).is_err() {
+            warn!("Failed to send summary result: receiver dropped");
+        }
+    });
+
+    vec![handle.abort_handle()]
+}
+
 /// Spawns a background task to fetch models from all configured providers.
 ///
 /// Runs OpenRouter and LM Studio fetches concurrently via `tokio::join!`.
 /// LM Studio has a 3s timeout so it won't block if the server isn't running.
 /// Results are deduped against pinned models in `ModelPickerState::set_fetched_models()`.
+/// Read the clipboard's image on the blocking pool, since the clipboard tools
+/// are separate processes, and stage it for the next message.
+pub fn spawn_clipboard_image_read(tx: mpsc::Sender<Action>) {
+    tokio::task::spawn_blocking(move || {
+        let action = match clipboard::read_image() {
+            Ok(Some(bytes)) => {
+                Action::StageImage(ImageAttachment::from_bytes(&bytes, "image/png", None))
+            }
+            Ok(None) => Action::ClipboardImageFailed(String::from("No image on the clipboard.")),
+            Err(e) => {
+                warn!("Clipboard image read failed: {}", e);
+                Action::ClipboardImageFailed(format!("Paste failed: {}", e))
+            }
+        };
+        if tx.send(action).is_err() {
+            warn!("Failed to send clipboard image: receiver dropped");
+        }
+    });
+}
+
 pub fn spawn_model_fetch(app: &App, tx: mpsc::Sender<Action>) {
     let openrouter_base_url = app.config.openrouter_base_url.clone();
     let openrouter_api_key = app.config.openrouter_api_key.clone();
@@ -0,0 +1,200 @@
+//! # Terminal Modes
+//!
+//! Entering and leaving the terminal modes Navi runs in, and job control
+//! (Ctrl+Z / `fg`) around them.
+//!
+//! Raw mode and the alternate screen belong to the process, not the
+//! terminal: a suspended Navi must hand the shell a normal terminal, and
+//! after `fg` the shell may have reset everything. Suspend leaves every mode
+//! before stopping, and resume enters them again and forces a full redraw.
+//!
+//! The escape sequences are written to any `Write` so the enter/leave pairs
+//! can be checked without a terminal.
+
+#[cfg(unix)]
+use std::io::stdout;
+use std::io::{self, Write};
+
+use crossterm::cursor::{Hide, SetCursorStyle, Show};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::queue;
+use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+#[cfg(unix)]
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+#[cfg(unix)]
+use log::{info, warn};
+#[cfg(unix)]
+use ratatui::DefaultTerminal;
+
+/// Writes the modes layered on top of raw mode and the alternate screen.
+pub fn enter_modes(out: &mut impl Write) -> io::Result<()> {
+    // Enable Kitty keyboard protocol unconditionally (allows Shift+Enter detection)
+    // Detection via supports_keyboard_enhancement() fails in WSL, but the protocol
+    // is harmlessly ignored by terminals that don't support it
+    queue!(
+        out,
+        EnableMouseCapture,
+        EnableBracketedPaste,
+        Show,                        // Show cursor for input editing
+        SetCursorStyle::SteadyBlock, // Non-blinking: avoids blink timer reset from continuous redraws
+        PushKeyboardEnhancementFlags(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+        )
+    )?;
+    out.flush()
+}
+
+/// Undoes `enter_modes`, in reverse order.
+pub fn leave_modes(out: &mut impl Write) -> io::Result<()> {
+    queue!(
+        out,
+        PopKeyboardEnhancementFlags,
+        DisableBracketedPaste,
+        DisableMouseCapture,
+        Hide // Hide cursor on exit
+    )?;
+    out.flush()
+}
+
+/// Everything a suspend leaves, alternate screen last so the modes are
+/// switched off while Navi's screen is still showing.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn write_suspend(out: &mut impl Write) -> io::Result<()> {
+    leave_modes(out)?;
+    queue!(out, LeaveAlternateScreen)?;
+    out.flush()
+}
+
+/// The mirror of `write_suspend`.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn write_resume(out: &mut impl Write) -> io::Result<()> {
+    queue!(out, EnterAlternateScreen)?;
+    enter_modes(out)
+}
+
+/// Leaves the terminal as the shell expects it, stops the process, and puts
+/// the terminal back once the shell continues it.
+#[cfg(unix)]
+pub fn suspend(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    write_suspend(&mut stdout())?;
+    disable_raw_mode()?;
+    info!("Suspending");
+    // SIGTSTP is caught by `JobSignals`; SIGSTOP can't be, so it always stops
+    // SAFETY: raise takes no pointers and only signals this process. SIGSTOP
+    // runs no handler, so no Rust code is interrupted mid-update.
+    if unsafe { libc::raise(libc::SIGSTOP) } != 0 {
+        warn!("Could not stop: {}", io::Error::last_os_error());
+    }
+    info!("Resumed");
+    resume(terminal)
+}
+
+/// Enters raw mode, the alternate screen, and the other modes again, then
+/// clears the terminal so the next draw repaints everything.
+#[cfg(unix)]
+pub fn resume(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    enable_raw_mode()?;
+    write_resume(&mut stdout())?;
+    terminal.clear()
+}
+
+/// Re-enters the modes after a SIGCONT Navi didn't ask for (say, an external
+/// SIGSTOP). The keyboard flags are a stack, so pop before pushing again.
+#[cfg(unix)]
+pub fn reapply(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    let mut out = stdout();
+    queue!(out, PopKeyboardEnhancementFlags)?;
+    resume(terminal)
+}
+
+/// A job-control signal the run loop has to act on.
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobSignal {
+    /// SIGTSTP: suspend now
+    Stop,
+    /// SIGCONT: continued, the terminal may have been reset
+    Continue,
+}
+
+/// SIGTSTP and SIGCONT listeners, checked by the run loop each tick.
+///
+/// Ctrl+Z doesn't raise SIGTSTP in raw mode (it arrives as a key), but
+/// `kill -TSTP` still does. Catching it replaces the default stop, which
+/// would leave the shell with a raw terminal.
+#[cfg(unix)]
+pub struct JobSignals {
+    stop: tokio::signal::unix::Signal,
+    cont: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl JobSignals {
+    pub fn new() -> io::Result<Self> {
+        use tokio::signal::unix::{SignalKind, signal};
+        Ok(Self {
+            stop: signal(SignalKind::from_raw(libc::SIGTSTP))?,
+            cont: signal(SignalKind::from_raw(libc::SIGCONT))?,
+        })
+    }
+
+    /// The signal received since the last call, if any. Never blocks.
+    pub fn poll(&mut self) -> Option<JobSignal> {
+        use futures::FutureExt;
+        if self.stop.recv().now_or_never().is_some() {
+            Some(JobSignal::Stop)
+        } else if self.cont.recv().now_or_never().is_some() {
+            Some(JobSignal::Continue)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(write: fn(&mut Vec<u8>) -> io::Result<()>) -> String {
+        let mut out = Vec::new();
+        write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_suspend_leaves_every_mode_resume_enters() {
+        let resume = written(write_resume);
+        let suspend = written(write_suspend);
+
+        let pairs = [
+            ("alternate screen", "\x1b[?1049h", "\x1b[?1049l"),
+            ("bracketed paste", "\x1b[?2004h", "\x1b[?2004l"),
+            ("mouse capture", "\x1b[?1000h", "\x1b[?1000l"),
+            ("keyboard flags", "\x1b[>", "\x1b[<"),
+            ("cursor", "\x1b[?25h", "\x1b[?25l"),
+        ];
+        for (mode, enable, disable) in pairs {
+            assert!(resume.contains(enable), "resume skips {mode}: {resume:?}");
+            assert!(
+                !resume.contains(disable),
+                "resume leaves {mode}: {resume:?}"
+            );
+            assert!(
+                suspend.contains(disable),
+                "suspend skips {mode}: {suspend:?}"
+            );
+            assert!(
+                !suspend.contains(enable),
+                "suspend enters {mode}: {suspend:?}"
+            );
+        }
+
+        // The alternate screen wraps the other modes
+        assert!(resume.starts_with("\x1b[?1049h"), "got: {resume:?}");
+        assert!(suspend.ends_with("\x1b[?1049l"), "got: {suspend:?}");
+    }
+}
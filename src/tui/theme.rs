@@ -0,0 +1,237 @@
+//! # Theme
+//!
+//! Central color palette for the TUI, built from the `[theme]` config table.
+//! Components receive a `&Theme` as a prop instead of hardcoding colors.
+//!
+//! Colors accept anything ratatui's `Color::from_str` understands: named
+//! colors (`"green"`, `"dark gray"`), indexed (`"42"`), or hex (`"#ff8800"`).
+//! Invalid values are logged and fall back to the default.
+//!
+//! The colors left unset come from a dark or light base palette, chosen by
+//! `background` or, failing that, the terminal's `COLORFGBG` variable.
+
+use std::env;
+use std::str::FromStr;
+
+use log::warn;
+use ratatui::style::Color;
+
+use crate::core::config::ThemeConfig;
+use crate::tui::markdown::LinkStyle;
+
+/// Syntect theme used for fenced code blocks when none is configured.
+pub const DEFAULT_CODE_THEME: &str = "base16-ocean.dark";
+
+/// Syntect theme for code blocks on a light background.
+const LIGHT_CODE_THEME: &str = "base16-ocean.light";
+
+/// Whether the terminal draws on a dark or a light background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Background {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Background {
+    /// From `[theme] background`; unknown names are logged and ignored.
+    fn from_config(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "dark" => Some(Background::Dark),
+            "light" => Some(Background::Light),
+            other => {
+                warn!("Unknown theme background '{}', ignoring", other);
+                None
+            }
+        }
+    }
+
+    /// From a `COLORFGBG` value such as `"15;0"` (foreground;background),
+    /// set by rxvt, Konsole and others. The last field is an ANSI color
+    /// index: 7 (light gray) and 9-15 (bright colors) are light.
+    fn from_colorfgbg(value: &str) -> Option<Self> {
+        let bg: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+        Some(match bg {
+            7 | 9..=15 => Background::Light,
+            _ => Background::Dark,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub user: Color,
+    pub model: Color,
+    pub system: Color,
+    pub thinking: Color,
+    pub tool: Color,
+    /// Secondary chrome: separators, code fences, blockquote bars, list markers.
+    pub border: Color,
+    /// Syntect theme name for syntax highlighting.
+    pub code_theme: String,
+    /// How markdown links show their URL.
+    pub link_style: LinkStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The palette for dark backgrounds, also the default.
+    pub fn dark() -> Self {
+        Self {
+            user: Color::Green,
+            model: Color::Blue,
+            system: Color::Yellow,
+            thinking: Color::DarkGray,
+            tool: Color::Yellow,
+            border: Color::DarkGray,
+            code_theme: DEFAULT_CODE_THEME.to_string(),
+            link_style: LinkStyle::default(),
+        }
+    }
+
+    /// Darker colors that stay legible on a light background.
+    pub fn light() -> Self {
+        Self {
+            user: Color::Indexed(28),      // dark green
+            model: Color::Indexed(25),     // dark blue
+            system: Color::Indexed(130),   // dark orange; yellow washes out
+            thinking: Color::Indexed(242), // mid gray
+            tool: Color::Indexed(130),
+            border: Color::Indexed(246),
+            code_theme: LIGHT_CODE_THEME.to_string(),
+            link_style: LinkStyle::default(),
+        }
+    }
+
+    /// Build a theme from config, keeping the base palette for unset or
+    /// invalid entries.
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        Self::from_config_with(config, env::var("COLORFGBG").ok().as_deref())
+    }
+
+    /// `from_config` with the `COLORFGBG` value passed in.
+    fn from_config_with(config: &ThemeConfig, colorfgbg: Option<&str>) -> Self {
+        let background = config
+            .background
+            .as_deref()
+            .and_then(Background::from_config)
+            .or_else(|| colorfgbg.and_then(Background::from_colorfgbg))
+            .unwrap_or_default();
+        let default = match background {
+            Background::Dark => Self::dark(),
+            Background::Light => Self::light(),
+        };
+        Self {
+            user: parse_color("user", &config.user, default.user),
+            model: parse_color("model", &config.model, default.model),
+            system: parse_color("system", &config.system, default.system),
+            thinking: parse_color("thinking", &config.thinking, default.thinking),
+            tool: parse_color("tool", &config.tool, default.tool),
+            border: parse_color("border", &config.border, default.border),
+            code_theme: config.code_theme.clone().unwrap_or(default.code_theme),
+            link_style: LinkStyle::from_config(config.link_style.as_deref()),
+        }
+    }
+}
+
+fn parse_color(key: &str, value: &Option<String>, fallback: Color) -> Color {
+    let Some(value) = value else {
+        return fallback;
+    };
+    Color::from_str(value).unwrap_or_else(|_| {
+        warn!("Invalid theme color {key} = {value:?}, using default");
+        fallback
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::NaviConfig;
+
+    #[test]
+    fn test_theme_table_parses_to_colors() {
+        let toml_str = r##"
+            [theme]
+            user = "magenta"
+            model = "#ff8800"
+            border = "dark gray"
+            code_theme = "InspiredGitHub"
+            link_style = "footnote"
+        "##;
+        let config: NaviConfig = toml::from_str(toml_str).unwrap();
+        let theme = Theme::from_config(&config.theme);
+
+        assert_eq!(theme.user, Color::Magenta);
+        assert_eq!(theme.model, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.border, Color::DarkGray);
+        assert_eq!(theme.code_theme, "InspiredGitHub");
+        assert_eq!(theme.link_style, LinkStyle::Footnote);
+        // Unset keys keep the default look
+        assert_eq!(theme.system, Theme::default().system);
+    }
+
+    #[test]
+    fn test_invalid_color_falls_back_to_default() {
+        let config = ThemeConfig {
+            user: Some("not-a-color".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(Theme::from_config(&config).user, Theme::default().user);
+    }
+
+    #[test]
+    fn test_empty_config_matches_default() {
+        assert_eq!(
+            Theme::from_config_with(&ThemeConfig::default(), None),
+            Theme::default()
+        );
+    }
+
+    #[test]
+    fn test_background_selects_palette() {
+        let light: NaviConfig = toml::from_str("[theme]\nbackground = \"light\"").unwrap();
+        assert_eq!(Theme::from_config_with(&light.theme, None), Theme::light());
+
+        let dark: NaviConfig = toml::from_str("[theme]\nbackground = \"dark\"").unwrap();
+        // The config wins over a light terminal
+        assert_eq!(
+            Theme::from_config_with(&dark.theme, Some("0;15")),
+            Theme::dark()
+        );
+    }
+
+    #[test]
+    fn test_colorfgbg_picks_palette_when_unset() {
+        let config = ThemeConfig::default();
+        assert_eq!(
+            Theme::from_config_with(&config, Some("0;15")),
+            Theme::light()
+        );
+        assert_eq!(
+            Theme::from_config_with(&config, Some("15;default;0")),
+            Theme::dark()
+        );
+        assert_eq!(
+            Theme::from_config_with(&config, Some("junk")),
+            Theme::dark()
+        );
+    }
+
+    #[test]
+    fn test_colors_override_light_palette() {
+        let config = ThemeConfig {
+            background: Some("light".to_string()),
+            user: Some("magenta".to_string()),
+            ..Default::default()
+        };
+        let theme = Theme::from_config_with(&config, None);
+        assert_eq!(theme.user, Color::Magenta);
+        assert_eq!(theme.model, Theme::light().model);
+    }
+}
@@ -2,31 +2,48 @@
 //!
 //! Top-level `draw_ui` function that composes all components into a frame.
 //!
-//! **Layout:** title bar (1 line) + main area (flex) + input box (3-7 lines).
+//! **Layout:** title bar (1 line) + tab bar (1 line, only with 2+ tabs) +
+//! main area (flex) + input box (3-7 lines) + key hints footer (1 line).
+//! The main area gives its right side to the reasoning panel while that is
+//! toggled on (Alt+T) and the terminal is wide enough.
 //!
 //! **Rendering order:** Main area renders first so `MessageList::render` can
 //! update the layout cache before `hit_test_message` needs it. Then title bar,
-//! input box, and finally overlays (session manager, model picker) on top.
+//! tab bar, input box, footer, and finally overlays (session manager, model
+//! picker, help) on top.
 
-use crate::core::state::App;
-use crate::tui::TuiState;
+use crate::core::state::{App, StreamPhase};
 use crate::tui::component::Component;
-use crate::tui::components::{MessageList, ModelPicker, SessionManager, TitleBar};
+use crate::tui::components::message_list::{LayoutCache, reasoning_context};
+use crate::tui::components::{
+    DiffOverlay, HelpOverlay, HintMode, MessageList, ModelPicker, SessionManager, StatusHints,
+    TabBar, TitleBar,
+};
+use crate::tui::event::KEY_BINDINGS;
+use crate::tui::{InputMode, TuiState};
+
+use std::collections::HashMap;
 
 use ratatui::Frame;
 use ratatui::layout::Alignment;
-use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::widgets::{Block, Paragraph};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, Paragraph};
 
-pub fn draw_ui(frame: &mut Frame, app: &App, tui: &mut TuiState, spinner_frame: usize) {
-    use Constraint::{Length, Min};
+/// Narrowest main area that is split to show the reasoning panel.
+const REASONING_PANEL_MIN_WIDTH: u16 = 60;
 
+pub fn draw_ui(frame: &mut Frame, app: &App, tui: &mut TuiState, spinner_frame: usize) {
     // Calculate input height dynamically based on content
     let input_height = tui.input_box.calculate_height(frame.area().width);
 
-    // Dynamic layout: title(1) + messages(flex) + input(3-7)
-    let layout = Layout::vertical([Length(1), Min(0), Length(input_height)]);
-    let [title_area, main_area, input_area] = layout.areas(frame.area());
+    let tab_height = tab_bar_height(tui);
+
+    let [title_area, tab_area, main_area, input_area, hints_area] =
+        screen_layout(tab_height, input_height).areas(frame.area());
+    let (main_area, panel_area) = split_reasoning_panel(main_area, tui.show_reasoning_panel);
+    // Only a rendered message list places pictures
+    tui.message_list.image_placements.clear();
 
     // 1. Render Main Area (MessageList or Error)
     // Rendered first so MessageList::render updates layout cache in TuiState.
@@ -34,7 +51,12 @@ pub fn draw_ui(frame: &mut Frame, app: &App, tui: &mut TuiState, spinner_frame:
         draw_error_view(frame, main_area, error_msg);
     } else if !app.session.context.has_visible_messages() {
         // Render Landing Page
-        let mut landing = crate::tui::components::LandingPage::new(spinner_frame);
+        let mut landing = crate::tui::components::LandingPage::new(
+            spinner_frame,
+            app.config.landing_message.as_deref(),
+            app.config.landing_tips.as_deref(),
+            tui.landing_tip_seed,
+        );
         landing.render(frame, main_area);
     } else {
         // Create MessageList wrapper around mutable persistent state
@@ -42,38 +64,190 @@ pub fn draw_ui(frame: &mut Frame, app: &App, tui: &mut TuiState, spinner_frame:
             &mut tui.message_list, // &mut MessageListState
             &app.session.context,
             app.session.is_loading,
+            app.session.stream_phase == StreamPhase::Thinking,
             tui.pulse_value,
             spinner_frame,
+            tui.spinner,
             &app.session.message_stats,
-        );
+            &tui.theme,
+            tui.wrap_messages,
+            tui.message_density,
+        )
+        .hide_thinking(panel_area.is_some() || !tui.show_thinking)
+        .hide_system(!tui.show_system)
+        .with_graphics(tui.inline_images.as_ref().map(|images| images.protocol()));
         // Mutable render call updates layout cache and renders to scroll view
         message_list.render(frame, main_area);
     }
+    if let Some(panel_area) = panel_area {
+        draw_reasoning_panel(frame, panel_area, app, tui, spinner_frame);
+    }
 
     // 2. Render TitleBar
+    // Context window from the pinned entry, else from the fetched catalog
+    let context_window = app.context_window().or_else(|| {
+        tui.fetched_models.as_ref().and_then(|models| {
+            models
+                .iter()
+                .find(|m| m.same_model(&app.model.name, &app.model.provider))
+                .and_then(|m| m.context_window)
+        })
+    });
+    let context_usage = context_window.map(|w| (app.session.context.estimate_tokens(), w));
     let mut title_bar = TitleBar::new(
         &app.model.name,
         &app.model.provider,
         app.session.is_loading,
         spinner_frame,
         &app.session.session_title,
-        app.session.session_total_tokens,
-    );
+        app.session.session_usage.total().unwrap_or(0),
+        context_usage,
+    )
+    .with_rate_limit(app.rate_limit.as_ref())
+    .with_effort(app.supports_reasoning.then_some(app.effort))
+    .with_live_rate(app.session.live_rate.tokens_per_sec());
     title_bar.render(frame, title_area);
 
-    // 3. Render InputBox
+    // 3. Render TabBar (zero-height, so skipped, with a single tab)
+    if tab_height > 0 {
+        let labels = tui.tabs.labels(app);
+        TabBar::new(&labels, tui.tabs.active()).render(frame, tab_area);
+    }
+
+    // 4. Render InputBox
     // InputBox state is persistent in TuiState
     tui.input_box.render(frame, input_area);
 
-    // 4. Session manager overlay (on top of everything)
+    // 5. Key hints for whatever has the keyboard
+    StatusHints::new(hint_mode(tui)).render(frame, hints_area);
+
+    // 6. Session manager overlay (on top of everything)
     if let Some(ref mut sm) = tui.session_manager {
-        SessionManager::new(sm).render(frame, frame.area());
+        SessionManager::new(sm, &tui.theme).render(frame, frame.area());
     }
 
-    // 5. Model picker overlay (on top of everything, including session manager)
+    // 7. Model picker overlay (on top of everything, including session manager)
     if let Some(ref mut mp) = tui.model_picker {
         ModelPicker::new(mp, &app.model.name).render(frame, frame.area());
     }
+
+    // 8. Answer diff overlay (dismissed by any key but scrolling)
+    if let Some(ref mut diff) = tui.variant_diff {
+        DiffOverlay::new(diff).render(frame, frame.area());
+    }
+
+    // 9. Help overlay (topmost; dismissed by any key)
+    if tui.show_help {
+        HelpOverlay::new(KEY_BINDINGS).render(frame, frame.area());
+    }
+
+    // Pictures would be drawn over an overlay
+    if !matches!(hint_mode(tui), HintMode::Input | HintMode::Cursor) {
+        tui.message_list.image_placements.clear();
+    }
+}
+
+/// What receives key presses, topmost overlay first (matching the order
+/// `handle_event` checks them in).
+fn hint_mode(tui: &TuiState) -> HintMode {
+    if tui.show_help {
+        HintMode::Help
+    } else if tui.variant_diff.is_some() {
+        HintMode::Diff
+    } else if tui.model_picker.is_some() {
+        HintMode::ModelPicker
+    } else if tui.session_manager.is_some() {
+        HintMode::SessionManager
+    } else {
+        match tui.input_mode {
+            InputMode::Cursor => HintMode::Cursor,
+            InputMode::Input => HintMode::Input,
+        }
+    }
+}
+
+/// Rows of the screen: title(1) + tabs(0-1) + messages(flex) + input(3-7) + hints(1).
+fn screen_layout(tab_height: u16, input_height: u16) -> Layout {
+    use Constraint::{Length, Min};
+    Layout::vertical([
+        Length(1),
+        Length(tab_height),
+        Min(0),
+        Length(input_height),
+        Length(1),
+    ])
+}
+
+/// Split the main area into the conversation and, when `show` is set and
+/// there is room for both, the reasoning panel on its right.
+fn split_reasoning_panel(main_area: Rect, show: bool) -> (Rect, Option<Rect>) {
+    if !show || main_area.width < REASONING_PANEL_MIN_WIDTH {
+        return (main_area, None);
+    }
+    let [conversation, panel] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .areas(main_area);
+    (conversation, Some(panel))
+}
+
+/// Where the reasoning panel is drawn, if it is.
+pub fn reasoning_panel_area(frame_area: Rect, tui: &TuiState) -> Option<Rect> {
+    let input_height = tui.input_box.calculate_height(frame_area.width);
+    let [_, _, main_area, _, _] =
+        screen_layout(tab_bar_height(tui), input_height).areas(frame_area);
+    split_reasoning_panel(main_area, tui.show_reasoning_panel).1
+}
+
+/// The conversation's thinking in its own scrollable list, so the answer
+/// isn't interleaved with it.
+fn draw_reasoning_panel(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    tui: &mut TuiState,
+    spinner_frame: usize,
+) {
+    let block = Block::new()
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(tui.theme.border))
+        .title(" Reasoning ")
+        .title_style(Style::default().fg(tui.theme.thinking));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let reasoning = reasoning_context(&app.session.context);
+    if reasoning.items.is_empty() {
+        let placeholder = Paragraph::new("No reasoning yet.")
+            .style(
+                Style::default()
+                    .fg(tui.theme.thinking)
+                    .add_modifier(Modifier::ITALIC),
+            )
+            .alignment(Alignment::Center);
+        frame.render_widget(placeholder, inner);
+        return;
+    }
+    // Stats are keyed by conversation index, so the panel shows none
+    let no_stats = HashMap::new();
+    MessageList::new(
+        &mut tui.reasoning_list,
+        &reasoning,
+        false,
+        false,
+        0.0,
+        spinner_frame,
+        tui.spinner,
+        &no_stats,
+        &tui.theme,
+        tui.wrap_messages,
+        tui.message_density,
+    )
+    .render(frame, inner);
+}
+
+/// The tab bar only takes a row once a second tab is open.
+pub fn tab_bar_height(tui: &TuiState) -> u16 {
+    if tui.tabs.len() > 1 { 1 } else { 0 }
 }
 
 fn draw_error_view(frame: &mut Frame, area: Rect, error_msg: &str) {
@@ -84,36 +258,28 @@ fn draw_error_view(frame: &mut Frame, area: Rect, error_msg: &str) {
     frame.render_widget(error_paragraph, area);
 }
 
-/// Hit test: given a screen Y coordinate, find which message index (if any) is at that position.
-/// Uses binary search on prefix_heights for O(log n) performance.
+/// Hit test: given a screen position, find which message index (if any) is at that position.
+/// Uses binary search on the layout's prefix heights for O(log n) performance.
 pub fn hit_test_message(
-    screen_y: u16,
+    screen: Position,
     frame_area: Rect,
     scroll_offset_y: u16,
-    prefix_heights: &[u16],
+    layout_cache: &LayoutCache,
+    tab_height: u16,
     input_height: u16,
+    reasoning_panel: bool,
 ) -> Option<usize> {
-    use Constraint::{Length, Min};
+    // Same layout as draw_ui, down to the conversation's part of the main area
+    let [_title_area, _tab_area, main_area, _input_area, _hints_area] =
+        screen_layout(tab_height, input_height).areas(frame_area);
+    let (main_area, _panel_area) = split_reasoning_panel(main_area, reasoning_panel);
 
-    // Calculate layout to find main_area
-    // NOTE: This MUST match the layout in draw_ui
-    let layout = Layout::vertical([Length(1), Min(0), Length(input_height)]);
-    let [_title_area, main_area, _input_area] = layout.areas(frame_area);
-
-    // Check if mouse is within the main content area
-    if screen_y < main_area.y || screen_y >= main_area.y + main_area.height {
+    // Check if mouse is within the conversation
+    if !main_area.contains(screen) {
         return None;
     }
 
     // Convert screen Y to content Y (accounting for scroll)
-    let content_y = (screen_y - main_area.y) + scroll_offset_y;
-
-    // Binary search: find first segment whose end position is > content_y
-    let idx = prefix_heights.partition_point(|&end_y| end_y <= content_y);
-
-    if idx < prefix_heights.len() {
-        Some(idx)
-    } else {
-        None // Below all content
-    }
+    let content_y = (screen.y - main_area.y) + scroll_offset_y;
+    layout_cache.index_at(content_y)
 }
@@ -1,11 +1,12 @@
 use navi::inference::{
-    CompletionProvider, CompletionRequest, Context, ContextSegment, Effort, LmStudioProvider,
-    OpenRouterProvider, ProviderError, Source, StreamChunk,
+    AzureOpenAiProvider, CompletionProvider, CompletionRequest, Context, ContextItem,
+    ContextSegment, Effort, LmStudioProvider, OpenRouterProvider, ProviderError, RateLimitInfo,
+    ReasoningMode, Source, StreamChunk, ToolCall, UsageStats,
 };
 use tokio::sync::mpsc;
 use wiremock::{
     Mock, MockServer, ResponseTemplate,
-    matchers::{method, path},
+    matchers::{body_partial_json, header, method, path, query_param},
 };
 
 // ============================================================================
@@ -18,6 +19,7 @@ fn create_test_context() -> Context {
     context.add(ContextSegment {
         source: Source::User,
         content: "Hello".to_string(),
+        timestamp: None,
     });
     context
 }
@@ -25,20 +27,61 @@ fn create_test_context() -> Context {
 struct CollectedStream {
     content: Vec<String>,
     thinking: Vec<String>,
+    error: Option<String>,
+    tool_calls: Vec<ToolCall>,
+    rate_limit: Option<RateLimitInfo>,
+    /// Stats from the final `Completed` chunk, if one arrived with any
+    usage: Option<UsageStats>,
+    /// Chunks received after an `Error` chunk (should always be zero).
+    after_error: usize,
+    /// Kind of every chunk in arrival order, e.g. "thinking_start"
+    kinds: Vec<&'static str>,
 }
 
+/// Chunk kinds of a stream that reasons, closes its reasoning item, then answers
+const REASONING_THEN_ANSWER: &[&str] = &[
+    "thinking_start",
+    "thinking",
+    "thinking_end",
+    "content",
+    "completed",
+];
+
 /// Collects all chunks from a stream
 async fn collect_chunks(mut receiver: mpsc::Receiver<StreamChunk>) -> CollectedStream {
     let mut result = CollectedStream {
         content: Vec::new(),
         thinking: Vec::new(),
+        error: None,
+        tool_calls: Vec::new(),
+        rate_limit: None,
+        usage: None,
+        after_error: 0,
+        kinds: Vec::new(),
     };
 
     while let Some(chunk) = receiver.recv().await {
+        if result.error.is_some() {
+            result.after_error += 1;
+        }
+        result.kinds.push(match &chunk {
+            StreamChunk::Content { .. } => "content",
+            StreamChunk::Thinking { .. } => "thinking",
+            StreamChunk::ThinkingStart { .. } => "thinking_start",
+            StreamChunk::ThinkingEnd { .. } => "thinking_end",
+            StreamChunk::ToolCall(_) => "tool_call",
+            StreamChunk::Completed(_) => "completed",
+            StreamChunk::Error(_) => "error",
+            StreamChunk::RateLimit(_) => "rate_limit",
+        });
         match chunk {
             StreamChunk::Content { text, .. } => result.content.push(text),
             StreamChunk::Thinking { text, .. } => result.thinking.push(text),
-            StreamChunk::Completed(_) | StreamChunk::ToolCall(_) => {}
+            StreamChunk::ThinkingStart { .. } | StreamChunk::ThinkingEnd { .. } => {}
+            StreamChunk::Error(message) => result.error = Some(message),
+            StreamChunk::ToolCall(tool_call) => result.tool_calls.push(tool_call),
+            StreamChunk::RateLimit(info) => result.rate_limit = Some(info),
+            StreamChunk::Completed(usage) => result.usage = usage,
         }
     }
 
@@ -81,8 +124,14 @@ data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_or_001\"}}
         model: "test-model",
         context: &context,
         effort: Effort::None,
+        reasoning: true,
         tools: &[],
         max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
     };
 
     let (tx, rx) = mpsc::channel(100);
@@ -96,22 +145,82 @@ data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_or_001\"}}
 }
 
 #[tokio::test]
-async fn test_openrouter_streaming_with_thinking() {
+async fn test_openrouter_rate_limit_headers_reported() {
     let mock_server = MockServer::start().await;
 
-    // Mock SSE response with thinking + content
     let sse_response = "\
-event: response.created
-data: {\"type\":\"response.created\"}
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hi\"}
 
-event: response.reasoning_summary_text.delta
-data: {\"type\":\"response.reasoning_summary_text.delta\",\"delta\":\"Thinking...\"}
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_or_rl\"}}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-ratelimit-limit", "20")
+                .insert_header("x-ratelimit-remaining", "3")
+                .insert_header("x-ratelimit-reset", "1700000000000")
+                .insert_header("x-credits-remaining", "4.25")
+                .set_body_string(sse_response),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
 
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.content, vec!["Hi"]);
+    assert_eq!(
+        collected.rate_limit,
+        Some(RateLimitInfo {
+            limit: Some(20),
+            remaining: Some(3),
+            reset_ms: Some(1_700_000_000_000),
+            credits_remaining: Some(4.25),
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_openrouter_malformed_delta_skipped_and_counted() {
+    let mock_server = MockServer::start().await;
+
+    // The middle delta's JSON is cut off
+    let sse_response = "\
 event: response.output_text.delta
-data: {\"type\":\"response.output_text.delta\",\"delta\":\"Answer\"}
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hello\"}
+
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"delta\":
+
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"delta\":\" world\"}
 
 event: response.completed
-data: {\"type\":\"response.completed\"}
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_or_bad\"}}
 ";
 
     Mock::given(method("POST"))
@@ -126,9 +235,15 @@ data: {\"type\":\"response.completed\"}
     let request = CompletionRequest {
         model: "test-model",
         context: &context,
-        effort: Effort::High,
+        effort: Effort::None,
+        reasoning: true,
         tools: &[],
         max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
     };
 
     let (tx, rx) = mpsc::channel(100);
@@ -137,50 +252,88 @@ data: {\"type\":\"response.completed\"}
     assert!(result.is_ok());
 
     let collected = collect_chunks(rx).await;
-    assert_eq!(collected.content, vec!["Answer"]);
-    assert_eq!(collected.thinking, vec!["Thinking..."]);
+    assert_eq!(collected.content, vec!["Hello", " world"]);
+    assert!(collected.error.is_none());
+    let usage = collected.usage.expect("stream should complete with stats");
+    assert_eq!(usage.dropped_chunks, Some(1));
 }
 
 #[tokio::test]
-async fn test_openrouter_api_error_response() {
+async fn test_openrouter_stream_log_records_raw_lines() {
     let mock_server = MockServer::start().await;
 
+    let sse_response = "\
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hello\"}
+
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_log\"}}
+";
+
     Mock::given(method("POST"))
         .and(path("/responses"))
-        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
         .mount(&mock_server)
         .await;
 
-    let provider = OpenRouterProvider::new("invalid-key".to_string(), Some(mock_server.uri()));
+    let log_path = std::env::temp_dir().join("navi_test_stream_log_openrouter.log");
+    let _ = std::fs::remove_file(&log_path);
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()))
+        .with_stream_log(Some(log_path.clone()));
 
     let context = create_test_context();
     let request = CompletionRequest {
         model: "test-model",
         context: &context,
         effort: Effort::None,
+        reasoning: true,
         tools: &[],
         max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
     };
 
-    let (tx, _rx) = mpsc::channel(100);
+    let (tx, rx) = mpsc::channel(100);
     let result = provider.stream_completion(request, tx).await;
+    assert!(result.is_ok());
+    assert_eq!(collect_chunks(rx).await.content, vec!["Hello"]);
 
-    assert!(matches!(
-        result,
-        Err(ProviderError::Api { status: 401, .. })
-    ));
+    // Every line as received, after the marker opening the stream
+    let transcript = std::fs::read_to_string(&log_path).unwrap();
+    let expected = format!(": navi openrouter stream\n{sse_response}");
+    assert_eq!(transcript, expected);
+    std::fs::remove_file(log_path).ok();
 }
 
 #[tokio::test]
-async fn test_openrouter_channel_closed_error() {
+async fn test_openrouter_reasoning_boundaries_precede_content() {
     let mock_server = MockServer::start().await;
 
+    // Reasoning item, then the message item carrying the answer
     let sse_response = "\
-event: response.output_text.delta
-data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hello\"}
+event: response.output_item.added
+data: {\"type\":\"response.output_item.added\",\"item\":{\"type\":\"reasoning\",\"id\":\"rs_1\"}}
+
+event: response.reasoning_summary_text.delta
+data: {\"type\":\"response.reasoning_summary_text.delta\",\"item_id\":\"rs_1\",\"delta\":\"Thinking it over\"}
+
+event: response.output_item.done
+data: {\"type\":\"response.output_item.done\",\"item\":{\"type\":\"reasoning\",\"id\":\"rs_1\"}}
+
+event: response.output_item.added
+data: {\"type\":\"response.output_item.added\",\"item\":{\"type\":\"message\",\"id\":\"msg_1\"}}
 
 event: response.output_text.delta
-data: {\"type\":\"response.output_text.delta\",\"delta\":\" world\"}
+data: {\"type\":\"response.output_text.delta\",\"item_id\":\"msg_1\",\"delta\":\"Done\"}
+
+event: response.output_item.done
+data: {\"type\":\"response.output_item.done\",\"item\":{\"type\":\"message\",\"id\":\"msg_1\"}}
+
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_rs\"}}
 ";
 
     Mock::given(method("POST"))
@@ -195,41 +348,44 @@ data: {\"type\":\"response.output_text.delta\",\"delta\":\" world\"}
     let request = CompletionRequest {
         model: "test-model",
         context: &context,
-        effort: Effort::None,
+        effort: Effort::High,
+        reasoning: true,
         tools: &[],
         max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
     };
 
-    let (tx, rx) = mpsc::channel(1);
-    // Drop receiver immediately to simulate channel closed
-    drop(rx);
-
+    let (tx, rx) = mpsc::channel(100);
     let result = provider.stream_completion(request, tx).await;
+    assert!(result.is_ok());
 
-    assert!(matches!(result, Err(ProviderError::ChannelClosed)));
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.kinds, REASONING_THEN_ANSWER);
+    assert_eq!(collected.thinking, vec!["Thinking it over"]);
+    assert_eq!(collected.content, vec!["Done"]);
 }
 
-// ============================================================================
-// LM Studio Provider Tests
-// ============================================================================
-
 #[tokio::test]
-async fn test_lmstudio_successful_streaming_content_only() {
+async fn test_openrouter_streaming_with_thinking() {
     let mock_server = MockServer::start().await;
 
-    // Mock SSE response for LM Studio format
+    // Mock SSE response with thinking + content
     let sse_response = "\
 event: response.created
-data: {\"id\":\"test\"}
+data: {\"type\":\"response.created\"}
 
-event: response.output_text.delta
-data: {\"delta\":\"Hello\"}
+event: response.reasoning_summary_text.delta
+data: {\"type\":\"response.reasoning_summary_text.delta\",\"delta\":\"Thinking...\"}
 
 event: response.output_text.delta
-data: {\"delta\":\" LM Studio\"}
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Answer\"}
 
 event: response.completed
-data: {\"response\":{\"id\":\"resp_lms_001\"}}
+data: {\"type\":\"response.completed\"}
 ";
 
     Mock::given(method("POST"))
@@ -238,15 +394,21 @@ data: {\"response\":{\"id\":\"resp_lms_001\"}}
         .mount(&mock_server)
         .await;
 
-    let provider = LmStudioProvider::new(mock_server.uri());
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
 
     let context = create_test_context();
     let request = CompletionRequest {
         model: "test-model",
         context: &context,
-        effort: Effort::None,
+        effort: Effort::High,
+        reasoning: true,
         tools: &[],
         max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
     };
 
     let (tx, rx) = mpsc::channel(100);
@@ -255,24 +417,27 @@ data: {\"response\":{\"id\":\"resp_lms_001\"}}
     assert!(result.is_ok());
 
     let collected = collect_chunks(rx).await;
-    assert_eq!(collected.content, vec!["Hello", " LM Studio"]);
-    assert!(collected.thinking.is_empty());
+    assert_eq!(collected.content, vec!["Answer"]);
+    assert_eq!(collected.thinking, vec!["Thinking..."]);
 }
 
-#[tokio::test]
-async fn test_lmstudio_streaming_with_reasoning() {
+/// Streams one summary delta and one full-reasoning delta through OpenRouter
+/// with the given mode, returning the thinking chunks that came out.
+async fn openrouter_thinking_for_mode(mode: ReasoningMode) -> Vec<String> {
     let mock_server = MockServer::start().await;
 
-    // Mock SSE response with reasoning (note different event name)
     let sse_response = "\
+event: response.reasoning_summary_text.delta
+data: {\"type\":\"response.reasoning_summary_text.delta\",\"delta\":\"Summary\"}
+
 event: response.reasoning_text.delta
-data: {\"delta\":\"Let me think...\"}
+data: {\"type\":\"response.reasoning_text.delta\",\"delta\":\"Full reasoning\"}
 
 event: response.output_text.delta
-data: {\"delta\":\"Response\"}
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Answer\"}
 
 event: response.completed
-data: {\"id\":\"test\"}
+data: {\"type\":\"response.completed\"}
 ";
 
     Mock::given(method("POST"))
@@ -281,47 +446,61 @@ data: {\"id\":\"test\"}
         .mount(&mock_server)
         .await;
 
-    let provider = LmStudioProvider::new(mock_server.uri());
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
 
     let context = create_test_context();
     let request = CompletionRequest {
         model: "test-model",
         context: &context,
-        effort: Effort::Medium,
+        effort: Effort::High,
+        reasoning: true,
         tools: &[],
         max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: mode,
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
     };
 
     let (tx, rx) = mpsc::channel(100);
     let result = provider.stream_completion(request, tx).await;
-
     assert!(result.is_ok());
 
     let collected = collect_chunks(rx).await;
-    assert_eq!(collected.content, vec!["Response"]);
-    assert_eq!(collected.thinking, vec!["Let me think..."]);
+    assert_eq!(collected.content, vec!["Answer"]);
+    collected.thinking
 }
 
 #[tokio::test]
-async fn test_lmstudio_handles_unknown_event_types() {
+async fn test_openrouter_summary_mode_drops_full_reasoning() {
+    let thinking = openrouter_thinking_for_mode(ReasoningMode::Summary).await;
+    assert_eq!(thinking, vec!["Summary"]);
+}
+
+#[tokio::test]
+async fn test_openrouter_full_mode_drops_reasoning_summary() {
+    let thinking = openrouter_thinking_for_mode(ReasoningMode::Full).await;
+    assert_eq!(thinking, vec!["Full reasoning"]);
+}
+
+/// Streams a content delta that a proxy resent after reconnecting through
+/// OpenRouter, returning the content chunks that came out.
+async fn openrouter_content_with_dedupe(dedupe_stream: bool) -> Vec<String> {
     let mock_server = MockServer::start().await;
 
-    // Include unknown event types
     let sse_response = "\
-event: response.created
-data: {\"id\":\"test\"}
-
-event: response.in_progress
-data: {\"status\":\"working\"}
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"item_id\":\"msg_1\",\"delta\":\"Hello\"}
 
 event: response.output_text.delta
-data: {\"delta\":\"Text\"}
+data: {\"type\":\"response.output_text.delta\",\"item_id\":\"msg_1\",\"delta\":\"Hello\"}
 
-event: response.metadata
-data: {\"usage\":100}
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"item_id\":\"msg_1\",\"delta\":\" world\"}
 
 event: response.completed
-data: {\"id\":\"test\"}
+data: {\"type\":\"response.completed\"}
 ";
 
     Mock::given(method("POST"))
@@ -330,66 +509,1070 @@ data: {\"id\":\"test\"}
         .mount(&mock_server)
         .await;
 
-    let provider = LmStudioProvider::new(mock_server.uri());
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
 
     let context = create_test_context();
     let request = CompletionRequest {
         model: "test-model",
         context: &context,
         effort: Effort::None,
+        reasoning: true,
         tools: &[],
         max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream,
+        prompt_cache: false,
+        turn_directive: None,
     };
 
     let (tx, rx) = mpsc::channel(100);
     let result = provider.stream_completion(request, tx).await;
-
     assert!(result.is_ok());
 
-    let collected = collect_chunks(rx).await;
-    // Should only receive the text chunk, ignoring unknown events
-    assert_eq!(collected.content, vec!["Text"]);
-    assert!(collected.thinking.is_empty());
+    collect_chunks(rx).await.content
 }
 
-// ============================================================================
-// Effort Level Tests
-// ============================================================================
+#[tokio::test]
+async fn test_openrouter_dedupe_drops_repeated_delta() {
+    let content = openrouter_content_with_dedupe(true).await;
+    assert_eq!(content, vec!["Hello", " world"]);
+}
 
 #[tokio::test]
-async fn test_effort_levels_affect_request() {
+async fn test_openrouter_keeps_repeated_delta_by_default() {
+    let content = openrouter_content_with_dedupe(false).await;
+    assert_eq!(content, vec!["Hello", "Hello", " world"]);
+}
+
+#[tokio::test]
+async fn test_openrouter_api_error_response() {
     let mock_server = MockServer::start().await;
 
-    let sse_response = "event: response.completed\ndata: {\"id\":\"test\"}\n";
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("invalid-key".to_string(), Some(mock_server.uri()));
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, _rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(matches!(
+        result,
+        Err(ProviderError::Api { status: 401, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_openrouter_error_event_stops_stream() {
+    let mock_server = MockServer::start().await;
+
+    // The error arrives mid-stream; nothing after it should be delivered
+    let sse_response = "\
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Partial\"}
+
+data: {\"type\":\"error\",\"code\":\"server_error\",\"message\":\"Upstream provider crashed\"}
+
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"never seen\"}
+
+data: {\"type\":\"response.completed\"}
+";
 
     Mock::given(method("POST"))
         .and(path("/responses"))
         .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
-        .expect(5) // Will be called 5 times (one for each effort level)
         .mount(&mock_server)
         .await;
 
-    let provider = LmStudioProvider::new(mock_server.uri());
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+
     let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
 
-    // Test all effort levels
-    for effort in [
-        Effort::None,
-        Effort::Auto,
-        Effort::Low,
-        Effort::Medium,
-        Effort::High,
-    ] {
-        let request = CompletionRequest {
-            model: "test-model",
-            context: &context,
-            effort,
-            tools: &[],
-            max_output_tokens: None,
-        };
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
 
-        let (tx, _rx) = mpsc::channel(100);
-        let result = provider.stream_completion(request, tx).await;
-        assert!(result.is_ok(), "Failed for effort level: {:?}", effort);
-    }
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.content, vec!["Partial"]);
+    assert_eq!(
+        collected.error.as_deref(),
+        Some("Upstream provider crashed (server_error)")
+    );
+    assert_eq!(collected.after_error, 0);
+}
+
+#[tokio::test]
+async fn test_openrouter_request_id_reported_with_later_error() {
+    let mock_server = MockServer::start().await;
+
+    // The id arrives with the response; the failure comes after
+    let sse_response = "\
+data: {\"type\":\"response.created\",\"response\":{\"id\":\"resp_abc\"}}
+
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Partial\"}
+
+data: {\"type\":\"error\",\"code\":\"server_error\",\"message\":\"Upstream provider crashed\"}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    assert_eq!(
+        collected.error.as_deref(),
+        Some("Upstream provider crashed (server_error) (request id: resp_abc)")
+    );
+}
+
+#[tokio::test]
+async fn test_openrouter_api_error_carries_request_id_header() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(
+            ResponseTemplate::new(500)
+                .insert_header("x-request-id", "req-123")
+                .set_body_string("Internal error"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, _rx) = mpsc::channel(100);
+    let error = provider.stream_completion(request, tx).await.unwrap_err();
+
+    assert!(matches!(
+        &error,
+        ProviderError::Api { status: 500, request_id: Some(id), .. } if id == "req-123"
+    ));
+    assert_eq!(
+        error.to_string(),
+        "API error (HTTP 500): Internal error (request id: req-123)"
+    );
+}
+
+#[tokio::test]
+async fn test_openrouter_malformed_tool_arguments_fall_back_to_empty_object() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "\
+event: response.output_item.added
+data: {\"type\":\"response.output_item.added\",\"item\":{\"type\":\"function_call\",\"id\":\"fc_1\",\"call_id\":\"call_1\",\"name\":\"add\"}}
+
+event: response.function_call_arguments.done
+data: {\"type\":\"response.function_call_arguments.done\",\"item_id\":\"fc_1\",\"name\":\"add\",\"arguments\":\"{\\\"a\\\": 1,\"}
+
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_or_tool\"}}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+
+    // The call still arrives, with arguments the tool registry can parse
+    let collected = collect_chunks(rx).await;
+    assert!(collected.error.is_none());
+    assert_eq!(collected.tool_calls.len(), 1);
+    assert_eq!(collected.tool_calls[0].call_id, "call_1");
+    assert_eq!(collected.tool_calls[0].arguments, "{}");
+}
+
+#[tokio::test]
+async fn test_openrouter_channel_closed_error() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "\
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hello\"}
+
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"delta\":\" world\"}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(1);
+    // Drop receiver immediately to simulate channel closed
+    drop(rx);
+
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(matches!(result, Err(ProviderError::ChannelClosed)));
+}
+
+// ============================================================================
+// Azure OpenAI Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_azure_uses_deployment_path_and_api_key_header() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "\
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hello from Azure\"}
+
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_az_001\"}}
+";
+
+    // Only a request with the Azure shape matches; anything else gets a 404
+    Mock::given(method("POST"))
+        .and(path("/openai/deployments/my-gpt/responses"))
+        .and(query_param("api-version", "2025-04-01-preview"))
+        .and(header("api-key", "azure-key"))
+        .and(body_partial_json(serde_json::json!({"model": "my-gpt"})))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let provider = AzureOpenAiProvider::new(
+        "azure-key".to_string(),
+        format!("{}/", mock_server.uri()),
+        "my-gpt".to_string(),
+        "2025-04-01-preview".to_string(),
+    );
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok(), "unexpected error: {:?}", result.err());
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.content, vec!["Hello from Azure"]);
+    assert!(collected.error.is_none());
+}
+
+// ============================================================================
+// LM Studio Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_lmstudio_successful_streaming_content_only() {
+    let mock_server = MockServer::start().await;
+
+    // Mock SSE response for LM Studio format
+    let sse_response = "\
+event: response.created
+data: {\"id\":\"test\"}
+
+event: response.output_text.delta
+data: {\"delta\":\"Hello\"}
+
+event: response.output_text.delta
+data: {\"delta\":\" LM Studio\"}
+
+event: response.completed
+data: {\"response\":{\"id\":\"resp_lms_001\"}}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.content, vec!["Hello", " LM Studio"]);
+    assert!(collected.thinking.is_empty());
+}
+
+#[tokio::test]
+async fn test_lmstudio_streaming_with_reasoning() {
+    let mock_server = MockServer::start().await;
+
+    // Mock SSE response with reasoning (note different event name)
+    let sse_response = "\
+event: response.reasoning_text.delta
+data: {\"delta\":\"Let me think...\"}
+
+event: response.output_text.delta
+data: {\"delta\":\"Response\"}
+
+event: response.completed
+data: {\"id\":\"test\"}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::Medium,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.content, vec!["Response"]);
+    assert_eq!(collected.thinking, vec!["Let me think..."]);
+}
+
+#[tokio::test]
+async fn test_lmstudio_reasoning_boundaries_precede_content() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "\
+event: response.output_item.added
+data: {\"item\":{\"type\":\"reasoning\",\"id\":\"rs_1\"}}
+
+event: response.reasoning_text.delta
+data: {\"item_id\":\"rs_1\",\"delta\":\"Hmm.\"}
+
+event: response.output_item.done
+data: {\"item\":{\"type\":\"reasoning\",\"id\":\"rs_1\"}}
+
+event: response.output_item.added
+data: {\"item\":{\"type\":\"message\",\"id\":\"msg_1\"}}
+
+event: response.output_text.delta
+data: {\"item_id\":\"msg_1\",\"delta\":\"Answer\"}
+
+event: response.output_item.done
+data: {\"item\":{\"type\":\"message\",\"id\":\"msg_1\"}}
+
+event: response.completed
+data: {\"id\":\"test\"}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::Medium,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.kinds, REASONING_THEN_ANSWER);
+}
+
+#[tokio::test]
+async fn test_lmstudio_handles_unknown_event_types() {
+    let mock_server = MockServer::start().await;
+
+    // Include unknown event types
+    let sse_response = "\
+event: response.created
+data: {\"id\":\"test\"}
+
+event: response.in_progress
+data: {\"status\":\"working\"}
+
+event: response.output_text.delta
+data: {\"delta\":\"Text\"}
+
+event: response.metadata
+data: {\"usage\":100}
+
+event: response.completed
+data: {\"id\":\"test\"}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    // Should only receive the text chunk, ignoring unknown events
+    assert_eq!(collected.content, vec!["Text"]);
+    assert!(collected.thinking.is_empty());
+}
+
+#[tokio::test]
+async fn test_lmstudio_response_failed_event_stops_stream() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "\
+event: response.output_text.delta
+data: {\"delta\":\"Partial\"}
+
+event: response.failed
+data: {\"type\":\"response.failed\",\"response\":{\"status\":\"failed\",\"error\":{\"code\":\"model_unloaded\",\"message\":\"Model was unloaded\"}}}
+
+event: response.output_text.delta
+data: {\"delta\":\"never seen\"}
+
+event: response.completed
+data: {\"response\":{\"id\":\"resp_lms_002\"}}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.content, vec!["Partial"]);
+    assert_eq!(
+        collected.error.as_deref(),
+        Some("Model was unloaded (model_unloaded)")
+    );
+    assert_eq!(collected.after_error, 0);
+}
+
+#[tokio::test]
+async fn test_lmstudio_oversized_tool_arguments_abandon_call() {
+    let mock_server = MockServer::start().await;
+
+    // Three 10-byte deltas against a 25-byte cap: the third trips it
+    let sse_response = "\
+event: response.output_item.added
+data: {\"type\":\"response.output_item.added\",\"item\":{\"type\":\"function_call\",\"id\":\"fc_1\",\"call_id\":\"call_1\",\"name\":\"add\"}}
+
+event: response.function_call_arguments.delta
+data: {\"item_id\":\"fc_1\",\"delta\":\"aaaaaaaaaa\"}
+
+event: response.function_call_arguments.delta
+data: {\"item_id\":\"fc_1\",\"delta\":\"bbbbbbbbbb\"}
+
+event: response.function_call_arguments.delta
+data: {\"item_id\":\"fc_1\",\"delta\":\"cccccccccc\"}
+
+event: response.function_call_arguments.done
+data: {\"item_id\":\"fc_1\",\"arguments\":\"{}\"}
+
+event: response.completed
+data: {\"response\":{\"id\":\"resp_lms_big\"}}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri()).with_max_tool_args_bytes(25);
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    assert!(collected.tool_calls.is_empty());
+    assert_eq!(
+        collected.error.as_deref(),
+        Some("Tool call 'add' abandoned: its arguments passed 25 bytes")
+    );
+    assert_eq!(collected.after_error, 0);
+}
+
+#[tokio::test]
+async fn test_lmstudio_slow_consumer_receives_every_chunk() {
+    let mock_server = MockServer::start().await;
+
+    let mut sse_response = String::new();
+    for n in 0..40 {
+        sse_response.push_str(&format!(
+            "event: response.output_text.delta\ndata: {{\"delta\":\"{n} \"}}\n\n"
+        ));
+    }
+    sse_response.push_str(
+        "event: response.completed\ndata: {\"response\":{\"id\":\"resp_slow\",\"usage\":{\"input_tokens\":1,\"output_tokens\":40}}}\n",
+    );
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    // A one-slot channel drained slowly keeps the provider waiting on it
+    let (tx, mut rx) = mpsc::channel(1);
+    let consumer = async {
+        let mut received = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+            received.push(chunk);
+        }
+        received
+    };
+    let (result, received) = tokio::join!(provider.stream_completion(request, tx), consumer);
+
+    assert!(result.is_ok());
+    let content: Vec<String> = received
+        .iter()
+        .filter_map(|chunk| match chunk {
+            StreamChunk::Content { text, .. } => Some(text.clone()),
+            _ => None,
+        })
+        .collect();
+    let expected: Vec<String> = (0..40).map(|n| format!("{n} ")).collect();
+    assert_eq!(content, expected);
+    assert!(matches!(received.last(), Some(StreamChunk::Completed(_))));
+}
+
+#[tokio::test]
+async fn test_lmstudio_dedupe_drops_repeated_delta() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "\
+event: response.reasoning_text.delta
+data: {\"item_id\":\"rs_1\",\"delta\":\"Hmm\"}
+
+event: response.reasoning_text.delta
+data: {\"item_id\":\"rs_1\",\"delta\":\"Hmm\"}
+
+event: response.output_text.delta
+data: {\"item_id\":\"msg_1\",\"delta\":\"Hi\"}
+
+event: response.output_text.delta
+data: {\"item_id\":\"msg_1\",\"delta\":\"Hi\"}
+
+event: response.completed
+data: {\"response\":{\"id\":\"resp_lms_003\"}}
+";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::Low,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: true,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+    assert!(result.is_ok());
+
+    let collected = collect_chunks(rx).await;
+    assert_eq!(collected.thinking, vec!["Hmm"]);
+    assert_eq!(collected.content, vec!["Hi"]);
+}
+
+#[tokio::test]
+async fn test_openrouter_prompt_cache_marks_stable_prefix() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "event: response.completed\ndata: {\"type\":\"response.completed\"}\n";
+
+    // Only matches if the last user message of the completed turn carries the marker
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(serde_json::json!({
+            "input": [
+                {"type": "message", "role": "system", "content": "Be brief."},
+                {
+                    "type": "message",
+                    "role": "user",
+                    "content": [{
+                        "type": "input_text",
+                        "text": "Hi",
+                        "cache_control": {"type": "ephemeral"}
+                    }]
+                },
+                {"type": "message", "role": "assistant", "content": "Hello"},
+                {"type": "message", "role": "user", "content": "Again"}
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+
+    let mut context = Context::with_system_prompt("Be brief.".to_string());
+    context.add_user_message("Hi".to_string());
+    context.add(ContextSegment {
+        source: Source::Model,
+        content: "Hello".to_string(),
+        timestamp: None,
+    });
+    context.advance_cache_watermark();
+    context.add_user_message("Again".to_string());
+
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: true,
+        turn_directive: None,
+    };
+
+    let (tx, _rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_openrouter_turn_directive_sent_but_not_stored() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "event: response.completed\ndata: {\"type\":\"response.completed\"}\n";
+
+    // Only matches if the directive follows the base system prompt
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(serde_json::json!({
+            "input": [
+                {"type": "message", "role": "system", "content": "Be brief."},
+                {"type": "message", "role": "system", "content": "Answer in French."},
+                {"type": "message", "role": "user", "content": "Hi"}
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+
+    let mut context = Context::with_system_prompt("Be brief.".to_string());
+    context.add_user_message("Hi".to_string());
+
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: None,
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: Some("Answer in French."),
+    };
+
+    let (tx, _rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+    // The directive never becomes part of the conversation
+    assert_eq!(context.items.len(), 2);
+    assert!(!context.items.iter().any(
+        |item| matches!(item, ContextItem::Message(seg) if seg.content == "Answer in French.")
+    ));
+}
+
+#[tokio::test]
+async fn test_lmstudio_sends_configured_max_output_tokens() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "event: response.completed\ndata: {\"id\":\"test\"}\n";
+
+    // Only matches if the body carries the requested cap
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(
+            serde_json::json!({"max_output_tokens": 4096}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+
+    let context = create_test_context();
+    let request = CompletionRequest {
+        model: "test-model",
+        context: &context,
+        effort: Effort::None,
+        reasoning: true,
+        tools: &[],
+        max_output_tokens: Some(4096),
+        temperature: None,
+        reasoning_mode: ReasoningMode::default(),
+        dedupe_stream: false,
+        prompt_cache: false,
+        turn_directive: None,
+    };
+
+    let (tx, _rx) = mpsc::channel(100);
+    let result = provider.stream_completion(request, tx).await;
+
+    assert!(result.is_ok());
+}
+
+// ============================================================================
+// Effort Level Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_effort_levels_affect_request() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "event: response.completed\ndata: {\"id\":\"test\"}\n";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .expect(5) // Will be called 5 times (one for each effort level)
+        .mount(&mock_server)
+        .await;
+
+    let provider = LmStudioProvider::new(mock_server.uri());
+    let context = create_test_context();
+
+    // Test all effort levels
+    for effort in [
+        Effort::None,
+        Effort::Auto,
+        Effort::Low,
+        Effort::Medium,
+        Effort::High,
+    ] {
+        let request = CompletionRequest {
+            model: "test-model",
+            context: &context,
+            effort,
+            reasoning: true,
+            tools: &[],
+            max_output_tokens: None,
+            temperature: None,
+            reasoning_mode: ReasoningMode::default(),
+            dedupe_stream: false,
+            prompt_cache: false,
+            turn_directive: None,
+        };
+
+        let (tx, _rx) = mpsc::channel(100);
+        let result = provider.stream_completion(request, tx).await;
+        assert!(result.is_ok(), "Failed for effort level: {:?}", effort);
+    }
+}
+
+#[tokio::test]
+async fn test_reasoning_block_omitted_for_non_reasoning_model() {
+    let mock_server = MockServer::start().await;
+
+    let sse_response = "event: response.completed\ndata: {\"id\":\"test\"}\n";
+
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sse_response))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(mock_server.uri()));
+    let context = create_test_context();
+
+    for reasoning in [true, false] {
+        let request = CompletionRequest {
+            model: "test-model",
+            context: &context,
+            effort: Effort::High,
+            reasoning,
+            tools: &[],
+            max_output_tokens: None,
+            temperature: None,
+            reasoning_mode: ReasoningMode::default(),
+            dedupe_stream: false,
+            prompt_cache: false,
+            turn_directive: None,
+        };
+
+        let (tx, _rx) = mpsc::channel(100);
+        let result = provider.stream_completion(request, tx).await;
+        assert!(result.is_ok());
+    }
+
+    let bodies: Vec<serde_json::Value> = mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.body_json().unwrap())
+        .collect();
+    assert_eq!(bodies[0]["reasoning"]["effort"], "high");
+    // The effort setting is dropped along with the whole block
+    assert!(bodies[1].get("reasoning").is_none());
 }